@@ -6,6 +6,7 @@ pub mod history_sync;
 pub mod inherents;
 pub mod push;
 pub(super) mod rebranch_utils;
+pub mod signalling;
 pub mod slots;
 pub mod verify;
 pub mod wrappers;
@@ -0,0 +1,190 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use nimiq_blockchain_interface::AbstractBlockchain;
+use nimiq_hash::Blake2bHash;
+use nimiq_keys::Address;
+use nimiq_primitives::{coin::Coin, policy::Policy};
+
+use crate::Blockchain;
+
+/// The result of tallying the `signal_data` of the validators for a given signal.
+///
+/// Signals are weighted in two ways: by the stake of the active validators that currently signal
+/// the value and by the number of slots owned in the current epoch by the elected validators that
+/// currently signal it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignalTally {
+    /// The signal that was tallied.
+    pub signal: Blake2bHash,
+    /// The block number at which the tally was computed.
+    pub block_number: u32,
+    /// The epoch at which the tally was computed.
+    pub epoch_number: u32,
+    /// The addresses of the active or elected validators signalling this value.
+    pub validators: Vec<Address>,
+    /// The combined stake of the active validators signalling this value.
+    pub stake: Coin,
+    /// The combined stake of all active validators.
+    pub total_stake: Coin,
+    /// The number of slots of the current epoch owned by elected validators signalling this value,
+    /// whether they are still active or not.
+    pub slots: u16,
+}
+
+impl SignalTally {
+    fn new(signal: Blake2bHash, block_number: u32, total_stake: Coin) -> Self {
+        SignalTally {
+            signal,
+            block_number,
+            epoch_number: Policy::epoch_at(block_number),
+            validators: vec![],
+            stake: Coin::ZERO,
+            total_stake,
+            slots: 0,
+        }
+    }
+
+    fn add_validator(&mut self, address: &Address) {
+        if !self.validators.contains(address) {
+            self.validators.push(address.clone());
+        }
+    }
+
+    /// Returns the fraction (between 0 and 1) of the active stake that signals this value.
+    pub fn stake_ratio(&self) -> f64 {
+        if self.total_stake.is_zero() {
+            return 0.0;
+        }
+        u64::from(self.stake) as f64 / u64::from(self.total_stake) as f64
+    }
+
+    /// Returns the fraction (between 0 and 1) of the current epoch's slots that signal this value.
+    pub fn slot_ratio(&self) -> f64 {
+        f64::from(self.slots) / f64::from(Policy::SLOTS)
+    }
+}
+
+/// Events emitted by the [`SignalThresholdTracker`] when the stake signalling a value crosses
+/// the configured threshold.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SignalEvent {
+    /// The stake signalling the value reached the threshold.
+    ThresholdReached(SignalTally),
+    /// The stake signalling the value dropped below the threshold again.
+    ThresholdLost(Blake2bHash),
+}
+
+/// Keeps track of which signals are above a given stake threshold and reports the crossings.
+pub struct SignalThresholdTracker {
+    threshold: f64,
+    above_threshold: BTreeSet<Blake2bHash>,
+}
+
+impl SignalThresholdTracker {
+    /// Creates a new tracker. The `threshold` is the fraction of the active stake (between 0 and 1)
+    /// that needs to signal a value for it to be reported.
+    pub fn new(threshold: f64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&threshold),
+            "Signal threshold must be between 0 and 1"
+        );
+        SignalThresholdTracker {
+            threshold,
+            above_threshold: BTreeSet::new(),
+        }
+    }
+
+    /// Updates the tracker with a fresh set of tallies and returns the threshold crossings since
+    /// the last update.
+    pub fn update(&mut self, tallies: Vec<SignalTally>) -> Vec<SignalEvent> {
+        let mut events = vec![];
+        let mut above_threshold = BTreeSet::new();
+
+        for tally in tallies {
+            if tally.stake_ratio() < self.threshold {
+                continue;
+            }
+            above_threshold.insert(tally.signal.clone());
+            if !self.above_threshold.contains(&tally.signal) {
+                events.push(SignalEvent::ThresholdReached(tally));
+            }
+        }
+
+        for signal in self.above_threshold.difference(&above_threshold) {
+            events.push(SignalEvent::ThresholdLost(signal.clone()));
+        }
+
+        self.above_threshold = above_threshold;
+        events
+    }
+}
+
+/// Implements methods to tally the validators' signal data.
+impl Blockchain {
+    /// Tallies the signal data of the validators. Stake is tallied over the active validators,
+    /// slots over the validators elected for the current epoch, including those that have been
+    /// deactivated or jailed since. Returns one tally per distinct signal, or `None` if the staking
+    /// contract is not available (e.g. because the state is incomplete).
+    pub fn get_signal_tallies(&self) -> Option<Vec<SignalTally>> {
+        let staking_contract = self.get_staking_contract_if_complete(None)?;
+        let data_store = self.get_staking_contract_store();
+        let txn = self.read_transaction();
+        let data_store = data_store.read(&txn);
+
+        let block_number = self.block_number();
+        let total_stake: Coin = staking_contract.active_validators.values().copied().sum();
+
+        let signal_of = |address: &Address| {
+            staking_contract
+                .get_validator(&data_store, address)
+                .and_then(|validator| validator.signal_data)
+        };
+
+        let mut tallies: BTreeMap<Blake2bHash, SignalTally> = BTreeMap::new();
+        for (address, stake) in staking_contract.active_validators.iter() {
+            let signal = match signal_of(address) {
+                Some(signal) => signal,
+                None => continue,
+            };
+
+            let tally = tallies
+                .entry(signal.clone())
+                .or_insert_with(|| SignalTally::new(signal, block_number, total_stake));
+            tally.add_validator(address);
+            tally.stake += *stake;
+        }
+
+        for validator in self.current_validators().iter().flat_map(|v| v.iter()) {
+            let signal = match signal_of(&validator.address) {
+                Some(signal) => signal,
+                None => continue,
+            };
+
+            let tally = tallies
+                .entry(signal.clone())
+                .or_insert_with(|| SignalTally::new(signal, block_number, total_stake));
+            tally.add_validator(&validator.address);
+            tally.slots += validator.num_slots();
+        }
+
+        Some(tallies.into_values().collect())
+    }
+
+    /// Tallies the signal data of all active validators for the given signal.
+    /// Returns `None` if the staking contract is not available.
+    pub fn get_signal_tally(&self, signal: &Blake2bHash) -> Option<SignalTally> {
+        let tally = self
+            .get_signal_tallies()?
+            .into_iter()
+            .find(|tally| &tally.signal == signal);
+
+        Some(tally.unwrap_or_else(|| {
+            let staking_contract = self.get_staking_contract();
+            SignalTally::new(
+                signal.clone(),
+                self.block_number(),
+                staking_contract.active_validators.values().copied().sum(),
+            )
+        }))
+    }
+}
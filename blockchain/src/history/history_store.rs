@@ -5,7 +5,7 @@ use std::{
 
 use nimiq_database::{
     traits::{Database, ReadCursor, ReadTransaction, WriteCursor, WriteTransaction},
-    DatabaseProxy, TableFlags, TableProxy, TransactionProxy, WriteTransactionProxy,
+    CursorProxy, DatabaseProxy, TableFlags, TableProxy, TransactionProxy, WriteTransactionProxy,
 };
 use nimiq_hash::{Blake2bHash, Hash};
 use nimiq_keys::Address;
@@ -439,6 +439,61 @@ impl HistoryStore {
         }
     }
 
+    /// Returns the position of the given transaction in the history, i.e. the block number and the
+    /// leaf index of its most recent leaf.
+    fn get_tx_position(
+        &self,
+        raw_tx_hash: &Blake2bHash,
+        txn: &TransactionProxy,
+    ) -> Option<(u32, u32)> {
+        self.get_leaves_by_tx_hash(raw_tx_hash, Some(txn))
+            .into_iter()
+            .filter_map(|leaf| {
+                let hist_tx = self.get_historic_tx(&leaf.hash, Some(txn))?;
+                Some((hist_tx.block_number, leaf.index))
+            })
+            .max()
+    }
+
+    /// Positions the cursor at the entry of the given transaction in the address database and
+    /// returns it. The entries of an address are ordered the same way as their transactions in the
+    /// history, so we binary search the address indexes between `low` and `high` for the position
+    /// of the transaction. Returns `None` if the transaction isn't indexed for the address.
+    fn seek_address_tx(
+        &self,
+        cursor: &mut CursorProxy,
+        address: &Address,
+        mut low: u32,
+        mut high: u32,
+        raw_tx_hash: &Blake2bHash,
+        txn: &TransactionProxy,
+    ) -> Option<OrderedHash> {
+        let target = self.get_tx_position(raw_tx_hash, txn)?;
+
+        while low <= high {
+            let mid = low + (high - low) / 2;
+
+            // The default hash sorts before all other entries with the same index, so this seeks
+            // to the first entry with an index of at least `mid`.
+            let entry = cursor.seek_key_value_range(
+                address,
+                &OrderedHash {
+                    index: mid,
+                    hash: Blake2bHash::default(),
+                },
+            )?;
+            let position = self.get_tx_position(&entry.hash, txn)?;
+
+            match position.cmp(&target) {
+                cmp::Ordering::Less => low = entry.index + 1,
+                cmp::Ordering::Greater => high = mid.checked_sub(1)?,
+                cmp::Ordering::Equal => return (entry.hash == *raw_tx_hash).then_some(entry),
+            }
+        }
+
+        None
+    }
+
     /// Calculates the history tree root from a vector of historic transactions. It doesn't use the
     /// database, it is just used to check the correctness of the history root when syncing.
     fn _root_from_hist_txs(hist_txs: &[HistoricTransaction]) -> Option<Blake2bHash> {
@@ -927,15 +982,17 @@ impl HistoryInterface for HistoryStore {
 
     /// Returns a vector containing all transaction (and reward inherents) hashes corresponding to the given
     /// address. It fetches the transactions from most recent to least recent up to the maximum
-    /// number given.
+    /// number given. If `start_after` is given, it starts with the transaction following it.
+    /// Returns `None` if `start_after` isn't a transaction of the given address.
     fn get_tx_hashes_by_address(
         &self,
         address: &Address,
         max: u16,
+        start_after: Option<&Blake2bHash>,
         txn_option: Option<&TransactionProxy>,
-    ) -> Vec<Blake2bHash> {
+    ) -> Option<Vec<Blake2bHash>> {
         if max == 0 {
-            return vec![];
+            return Some(vec![]);
         }
 
         let read_txn: TransactionProxy;
@@ -952,23 +1009,54 @@ impl HistoryInterface for HistoryStore {
         // Seek to the first transaction hash at the given address. If there's none, stop here.
         let mut cursor = txn.cursor(&self.address_table);
 
-        if cursor.seek_key::<Address, OrderedHash>(address).is_none() {
-            return tx_hashes;
-        }
+        let first = match cursor.seek_key::<Address, OrderedHash>(address) {
+            Some(first) => first,
+            None => return start_after.is_none().then_some(tx_hashes),
+        };
+
+        // Then go to the last transaction hash at the given address.
+        let last = cursor.last_duplicate::<OrderedHash>()?;
+
+        let mut current = match start_after {
+            None => Some(last.hash),
+            Some(start_after) => {
+                // Position the cursor directly at the transaction to start after and continue
+                // with the one preceding it.
+                self.seek_address_tx(
+                    &mut cursor,
+                    address,
+                    first.index,
+                    last.index,
+                    start_after,
+                    txn,
+                )?;
+
+                // A transaction from an address to itself is indexed twice for that address.
+                let mut previous = cursor
+                    .prev_duplicate::<Address, OrderedHash>()
+                    .map(|(_, v)| v.hash);
+                while previous.as_ref() == Some(start_after) {
+                    previous = cursor
+                        .prev_duplicate::<Address, OrderedHash>()
+                        .map(|(_, v)| v.hash);
+                }
+                previous
+            }
+        };
 
-        // Then go to the last transaction hash at the given address and add it to the transaction
-        // hashes list.
-        tx_hashes.push(cursor.last_duplicate::<OrderedHash>().expect("This shouldn't panic since we already verified before that there is at least one transactions at this address!").hash);
+        while let Some(hash) = current {
+            tx_hashes.push(hash);
+            if tx_hashes.len() >= max as usize {
+                break;
+            }
 
-        while tx_hashes.len() < max as usize {
             // Get previous transaction hash.
-            match cursor.prev_duplicate::<Address, OrderedHash>() {
-                Some((_, v)) => tx_hashes.push(v.hash),
-                None => break,
-            };
+            current = cursor
+                .prev_duplicate::<Address, OrderedHash>()
+                .map(|(_, v)| v.hash);
         }
 
-        tx_hashes
+        Some(tx_hashes)
     }
 
     /// Returns a proof for transactions with the given hashes. The proof also includes the extended
@@ -1668,12 +1756,17 @@ mod tests {
         history_store.add_to_history(&mut txn, 1, &hist_txs[3..]);
 
         // Verify method works.
-        let query_1 = history_store.get_tx_hashes_by_address(
-            &Address::from_user_friendly_address("NQ09 VF5Y 1PKV MRM4 5LE1 55KV P6R2 GXYJ XYQF")
+        let query_1 = history_store
+            .get_tx_hashes_by_address(
+                &Address::from_user_friendly_address(
+                    "NQ09 VF5Y 1PKV MRM4 5LE1 55KV P6R2 GXYJ XYQF",
+                )
                 .unwrap(),
-            99,
-            Some(&txn),
-        );
+                99,
+                None,
+                Some(&txn),
+            )
+            .unwrap();
 
         let hashes: Vec<_> = hist_txs.iter().map(|hist_tx| hist_tx.tx_hash()).collect();
 
@@ -1684,35 +1777,120 @@ mod tests {
         assert_eq!(query_1[3], *hashes[1]);
         assert_eq!(query_1[4], *hashes[0]);
 
-        let query_2 =
-            history_store.get_tx_hashes_by_address(&Address::burn_address(), 2, Some(&txn));
+        let query_2 = history_store
+            .get_tx_hashes_by_address(&Address::burn_address(), 2, None, Some(&txn))
+            .unwrap();
 
         assert_eq!(query_2.len(), 2);
         assert_eq!(query_2[0], *hashes[6]);
         assert_eq!(query_2[1], *hashes[5]);
 
-        let query_3 = history_store.get_tx_hashes_by_address(
-            &Address::from_user_friendly_address("NQ04 B79B R4FF 4NGU A9H0 2PT9 9ART 5A88 J73T")
+        // Continue after the last transaction of the previous query.
+        let query_2_next = history_store
+            .get_tx_hashes_by_address(&Address::burn_address(), 2, Some(&query_2[1]), Some(&txn))
+            .unwrap();
+
+        assert_eq!(query_2_next.len(), 2);
+        assert_eq!(query_2_next[0], *hashes[3]);
+        assert_eq!(query_2_next[1], *hashes[1]);
+
+        let query_3 = history_store
+            .get_tx_hashes_by_address(
+                &Address::from_user_friendly_address(
+                    "NQ04 B79B R4FF 4NGU A9H0 2PT9 9ART 5A88 J73T",
+                )
                 .unwrap(),
-            99,
-            Some(&txn),
-        );
+                99,
+                None,
+                Some(&txn),
+            )
+            .unwrap();
 
         assert_eq!(query_3.len(), 3);
         assert_eq!(query_3[0], *hashes[7]);
         assert_eq!(query_3[1], *hashes[4]);
         assert_eq!(query_3[2], *hashes[2]);
 
-        let query_4 = history_store.get_tx_hashes_by_address(
-            &Address::from_user_friendly_address("NQ28 1U7R M38P GN5A 7J8R GE62 8QS7 PK2S 4S31")
+        let query_4 = history_store
+            .get_tx_hashes_by_address(
+                &Address::from_user_friendly_address(
+                    "NQ28 1U7R M38P GN5A 7J8R GE62 8QS7 PK2S 4S31",
+                )
                 .unwrap(),
-            99,
-            Some(&txn),
-        );
+                99,
+                None,
+                Some(&txn),
+            )
+            .unwrap();
 
         assert_eq!(query_4.len(), 0);
     }
 
+    #[test]
+    fn get_tx_hashes_by_address_pages_from_cursor() {
+        // Initialize History Store.
+        let env = VolatileDatabase::new(20).unwrap();
+        let history_store = HistoryStore::new(env.clone());
+
+        // Create historic transactions.
+        let hist_txs = gen_hist_txs();
+
+        // Add historic transactions to History Store.
+        let mut txn = env.write_transaction();
+        history_store.add_to_history(&mut txn, 0, &hist_txs[..3]);
+        history_store.add_to_history(&mut txn, 1, &hist_txs[3..]);
+
+        let hashes: Vec<_> = hist_txs.iter().map(|hist_tx| hist_tx.tx_hash()).collect();
+        let address = Address::burn_address();
+
+        // Page through all transactions of the address one at a time.
+        let mut pages = vec![];
+        let mut cursor = None;
+        loop {
+            let page = history_store
+                .get_tx_hashes_by_address(&address, 1, cursor.as_ref(), Some(&txn))
+                .unwrap();
+            match page.first() {
+                Some(hash) => cursor = Some(hash.clone()),
+                None => break,
+            }
+            pages.extend(page);
+        }
+
+        assert_eq!(
+            pages,
+            history_store
+                .get_tx_hashes_by_address(&address, 99, None, Some(&txn))
+                .unwrap()
+        );
+
+        // Continue in the middle of the transactions of the address.
+        let query = history_store
+            .get_tx_hashes_by_address(&address, 99, Some(&*hashes[3]), Some(&txn))
+            .unwrap();
+
+        assert_eq!(query, vec![(*hashes[1]).clone(), (*hashes[0]).clone()]);
+
+        // A cursor that isn't a transaction of the address is rejected.
+        assert!(history_store
+            .get_tx_hashes_by_address(&address, 2, Some(&*hashes[2]), Some(&txn))
+            .is_none());
+        assert!(history_store
+            .get_tx_hashes_by_address(&address, 2, Some(&Blake2bHash::default()), Some(&txn))
+            .is_none());
+        assert!(history_store
+            .get_tx_hashes_by_address(
+                &Address::from_user_friendly_address(
+                    "NQ28 1U7R M38P GN5A 7J8R GE62 8QS7 PK2S 4S31"
+                )
+                .unwrap(),
+                2,
+                Some(&*hashes[0]),
+                Some(&txn),
+            )
+            .is_none());
+    }
+
     #[test]
     fn prove_works() {
         // Initialize History Store.
@@ -126,13 +126,15 @@ pub trait HistoryInterface {
 
     /// Returns a vector containing all transaction (and reward inherents) hashes corresponding to the given
     /// address. It fetches the transactions from most recent to least recent up to the maximum
-    /// number given.
+    /// number given. If `start_after` is given, it starts with the transaction following it.
+    /// Returns `None` if `start_after` isn't a transaction of the given address.
     fn get_tx_hashes_by_address(
         &self,
         address: &Address,
         max: u16,
+        start_after: Option<&Blake2bHash>,
         txn_option: Option<&TransactionProxy>,
-    ) -> Vec<Blake2bHash>;
+    ) -> Option<Vec<Blake2bHash>>;
 
     /// Returns a proof for transactions with the given hashes. The proof also includes the extended
     /// transactions.
@@ -266,8 +266,9 @@ impl HistoryInterface for LightHistoryStore {
         &self,
         _address: &nimiq_keys::Address,
         _max: u16,
+        _start_after: Option<&Blake2bHash>,
         _txn_option: Option<&TransactionProxy>,
-    ) -> Vec<Blake2bHash> {
+    ) -> Option<Vec<Blake2bHash>> {
         unimplemented!()
     }
 
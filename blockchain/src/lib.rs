@@ -2,7 +2,10 @@
 extern crate log;
 
 pub use block_production::BlockProducer;
-pub use blockchain::blockchain::{Blockchain, BlockchainConfig, TransactionVerificationCache};
+pub use blockchain::{
+    blockchain::{Blockchain, BlockchainConfig, TransactionVerificationCache},
+    signalling::{SignalEvent, SignalTally, SignalThresholdTracker},
+};
 pub use history::*;
 
 pub(crate) mod block_production;
@@ -0,0 +1,86 @@
+use nimiq_blockchain::{SignalEvent, SignalTally, SignalThresholdTracker};
+use nimiq_hash::Blake2bHash;
+use nimiq_primitives::coin::Coin;
+use nimiq_test_log::test;
+use nimiq_test_utils::block_production::TemporaryBlockProducer;
+
+fn tally(signal: Blake2bHash, stake: u64) -> SignalTally {
+    SignalTally {
+        signal,
+        block_number: 0,
+        epoch_number: 0,
+        validators: vec![],
+        stake: Coin::from_u64_unchecked(stake),
+        total_stake: Coin::from_u64_unchecked(100),
+        slots: 0,
+    }
+}
+
+#[test]
+fn signal_threshold_tracker_reports_crossings() {
+    let signal_a = Blake2bHash::from([1u8; 32]);
+    let signal_b = Blake2bHash::from([2u8; 32]);
+    let mut tracker = SignalThresholdTracker::new(0.5);
+
+    // Nothing crosses the threshold.
+    let events = tracker.update(vec![
+        tally(signal_a.clone(), 20),
+        tally(signal_b.clone(), 49),
+    ]);
+    assert!(events.is_empty());
+
+    // Signal A reaches the threshold.
+    let events = tracker.update(vec![
+        tally(signal_a.clone(), 50),
+        tally(signal_b.clone(), 49),
+    ]);
+    assert_eq!(
+        events,
+        vec![SignalEvent::ThresholdReached(tally(signal_a.clone(), 50))]
+    );
+
+    // Staying above the threshold is not reported again.
+    let events = tracker.update(vec![tally(signal_a.clone(), 60)]);
+    assert!(events.is_empty());
+
+    // Signal A disappears and signal B reaches the threshold.
+    let events = tracker.update(vec![tally(signal_b.clone(), 70)]);
+    assert_eq!(
+        events,
+        vec![
+            SignalEvent::ThresholdReached(tally(signal_b, 70)),
+            SignalEvent::ThresholdLost(signal_a),
+        ]
+    );
+}
+
+#[test]
+fn signal_tally_ratios() {
+    let quarter = SignalTally {
+        slots: 128,
+        ..tally(Blake2bHash::default(), 25)
+    };
+    assert_eq!(quarter.stake_ratio(), 0.25);
+    assert_eq!(quarter.slot_ratio(), 0.25);
+
+    let empty = SignalTally {
+        total_stake: Coin::ZERO,
+        ..quarter
+    };
+    assert_eq!(empty.stake_ratio(), 0.0);
+}
+
+#[test]
+fn genesis_has_no_signals() {
+    let producer = TemporaryBlockProducer::new();
+    let blockchain = producer.blockchain.read();
+
+    assert_eq!(blockchain.get_signal_tallies(), Some(vec![]));
+
+    let tally = blockchain
+        .get_signal_tally(&Blake2bHash::default())
+        .unwrap();
+    assert!(tally.validators.is_empty());
+    assert_eq!(tally.stake, Coin::ZERO);
+    assert_eq!(tally.slots, 0);
+}
@@ -1,7 +1,7 @@
 use std::time::Duration;
 
 use log::info;
-use nimiq::prover::prover_main;
+use nimiq::prover::{prover_main, prover_worker_main};
 pub use nimiq::{
     client::{Client, Consensus},
     config::{command_line::CommandLine, config::ClientConfig, config_file::ConfigFile},
@@ -30,7 +30,7 @@ async fn main_inner() -> Result<(), Error> {
     // Initialize logging with config values.
     initialize_logging(
         Some(&command_line),
-        if command_line.prove {
+        if command_line.prove || command_line.zkp_worker.is_some() {
             Some(&config_file.prover_log)
         } else {
             Some(&config_file.log)
@@ -59,6 +59,18 @@ async fn main_inner() -> Result<(), Error> {
     let config = builder.build()?;
     log::debug!("Final configuration: {:#?}", config);
 
+    // Early return in case of a prover worker process.
+    if let Some(listen_address) = command_line.zkp_worker {
+        info!("Starting prover worker.");
+        return Ok(prover_worker_main(
+            listen_address,
+            config.zkp.prover_keys_path,
+            config.zkp.prover_worker_secret,
+            config.zkp.prover_worker_debug_mode,
+        )
+        .await?);
+    }
+
     // Clone config for RPC and metrics server
     let rpc_config = config.rpc_server.clone();
     let metrics_config = config.metrics_server.clone();
@@ -104,10 +116,18 @@ async fn main_inner() -> Result<(), Error> {
     let zkp_component = client.take_zkp_component().unwrap();
     tokio::spawn(zkp_component); //ITODO get metrics on this? ask JD
 
+    // Start the fork proof watchtower
+    if let Some(watchtower) = client.take_watchtower() {
+        info!("Starting fork proof watchtower");
+        tokio::spawn(watchtower);
+    }
+
     // Start validator
     let val_metric_monitor = tokio_metrics::TaskMonitor::new();
+    let mut handel_metrics = None;
     if let Some(validator) = client.take_validator() {
         info!("Initializing validator {}", validator.validator_address());
+        handel_metrics = validator.get_handel_metrics();
 
         if metrics_enabled {
             let mp_metrics_monitor = validator.get_mempool_monitor();
@@ -132,6 +152,7 @@ async fn main_inner() -> Result<(), Error> {
             metrics_config.addr,
             client.blockchain(),
             mempool,
+            handel_metrics,
             client.consensus_proxy(),
             client.network(),
             &nimiq_task_metric,
@@ -40,6 +40,18 @@ use crate::{
     ConsensusEvent,
 };
 
+/// The block against whose state root an accounts trie proof was verified.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TrieProofBlock {
+    /// The hash of the block the proof was created for.
+    pub block_hash: Blake2bHash,
+    pub block_number: u32,
+    /// The state root the proven values were verified against.
+    pub state_root: Blake2bHash,
+    /// Whether the block was our head block at the time of the verification.
+    pub is_head: bool,
+}
+
 pub struct ConsensusProxy<N: Network> {
     pub blockchain: BlockchainProxy,
     pub network: Arc<N>,
@@ -454,18 +466,32 @@ impl<N: Network> ConsensusProxy<N> {
         addresses: Vec<Address>,
         min_peers: usize,
     ) -> Result<BTreeMap<Address, Option<Account>>, RequestError> {
+        self.request_proven_accounts_by_addresses(addresses, min_peers)
+            .await
+            .map(|(accounts, _)| accounts)
+    }
+
+    /// Same as `request_accounts_by_addresses`, but additionally returns the block against
+    /// whose state root the accounts were verified. All accounts are proven by a single
+    /// multi-proof.
+    pub async fn request_proven_accounts_by_addresses(
+        &self,
+        addresses: Vec<Address>,
+        min_peers: usize,
+    ) -> Result<(BTreeMap<Address, Option<Account>>, TrieProofBlock), RequestError> {
         let mut keys = HashMap::<KeyNibbles, Address>::from_iter(
             addresses
                 .iter()
                 .map(|address| (KeyNibbles::from(address), address.clone())),
         );
-        let accounts: BTreeMap<KeyNibbles, Option<Account>> = RemoteDataStore::get_trie(
-            Arc::clone(&self.network),
-            self.blockchain.clone(),
-            &keys.keys().cloned().collect::<Vec<KeyNibbles>>(),
-            min_peers,
-        )
-        .await?;
+        let (accounts, proof_block): (BTreeMap<KeyNibbles, Option<Account>>, _) =
+            RemoteDataStore::get_trie_with_block(
+                Arc::clone(&self.network),
+                self.blockchain.clone(),
+                &keys.keys().cloned().collect::<Vec<KeyNibbles>>(),
+                min_peers,
+            )
+            .await?;
 
         let accounts = accounts
             .iter()
@@ -477,7 +503,7 @@ impl<N: Network> ConsensusProxy<N> {
                 )
             })
             .collect();
-        Ok(accounts)
+        Ok((accounts, proof_block))
     }
 
     /// Gets a set of validators given their addresses. The returned type is a
@@ -512,6 +538,22 @@ impl<N: Network> ConsensusProxy<N> {
         remote_ds.get_stakers(addresses).await
     }
 
+    /// Same as `request_stakers_by_addresses`, but additionally returns the block against
+    /// whose state root the stakers were verified. All stakers are proven by a single
+    /// multi-proof.
+    pub async fn request_proven_stakers_by_addresses(
+        &self,
+        addresses: Vec<Address>,
+        min_peers: usize,
+    ) -> Result<(BTreeMap<Address, Option<Staker>>, TrieProofBlock), RequestError> {
+        let remote_ds = RemoteDataStore {
+            network: Arc::clone(&self.network),
+            blockchain: self.blockchain.clone(),
+            min_peers,
+        };
+        remote_ds.get_proven_stakers(addresses).await
+    }
+
     pub async fn subscribe_to_addresses(
         &self,
         addresses: Vec<Address>,
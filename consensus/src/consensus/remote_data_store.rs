@@ -17,7 +17,7 @@ use nimiq_network_interface::{
 use nimiq_primitives::{key_nibbles::KeyNibbles, policy::Policy};
 use nimiq_serde::Deserialize;
 
-use crate::messages::RequestTrieProof;
+use crate::{consensus::consensus_proxy::TrieProofBlock, messages::RequestTrieProof};
 
 /// The Remote Data Store is a component to remotely request data from the staking
 /// contract such as:
@@ -35,8 +35,8 @@ pub(crate) struct RemoteDataStore<N: Network> {
     pub(crate) min_peers: usize,
 }
 
-/// Internal Remote operations the Remote Data Store can perform over addresses on
-/// wasm
+/// Internal Remote operations the Remote Data Store can perform over addresses by
+/// proving their trie keys directly
 enum RemoteDataStoreOps {
     /// Gets a set of validators by their addresses
     Validator(Vec<Address>),
@@ -55,6 +55,19 @@ impl<N: Network> RemoteDataStore<N> {
         keys: &[KeyNibbles],
         min_peers: usize,
     ) -> Result<BTreeMap<KeyNibbles, Option<T>>, RequestError> {
+        Self::get_trie_with_block(network, blockchain, keys, min_peers)
+            .await
+            .map(|(items, _)| items)
+    }
+
+    /// Same as `get_trie`, but additionally returns the block against whose state root the
+    /// proof was verified. All items are proven by a single multi-proof.
+    pub(crate) async fn get_trie_with_block<T: Deserialize>(
+        network: Arc<N>,
+        blockchain: BlockchainProxy,
+        keys: &[KeyNibbles],
+        min_peers: usize,
+    ) -> Result<(BTreeMap<KeyNibbles, Option<T>>, TrieProofBlock), RequestError> {
         // First we tell the network to provide us with a vector that contains all the connected peers that support such services
         // Note: If the network could not provide enough peers that satisfies our requirement, then an error would be returned
         let peers = network
@@ -95,12 +108,19 @@ impl<N: Network> RemoteDataStore<N> {
                             .proof
                             .verify_values(block.state_root(), &keys.iter().collect::<Vec<_>>())
                         {
-                            return Ok(values
+                            let proof_block = TrieProofBlock {
+                                block_hash: response.block_hash.clone(),
+                                block_number: block.block_number(),
+                                state_root: block.state_root().clone(),
+                                is_head: response.block_hash == blockchain.head_hash(),
+                            };
+                            let values = values
                                 .into_iter()
                                 .map(|(key, value)| {
                                     (key, value.map(|v| T::deserialize_from_vec(&v).unwrap()))
                                 })
-                                .collect());
+                                .collect();
+                            return Ok((values, proof_block));
                         } else {
                             // If the proof does not verify, we disconnect from the peer
                             log::debug!(peer = %peer_id, "Disconnecting from peer because the accounts proof didn't verify");
@@ -244,10 +264,29 @@ impl<N: Network> RemoteDataStore<N> {
         }
     }
 
+    /// Gets a set of stakers given their addresses, proven by a single multi-proof of their
+    /// trie keys. Also returns the block against whose state root the proof was verified.
+    pub(crate) async fn get_proven_stakers(
+        &self,
+        addresses: Vec<Address>,
+    ) -> Result<(BTreeMap<Address, Option<Staker>>, TrieProofBlock), RequestError> {
+        self.get_by_keys_with_block(RemoteDataStoreOps::Staker(addresses))
+            .await
+    }
+
     async fn wasm_exec<T: Deserialize + Clone>(
         &self,
         op: RemoteDataStoreOps,
     ) -> Result<BTreeMap<Address, Option<T>>, RequestError> {
+        self.get_by_keys_with_block(op)
+            .await
+            .map(|(items, _)| items)
+    }
+
+    async fn get_by_keys_with_block<T: Deserialize + Clone>(
+        &self,
+        op: RemoteDataStoreOps,
+    ) -> Result<(BTreeMap<Address, Option<T>>, TrieProofBlock), RequestError> {
         let staking_contract_key = KeyNibbles::from(&Policy::STAKING_CONTRACT_ADDRESS);
         let mut keys_to_address: HashMap<KeyNibbles, Address> = match op {
             RemoteDataStoreOps::Validator(addresses) => {
@@ -278,7 +317,7 @@ impl<N: Network> RemoteDataStore<N> {
 
         let keys: Vec<KeyNibbles> = keys_to_address.keys().cloned().collect();
 
-        let items = Self::get_trie::<T>(
+        let (items, proof_block) = Self::get_trie_with_block::<T>(
             Arc::clone(&self.network),
             self.blockchain.clone(),
             &keys,
@@ -286,7 +325,7 @@ impl<N: Network> RemoteDataStore<N> {
         )
         .await?;
 
-        Ok(items
+        let items = items
             .iter()
             .map(|(key, item)| {
                 (
@@ -296,7 +335,8 @@ impl<N: Network> RemoteDataStore<N> {
                     item.clone(),
                 )
             })
-            .collect())
+            .collect();
+        Ok((items, proof_block))
     }
 }
 
@@ -8,3 +8,5 @@ pub mod consensus;
 pub mod error;
 pub mod messages;
 pub mod sync;
+#[cfg(feature = "full")]
+pub mod watchtower;
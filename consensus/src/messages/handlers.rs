@@ -668,11 +668,10 @@ impl<N: Network> Handle<N, Arc<RwLock<Blockchain>>> for RequestTransactionReceip
         let blockchain = blockchain.read();
 
         // Get the transaction hashes for this address.
-        let raw_tx_hashes = blockchain.history_store.get_tx_hashes_by_address(
-            &self.address,
-            self.max.unwrap_or(500).min(500),
-            None,
-        );
+        let raw_tx_hashes = blockchain
+            .history_store
+            .get_tx_hashes_by_address(&self.address, self.max.unwrap_or(500).min(500), None, None)
+            .unwrap_or_default();
 
         let mut receipts = vec![];
 
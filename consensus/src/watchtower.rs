@@ -0,0 +1,425 @@
+use std::{
+    collections::{HashMap, HashSet},
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use futures::{stream::BoxStream, Future, StreamExt};
+use nimiq_block::{DoubleVoteProof, EquivocationProof, EquivocationProofTopic, MultiSignature};
+use nimiq_blockchain::Blockchain;
+use nimiq_blockchain_interface::{AbstractBlockchain, BlockchainEvent, ForkEvent};
+use nimiq_hash::Blake2sHash;
+use nimiq_keys::Address;
+use nimiq_network_interface::network::{MsgAcceptance, Network};
+use nimiq_primitives::{policy::Policy, slots_allocation::Validators, TendermintIdentifier};
+use nimiq_transaction::EquivocationLocator;
+use parking_lot::RwLock;
+use tokio::sync::{
+    broadcast::{channel as broadcast, Sender as BroadcastSender},
+    mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
+};
+use tokio_stream::wrappers::BroadcastStream;
+
+const BROADCAST_MAX_CAPACITY: usize = 64;
+
+/// Describes how the watchtower learned about an equivocation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EquivocationSource {
+    /// The equivocation was detected by our own node, i.e. a fork seen by our blockchain or a
+    /// double vote observed by our validator.
+    Local,
+    /// The equivocation proof was gossiped to us by a peer.
+    Network,
+}
+
+/// An equivocation the watchtower has seen and which is still punishable.
+#[derive(Clone, Debug)]
+pub struct SeenEquivocation {
+    pub proof: EquivocationProof,
+    pub source: EquivocationSource,
+    /// Block number of our head when we first saw the equivocation.
+    pub seen_at: u32,
+}
+
+/// The set of equivocations seen by the watchtower, indexed by their locator. Only the first
+/// proof per locator is kept, since only one of them can be included in the chain.
+#[derive(Default)]
+pub struct SeenEquivocations {
+    equivocations: HashMap<EquivocationLocator, SeenEquivocation>,
+}
+
+impl SeenEquivocations {
+    /// Adds an equivocation if no proof with the same locator was seen yet.
+    /// Returns whether it has been added.
+    fn insert(&mut self, equivocation: SeenEquivocation) -> bool {
+        let locator = equivocation.proof.locator();
+        if self.equivocations.contains_key(&locator) {
+            return false;
+        }
+        self.equivocations.insert(locator, equivocation);
+        true
+    }
+
+    /// Returns whether an equivocation with the given locator was seen.
+    pub fn contains(&self, locator: &EquivocationLocator) -> bool {
+        self.equivocations.contains_key(locator)
+    }
+
+    /// Removes all equivocations that cannot be reported anymore at the given block number.
+    fn prune(&mut self, block_number: u32) {
+        self.equivocations
+            .retain(|_, equivocation| equivocation.proof.is_valid_at(block_number));
+    }
+
+    /// Returns all seen equivocations, ordered by the block number of the offense.
+    pub fn all(&self) -> Vec<SeenEquivocation> {
+        let mut equivocations: Vec<_> = self.equivocations.values().cloned().collect();
+        equivocations.sort_by_key(|equivocation| equivocation.proof.block_number());
+        equivocations
+    }
+
+    /// Returns the seen equivocations of the given validator.
+    pub fn by_validator(&self, validator_address: &Address) -> Vec<SeenEquivocation> {
+        let mut equivocations: Vec<_> = self
+            .equivocations
+            .values()
+            .filter(|equivocation| equivocation.proof.validator_address() == validator_address)
+            .cloned()
+            .collect();
+        equivocations.sort_by_key(|equivocation| equivocation.proof.block_number());
+        equivocations
+    }
+
+    pub fn len(&self) -> usize {
+        self.equivocations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.equivocations.is_empty()
+    }
+}
+
+/// Detects validators voting for different proposals in the same Tendermint round and step.
+///
+/// Votes for the same proposal are aggregated, such that a double vote shows up as a slot that is
+/// contained in the signers of two different proposals. The votes are expected to be verified
+/// already, e.g. by the Handel aggregation of a validator.
+#[derive(Debug)]
+pub struct DoubleVoteDetector {
+    id: TendermintIdentifier,
+    validators: Validators,
+    votes: Vec<(Option<Blake2sHash>, MultiSignature)>,
+    reported: HashSet<Address>,
+}
+
+impl DoubleVoteDetector {
+    /// The maximum number of votes that are kept to be compared with later votes.
+    const MAX_VOTES: usize = 256;
+
+    pub fn new(id: TendermintIdentifier, validators: Validators) -> Self {
+        Self {
+            id,
+            validators,
+            votes: vec![],
+            reported: HashSet::new(),
+        }
+    }
+
+    /// Adds a verified vote for the given proposal and returns the proofs of all validators that
+    /// also voted for another proposal. Every validator is reported at most once.
+    pub fn observe(
+        &mut self,
+        proposal_hash: &Option<Blake2sHash>,
+        vote: &MultiSignature,
+    ) -> Vec<EquivocationProof> {
+        let mut proofs = vec![];
+
+        for (other_proposal_hash, other_vote) in &self.votes {
+            if other_proposal_hash == proposal_hash {
+                continue;
+            }
+
+            let overlap = &vote.signers & &other_vote.signers;
+            for slot in overlap.iter() {
+                let validator = self.validators.get_validator_by_slot_number(slot as u16);
+                if !self.reported.insert(validator.address.clone()) {
+                    continue;
+                }
+
+                proofs.push(
+                    DoubleVoteProof::new(
+                        self.id.clone(),
+                        validator.address.clone(),
+                        proposal_hash.clone(),
+                        vote.signature.clone(),
+                        vote.signers.clone(),
+                        other_proposal_hash.clone(),
+                        other_vote.signature.clone(),
+                        other_vote.signers.clone(),
+                    )
+                    .into(),
+                );
+            }
+        }
+
+        let known = self.votes.iter().any(|(other_proposal_hash, other_vote)| {
+            other_proposal_hash == proposal_hash && other_vote.signers == vote.signers
+        });
+        if !known && self.votes.len() < Self::MAX_VOTES {
+            self.votes.push((proposal_hash.clone(), vote.clone()));
+        }
+
+        proofs
+    }
+}
+
+pub struct ForkProofWatchtowerProxy {
+    seen: Arc<RwLock<SeenEquivocations>>,
+    events: BroadcastSender<SeenEquivocation>,
+    reports: UnboundedSender<EquivocationProof>,
+}
+
+impl Clone for ForkProofWatchtowerProxy {
+    fn clone(&self) -> Self {
+        Self {
+            seen: Arc::clone(&self.seen),
+            events: self.events.clone(),
+            reports: self.reports.clone(),
+        }
+    }
+}
+
+impl ForkProofWatchtowerProxy {
+    /// Returns all equivocations seen by the watchtower that can still be reported.
+    pub fn get_seen_equivocations(&self) -> Vec<SeenEquivocation> {
+        self.seen.read().all()
+    }
+
+    /// Returns the equivocations of the given validator seen by the watchtower that can still
+    /// be reported.
+    pub fn get_seen_equivocations_by_validator(
+        &self,
+        validator_address: &Address,
+    ) -> Vec<SeenEquivocation> {
+        self.seen.read().by_validator(validator_address)
+    }
+
+    /// Subscribes to newly seen equivocations.
+    pub fn subscribe(&self) -> BroadcastStream<SeenEquivocation> {
+        BroadcastStream::new(self.events.subscribe())
+    }
+
+    /// Reports an equivocation observed outside of the blockchain, e.g. a double vote seen by our
+    /// validator. The proof is verified and gossiped to the network if it is new.
+    pub fn report(&self, proof: EquivocationProof) {
+        // The watchtower only stops if the network does, so there is nothing to report to then.
+        _ = self.reports.send(proof);
+    }
+}
+
+/// The fork proof watchtower allows any full node to help punishing misbehaving validators.
+///
+/// The only equivocations it detects by itself are forks of micro blocks, which our blockchain
+/// reports when it sees two micro blocks produced by the same validator at the same height. The
+/// resulting fork proofs are gossiped to the network, where they are picked up by the validators
+/// which include them in their next micro block.
+///
+/// Other equivocations are only relayed:
+/// - Double votes are only observable in the Tendermint messages exchanged between validators, so
+///   they are detected by validators while aggregating votes (see [`DoubleVoteDetector`]). If this
+///   node runs a validator, the proofs it detects are reported to the watchtower.
+/// - Double proposals are never detected, since the gossiped proposals are signed over the
+///   proposal message rather than the header hash a double proposal proof is justified by.
+///
+/// Proofs of either kind that are reported by our validator or received from peers are verified
+/// against our blockchain before they are accepted and relayed.
+///
+/// Awaiting this future ensures that the watchtower works, this component should run forever.
+pub struct ForkProofWatchtower<N: Network> {
+    blockchain: Arc<RwLock<Blockchain>>,
+    network: Arc<N>,
+    blockchain_event_rx: BoxStream<'static, BlockchainEvent>,
+    fork_event_rx: BoxStream<'static, ForkEvent>,
+    proof_rx: BoxStream<'static, (EquivocationProof, N::PubsubId)>,
+    report_rx: UnboundedReceiver<EquivocationProof>,
+    reports: UnboundedSender<EquivocationProof>,
+    seen: Arc<RwLock<SeenEquivocations>>,
+    events: BroadcastSender<SeenEquivocation>,
+}
+
+impl<N: Network> ForkProofWatchtower<N> {
+    pub async fn new(blockchain: Arc<RwLock<Blockchain>>, network: Arc<N>) -> Self {
+        let blockchain_rg = blockchain.read();
+        let blockchain_event_rx = blockchain_rg.notifier_as_stream();
+        let fork_event_rx = blockchain_rg.fork_notifier_as_stream();
+        drop(blockchain_rg);
+
+        let proof_rx = network
+            .subscribe::<EquivocationProofTopic>()
+            .await
+            .expect("Failed to subscribe to equivocation proof topic")
+            .boxed();
+
+        let (events, _rx) = broadcast(BROADCAST_MAX_CAPACITY);
+        let (reports, report_rx) = unbounded_channel();
+
+        Self {
+            blockchain,
+            network,
+            blockchain_event_rx,
+            fork_event_rx,
+            proof_rx,
+            report_rx,
+            reports,
+            seen: Arc::new(RwLock::new(SeenEquivocations::default())),
+            events,
+        }
+    }
+
+    pub fn proxy(&self) -> ForkProofWatchtowerProxy {
+        ForkProofWatchtowerProxy {
+            seen: Arc::clone(&self.seen),
+            events: self.events.clone(),
+            reports: self.reports.clone(),
+        }
+    }
+
+    /// Checks that the proof is a valid, punishable offense that has not been included in the
+    /// chain yet.
+    fn verify(&self, proof: &EquivocationProof) -> bool {
+        let blockchain = self.blockchain.read();
+
+        if !proof.is_valid_at(blockchain.block_number() + 1) {
+            return false;
+        }
+
+        if blockchain
+            .history_store
+            .has_equivocation_proof(proof.locator(), None)
+        {
+            return false;
+        }
+
+        let validators = match blockchain
+            .get_validators_for_epoch(Policy::epoch_at(proof.block_number()), None)
+        {
+            Ok(validators) => validators,
+            Err(_) => return false,
+        };
+
+        match proof.verify(blockchain.network_id(), &validators) {
+            Ok(()) => true,
+            Err(error) => {
+                debug!(?error, ?proof, "Received invalid equivocation proof");
+                false
+            }
+        }
+    }
+
+    fn add_equivocation(&self, proof: EquivocationProof, source: EquivocationSource) -> bool {
+        let equivocation = SeenEquivocation {
+            proof,
+            source,
+            seen_at: self.blockchain.read().block_number(),
+        };
+
+        if !self.seen.write().insert(equivocation.clone()) {
+            return false;
+        }
+
+        info!(
+            validator_address = %equivocation.proof.validator_address(),
+            block_number = equivocation.proof.block_number(),
+            source = ?equivocation.source,
+            "Observed equivocation"
+        );
+
+        // We shouldn't log errors if there are no listeners.
+        _ = self.events.send(equivocation);
+        true
+    }
+
+    fn on_fork_event(&self, event: ForkEvent) {
+        match event {
+            ForkEvent::Detected(fork_proof) => {
+                let proof: EquivocationProof = fork_proof.into();
+                if self.add_equivocation(proof.clone(), EquivocationSource::Local) {
+                    self.publish(proof);
+                }
+            }
+        }
+    }
+
+    fn on_reported_proof(&self, proof: EquivocationProof) {
+        if self.seen.read().contains(&proof.locator()) || !self.verify(&proof) {
+            return;
+        }
+
+        if self.add_equivocation(proof.clone(), EquivocationSource::Local) {
+            self.publish(proof);
+        }
+    }
+
+    fn on_gossiped_proof(&self, proof: EquivocationProof, pubsub_id: N::PubsubId) {
+        if self.seen.read().contains(&proof.locator()) {
+            self.network
+                .validate_message::<EquivocationProofTopic>(pubsub_id, MsgAcceptance::Ignore);
+            return;
+        }
+
+        let acceptance = if self.verify(&proof) {
+            self.add_equivocation(proof, EquivocationSource::Network);
+            MsgAcceptance::Accept
+        } else {
+            MsgAcceptance::Reject
+        };
+        self.network
+            .validate_message::<EquivocationProofTopic>(pubsub_id, acceptance);
+    }
+
+    fn publish(&self, proof: EquivocationProof) {
+        let network = Arc::clone(&self.network);
+        tokio::spawn(async move {
+            if let Err(error) = network.publish::<EquivocationProofTopic>(proof).await {
+                debug!(?error, "Failed to publish equivocation proof");
+            }
+        });
+    }
+}
+
+impl<N: Network> Future for ForkProofWatchtower<N> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // Prune equivocations that can't be reported anymore.
+        while let Poll::Ready(Some(event)) = self.blockchain_event_rx.poll_next_unpin(cx) {
+            if let BlockchainEvent::Finalized(_) | BlockchainEvent::EpochFinalized(_) = event {
+                let block_number = self.blockchain.read().block_number();
+                self.seen.write().prune(block_number + 1);
+            }
+        }
+
+        // Process equivocations detected by our own blockchain.
+        while let Poll::Ready(Some(event)) = self.fork_event_rx.poll_next_unpin(cx) {
+            self.on_fork_event(event);
+        }
+
+        // Process equivocations reported by our validator.
+        while let Poll::Ready(Some(proof)) = self.report_rx.poll_recv(cx) {
+            self.on_reported_proof(proof);
+        }
+
+        // Process equivocation proofs gossiped by our peers.
+        loop {
+            match self.proof_rx.poll_next_unpin(cx) {
+                Poll::Ready(Some((proof, pubsub_id))) => self.on_gossiped_proof(proof, pubsub_id),
+                // The stream was closed so we quit as well.
+                Poll::Ready(None) => return Poll::Ready(()),
+                Poll::Pending => break,
+            }
+        }
+
+        Poll::Pending
+    }
+}
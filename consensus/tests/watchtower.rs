@@ -0,0 +1,261 @@
+use std::{ops::Range, sync::Arc, time::Duration};
+
+use futures::StreamExt;
+use nimiq_block::{DoubleVoteProof, EquivocationProof, EquivocationProofTopic, MultiSignature};
+use nimiq_blockchain::{BlockProducer, Blockchain, BlockchainConfig};
+use nimiq_blockchain_interface::AbstractBlockchain;
+use nimiq_bls::{AggregateSignature, KeyPair as BlsKeyPair};
+use nimiq_collections::BitSet;
+use nimiq_consensus::watchtower::{DoubleVoteDetector, EquivocationSource, ForkProofWatchtower};
+use nimiq_database::volatile::VolatileDatabase;
+use nimiq_hash::{Blake2sHash, Hash};
+use nimiq_keys::{Address, KeyPair, SecureGenerate};
+use nimiq_network_interface::network::Network;
+use nimiq_network_mock::MockHub;
+use nimiq_primitives::{
+    networks::NetworkId,
+    policy::Policy,
+    slots_allocation::{Validator, Validators},
+    TendermintIdentifier, TendermintStep, TendermintVote,
+};
+use nimiq_test_log::test;
+use nimiq_test_utils::{
+    blockchain::{produce_macro_blocks, push_micro_block, signing_key, voting_key},
+    validator::seeded_rng,
+};
+use nimiq_utils::time::OffsetTime;
+use parking_lot::RwLock;
+use tokio::time::{sleep, timeout};
+
+/// Signs a vote for the given proposal with all slots of the given voters.
+fn vote(
+    id: &TendermintIdentifier,
+    proposal_hash: &Option<Blake2sHash>,
+    voters: &[(&BlsKeyPair, Range<u16>)],
+) -> MultiSignature {
+    let message = TendermintVote {
+        proposal_hash: proposal_hash.clone(),
+        id: id.clone(),
+    };
+
+    let mut signatures = vec![];
+    let mut signers = BitSet::new();
+    for (key_pair, slots) in voters {
+        signatures.push(key_pair.sign(&message).multiply(slots.len() as u16));
+        for slot in slots.clone() {
+            signers.insert(slot as usize);
+        }
+    }
+
+    MultiSignature::new(AggregateSignature::from_signatures(&signatures), signers)
+}
+
+fn double_vote(
+    id: &TendermintIdentifier,
+    validator: &Validator,
+    key_pair: &BlsKeyPair,
+) -> EquivocationProof {
+    let proposal_hash1 = None;
+    let proposal_hash2 = Some("proposal".hash());
+    let vote1 = vote(id, &proposal_hash1, &[(key_pair, validator.slots.clone())]);
+    let vote2 = vote(id, &proposal_hash2, &[(key_pair, validator.slots.clone())]);
+
+    DoubleVoteProof::new(
+        id.clone(),
+        validator.address.clone(),
+        proposal_hash1,
+        vote1.signature,
+        vote1.signers,
+        proposal_hash2,
+        vote2.signature,
+        vote2.signers,
+    )
+    .into()
+}
+
+fn blockchain() -> Arc<RwLock<Blockchain>> {
+    Arc::new(RwLock::new(
+        Blockchain::new(
+            VolatileDatabase::new(20).unwrap(),
+            BlockchainConfig::default(),
+            NetworkId::UnitAlbatross,
+            Arc::new(OffsetTime::new()),
+        )
+        .unwrap(),
+    ))
+}
+
+#[test]
+fn it_detects_double_votes() {
+    let key_pair1 = BlsKeyPair::generate(&mut seeded_rng(0));
+    let key_pair2 = BlsKeyPair::generate(&mut seeded_rng(1));
+    let address1 = Address::from(&KeyPair::generate(&mut seeded_rng(2)));
+    let address2 = Address::from(&KeyPair::generate(&mut seeded_rng(3)));
+    let validators = Validators::new(vec![
+        Validator::new(
+            address1.clone(),
+            key_pair1.public_key,
+            signing_key().public,
+            0..Policy::SLOTS / 2,
+        ),
+        Validator::new(
+            address2.clone(),
+            key_pair2.public_key,
+            signing_key().public,
+            Policy::SLOTS / 2..Policy::SLOTS,
+        ),
+    ]);
+    let slots1 = validators.validators[0].slots.clone();
+    let slots2 = validators.validators[1].slots.clone();
+
+    let id = TendermintIdentifier {
+        network: NetworkId::UnitAlbatross,
+        block_number: Policy::blocks_per_batch(),
+        round_number: 0,
+        step: TendermintStep::PreCommit,
+    };
+    let proposal_hash1 = Some("proposal1".hash());
+    let proposal_hash2 = Some("proposal2".hash());
+
+    let mut detector = DoubleVoteDetector::new(id.clone(), validators.clone());
+
+    // Votes of different validators for different proposals are no equivocation.
+    let vote1 = vote(&id, &proposal_hash1, &[(&key_pair1, slots1.clone())]);
+    let vote2 = vote(&id, &proposal_hash2, &[(&key_pair2, slots2.clone())]);
+    assert!(detector.observe(&proposal_hash1, &vote1).is_empty());
+    assert!(detector.observe(&proposal_hash2, &vote2).is_empty());
+
+    // Neither is the same vote received again.
+    assert!(detector.observe(&proposal_hash1, &vote1).is_empty());
+
+    // The second validator also voting for the first proposal is a double vote.
+    let vote3 = vote(
+        &id,
+        &proposal_hash1,
+        &[(&key_pair1, slots1.clone()), (&key_pair2, slots2.clone())],
+    );
+    let proofs = detector.observe(&proposal_hash1, &vote3);
+    assert_eq!(proofs.len(), 1);
+    assert_eq!(proofs[0].validator_address(), &address2);
+    assert_eq!(proofs[0].block_number(), id.block_number);
+    assert_eq!(
+        proofs[0].verify(NetworkId::UnitAlbatross, &validators),
+        Ok(())
+    );
+
+    // Every validator is reported only once.
+    assert!(detector.observe(&proposal_hash1, &vote3).is_empty());
+
+    // A validator voting for nil and a proposal is a double vote as well.
+    let vote4 = vote(&id, &None, &[(&key_pair1, slots1)]);
+    let proofs = detector.observe(&None, &vote4);
+    assert_eq!(proofs.len(), 1);
+    assert_eq!(proofs[0].validator_address(), &address1);
+    assert_eq!(
+        proofs[0].verify(NetworkId::UnitAlbatross, &validators),
+        Ok(())
+    );
+}
+
+#[test(tokio::test)]
+async fn it_verifies_relays_and_prunes_equivocations() {
+    let blockchain = blockchain();
+    let producer = BlockProducer::new(signing_key(), voting_key());
+    push_micro_block(&producer, &blockchain);
+
+    let validators = blockchain.read().current_validators().unwrap();
+    let validator = validators.validators[0].clone();
+    let block_number = Policy::macro_block_after(blockchain.read().block_number());
+    let id = |round_number| TendermintIdentifier {
+        network: NetworkId::UnitAlbatross,
+        block_number,
+        round_number,
+        step: TendermintStep::PreVote,
+    };
+
+    let mut hub = MockHub::default();
+    let net1 = Arc::new(hub.new_network());
+    let net2 = Arc::new(hub.new_network());
+    net1.dial_mock(&net2);
+    let mut gossiped = net2.subscribe::<EquivocationProofTopic>().await.unwrap();
+
+    let watchtower = ForkProofWatchtower::new(Arc::clone(&blockchain), Arc::clone(&net1)).await;
+    let proxy = watchtower.proxy();
+    let mut events = proxy.subscribe();
+    tokio::spawn(watchtower);
+
+    // A proof signed by someone else than the validator is rejected.
+    let forged = double_vote(
+        &id(0),
+        &validator,
+        &BlsKeyPair::generate(&mut seeded_rng(0)),
+    );
+    net2.publish::<EquivocationProofTopic>(forged)
+        .await
+        .unwrap();
+
+    // A valid proof gossiped by a peer is accepted.
+    let proof1 = double_vote(&id(0), &validator, &voting_key());
+    net2.publish::<EquivocationProofTopic>(proof1.clone())
+        .await
+        .unwrap();
+    let seen = timeout(Duration::from_secs(5), events.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    assert_eq!(seen.proof, proof1);
+    assert_eq!(seen.source, EquivocationSource::Network);
+
+    // Proofs for the same offense are only kept once.
+    proxy.report(proof1.clone());
+    net2.publish::<EquivocationProofTopic>(proof1.clone())
+        .await
+        .unwrap();
+
+    // A reported proof is verified and gossiped to the network.
+    let proof2 = double_vote(&id(1), &validator, &voting_key());
+    proxy.report(proof2.clone());
+    let seen = timeout(Duration::from_secs(5), events.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    assert_eq!(seen.proof, proof2);
+    assert_eq!(seen.source, EquivocationSource::Local);
+    loop {
+        let (proof, _) = timeout(Duration::from_secs(5), gossiped.next())
+            .await
+            .unwrap()
+            .unwrap();
+        if proof == proof2 {
+            break;
+        }
+    }
+
+    let seen = proxy.get_seen_equivocations();
+    assert_eq!(seen.len(), 2);
+    assert!(seen.iter().any(|equivocation| equivocation.proof == proof1));
+    assert!(seen.iter().any(|equivocation| equivocation.proof == proof2));
+    assert_eq!(
+        proxy
+            .get_seen_equivocations_by_validator(&validator.address)
+            .len(),
+        2
+    );
+
+    // Once the reporting window has passed, the equivocations are pruned.
+    produce_macro_blocks(
+        &producer,
+        &blockchain,
+        Policy::batches_per_epoch() as usize + 2,
+    );
+    assert!(!proof1.is_valid_at(blockchain.read().block_number() + 1));
+    timeout(Duration::from_secs(5), async {
+        while !proxy.get_seen_equivocations().is_empty() {
+            sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap();
+}
@@ -220,6 +220,10 @@ mod tests {
             );
             assert!(cursor.seek_key::<str, u32>("test").is_none());
             assert_eq!(cursor.seek_key::<str, u32>("test1"), Some(12));
+            assert_eq!(
+                cursor.seek_key_value_range::<str, u32>("test1", &13),
+                Some(125)
+            );
             assert_eq!(cursor.count_duplicates(), 3);
             assert_eq!(cursor.last_duplicate::<u32>(), Some(5783));
 
@@ -231,6 +235,9 @@ mod tests {
             assert_eq!(cursor.get_current::<String, u32>(), Some((test1, 5783)));
             assert!(cursor.prev_no_duplicate::<String, u32>().is_none());
             assert_eq!(cursor.next::<String, u32>(), Some((test2, 5783)));
+            assert!(cursor
+                .seek_key_value_range::<str, u32>("test2", &5784)
+                .is_none());
         }
         tempdir.close().unwrap();
     }
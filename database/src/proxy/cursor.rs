@@ -160,6 +160,17 @@ impl<'txn> ReadCursor<'txn> for CursorProxy<'txn> {
         }
     }
 
+    fn seek_key_value_range<K, V>(&mut self, key: &K, value: &V) -> Option<V>
+    where
+        K: AsDatabaseBytes + ?Sized,
+        V: AsDatabaseBytes + FromDatabaseValue,
+    {
+        match self {
+            CursorProxy::ReadCursor(cursor) => cursor.seek_key_value_range(key, value),
+            CursorProxy::WriteCursor(cursor) => cursor.seek_key_value_range(key, value),
+        }
+    }
+
     fn count_duplicates(&mut self) -> usize {
         match self {
             CursorProxy::ReadCursor(cursor) => cursor.count_duplicates(),
@@ -205,12 +205,21 @@ impl<
             );
             {
                 // Acquire write lock and set the level state for this level to completed.
-                self.levels
+                let mut state = self
+                    .levels
                     .get(level_id)
                     .unwrap() // would have panicked earlier.
                     .state
-                    .write()
-                    .receive_completed = true;
+                    .write();
+                #[cfg(feature = "metrics")]
+                if !state.receive_completed {
+                    if let (Some(metrics), Some(started_at)) =
+                        (&self.config.metrics, state.started_at)
+                    {
+                        metrics.note_level_completed(level_id, started_at.elapsed());
+                    }
+                }
+                state.receive_completed = true;
             }
             // if there is a level with a higher id than the completed one it needs to be activated.
             if level_id + 1 < self.levels.len() {
@@ -326,6 +335,14 @@ impl<
             // next time the timeout triggers the next level needs activating
             self.next_level_timeout += 1;
 
+            #[cfg(feature = "metrics")]
+            if let Some(metrics) = &self.config.metrics {
+                // Only levels which have not been started by completion of the previous one count as timed out.
+                if !self.levels[level].state.read().send_started {
+                    metrics.note_level_timeout(level);
+                }
+            }
+
             // finally start the level.
             self.start_level(level);
         }
@@ -348,6 +365,11 @@ impl<
                             let result = self.protocol.verify(&todo.contribution).await;
 
                             if result.is_ok() {
+                                #[cfg(feature = "metrics")]
+                                if let Some(metrics) = &self.config.metrics {
+                                    metrics.note_contribution(todo.level);
+                                }
+
                                 // special case of full contributions
                                 if todo.level == self.protocol.partitioner().levels() {
                                     return (todo.contribution, Some(self));
@@ -382,6 +404,11 @@ impl<
                                     return (best, Some(self));
                                 }
                             } else {
+                                #[cfg(feature = "metrics")]
+                                if let Some(metrics) = &self.config.metrics {
+                                    metrics.note_verification_failure(todo.level);
+                                }
+
                                 // Invalid contributions create a warning, but do not terminate. -> Continue with the next best todo item.
                                 warn!(
                                     id = ?self.protocol.identify(),
@@ -1,5 +1,10 @@
+#[cfg(feature = "metrics")]
+use std::sync::Arc;
 use std::time::Duration;
 
+#[cfg(feature = "metrics")]
+use crate::metrics::HandelMetrics;
+
 /// Handel configuration settings
 #[derive(Clone, Debug)]
 pub struct Config {
@@ -14,6 +19,10 @@ pub struct Config {
 
     /// How many peers are contacted at each level
     pub peer_count: usize,
+
+    /// Metrics the aggregations report their per level progress to, if any.
+    #[cfg(feature = "metrics")]
+    pub metrics: Option<Arc<HandelMetrics>>,
 }
 
 impl Default for Config {
@@ -23,6 +32,8 @@ impl Default for Config {
             update_interval: Duration::from_millis(500),
             timeout: Duration::from_millis(400),
             peer_count: 2,
+            #[cfg(feature = "metrics")]
+            metrics: None,
         }
     }
 }
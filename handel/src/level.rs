@@ -2,6 +2,7 @@ use std::{cmp::min, sync::Arc};
 
 use parking_lot::RwLock;
 use rand::{seq::SliceRandom, thread_rng};
+use tokio::time::Instant;
 
 use crate::{
     contribution::AggregatableContribution,
@@ -13,6 +14,8 @@ use crate::{
 pub struct LevelState {
     /// Send is already started
     pub send_started: bool,
+    /// Point in time the level was started at
+    pub started_at: Option<Instant>,
     /// Receive is already completed
     pub receive_completed: bool,
     /// The position of the peer where the next send must go to
@@ -47,6 +50,7 @@ impl Level {
             send_expected_full_size,
             state: RwLock::new(LevelState {
                 send_started: false,
+                started_at: None,
                 receive_completed: false,
                 send_peers_pos: 0,
                 send_signature_size: 0,
@@ -92,7 +96,7 @@ impl Level {
 
                     if !first_active {
                         first_active = true;
-                        level.state.write().send_started = true;
+                        level.state.write().start();
                     }
 
                     levels.push(level);
@@ -155,7 +159,7 @@ impl Level {
         state.send_peers_count = 0;
 
         if state.send_signature_size == self.send_expected_full_size {
-            state.send_started = true;
+            state.start();
             return true;
         }
 
@@ -166,11 +170,18 @@ impl Level {
     ///
     /// If the level was started before returns false, otherwise returns true.
     pub fn start(&self) -> bool {
-        let mut state = self.state.write();
-        if state.send_started {
+        self.state.write().start()
+    }
+}
+
+impl LevelState {
+    /// Marks the level as started if it wasn't started before and returns whether it was.
+    fn start(&mut self) -> bool {
+        if self.send_started {
             false
         } else {
-            state.send_started = true;
+            self.send_started = true;
+            self.started_at = Some(Instant::now());
             true
         }
     }
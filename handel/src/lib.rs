@@ -15,6 +15,8 @@ pub mod contribution;
 pub mod evaluator;
 pub mod identity;
 pub mod level;
+#[cfg(feature = "metrics")]
+pub mod metrics;
 pub mod network;
 pub mod partitioner;
 pub mod protocol;
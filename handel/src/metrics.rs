@@ -0,0 +1,135 @@
+use std::{fmt, time::Duration};
+
+use prometheus_client::{
+    encoding::EncodeLabelSet,
+    metrics::{counter::Counter, family::Family, histogram::Histogram},
+    registry::Registry,
+};
+
+/// Metrics of all Handel aggregations of a node, labeled by aggregation level.
+#[derive(Clone)]
+pub struct HandelMetrics {
+    level_completion_times: Family<LevelLabels, Histogram, fn() -> Histogram>,
+    level_timeouts: Family<LevelLabels, Counter>,
+    contributions: Family<LevelLabels, Counter>,
+    verification_failures: Family<LevelLabels, Counter>,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+struct LevelLabels {
+    level: String,
+}
+
+impl LevelLabels {
+    fn new(level: usize) -> Self {
+        LevelLabels {
+            level: level.to_string(),
+        }
+    }
+}
+
+fn level_completion_histogram() -> Histogram {
+    Histogram::new([0.01, 0.02, 0.05, 0.1, 0.2, 0.5, 1.0, 2.0, 5.0, 10.0].into_iter())
+}
+
+impl Default for HandelMetrics {
+    fn default() -> Self {
+        HandelMetrics {
+            level_completion_times: Family::new_with_constructor(level_completion_histogram),
+            level_timeouts: Default::default(),
+            contributions: Default::default(),
+            verification_failures: Default::default(),
+        }
+    }
+}
+
+impl fmt::Debug for HandelMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HandelMetrics").finish_non_exhaustive()
+    }
+}
+
+impl HandelMetrics {
+    pub fn register(&self, registry: &mut Registry) {
+        registry.register(
+            "level_completion_seconds",
+            "Time between the start and the completion of a level",
+            self.level_completion_times.clone(),
+        );
+
+        registry.register(
+            "level_timeouts",
+            "Number of levels activated by timeout instead of completion of the previous level",
+            self.level_timeouts.clone(),
+        );
+
+        registry.register(
+            "contributions",
+            "Number of verified contributions per level",
+            self.contributions.clone(),
+        );
+
+        registry.register(
+            "verification_failures",
+            "Number of contributions per level which failed to verify",
+            self.verification_failures.clone(),
+        );
+    }
+
+    pub(crate) fn note_level_completed(&self, level: usize, elapsed: Duration) {
+        self.level_completion_times
+            .get_or_create(&LevelLabels::new(level))
+            .observe(elapsed.as_secs_f64());
+    }
+
+    pub(crate) fn note_level_timeout(&self, level: usize) {
+        self.level_timeouts
+            .get_or_create(&LevelLabels::new(level))
+            .inc();
+    }
+
+    pub(crate) fn note_contribution(&self, level: usize) {
+        self.contributions
+            .get_or_create(&LevelLabels::new(level))
+            .inc();
+    }
+
+    pub(crate) fn note_verification_failure(&self, level: usize) {
+        self.verification_failures
+            .get_or_create(&LevelLabels::new(level))
+            .inc();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nimiq_test_log::test;
+    use prometheus_client::encoding::text::encode;
+
+    use super::*;
+
+    #[test]
+    fn it_reports_metrics_per_level() {
+        let metrics = HandelMetrics::default();
+        let mut registry = Registry::default();
+        metrics.register(&mut registry);
+
+        metrics.note_level_completed(1, Duration::from_millis(30));
+        metrics.note_level_completed(2, Duration::from_millis(300));
+        metrics.note_level_timeout(2);
+        metrics.note_contribution(1);
+        metrics.note_contribution(1);
+        metrics.note_verification_failure(2);
+
+        let mut encoded = String::new();
+        encode(&mut encoded, &registry).unwrap();
+
+        assert!(encoded.contains(r#"level_completion_seconds_count{level="1"} 1"#));
+        assert!(encoded.contains(r#"level_completion_seconds_bucket{le="0.05",level="1"} 1"#));
+        assert!(encoded.contains(r#"level_completion_seconds_bucket{le="0.2",level="2"} 0"#));
+        assert!(encoded.contains(r#"level_completion_seconds_bucket{le="0.5",level="2"} 1"#));
+        assert!(encoded.contains(r#"level_timeouts_total{level="2"} 1"#));
+        assert!(encoded.contains(r#"contributions_total{level="1"} 2"#));
+        assert!(encoded.contains(r#"verification_failures_total{level="2"} 1"#));
+    }
+}
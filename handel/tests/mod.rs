@@ -203,6 +203,7 @@ async fn it_can_aggregate() {
         update_interval: Duration::from_millis(500),
         timeout: Duration::from_millis(500),
         peer_count: 1,
+        ..Default::default()
     };
 
     let stopped = Arc::new(RwLock::new(false));
@@ -374,6 +375,68 @@ async fn it_can_aggregate() {
     *stopped.write() = true;
 }
 
+#[cfg(feature = "metrics")]
+#[test(tokio::test)]
+async fn it_reports_level_completions() {
+    use nimiq_handel::metrics::HandelMetrics;
+    use prometheus_client::{encoding::text::encode, registry::Registry};
+
+    let metrics = Arc::new(HandelMetrics::default());
+    let mut registry = Registry::default();
+    metrics.register(&mut registry);
+
+    let config = Config {
+        metrics: Some(Arc::clone(&metrics)),
+        ..Default::default()
+    };
+
+    let mut hub = MockHub::default();
+    let net0 = Arc::new(hub.new_network_with_address(0));
+    let net1 = Arc::new(hub.new_network_with_address(1));
+    net1.dial_mock(&net0);
+
+    let aggregation = |id: usize, net: Arc<MockNetwork>| {
+        let mut contributors = BitSet::new();
+        contributors.insert(id);
+        Aggregation::new(
+            Protocol::new(id, 2, 2),
+            config.clone(),
+            Contribution {
+                value: id as u64 + 1,
+                contributors,
+            },
+            Box::pin(
+                net.receive_messages::<Update<Contribution>>()
+                    .map(move |msg| msg.0 .0),
+            ),
+            NetworkWrapper(net),
+        )
+    };
+
+    let mut aggregation0 = aggregation(0, net0);
+    let handle = tokio::spawn(async move { while aggregation0.next().await.is_some() {} });
+
+    let mut aggregation1 = aggregation(1, net1);
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while let Some(aggregate) = aggregation1.next().await {
+            if aggregate.num_contributors() == 2 {
+                break;
+            }
+        }
+    })
+    .await
+    .expect("Aggregate took too long");
+    handle.abort();
+
+    let mut encoded = String::new();
+    encode(&mut encoded, &registry).unwrap();
+
+    // The second node completed level 1 by receiving the contribution of the first one. The levels
+    // are only reported once they have been observed.
+    assert!(encoded.contains(r#"level_completion_seconds_count{level="1"}"#));
+    assert!(encoded.contains(r#"contributions_total{level="1"}"#));
+}
+
 // additional tests:
 // it_sends_periodic_updates
 // it_activates_levels
@@ -20,7 +20,13 @@ impl ExtendedPrivateKey {
 
     /// Returns the corresponding master extended private key for a seed.
     pub fn from_seed(seed: Vec<u8>) -> Self {
-        let hash = compute_hmac_sha512(&B_CURVE, seed.as_slice());
+        ExtendedPrivateKey::from_seed_bytes(&seed)
+    }
+
+    /// Returns the corresponding master extended private key for a seed, without taking
+    /// ownership of it.
+    pub fn from_seed_bytes(seed: &[u8]) -> Self {
+        let hash = compute_hmac_sha512(&B_CURVE, seed);
         ExtendedPrivateKey::from(hash)
     }
 
@@ -6,6 +6,10 @@ use nimiq_blockchain::{Blockchain, BlockchainConfig};
 use nimiq_blockchain_interface::AbstractBlockchain;
 use nimiq_blockchain_proxy::BlockchainProxy;
 use nimiq_bls::cache::PublicKeyCache;
+#[cfg(feature = "full-consensus")]
+use nimiq_consensus::watchtower::{
+    ForkProofWatchtower as AbstractForkProofWatchtower, ForkProofWatchtowerProxy,
+};
 use nimiq_consensus::{
     sync::{light::full_sync_threshold, syncer_proxy::SyncerProxy},
     Consensus as AbstractConsensus, ConsensusProxy as AbstractConsensusProxy,
@@ -29,6 +33,8 @@ use nimiq_network_libp2p::{
 use nimiq_primitives::{policy::Policy, task_executor::TaskExecutor};
 use nimiq_utils::time::OffsetTime;
 #[cfg(feature = "validator")]
+use nimiq_validator::handover::HandoverLease;
+#[cfg(feature = "validator")]
 use nimiq_validator::validator::Validator as AbstractValidator;
 #[cfg(feature = "validator")]
 use nimiq_validator::validator::ValidatorProxy as AbstractValidatorProxy;
@@ -40,9 +46,12 @@ use nimiq_zkp::ZKP_VERIFYING_DATA;
 #[cfg(feature = "zkp-prover")]
 use nimiq_zkp_circuits::setup::{all_files_created, load_verifying_data, setup, DEVELOPMENT_SEED};
 #[cfg(feature = "database-storage")]
-use nimiq_zkp_component::proof_store::{DBProofStore, ProofStore};
-use nimiq_zkp_component::zkp_component::{
-    ZKPComponent as AbstractZKPComponent, ZKPComponentProxy as AbstractZKPComponentProxy,
+use nimiq_zkp_component::proof_store::DBProofStore;
+use nimiq_zkp_component::{
+    proof_store::ProofStore,
+    zkp_component::{
+        ZKPComponent as AbstractZKPComponent, ZKPComponentProxy as AbstractZKPComponentProxy,
+    },
 };
 #[cfg(feature = "zkp-prover")]
 use nimiq_zkp_primitives::NanoZKPError;
@@ -69,6 +78,9 @@ pub type ValidatorProxy = AbstractValidatorProxy;
 pub type ZKPComponent = AbstractZKPComponent<Network>;
 pub type ZKPComponentProxy = AbstractZKPComponentProxy<Network>;
 
+#[cfg(feature = "full-consensus")]
+pub type ForkProofWatchtower = AbstractForkProofWatchtower<Network>;
+
 /// Holds references to the relevant structs. This is then Arc'd in `Client` and a nice API is
 /// exposed.
 ///
@@ -94,6 +106,9 @@ pub(crate) struct ClientInner {
     wallet_store: Arc<WalletStore>,
 
     zkp_component: ZKPComponentProxy,
+
+    #[cfg(feature = "full-consensus")]
+    watchtower: Option<ForkProofWatchtowerProxy>,
 }
 
 /// This function is used to generate the services flags (provided, needed) based upon the configured sync mode
@@ -131,6 +146,7 @@ impl ClientInner {
     async fn from_config(
         config: ClientConfig,
         executor: impl TaskExecutor + Send + 'static + Clone,
+        proof_store: Option<Box<dyn ProofStore>>,
     ) -> Result<Client, Error> {
         // Get network info (i.e. which specific blockchain we're on)
         if !config.network_id.is_albatross() {
@@ -340,9 +356,9 @@ impl ClientInner {
 
         #[cfg(feature = "database-storage")]
         let zkp_storage: Option<Box<dyn ProofStore>> =
-            Some(Box::new(DBProofStore::new(environment.clone())));
+            Some(proof_store.unwrap_or_else(|| Box::new(DBProofStore::new(environment.clone()))));
         #[cfg(not(feature = "database-storage"))]
-        let zkp_storage = None;
+        let zkp_storage = proof_store;
 
         let (blockchain_proxy, syncer_proxy, zkp_component) = match config.consensus.sync_mode {
             #[cfg(not(feature = "full-consensus"))]
@@ -378,6 +394,8 @@ impl ClientInner {
                         config.zkp.prover_active,
                         None,
                         config.zkp.prover_keys_path,
+                        config.zkp.prover_workers,
+                        config.zkp.prover_worker_secret,
                         zkp_storage,
                     )
                     .await
@@ -434,6 +452,8 @@ impl ClientInner {
                         config.zkp.prover_active,
                         None,
                         config.zkp.prover_keys_path,
+                        config.zkp.prover_workers,
+                        config.zkp.prover_worker_secret,
                         zkp_storage,
                     )
                     .await
@@ -476,6 +496,20 @@ impl ClientInner {
                     zkp_storage,
                 )
                 .await;
+
+                // Resume from the proof that was loaded from the proof store, so only newer
+                // proofs have to be requested from our peers.
+                let zkp_state = zkp_component.proxy().get_zkp_state();
+                if let Some(proof) = zkp_state.latest_proof {
+                    let result = LightBlockchain::push_zkp(
+                        blockchain.upgradable_read(),
+                        Block::Macro(zkp_state.latest_block),
+                        proof,
+                        true,
+                    );
+                    log::debug!(?result, "Resumed the blockchain from the stored ZKP");
+                }
+
                 let syncer = SyncerProxy::new_light(
                     blockchain_proxy.clone(),
                     Arc::clone(&network),
@@ -503,6 +537,15 @@ impl ClientInner {
             executor,
         );
 
+        // Initialize the fork proof watchtower
+        #[cfg(feature = "full-consensus")]
+        let watchtower = match blockchain_proxy {
+            BlockchainProxy::Full(ref blockchain) if config.consensus.equivocation_watchtower => {
+                Some(ForkProofWatchtower::new(Arc::clone(blockchain), Arc::clone(&network)).await)
+            }
+            _ => None,
+        };
+
         #[cfg(feature = "validator")]
         let (validator, validator_proxy) = match config.validator {
             Some(validator_config) => {
@@ -513,6 +556,15 @@ impl ClientInner {
                     // Load validator address
                     let automatic_reactivate = validator_config.automatic_reactivate;
 
+                    // Load the signal data configured by the operator
+                    let signal_data = validator_config.signal_data;
+
+                    // Build the configuration for the Handel aggregations
+                    let handel_config = validator_config.handel_config();
+
+                    // Load the handover configuration (before we give away ownership of the validator config)
+                    let handover_config = validator_config.handover;
+
                     // Load signing key (before we give away ownership of the storage config)
                     let signing_key = config.storage.signing_keypair()?;
 
@@ -525,19 +577,32 @@ impl ClientInner {
                     let validator_network =
                         Arc::new(ValidatorNetworkImpl::new(Arc::clone(&network)));
 
-                    let validator = Validator::new(
+                    let mut validator = Validator::new(
                         environment.clone(),
                         &consensus,
                         Arc::clone(blockchain),
                         validator_network,
                         validator_address,
                         automatic_reactivate,
+                        signal_data,
                         signing_key,
                         voting_key,
                         fee_key,
                         config.mempool,
+                        handel_config,
                     );
 
+                    // Only sign while holding the handover lease, if running as primary or hot-standby.
+                    if let Some(handover_config) = handover_config {
+                        validator.enable_handover(HandoverLease::new(handover_config)?.spawn()?);
+                    }
+
+                    // Include the equivocation proofs relayed by the watchtower in our blocks.
+                    #[cfg(feature = "full-consensus")]
+                    if let Some(ref watchtower) = watchtower {
+                        validator.subscribe_equivocations(&watchtower.proxy());
+                    }
+
                     // Use the validator's mempool as TransactionVerificationCache in the blockchain.
                     blockchain.write().tx_verification_cache =
                         Arc::<Mempool>::clone(&validator.mempool);
@@ -565,11 +630,15 @@ impl ClientInner {
                 #[cfg(feature = "wallet")]
                 wallet_store,
                 zkp_component: zkp_component.proxy(),
+                #[cfg(feature = "full-consensus")]
+                watchtower: watchtower.as_ref().map(|watchtower| watchtower.proxy()),
             }),
             consensus: Some(consensus),
             #[cfg(feature = "validator")]
             validator,
             zkp_component: Some(zkp_component),
+            #[cfg(feature = "full-consensus")]
+            watchtower,
         })
     }
 }
@@ -598,6 +667,8 @@ pub struct Client {
     #[cfg(feature = "validator")]
     validator: Option<Validator>,
     zkp_component: Option<ZKPComponent>,
+    #[cfg(feature = "full-consensus")]
+    watchtower: Option<ForkProofWatchtower>,
 }
 
 impl Client {
@@ -605,7 +676,17 @@ impl Client {
         config: ClientConfig,
         executor: impl TaskExecutor + Send + 'static + Clone,
     ) -> Result<Self, Error> {
-        ClientInner::from_config(config, executor).await
+        ClientInner::from_config(config, executor, None).await
+    }
+
+    /// Same as `from_config`, but stores the ZK proof in the given proof store instead of the
+    /// database. A light client resumes from the proof found in the store.
+    pub async fn from_config_with_proof_store(
+        config: ClientConfig,
+        executor: impl TaskExecutor + Send + 'static + Clone,
+        proof_store: Box<dyn ProofStore>,
+    ) -> Result<Self, Error> {
+        ClientInner::from_config(config, executor, Some(proof_store)).await
     }
 
     pub fn take_consensus(&mut self) -> Option<Consensus> {
@@ -665,4 +746,16 @@ impl Client {
     pub fn zkp_component(&self) -> ZKPComponentProxy {
         self.inner.zkp_component.clone()
     }
+
+    /// Returns a reference to the *Equivocation Watchtower* or none.
+    #[cfg(feature = "full-consensus")]
+    pub fn take_watchtower(&mut self) -> Option<ForkProofWatchtower> {
+        self.watchtower.take()
+    }
+
+    /// Returns a reference to the *Equivocation Watchtower Proxy* or none.
+    #[cfg(feature = "full-consensus")]
+    pub fn watchtower_proxy(&self) -> Option<ForkProofWatchtowerProxy> {
+        self.inner.watchtower.clone()
+    }
 }
@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::{net::SocketAddr, path::PathBuf};
 
 use clap::Parser;
 use log::level_filters::{LevelFilter, ParseLevelFilterError};
@@ -67,6 +67,17 @@ pub struct CommandLine {
     /// Internally used flag to start a zero-knowledge prover process.
     #[clap(long, action)]
     pub prove: bool,
+
+    /// Run as a zero-knowledge prover worker listening on the given address. The worker generates
+    /// public key tree sub-proofs for the provers listing it in `prover_workers`, which must be
+    /// configured with the same `prover_worker_secret`.
+    ///
+    /// # Examples
+    ///
+    /// * `nimiq-client --zkp-worker 0.0.0.0:8650`
+    ///
+    #[clap(long, value_name = "ADDR")]
+    pub zkp_worker: Option<SocketAddr>,
 }
 
 impl CommandLine {
@@ -1,9 +1,12 @@
+#[cfg(feature = "rpc-server")]
+use std::collections::HashMap;
 #[cfg(any(feature = "rpc-server", feature = "metrics-server"))]
 use std::net::IpAddr;
-#[cfg(feature = "metrics-server")]
-use std::net::SocketAddr;
+#[cfg(any(feature = "rpc-server", feature = "validator"))]
+use std::time::Duration;
 use std::{
     fmt::Debug,
+    net::SocketAddr,
     num::NonZeroU8,
     path::{Path, PathBuf},
     string::ToString,
@@ -14,6 +17,8 @@ use derive_builder::Builder;
 use nimiq_bls::{KeyPair as BlsKeyPair, SecretKey as BlsSecretKey};
 #[cfg(feature = "database-storage")]
 use nimiq_database::{mdbx::MdbxDatabase, volatile::VolatileDatabase, DatabaseProxy};
+#[cfg(feature = "validator")]
+use nimiq_handel::config::Config as HandelConfig;
 use nimiq_hash::{Blake2bHash, Hash};
 #[cfg(feature = "validator")]
 use nimiq_keys::{Address, KeyPair, PrivateKey};
@@ -26,13 +31,15 @@ use nimiq_serde::Deserialize;
 #[cfg(feature = "validator")]
 use nimiq_utils::key_rng::SecureGenerate;
 use nimiq_utils::{file_store::FileStore, Sensitive};
+#[cfg(feature = "validator")]
+use nimiq_validator::handover::HandoverConfig;
 use nimiq_zkp_circuits::DEFAULT_KEYS_PATH;
 use strum_macros::Display;
 use subtle::ConstantTimeEq;
 
 #[cfg(feature = "database-storage")]
 use crate::config::config_file::DatabaseSettings;
-#[cfg(any(feature = "rpc-server", feature = "metrics-server"))]
+#[cfg(any(feature = "metrics-server", feature = "validator"))]
 use crate::config::consts;
 #[cfg(feature = "metrics-server")]
 use crate::config::consts::default_bind;
@@ -81,6 +88,9 @@ pub struct ConsensusConfig {
     #[builder(default = "1")]
     /// Maximum number of epochs that are stored in the client
     pub max_epochs_stored: u32,
+    #[builder(default)]
+    /// Run the fork proof watchtower (full and history nodes only)
+    pub equivocation_watchtower: bool,
 }
 
 impl Default for ConsensusConfig {
@@ -89,6 +99,7 @@ impl Default for ConsensusConfig {
             sync_mode: SyncMode::default(),
             min_peers: 3,
             max_epochs_stored: Policy::MIN_EPOCHS_STORED,
+            equivocation_watchtower: false,
         }
     }
 }
@@ -497,6 +508,59 @@ pub struct ValidatorConfig {
 
     /// Config if the validator automatically reactivates itself.
     pub automatic_reactivate: bool,
+
+    /// The signal data the validator operator wants to signal for chain upgrades.
+    pub signal_data: Option<Blake2bHash>,
+
+    /// Number of peers contacted on each level during a periodic Handel update.
+    pub handel_update_count: Option<usize>,
+
+    /// Interval between periodic Handel updates.
+    pub handel_update_interval: Option<Duration>,
+
+    /// Timeout after which the next Handel level is started.
+    pub handel_level_timeout: Option<Duration>,
+
+    /// Number of peers contacted on each level when a Handel level is started.
+    pub handel_peer_count: Option<usize>,
+
+    /// The lease based handover between a primary validator and its hot-standbys, if enabled.
+    pub handover: Option<HandoverConfig>,
+}
+
+#[cfg(feature = "validator")]
+fn non_zero_setting<T: Default + PartialEq>(
+    value: Option<T>,
+    name: &str,
+) -> Result<Option<T>, Error> {
+    match value {
+        Some(value) if value == T::default() => Err(Error::config_error(format!(
+            "Validator setting {name} must be greater than zero"
+        ))),
+        value => Ok(value),
+    }
+}
+
+#[cfg(feature = "validator")]
+impl ValidatorConfig {
+    /// Builds the Handel configuration for the validator's aggregations, using the defaults for
+    /// everything that has not been configured.
+    pub fn handel_config(&self) -> HandelConfig {
+        let mut config = HandelConfig::default();
+        if let Some(update_count) = self.handel_update_count {
+            config.update_count = update_count;
+        }
+        if let Some(update_interval) = self.handel_update_interval {
+            config.update_interval = update_interval;
+        }
+        if let Some(timeout) = self.handel_level_timeout {
+            config.timeout = timeout;
+        }
+        if let Some(peer_count) = self.handel_peer_count {
+            config.peer_count = peer_count;
+        }
+        config
+    }
 }
 
 /// Credentials for JSON RPC server, metrics server or websocket RPC server
@@ -558,6 +622,39 @@ pub struct RpcServerConfig {
     /// If specified, require HTTP basic auth with these credentials
     #[builder(setter(strip_option))]
     pub credentials: Option<Credentials>,
+
+    /// Additional users, each restricted to the methods of its role.
+    #[builder(default)]
+    pub users: Vec<RpcUserConfig>,
+
+    /// If specified, the cost of requests each client may spend per minute. Each user counts as
+    /// one client, and callers without credentials are limited per IP address.
+    #[builder(setter(strip_option))]
+    pub rate_limit: Option<u32>,
+
+    /// The cost of methods for the rate limit. Methods that aren't listed cost 1.
+    #[builder(default)]
+    pub method_costs: HashMap<String, u32>,
+
+    /// If specified, the maximum number of items returned by methods that return lists.
+    #[builder(setter(strip_option))]
+    pub max_result_size: Option<usize>,
+
+    /// If specified, the maximum time between receiving a request and answering it.
+    #[builder(setter(strip_option))]
+    pub request_timeout: Option<Duration>,
+}
+
+/// An additional user of the RPC server.
+#[cfg(feature = "rpc-server")]
+#[derive(Clone, Debug)]
+pub struct RpcUserConfig {
+    /// The credentials required to access the RPC server.
+    pub credentials: Credentials,
+    /// The name of the role of the user.
+    pub role: String,
+    /// The patterns of the methods the role allows, e.g. `get*` or `wallet.*`.
+    pub methods: Vec<String>,
 }
 
 #[cfg(feature = "metrics-server")]
@@ -752,6 +849,7 @@ impl ClientConfigBuilder {
         // Configure consensus
         let mut consensus = ConsensusConfigBuilder::default()
             .sync_mode(config_file.consensus.sync_mode)
+            .equivocation_watchtower(config_file.consensus.equivocation_watchtower)
             .build()
             .unwrap();
         if let Some(min_peers) = config_file.consensus.min_peers {
@@ -782,6 +880,64 @@ impl ClientConfigBuilder {
             self.validator(ValidatorConfig {
                 validator_address: Address::from_any_str(&validator_config.validator_address)?,
                 automatic_reactivate: validator_config.automatic_reactivate,
+                signal_data: validator_config
+                    .signal_data
+                    .as_ref()
+                    .map(|signal_data| {
+                        signal_data.parse::<Blake2bHash>().map_err(|e| {
+                            Error::config_error(format!("Invalid validator signal data: {e}"))
+                        })
+                    })
+                    .transpose()?,
+                handel_update_count: non_zero_setting(
+                    validator_config.handel_update_count,
+                    "handel_update_count",
+                )?,
+                handel_update_interval: non_zero_setting(
+                    validator_config.handel_update_interval,
+                    "handel_update_interval",
+                )?
+                .map(Duration::from_millis),
+                handel_level_timeout: non_zero_setting(
+                    validator_config.handel_level_timeout,
+                    "handel_level_timeout",
+                )?
+                .map(Duration::from_millis),
+                handel_peer_count: non_zero_setting(
+                    validator_config.handel_peer_count,
+                    "handel_peer_count",
+                )?,
+                handover: validator_config
+                    .handover_lease_file
+                    .as_ref()
+                    .map(|lease_file| {
+                        Ok::<_, Error>(HandoverConfig {
+                            lease_file: PathBuf::from(lease_file),
+                            holder_id: validator_config.handover_lease_holder.clone().ok_or_else(
+                                || {
+                                    Error::config_error(
+                                        "Validator setting handover_lease_holder is required",
+                                    )
+                                },
+                            )?,
+                            lease_duration: Duration::from_millis(
+                                non_zero_setting(
+                                    validator_config.handover_lease_duration,
+                                    "handover_lease_duration",
+                                )?
+                                .unwrap_or(consts::HANDOVER_LEASE_DURATION),
+                            ),
+                            takeover_delay: Duration::from_millis(
+                                non_zero_setting(
+                                    validator_config.handover_takeover_delay,
+                                    "handover_takeover_delay",
+                                )?
+                                .unwrap_or(consts::HANDOVER_TAKEOVER_DELAY),
+                            ),
+                            standby: validator_config.standby,
+                        })
+                    })
+                    .transpose()?,
             });
 
             if let Some(key_path) = &validator_config.voting_key_file {
@@ -816,9 +972,29 @@ impl ClientConfigBuilder {
                 prover_keys_path = PathBuf::from(zkp_path);
             }
 
+            let prover_workers = zkp_settings
+                .prover_workers
+                .iter()
+                .map(|address| {
+                    address.parse().map_err(|_| {
+                        Error::config_error(format!("Invalid prover worker address: {address}"))
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let prover_worker_secret = zkp_settings.prover_worker_secret.clone();
+            if !prover_workers.is_empty() && prover_worker_secret.is_none() {
+                return Err(Error::config_error(
+                    "The prover workers require a prover_worker_secret",
+                ));
+            }
+
             self.zkp = Some(ZKPConfig {
                 prover_active: zkp_settings.prover_active,
                 prover_keys_path,
+                prover_workers,
+                prover_worker_secret: prover_worker_secret.unwrap_or_default(),
+                prover_worker_debug_mode: zkp_settings.prover_worker_debug_mode.unwrap_or(true),
             });
         }
 
@@ -862,6 +1038,38 @@ impl ClientConfigBuilder {
                     }
                 };
 
+                // Without main credentials, anyone could call every method and the roles of the
+                // users would be pointless.
+                if credentials.is_none() && !rpc_config.users.is_empty() {
+                    return Err(Error::config_error(
+                        "RPC: Additional users require the username and password to be set.",
+                    ));
+                }
+
+                let mut usernames: Vec<&str> = rpc_config.username.as_deref().into_iter().collect();
+                let mut users = vec![];
+                for user in &rpc_config.users {
+                    let methods = rpc_config.roles.get(&user.role).ok_or_else(|| {
+                        Error::config_error(format!(
+                            "RPC: Unknown role {} of user {}",
+                            user.role, user.username
+                        ))
+                    })?;
+                    if usernames.contains(&user.username.as_str()) {
+                        return Err(Error::config_error(format!(
+                            "RPC: Username {} is used more than once",
+                            user.username
+                        )));
+                    }
+                    usernames.push(&user.username);
+
+                    users.push(RpcUserConfig {
+                        credentials: Credentials::new(&user.username, &*user.password),
+                        role: user.role.clone(),
+                        methods: methods.clone(),
+                    });
+                }
+
                 self.rpc_server = Some(Some(RpcServerConfig {
                     bind_to,
                     port: rpc_config.port.unwrap_or(consts::RPC_DEFAULT_PORT),
@@ -869,6 +1077,11 @@ impl ClientConfigBuilder {
                     allow_ips,
                     allowed_methods: Some(rpc_config.methods.clone()),
                     credentials,
+                    users,
+                    rate_limit: rpc_config.rate_limit,
+                    method_costs: rpc_config.method_costs.clone(),
+                    max_result_size: rpc_config.max_result_size,
+                    request_timeout: rpc_config.request_timeout.map(Duration::from_millis),
                 }));
             }
         }
@@ -937,6 +1150,16 @@ pub struct ZKPConfig {
 
     /// Prover keys path for the zkp prover.
     pub prover_keys_path: PathBuf,
+
+    /// Addresses of the workers the public key tree sub-proofs are distributed to.
+    /// If empty, the whole proof is generated locally.
+    pub prover_workers: Vec<SocketAddr>,
+
+    /// The secret the prover and its workers authenticate each other with.
+    pub prover_worker_secret: Sensitive<String>,
+
+    /// Whether a prover worker verifies each proof right after generating it.
+    pub prover_worker_debug_mode: bool,
 }
 
 impl Default for ZKPConfig {
@@ -944,6 +1167,9 @@ impl Default for ZKPConfig {
         Self {
             prover_active: false,
             prover_keys_path: PathBuf::from(DEFAULT_KEYS_PATH),
+            prover_workers: vec![],
+            prover_worker_secret: Sensitive::default(),
+            prover_worker_debug_mode: true,
         }
     }
 }
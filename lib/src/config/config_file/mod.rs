@@ -186,6 +186,9 @@ pub struct ConsensusSettings {
     pub network: Option<NetworkId>,
     /// Minimum number of peers necessary to reach consensus
     pub min_peers: Option<usize>,
+    #[serde(default)]
+    /// Run the fork proof watchtower, which gossips the forks it detects and relays equivocation proofs (full and history nodes only)
+    pub equivocation_watchtower: bool,
 }
 
 #[derive(Clone, Copy, Deserialize, Debug, Default, Eq, PartialEq)]
@@ -243,6 +246,29 @@ pub struct RpcServerSettings {
     pub methods: Vec<String>,
     pub username: Option<String>,
     pub password: Option<Sensitive<String>>,
+    /// Named sets of method patterns that can be assigned to users.
+    #[serde(default)]
+    pub roles: HashMap<String, Vec<String>>,
+    #[serde(default)]
+    pub users: Vec<RpcUserSettings>,
+    /// The cost of requests each client may spend per minute.
+    pub rate_limit: Option<u32>,
+    /// The cost of methods for the rate limit. Methods that aren't listed cost 1.
+    #[serde(default)]
+    pub method_costs: HashMap<String, u32>,
+    /// The maximum number of items returned by methods that return lists.
+    pub max_result_size: Option<usize>,
+    /// The maximum time in milliseconds between receiving a request and answering it.
+    pub request_timeout: Option<u64>,
+}
+
+/// An additional user of the RPC server, restricted to the methods of its role.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RpcUserSettings {
+    pub username: String,
+    pub password: Sensitive<String>,
+    pub role: String,
 }
 
 #[derive(Clone, Debug, Deserialize, Default)]
@@ -416,6 +442,17 @@ pub struct ValidatorSettings {
     pub fee_key: Option<Sensitive<String>>,
     #[serde(default)]
     pub automatic_reactivate: bool,
+    pub signal_data: Option<String>,
+    pub handel_update_count: Option<usize>,
+    pub handel_update_interval: Option<u64>,
+    pub handel_level_timeout: Option<u64>,
+    pub handel_peer_count: Option<usize>,
+    pub handover_lease_file: Option<String>,
+    pub handover_lease_holder: Option<String>,
+    pub handover_lease_duration: Option<u64>,
+    pub handover_takeover_delay: Option<u64>,
+    #[serde(default)]
+    pub standby: bool,
 }
 
 #[derive(Clone, Debug, Deserialize, Default)]
@@ -425,4 +462,8 @@ pub struct ZKPSettings {
     pub prover_active: bool,
     #[serde(default)]
     pub prover_keys_path: Option<String>,
+    #[serde(default)]
+    pub prover_workers: Vec<String>,
+    pub prover_worker_secret: Option<Sensitive<String>>,
+    pub prover_worker_debug_mode: Option<bool>,
 }
@@ -12,6 +12,12 @@ pub const RPC_DEFAULT_PORT: u16 = 8648;
 /// The default port for the metrics server
 pub const METRICS_DEFAULT_PORT: u16 = 9100;
 
+/// The default duration of a validator handover lease in milliseconds
+pub const HANDOVER_LEASE_DURATION: u64 = 10_000;
+
+/// The default time in milliseconds a handover lease must be expired before a standby takes over
+pub const HANDOVER_TAKEOVER_DELAY: u64 = 2_000;
+
 /// Returns the default bind, i.e. localhost
 pub fn default_bind() -> IpAddr {
     IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1))
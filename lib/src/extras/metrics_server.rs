@@ -2,6 +2,8 @@ use std::{net::SocketAddr, sync::Arc};
 
 use nimiq_blockchain_proxy::BlockchainProxy;
 use nimiq_consensus::ConsensusProxy;
+#[cfg(feature = "nimiq-handel")]
+use nimiq_handel::metrics::HandelMetrics;
 #[cfg(feature = "nimiq-mempool")]
 use nimiq_mempool::mempool::Mempool;
 pub use nimiq_metrics_server::NimiqTaskMonitor;
@@ -11,16 +13,20 @@ pub fn start_metrics_server<TNetwork: Network>(
     addr: SocketAddr,
     blockchain_proxy: BlockchainProxy,
     #[cfg(feature = "nimiq-mempool")] mempool: Option<Arc<Mempool>>,
+    #[cfg(feature = "nimiq-handel")] handel_metrics: Option<Arc<HandelMetrics>>,
     consensus_proxy: ConsensusProxy<TNetwork>,
     network: Arc<nimiq_network_libp2p::Network>,
     task_monitors: &[NimiqTaskMonitor],
 ) {
     #[cfg(not(feature = "nimiq-mempool"))]
     let mempool = None;
+    #[cfg(not(feature = "nimiq-handel"))]
+    let handel_metrics = None;
     nimiq_metrics_server::start_metrics_server(
         addr,
         blockchain_proxy,
         mempool,
+        handel_metrics,
         consensus_proxy,
         network,
         task_monitors,
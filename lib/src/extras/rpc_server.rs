@@ -1,17 +1,23 @@
-use std::{collections::HashSet, iter::FromIterator, sync::Arc};
+use std::{collections::HashSet, sync::Arc};
 
-use nimiq_jsonrpc_server::{
-    AllowListDispatcher, Config, Credentials, ModularDispatcher, Server as _Server,
+use nimiq_jsonrpc_server::Dispatcher;
+use nimiq_rpc_server::{
+    authorization::{resolve_method_patterns, AuthorizedDispatcher, MethodNamespace},
+    dispatchers::*,
+    limits::{LimitedDispatcher, RequestLimits},
+    modular::ModularDispatcher,
+    server::{RpcServer, RpcUser, ServerConfig},
 };
-use nimiq_rpc_server::dispatchers::*;
 use nimiq_wallet::WalletStore;
 
 #[cfg(feature = "rpc-server")]
 use crate::config::config::RpcServerConfig;
 use crate::{client::Client, config::consts::default_bind, error::Error};
 
-pub type Server = _Server<AllowListDispatcher<ModularDispatcher>>;
+pub type Server = RpcServer<LimitedDispatcher<AuthorizedDispatcher<ModularDispatcher>>>;
 
+/// Initializes the RPC server. Each request is authenticated with the credentials of the main
+/// user or of one of the additional users, and may only call the methods of that user's role.
 #[cfg(feature = "rpc-server")]
 pub fn initialize_rpc_server(
     client: &Client,
@@ -21,51 +27,166 @@ pub fn initialize_rpc_server(
     let ip = config.bind_to.unwrap_or_else(default_bind);
     log::info!("Initializing RPC server: {}:{}", ip, config.port);
 
-    // Configure RPC server
-    let basic_auth = config.credentials.map(|credentials| {
-        Credentials::new_from_blake2b(credentials.username, credentials.password_hash.0 .0)
-    });
+    // TODO: Pass this to the rpc server config
+    let _corsdomain = config.corsdomain.unwrap_or_default();
+
+    let limits = RequestLimits {
+        rate_limit: config.rate_limit,
+        method_costs: config.method_costs,
+        timeout: config.request_timeout,
+    };
+
+    let (dispatcher, namespaces) = create_dispatcher(client, wallet_store, config.max_result_size);
 
     let allowed_methods = config.allowed_methods.unwrap_or_default();
     let allowed_methods = if allowed_methods.is_empty() {
         None
     } else {
-        Some(HashSet::from_iter(allowed_methods))
+        Some(resolve_allowed_methods(&allowed_methods, &namespaces))
     };
 
-    // TODO: Pass this to the rpc server config
-    let _corsdomain = config.corsdomain.unwrap_or_default();
+    // Without credentials, the server is open to anyone, restricted to the allowed methods.
+    let allow_anonymous = config.credentials.is_none();
+    let mut users: Vec<RpcUser> = config
+        .credentials
+        .into_iter()
+        .map(|credentials| RpcUser {
+            username: credentials.username,
+            password_hash: credentials.password_hash.0,
+            allowed_methods: allowed_methods.clone(),
+        })
+        .collect();
 
+    for user in config.users {
+        log::info!(
+            username = %user.credentials.username,
+            role = %user.role,
+            "Adding RPC user"
+        );
+
+        let allowed_methods = resolve_allowed_methods(&user.methods, &namespaces);
+        if allowed_methods.is_empty() {
+            log::warn!(role = %user.role, "RPC role doesn't allow any method");
+        }
+        users.push(RpcUser {
+            username: user.credentials.username,
+            password_hash: user.credentials.password_hash.0,
+            allowed_methods: Some(allowed_methods),
+        });
+    }
+
+    Ok(Server::new(
+        ServerConfig {
+            bind_to: (ip, config.port).into(),
+            users,
+            allow_anonymous,
+            anonymous_methods: allowed_methods,
+        },
+        LimitedDispatcher::new(AuthorizedDispatcher::new(dispatcher), limits),
+    ))
+}
+
+/// Creates a dispatcher for all RPC methods the client supports, along with the names of the
+/// methods of each namespace.
+fn create_dispatcher(
+    client: &Client,
+    wallet_store: Arc<WalletStore>,
+    max_result_size: Option<usize>,
+) -> (ModularDispatcher, Vec<MethodNamespace>) {
     let mut dispatcher = ModularDispatcher::default();
+    let mut namespaces = vec![];
 
-    let wallet_dispatcher = WalletDispatcher::new(wallet_store);
+    let wallet_dispatcher = WalletDispatcher::new(wallet_store, client.blockchain());
     let unlocked_wallets = Arc::clone(&wallet_dispatcher.unlocked_wallets);
 
-    dispatcher.add(BlockchainDispatcher::new(client.blockchain()));
-
-    dispatcher.add(ConsensusDispatcher::new(
-        client.consensus_proxy(),
-        Some(unlocked_wallets),
-    ));
-    dispatcher.add(NetworkDispatcher::new(client.network()));
+    add_dispatcher(
+        &mut dispatcher,
+        &mut namespaces,
+        "blockchain",
+        BlockchainDispatcher::new(client.blockchain(), max_result_size),
+    );
+    add_dispatcher(
+        &mut dispatcher,
+        &mut namespaces,
+        "consensus",
+        ConsensusDispatcher::new(client.consensus_proxy(), Some(unlocked_wallets)),
+    );
+    add_dispatcher(
+        &mut dispatcher,
+        &mut namespaces,
+        "network",
+        NetworkDispatcher::new(client.network()),
+    );
     if let Some(mempool) = client.mempool() {
-        dispatcher.add(MempoolDispatcher::new(mempool));
+        add_dispatcher(
+            &mut dispatcher,
+            &mut namespaces,
+            "mempool",
+            MempoolDispatcher::new(mempool, max_result_size),
+        );
     }
-    dispatcher.add(PolicyDispatcher {});
+    add_dispatcher(
+        &mut dispatcher,
+        &mut namespaces,
+        "policy",
+        PolicyDispatcher {},
+    );
     if let Some(validator_proxy) = client.validator_proxy() {
-        dispatcher.add(ValidatorDispatcher::new(validator_proxy));
+        add_dispatcher(
+            &mut dispatcher,
+            &mut namespaces,
+            "validator",
+            ValidatorDispatcher::new(validator_proxy),
+        );
+    }
+    add_dispatcher(
+        &mut dispatcher,
+        &mut namespaces,
+        "wallet",
+        wallet_dispatcher,
+    );
+    #[cfg(feature = "full-consensus")]
+    if let Some(watchtower_proxy) = client.watchtower_proxy() {
+        add_dispatcher(
+            &mut dispatcher,
+            &mut namespaces,
+            "watchtower",
+            WatchtowerDispatcher::new(watchtower_proxy),
+        );
     }
-    dispatcher.add(wallet_dispatcher);
 
-    dispatcher.add(ZKPComponentDispatcher::new(client.zkp_component()));
+    add_dispatcher(
+        &mut dispatcher,
+        &mut namespaces,
+        "zkpComponent",
+        ZKPComponentDispatcher::new(client.zkp_component()),
+    );
 
-    Ok(Server::new(
-        Config {
-            bind_to: (config.bind_to.unwrap_or_else(default_bind), config.port).into(),
-            enable_websocket: false,
-            ip_whitelist: None,
-            basic_auth,
-        },
-        AllowListDispatcher::new(dispatcher, allowed_methods),
-    ))
+    (dispatcher, namespaces)
+}
+
+fn add_dispatcher<D: Dispatcher + Clone>(
+    dispatcher: &mut ModularDispatcher,
+    namespaces: &mut Vec<MethodNamespace>,
+    name: &'static str,
+    inner: D,
+) {
+    namespaces.push(MethodNamespace {
+        name,
+        methods: inner
+            .method_names()
+            .iter()
+            .map(ToString::to_string)
+            .collect(),
+    });
+    dispatcher.add(inner);
+}
+
+/// Resolves the method patterns and warns about the ones that don't match any method.
+fn resolve_allowed_methods(patterns: &[String], namespaces: &[MethodNamespace]) -> HashSet<String> {
+    let (allowed_methods, unmatched) = resolve_method_patterns(patterns, namespaces);
+    for pattern in unmatched {
+        log::warn!(pattern, "RPC method pattern doesn't match any method");
+    }
+    allowed_methods
 }
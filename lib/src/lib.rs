@@ -5,5 +5,5 @@ pub mod extras;
 
 #[cfg(feature = "zkp-prover")]
 pub mod prover {
-    pub use nimiq_zkp_component::prover_binary::prover_main;
+    pub use nimiq_zkp_component::prover_binary::{prover_main, prover_worker_main};
 }
@@ -0,0 +1,14 @@
+use std::sync::Arc;
+
+use nimiq_handel::metrics::HandelMetrics;
+use prometheus_client::registry::Registry;
+
+pub struct AggregationMetrics {}
+
+impl AggregationMetrics {
+    pub fn register(registry: &mut Registry, handel_metrics: Arc<HandelMetrics>) {
+        let sub_registry = registry.sub_registry_with_prefix("handel");
+
+        handel_metrics.register(sub_registry);
+    }
+}
@@ -2,6 +2,7 @@ use std::{fmt::Debug, net::SocketAddr, sync::Arc};
 
 use nimiq_blockchain_proxy::BlockchainProxy;
 use nimiq_consensus::ConsensusProxy;
+use nimiq_handel::metrics::HandelMetrics;
 use nimiq_mempool::mempool::Mempool;
 use nimiq_network_interface::network::Network;
 use parking_lot::RwLock;
@@ -17,12 +18,14 @@ use tokio_metrics::TaskMonitor;
 #[cfg(tokio_unstable)]
 use crate::tokio_runtime::TokioRuntimeMetrics;
 use crate::{
-    chain::BlockMetrics, consensus::ConsensusMetrics, mempool::MempoolMetrics,
-    network::NetworkMetrics, server::metrics_server, tokio_task::TokioTaskMetrics,
+    chain::BlockMetrics, consensus::ConsensusMetrics, handel::AggregationMetrics,
+    mempool::MempoolMetrics, network::NetworkMetrics, server::metrics_server,
+    tokio_task::TokioTaskMetrics,
 };
 
 mod chain;
 mod consensus;
+mod handel;
 mod mempool;
 mod network;
 mod server;
@@ -82,6 +85,7 @@ pub fn start_metrics_server<TNetwork: Network>(
     addr: SocketAddr,
     blockchain_proxy: BlockchainProxy,
     mempool: Option<Arc<Mempool>>,
+    handel_metrics: Option<Arc<HandelMetrics>>,
     consensus_proxy: ConsensusProxy<TNetwork>,
     network: Arc<nimiq_network_libp2p::Network>,
     task_monitors: &[NimiqTaskMonitor],
@@ -97,6 +101,10 @@ pub fn start_metrics_server<TNetwork: Network>(
         MempoolMetrics::register(nimiq_registry, mempool);
     }
 
+    if let Some(handel_metrics) = handel_metrics {
+        AggregationMetrics::register(nimiq_registry, handel_metrics);
+    }
+
     // Setup the task metrics
     let task_metrics = Arc::new(RwLock::new(TokioTaskMetrics::new()));
     task_metrics.write().register(
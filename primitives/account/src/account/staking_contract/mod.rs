@@ -114,6 +114,32 @@ impl StakingContract {
         vec![]
     }
 
+    /// Get a page of at most `limit` stakers delegating for a given validator, ordered by address.
+    /// The page starts after the `start_after` address, which is the address of the last staker of
+    /// the previous page.
+    /// IMPORTANT: This is an expensive operation, iterating over the stakers in the contract until
+    /// the page is filled.
+    pub fn get_stakers_for_validator_page<T: DataStoreReadOps + DataStoreIterOps>(
+        &self,
+        data_store: &T,
+        address: &Address,
+        start_after: Option<&Address>,
+        limit: usize,
+    ) -> Vec<Staker> {
+        let read = StakingContractStoreRead::new(data_store);
+
+        if let Some(validator) = read.get_validator(address) {
+            let start = start_after.unwrap_or(&Address::START_ADDRESS);
+            return read
+                .iter_stakers_from(start)
+                .filter(|staker| Some(&staker.address) != start_after)
+                .filter(|staker| staker.delegation.as_ref() == Some(address))
+                .take(limit.min(validator.num_stakers as usize))
+                .collect();
+        }
+        vec![]
+    }
+
     /// Get a list containing all validators
     /// IMPORTANT: This is a very expensive operation, iterating over all existing validators in the contract.
     pub fn get_validators<T: DataStoreReadOps + DataStoreIterOps>(
@@ -125,6 +151,22 @@ impl StakingContract {
             .collect()
     }
 
+    /// Get a page of at most `limit` validators, ordered by address. The page starts after the
+    /// `start_after` address, which is the address of the last validator of the previous page.
+    pub fn get_validators_page<T: DataStoreReadOps + DataStoreIterOps>(
+        &self,
+        data_store: &T,
+        start_after: Option<&Address>,
+        limit: usize,
+    ) -> Vec<Validator> {
+        let start = start_after.unwrap_or(&Address::START_ADDRESS);
+        StakingContractStoreRead::new(data_store)
+            .iter_validators_from(start)
+            .filter(|validator| Some(&validator.address) != start_after)
+            .take(limit)
+            .collect()
+    }
+
     /// Given a seed, it randomly distributes the validator slots across all validators. It is
     /// used to select the validators for the next epoch.
     pub fn select_validators<T: DataStoreReadOps>(
@@ -70,15 +70,25 @@ impl<'read, T: DataStoreReadOps> StakingContractStoreReadOps
 
 impl<'read, T: DataStoreReadOps + DataStoreIterOps> StakingContractStoreRead<'read, T> {
     pub(crate) fn iter_stakers(&self) -> impl Iterator<Item = Staker> {
+        self.iter_stakers_from(&Address::START_ADDRESS)
+    }
+
+    /// Iterates over the stakers ordered by address, starting at the given address (inclusive).
+    pub(crate) fn iter_stakers_from(&self, start: &Address) -> impl Iterator<Item = Staker> {
         self.0.iter(
-            &StakingContractStore::staker_key(&Address::START_ADDRESS),
+            &StakingContractStore::staker_key(start),
             &StakingContractStore::staker_key(&Address::END_ADDRESS),
         )
     }
 
     pub(crate) fn iter_validators(&self) -> impl Iterator<Item = Validator> {
+        self.iter_validators_from(&Address::START_ADDRESS)
+    }
+
+    /// Iterates over the validators ordered by address, starting at the given address (inclusive).
+    pub(crate) fn iter_validators_from(&self, start: &Address) -> impl Iterator<Item = Validator> {
         self.0.iter(
-            &StakingContractStore::validator_key(&Address::START_ADDRESS),
+            &StakingContractStore::validator_key(start),
             &StakingContractStore::validator_key(&Address::END_ADDRESS),
         )
     }
@@ -173,6 +173,62 @@ fn can_iter_stakers() {
     );
 }
 
+#[test]
+fn can_page_stakers() {
+    let env = VolatileDatabase::new(20).unwrap();
+    let accounts = Accounts::new(env.clone());
+    let data_store = accounts.data_store(&Policy::STAKING_CONTRACT_ADDRESS);
+    let mut db_txn = env.write_transaction();
+    let mut db_txn = (&mut db_txn).into();
+
+    let (validator_address, _, mut staking_contract) =
+        make_sample_contract(data_store.write(&mut db_txn), Some(150_000_000));
+
+    // Add two more stakers delegating to the validator.
+    let mut data_store_write = data_store.write(&mut db_txn);
+    let mut store = StakingContractStoreWrite::new(&mut data_store_write);
+    for address in [Address::from([1u8; 20]), Address::from([250u8; 20])] {
+        staking_contract
+            .create_staker(
+                &mut store,
+                &address,
+                Coin::from_u64_unchecked(100_000_000),
+                Some(validator_address.clone()),
+                Coin::ZERO,
+                None,
+                &mut TransactionLog::empty(),
+            )
+            .unwrap();
+    }
+
+    let read = data_store.read(&db_txn);
+    let all = staking_contract.get_stakers_for_validator(&read, &validator_address);
+    assert_eq!(all.len(), 3);
+
+    let first_page =
+        staking_contract.get_stakers_for_validator_page(&read, &validator_address, None, 2);
+    assert_eq!(first_page.len(), 2);
+    assert_eq!(first_page[0].address, all[0].address);
+    assert_eq!(first_page[1].address, all[1].address);
+
+    let second_page = staking_contract.get_stakers_for_validator_page(
+        &read,
+        &validator_address,
+        Some(&first_page[1].address),
+        2,
+    );
+    assert_eq!(second_page.len(), 1);
+    assert_eq!(second_page[0].address, all[2].address);
+
+    let last_page = staking_contract.get_stakers_for_validator_page(
+        &read,
+        &validator_address,
+        Some(&second_page[0].address),
+        2,
+    );
+    assert!(last_page.is_empty());
+}
+
 #[test]
 fn create_staker_works() {
     let env = VolatileDatabase::new(20).unwrap();
@@ -33,6 +33,7 @@ pub mod history_proof;
 pub mod inherent;
 pub mod reward;
 pub mod signature_proof;
+pub mod signed_message;
 
 pub use self::equivocation_locator::{
     DoubleProposalLocator, DoubleVoteLocator, EquivocationLocator, ForkLocator,
@@ -0,0 +1,114 @@
+use nimiq_hash::{Hash, Sha256Hash};
+use nimiq_keys::{Address, PublicKey};
+use nimiq_serde::{Deserialize, DeserializeError, Serialize};
+use thiserror::Error;
+
+use crate::SignatureProof;
+
+/// The prefix of signed messages. It makes the signature recognisable as a Nimiq specific
+/// message signature.
+pub const NIMIQ_SIGN_MESSAGE_PREFIX: &[u8] = b"\x16Nimiq Signed Message:\n";
+
+/// The version of the signed message envelope written by this implementation.
+pub const SIGNED_MESSAGE_VERSION: u8 = 1;
+
+/// A signed message envelope proving that the owner of an address signed a message.
+///
+/// The embedded signature proof carries the key type, the public key and the signature. For
+/// passkey-controlled accounts, it also carries the Webauthn fields, in which case the challenge
+/// is the Blake2b hash of the [`SignedMessage::message_hash`], just like for transactions. The
+/// proof may also contain a merkle path for multisig addresses.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SignedMessage {
+    version: u8,
+    /// The address claimed to have signed the message.
+    pub signer: Address,
+    /// The proof of the signature over the message hash.
+    pub proof: SignatureProof,
+}
+
+impl SignedMessage {
+    pub fn new(signer: Address, proof: SignatureProof) -> Self {
+        SignedMessage {
+            version: SIGNED_MESSAGE_VERSION,
+            signer,
+            proof,
+        }
+    }
+
+    /// Creates the envelope from a signature proof, with the signer being the address the proof
+    /// computes to.
+    pub fn from_proof(proof: SignatureProof) -> Self {
+        Self::new(proof.compute_signer(), proof)
+    }
+
+    /// Returns the version of the envelope format.
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    /// Returns the public key the message was signed with.
+    pub fn public_key(&self) -> &PublicKey {
+        &self.proof.public_key
+    }
+
+    /// Computes the hash that is signed for a message.
+    pub fn message_hash(message: &[u8]) -> Sha256Hash {
+        /*
+         * Adding a prefix to the message makes the calculated signature recognisable as
+         * a Nimiq specific signature. This and the hashing prevents misuse where a malicious
+         * request can sign arbitrary data (e.g. a transaction) and use the signature to
+         * impersonate the victim.
+         *
+         * See also
+         * https://github.com/ethereum/EIPs/blob/af249ed715879ca2d77c6b43ed331c9c0ab8f6cb/EIPS/eip-191.md#specification.
+         */
+        let mut buffer = NIMIQ_SIGN_MESSAGE_PREFIX.to_vec();
+        // Append length of message as encoded string.
+        let mut encoded_len = message.len().to_string().into_bytes();
+        buffer.append(&mut encoded_len);
+        // Append actual message.
+        buffer.extend_from_slice(message);
+
+        buffer.hash::<Sha256Hash>()
+    }
+
+    /// Verifies that the envelope is a valid signature of the message by its signer.
+    pub fn verify(&self, message: &[u8]) -> Result<(), SignedMessageError> {
+        if self.version != SIGNED_MESSAGE_VERSION {
+            return Err(SignedMessageError::UnsupportedVersion(self.version));
+        }
+        if !self.proof.is_signed_by(&self.signer) {
+            return Err(SignedMessageError::SignerMismatch);
+        }
+        if !self.proof.verify(Self::message_hash(message).as_slice()) {
+            return Err(SignedMessageError::InvalidSignature);
+        }
+        Ok(())
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.serialize_to_vec()
+    }
+
+    /// Parses an envelope, rejecting envelopes of unsupported versions.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SignedMessageError> {
+        match bytes.first() {
+            Some(&SIGNED_MESSAGE_VERSION) | None => Ok(Self::deserialize_from_vec(bytes)?),
+            Some(&version) => Err(SignedMessageError::UnsupportedVersion(version)),
+        }
+    }
+}
+
+/// Possible signed message errors.
+#[derive(Debug, Error)]
+pub enum SignedMessageError {
+    #[error("Unsupported signed message version: {0}")]
+    UnsupportedVersion(u8),
+    #[error("Signature proof does not match the signer address")]
+    SignerMismatch,
+    #[error("Invalid signature")]
+    InvalidSignature,
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] DeserializeError),
+}
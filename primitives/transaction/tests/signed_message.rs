@@ -0,0 +1,94 @@
+use nimiq_keys::{
+    Address, ES256PublicKey, ES256Signature, KeyPair, PublicKey, SecureGenerate, Signature,
+};
+use nimiq_test_log::test;
+use nimiq_test_utils::test_rng::test_rng;
+use nimiq_transaction::{
+    signed_message::{SignedMessage, SignedMessageError, SIGNED_MESSAGE_VERSION},
+    SignatureProof,
+};
+
+fn sign(key_pair: &KeyPair, message: &[u8]) -> SignedMessage {
+    let signature = key_pair.sign(SignedMessage::message_hash(message).as_slice());
+    SignedMessage::from_proof(SignatureProof::from_ed25519(key_pair.public, signature))
+}
+
+#[test]
+fn it_can_sign_and_verify_messages() {
+    let key_pair = KeyPair::generate(&mut test_rng(false));
+    let signed_message = sign(&key_pair, b"I own this address");
+    assert_eq!(signed_message.signer, Address::from(&key_pair));
+
+    let bytes = signed_message.to_bytes();
+    assert_eq!(bytes[0], SIGNED_MESSAGE_VERSION);
+
+    let restored = SignedMessage::from_bytes(&bytes).unwrap();
+    assert!(restored.verify(b"I own this address").is_ok());
+    assert!(matches!(
+        restored.verify(b"I own another address"),
+        Err(SignedMessageError::InvalidSignature)
+    ));
+}
+
+#[test]
+fn it_rejects_mismatching_signers_and_unsupported_versions() {
+    let mut rng = test_rng(false);
+    let key_pair = KeyPair::generate(&mut rng);
+    let other_key_pair = KeyPair::generate(&mut rng);
+
+    let mut signed_message = sign(&key_pair, b"message");
+    signed_message.signer = Address::from(&other_key_pair);
+    assert!(matches!(
+        signed_message.verify(b"message"),
+        Err(SignedMessageError::SignerMismatch)
+    ));
+
+    let mut bytes = sign(&key_pair, b"message").to_bytes();
+    bytes[0] = SIGNED_MESSAGE_VERSION + 1;
+    assert!(matches!(
+        SignedMessage::from_bytes(&bytes),
+        Err(SignedMessageError::UnsupportedVersion(_))
+    ));
+}
+
+#[test]
+fn it_can_verify_webauthn_signed_messages() {
+    let public_key = PublicKey::ES256(
+        ES256PublicKey::from_bytes(
+            &hex::decode("032e9b396b3c41f5befda1cb8cd8e104ceebc13e2cca81d89d72e8c1bebcf9f1b2")
+                .unwrap(),
+        )
+        .unwrap(),
+    );
+    let proof = SignatureProof::try_from_webauthn(
+        public_key.clone(),
+        None,
+        Signature::ES256(
+            ES256Signature::from_bytes(
+                &hex::decode("a5ad17adccf477e1e0afcb0102295c5471a776ce82c5f7af1cb0b91ac231978802b10e96047f0f62ade1df336d4af2ce15f9b36a26c3b5a247c06922fb56e293").unwrap(),
+            )
+            .unwrap(),
+        ),
+        &hex::decode("49960de5880e8c687434170f6476605b8fe4aeb9a28632c7995cf3ba831d97630500000001").unwrap(),
+        r#"{"type":"webauthn.get","challenge":"7tAY5E2mYTdbcILgahylZEjIG7b2b0Sjxu9qK6jGl4w","origin":"http://localhost:3000","crossOrigin":false}"#,
+    )
+    .unwrap();
+
+    let signed_message = SignedMessage::from_proof(proof);
+    assert_eq!(signed_message.signer, Address::from(&public_key));
+    assert_eq!(signed_message.public_key(), &public_key);
+
+    let restored = SignedMessage::from_bytes(&signed_message.to_bytes()).unwrap();
+    assert!(restored.verify(b"I own this address").is_ok());
+    assert!(matches!(
+        restored.verify(b"I own another address"),
+        Err(SignedMessageError::InvalidSignature)
+    ));
+
+    let mut mismatching = restored;
+    mismatching.signer = Address::from(&KeyPair::generate(&mut test_rng(false)));
+    assert!(matches!(
+        mismatching.verify(b"I own this address"),
+        Err(SignedMessageError::SignerMismatch)
+    ));
+}
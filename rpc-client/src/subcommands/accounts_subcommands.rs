@@ -92,8 +92,16 @@ pub enum AccountCommand {
         is_hex: bool,
     },
 
-    /// Queries all accounts in the accounts tree
-    GetAll {},
+    /// Queries the accounts in the accounts tree, ordered by address.
+    GetAll {
+        /// Fetch the accounts after this address, i.e. the next page after the last account of a previous query.
+        #[clap(long)]
+        start_after: Option<Address>,
+
+        /// Max number of accounts to fetch. If absent all accounts are fetched.
+        #[clap(long)]
+        limit: Option<u16>,
+    },
 
     /// Queries the account state (e.g. account balance for basic accounts).
     Get {
@@ -179,8 +187,11 @@ impl HandleSubcommand for AccountCommand {
                 );
             }
 
-            AccountCommand::GetAll {} => {
-                println!("{:#?}", client.blockchain.get_accounts().await?);
+            AccountCommand::GetAll { start_after, limit } => {
+                println!(
+                    "{:#?}",
+                    client.blockchain.get_accounts(start_after, limit).await?
+                );
             }
         }
 
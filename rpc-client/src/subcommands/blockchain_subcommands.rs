@@ -92,6 +92,11 @@ pub enum BlockchainCommand {
         #[clap(long)]
         max: Option<u16>,
 
+        /// Fetch the transactions older than the one with this hash, i.e. the next page after
+        /// the last transaction of a previous query.
+        #[clap(long)]
+        start_after: Option<Blake2bHash>,
+
         /// If set true only the hash of the transactions will be fetched. Otherwise the full transactions will be retrieved.
         #[clap(short = 'h')]
         just_hash: bool,
@@ -123,15 +128,31 @@ pub enum BlockchainCommand {
         address: Address,
     },
 
-    /// Tries to fetch all validators in the staking contract.
-    /// IMPORTANT: This is a very expensive operation, iterating over all existing validators in the contract.
-    Validators {},
+    /// Tries to fetch the validators in the staking contract, ordered by address.
+    /// IMPORTANT: Without a limit, this is a very expensive operation, iterating over all existing validators in the contract.
+    Validators {
+        /// Fetch the validators after this address, i.e. the next page after the last validator of a previous query.
+        #[clap(long)]
+        start_after: Option<Address>,
 
-    /// Tries to fetch all stakers of a given validator.
-    /// IMPORTANT: This is a very expensive operation, iterating over all existing stakers in the contract.
+        /// Max number of validators to fetch. If absent all validators are fetched.
+        #[clap(long)]
+        limit: Option<u16>,
+    },
+
+    /// Tries to fetch the stakers of a given validator, ordered by address.
+    /// IMPORTANT: This is a very expensive operation, iterating over the existing stakers in the contract.
     StakersByValidator {
         /// The validator address to query by.
         address: Address,
+
+        /// Fetch the stakers after this address, i.e. the next page after the last staker of a previous query.
+        #[clap(long)]
+        start_after: Option<Address>,
+
+        /// Max number of stakers to fetch. If absent all stakers are fetched.
+        #[clap(long)]
+        limit: Option<u16>,
     },
 
     /// Tries to fetch a staker information given its address.
@@ -265,6 +286,7 @@ impl HandleSubcommand for BlockchainCommand {
             BlockchainCommand::TransactionsByAddress {
                 address,
                 max,
+                start_after,
                 just_hash,
             } => {
                 if just_hash {
@@ -272,7 +294,7 @@ impl HandleSubcommand for BlockchainCommand {
                         "{:#?}",
                         client
                             .blockchain
-                            .get_transaction_hashes_by_address(address, max)
+                            .get_transaction_hashes_by_address(address, max, start_after)
                             .await?
                     )
                 } else {
@@ -280,7 +302,7 @@ impl HandleSubcommand for BlockchainCommand {
                         "{:#?}",
                         client
                             .blockchain
-                            .get_transactions_by_address(address, max)
+                            .get_transactions_by_address(address, max, start_after)
                             .await?
                     )
                 }
@@ -303,15 +325,22 @@ impl HandleSubcommand for BlockchainCommand {
                 client.blockchain.get_validator_by_address(address).await?
             ),
 
-            BlockchainCommand::Validators {} => {
-                println!("{:#?}", client.blockchain.get_validators().await?)
+            BlockchainCommand::Validators { start_after, limit } => {
+                println!(
+                    "{:#?}",
+                    client.blockchain.get_validators(start_after, limit).await?
+                )
             }
 
-            BlockchainCommand::StakersByValidator { address } => println!(
+            BlockchainCommand::StakersByValidator {
+                address,
+                start_after,
+                limit,
+            } => println!(
                 "{:#?}",
                 client
                     .blockchain
-                    .get_stakers_by_validator_address(address)
+                    .get_stakers_by_validator_address(address, start_after, limit)
                     .await?
             ),
             BlockchainCommand::Staker { address } => {
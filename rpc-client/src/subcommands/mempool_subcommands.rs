@@ -1,6 +1,7 @@
 use anyhow::Error;
 use async_trait::async_trait;
 use clap::Parser;
+use nimiq_hash::Blake2bHash;
 use nimiq_rpc_interface::mempool::MempoolInterface;
 
 use super::accounts_subcommands::HandleSubcommand;
@@ -18,11 +19,19 @@ pub enum MempoolCommand {
         high_priority: bool,
     },
 
-    /// Returns the hashes or the full transactions of the local mempool.
+    /// Returns the hashes or the full transactions of the local mempool, ordered by hash.
     MempoolContent {
         /// Includes the full transactions.
         #[clap(short = 't', long)]
         include_transactions: bool,
+
+        /// Fetch the transactions after this hash, i.e. the next page after the last transaction of a previous query.
+        #[clap(long)]
+        start_after: Option<Blake2bHash>,
+
+        /// Max number of transactions to fetch. If absent all transactions are fetched.
+        #[clap(long)]
+        limit: Option<u16>,
     },
 
     /// Returns information about the local mempool.
@@ -54,10 +63,15 @@ impl HandleSubcommand for MempoolCommand {
             }
             MempoolCommand::MempoolContent {
                 include_transactions,
+                start_after,
+                limit,
             } => {
                 println!(
                     "{:#?}",
-                    client.mempool.mempool_content(include_transactions).await?
+                    client
+                        .mempool
+                        .mempool_content(include_transactions, start_after, limit)
+                        .await?
                 );
             }
             MempoolCommand::MempoolInfo {} => {
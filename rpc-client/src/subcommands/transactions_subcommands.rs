@@ -1,15 +1,21 @@
-use std::str::FromStr;
+use std::{path::PathBuf, str::FromStr};
 
-use anyhow::Error;
+use anyhow::{bail, Error};
 use async_trait::async_trait;
 use clap::{Args, Parser};
+use futures::StreamExt;
 use nimiq_keys::Address;
 use nimiq_primitives::coin::Coin;
 use nimiq_rpc_interface::{
+    blockchain::BlockchainInterface,
     consensus::ConsensusInterface,
-    types::{HashAlgorithm, ValidityStartHeight},
+    types::{BatchPayment, FeePolicy, HashAlgorithm, ValidityStartHeight},
 };
+use nimiq_serde::Serialize;
 use nimiq_transaction::account::htlc_contract::{AnyHash, AnyHash32, AnyHash64, PreImage};
+use nimiq_transaction_builder::{
+    BatchPayment as BatchPaymentBuilder, ExternalSigner, KeyFileSigner, TransactionBuilder,
+};
 
 use super::accounts_subcommands::HandleSubcommand;
 use crate::Client;
@@ -50,10 +56,37 @@ pub enum TransactionCommand {
         /// Recipient for this transaction. This must be a basic account.
         recipient: Address,
 
+        /// Sign the transaction locally with the private key, in hex format, stored in this file
+        /// instead of with the node's wallet. The key must belong to the sender wallet.
+        #[clap(long)]
+        key_file: Option<PathBuf>,
+
         #[clap(flatten)]
         tx_commons: TxCommonWithValue,
     },
 
+    /// Sends basic transactions from the wallet `wallet` to all recipients listed in a CSV file
+    /// and follows their status until they are confirmed or expired.
+    Batch {
+        /// Transactions will be sent from this address. The sender wallet must be unlocked prior to this action.
+        sender_wallet: Address,
+
+        /// A CSV file with one `recipient,value[,data]` payment per line. The value is given in
+        /// NIM and the optional data in hex format.
+        file: PathBuf,
+
+        /// Pay the fee for each byte of the transaction instead of per transaction.
+        #[clap(long)]
+        fee_per_byte: bool,
+
+        /// The maximum number of transactions waiting to be included in a block at the same time.
+        #[clap(long)]
+        max_pending: Option<u32>,
+
+        #[clap(flatten)]
+        tx_commons: TxCommon,
+    },
+
     /* Staker transactions */
     /// Sends a `new_staker` transaction to the network. You need to provide the address of a basic
     /// account (the sender wallet) to pay the transaction fee.
@@ -347,6 +380,39 @@ impl HandleSubcommand for TransactionCommand {
             TransactionCommand::Basic {
                 sender_wallet,
                 recipient,
+                key_file: Some(key_file),
+                tx_commons,
+            } => {
+                let signer = KeyFileSigner::new(key_file)?;
+                if signer.address() != sender_wallet {
+                    bail!("The key file doesn't belong to the sender wallet {sender_wallet}");
+                }
+
+                let block = client.blockchain.get_latest_block(None).await?.data;
+                let tx = TransactionBuilder::new_basic(
+                    &signer,
+                    recipient,
+                    tx_commons.value,
+                    tx_commons.common_tx_fields.fee,
+                    tx_commons
+                        .common_tx_fields
+                        .validity_start_height
+                        .block_number(block.number),
+                    block.network,
+                )?;
+                let raw_tx = hex::encode(tx.serialize_to_vec());
+
+                if tx_commons.common_tx_fields.dry {
+                    println!("{raw_tx}");
+                } else {
+                    let txid = client.consensus.send_raw_transaction(raw_tx).await?;
+                    println!("{txid:#?}");
+                }
+            }
+            TransactionCommand::Basic {
+                sender_wallet,
+                recipient,
+                key_file: None,
                 tx_commons,
             } => {
                 if tx_commons.common_tx_fields.dry {
@@ -375,6 +441,54 @@ impl HandleSubcommand for TransactionCommand {
                     println!("{txid:#?}");
                 }
             }
+            TransactionCommand::Batch {
+                sender_wallet,
+                file,
+                fee_per_byte,
+                max_pending,
+                tx_commons,
+            } => {
+                let payments = BatchPaymentBuilder::parse_csv(&std::fs::read_to_string(file)?)?
+                    .into_iter()
+                    .map(|payment| BatchPayment {
+                        recipient: payment.recipient,
+                        value: payment.value,
+                        data: (!payment.data.is_empty()).then(|| hex::encode(payment.data)),
+                    })
+                    .collect();
+                let fee_policy = if fee_per_byte {
+                    FeePolicy::PerByte(tx_commons.fee)
+                } else {
+                    FeePolicy::Fixed(tx_commons.fee)
+                };
+
+                if tx_commons.dry {
+                    let txs = client
+                        .consensus
+                        .create_batch_payment_transactions(
+                            sender_wallet,
+                            payments,
+                            fee_policy,
+                            tx_commons.validity_start_height,
+                        )
+                        .await?;
+                    println!("{txs:#?}");
+                } else {
+                    let mut stream = client
+                        .consensus
+                        .send_batch_payment(
+                            sender_wallet,
+                            payments,
+                            fee_policy,
+                            tx_commons.validity_start_height,
+                            max_pending,
+                        )
+                        .await?;
+                    while let Some(status) = stream.next().await {
+                        println!("{status:#?}");
+                    }
+                }
+            }
             TransactionCommand::NewStaker {
                 sender_wallet,
                 staker_wallet,
@@ -5,7 +5,7 @@ use nimiq_keys::Address;
 
 use crate::types::{
     Account, Block, BlockLog, BlockchainState, ExecutedTransaction, Inherent, LogType,
-    PenalizedSlots, RPCData, RPCResult, Slot, Staker, Validator,
+    PenalizedSlots, RPCData, RPCResult, SignalEvent, SignalTally, Slot, Staker, Validator,
 };
 
 #[nimiq_jsonrpc_derive::proxy(name = "BlockchainProxy", rename_all = "camelCase")]
@@ -93,22 +93,29 @@ pub trait BlockchainInterface {
     /// Returns the hashes for the latest transactions for a given address. All the transactions
     /// where the given address is listed as a recipient or as a sender are considered. Reward
     /// transactions are also returned. It has an option to specify the maximum number of hashes to
-    /// fetch, it defaults to 500.
+    /// fetch, it defaults to 500 or the maximum result size of the server if that is lower.
+    /// To fetch the next page of older hashes, pass the last hash of the previous page as
+    /// `start_after`. A `start_after` that isn't a transaction of the address is rejected.
     // TODO: includes reward txs
     async fn get_transaction_hashes_by_address(
         &mut self,
         address: Address,
         max: Option<u16>,
+        start_after: Option<Blake2bHash>,
     ) -> RPCResult<Vec<Blake2bHash>, (), Self::Error>;
 
     /// Returns the latest transactions for a given address. All the transactions
     /// where the given address is listed as a recipient or as a sender are considered. Reward
     /// transactions are also returned. It has an option to specify the maximum number of transactions
-    /// to fetch, it defaults to 500.
+    /// to fetch, it defaults to 500 or the maximum result size of the server if that is lower.
+    /// To fetch the next page of older transactions, pass the hash of the last transaction of the
+    /// previous page as `start_after`. A `start_after` that isn't a transaction of the address is
+    /// rejected.
     async fn get_transactions_by_address(
         &mut self,
         address: Address,
         max: Option<u16>,
+        start_after: Option<Blake2bHash>,
     ) -> RPCResult<Vec<ExecutedTransaction>, (), Self::Error>;
 
     /// Tries to fetch the account at the given address.
@@ -117,10 +124,17 @@ pub trait BlockchainInterface {
         address: Address,
     ) -> RPCResult<Account, BlockchainState, Self::Error>;
 
-    /// Fetches all accounts in the accounts tree.
-    /// IMPORTANT: This operation iterates over all accounts in the accounts tree
+    /// Fetches the accounts in the accounts tree, ordered by address. It has an option to specify
+    /// the maximum number of accounts to fetch, all accounts are fetched if it is not given.
+    /// To fetch the next page, pass the address of the last account of the previous page as
+    /// `start_after`.
+    /// IMPORTANT: Without a limit, this operation iterates over all accounts in the accounts tree
     /// and thus is extremely computationally expensive.
-    async fn get_accounts(&mut self) -> RPCResult<Vec<Account>, BlockchainState, Self::Error>;
+    async fn get_accounts(
+        &mut self,
+        start_after: Option<Address>,
+        limit: Option<u16>,
+    ) -> RPCResult<Vec<Account>, BlockchainState, Self::Error>;
 
     /// Returns a collection of the currently active validator's addresses and balances.
     async fn get_active_validators(
@@ -139,23 +153,51 @@ pub trait BlockchainInterface {
         &mut self,
     ) -> RPCResult<PenalizedSlots, BlockchainState, Self::Error>;
 
+    /// Returns the tally of the validators signalling the given value, weighted by the stake of
+    /// the active validators and by the slots the elected validators own in the current epoch.
+    /// The tally is always computed for the current head: tallies of past epochs are not stored,
+    /// so the `epochNumber` of the result is the current epoch. Clients interested in the tally at
+    /// the end of an epoch need to query it before the next election block.
+    async fn get_signal_tally(
+        &mut self,
+        signal: Blake2bHash,
+    ) -> RPCResult<SignalTally, BlockchainState, Self::Error>;
+
+    /// Returns the tallies of all values currently signalled by active or elected validators.
+    /// Like `getSignalTally`, only the tallies at the current head are available.
+    async fn get_signal_tallies(
+        &mut self,
+    ) -> RPCResult<Vec<SignalTally>, BlockchainState, Self::Error>;
+
     /// Tries to fetch a validator information given its address.
     async fn get_validator_by_address(
         &mut self,
         address: Address,
     ) -> RPCResult<Validator, BlockchainState, Self::Error>;
 
-    /// Fetches all validators in the staking contract.
-    /// IMPORTANT: This operation iterates over all validators in the staking contract
-    /// and thus is extremely computationally expensive.
-    async fn get_validators(&mut self) -> RPCResult<Vec<Validator>, BlockchainState, Self::Error>;
+    /// Fetches the validators in the staking contract, ordered by address. It has an option to
+    /// specify the maximum number of validators to fetch, all validators are fetched if it is not
+    /// given. To fetch the next page, pass the address of the last validator of the previous page
+    /// as `start_after`.
+    /// IMPORTANT: Without a limit, this operation iterates over all validators in the staking
+    /// contract and thus is extremely computationally expensive.
+    async fn get_validators(
+        &mut self,
+        start_after: Option<Address>,
+        limit: Option<u16>,
+    ) -> RPCResult<Vec<Validator>, BlockchainState, Self::Error>;
 
-    /// Fetches all stakers for a given validator.
-    /// IMPORTANT: This operation iterates over all stakers of the staking contract
-    /// and thus is extremely computationally expensive.
+    /// Fetches the stakers for a given validator, ordered by address. It has an option to specify
+    /// the maximum number of stakers to fetch, all stakers are fetched if it is not given.
+    /// To fetch the next page, pass the address of the last staker of the previous page as
+    /// `start_after`.
+    /// IMPORTANT: This operation iterates over the stakers of the staking contract until the page
+    /// is filled and thus is computationally expensive.
     async fn get_stakers_by_validator_address(
         &mut self,
         address: Address,
+        start_after: Option<Address>,
+        limit: Option<u16>,
     ) -> RPCResult<Vec<Staker>, BlockchainState, Self::Error>;
 
     /// Tries to fetch a staker information given its address.
@@ -184,6 +226,15 @@ pub trait BlockchainInterface {
         address: Address,
     ) -> Result<BoxStream<'static, RPCData<Validator, BlockchainState>>, Self::Error>;
 
+    /// Subscribes to signal threshold events. An event is emitted when the percentage (0-100) of
+    /// the active stake signalling a value reaches the given threshold, or drops below it again.
+    /// Signals are tallied after every finalized macro block.
+    #[stream]
+    async fn subscribe_for_signal_threshold(
+        &mut self,
+        threshold: f64,
+    ) -> Result<BoxStream<'static, RPCData<SignalEvent, BlockchainState>>, Self::Error>;
+
     /// Subscribes to log events related to a given list of addresses and of any of the log types provided.
     /// If addresses is empty it does not filter by address. If log_types is empty it won't filter by log types.
     /// Thus the behavior is to assume all addresses or log_types are to be provided if the corresponding vec is empty.
@@ -1,10 +1,14 @@
 use async_trait::async_trait;
+use futures::stream::BoxStream;
 use nimiq_hash::Blake2bHash;
 use nimiq_keys::Address;
 use nimiq_primitives::coin::Coin;
 use nimiq_transaction::account::htlc_contract::{AnyHash, PreImage};
 
-use crate::types::{RPCResult, Transaction, ValidityStartHeight};
+use crate::types::{
+    BatchPayment, BatchTransactionStatus, BlockchainState, FeePolicy, RPCData, RPCResult,
+    Transaction, ValidityStartHeight,
+};
 
 #[nimiq_jsonrpc_derive::proxy(name = "ConsensusProxy", rename_all = "camelCase")]
 #[async_trait]
@@ -70,6 +74,32 @@ pub trait ConsensusInterface {
         validity_start_height: ValidityStartHeight,
     ) -> RPCResult<Blake2bHash, (), Self::Error>;
 
+    /// Returns the serialized basic transactions paying all recipients of the batch, in order. All
+    /// transactions share the fee policy and the validity start height.
+    async fn create_batch_payment_transactions(
+        &mut self,
+        wallet: Address,
+        payments: Vec<BatchPayment>,
+        fee_policy: FeePolicy,
+        validity_start_height: ValidityStartHeight,
+    ) -> RPCResult<Vec<String>, (), Self::Error>;
+
+    /// Sends the basic transactions paying all recipients of the batch and reports the status of
+    /// each transaction until it is confirmed, failed, expired or rejected. At most `max_pending`
+    /// transactions (default 64) wait to be included in a block at the same time, the others are
+    /// queued so the batch doesn't flood the mempool. Transactions that can't be sent, e.g. because
+    /// the mempool is full, are retried with the following blocks, and pending transactions are
+    /// sent again if they aren't included for a while.
+    #[stream]
+    async fn send_batch_payment(
+        &mut self,
+        wallet: Address,
+        payments: Vec<BatchPayment>,
+        fee_policy: FeePolicy,
+        validity_start_height: ValidityStartHeight,
+        max_pending: Option<u32>,
+    ) -> Result<BoxStream<'static, RPCData<BatchTransactionStatus, BlockchainState>>, Self::Error>;
+
     /// Returns a serialized transaction creating a new vesting contract.
     async fn create_new_vesting_transaction(
         &mut self,
@@ -8,4 +8,5 @@ mod serde_helpers;
 pub mod types;
 pub mod validator;
 pub mod wallet;
+pub mod watchtower;
 pub mod zkp_component;
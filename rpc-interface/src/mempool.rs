@@ -19,10 +19,15 @@ pub trait MempoolInterface {
         raw_tx: String,
     ) -> RPCResult<Blake2bHash, (), Self::Error>;
 
-    /// Obtains the list of transactions that are currently in the mempool.
+    /// Obtains the list of transactions that are currently in the mempool, ordered by hash. It has
+    /// an option to specify the maximum number of transactions to fetch, all transactions are
+    /// fetched if it is not given. To fetch the next page, pass the hash of the last transaction of
+    /// the previous page as `start_after`.
     async fn mempool_content(
         &mut self,
         include_transactions: bool,
+        start_after: Option<Blake2bHash>,
+        limit: Option<u16>,
     ) -> RPCResult<Vec<HashOrTx>, (), Self::Error>;
 
     /// Obtains the mempool content in fee per byte buckets.
@@ -443,6 +443,43 @@ pub enum EquivocationProof {
     DoubleVote(DoubleVoteProof),
 }
 
+/// An equivocation observed by the watchtower that can still be reported.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeenEquivocation {
+    pub validator_address: Address,
+    pub block_number: u32,
+    pub proof: EquivocationProof,
+    /// Whether the equivocation was detected by this node or received from a peer.
+    pub source: EquivocationSource,
+    /// The block number of our head when we first saw the equivocation.
+    pub seen_at: u32,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EquivocationSource {
+    Local,
+    Network,
+}
+
+impl From<nimiq_consensus::watchtower::SeenEquivocation> for SeenEquivocation {
+    fn from(equivocation: nimiq_consensus::watchtower::SeenEquivocation) -> Self {
+        SeenEquivocation {
+            validator_address: equivocation.proof.validator_address().clone(),
+            block_number: equivocation.proof.block_number(),
+            proof: equivocation.proof.into(),
+            source: match equivocation.source {
+                nimiq_consensus::watchtower::EquivocationSource::Local => EquivocationSource::Local,
+                nimiq_consensus::watchtower::EquivocationSource::Network => {
+                    EquivocationSource::Network
+                }
+            },
+            seen_at: equivocation.seen_at,
+        }
+    }
+}
+
 impl From<nimiq_block::EquivocationProof> for EquivocationProof {
     fn from(proof: nimiq_block::EquivocationProof) -> Self {
         match proof {
@@ -724,6 +761,40 @@ pub struct ReturnAccount {
     pub private_key: PrivateKey,
 }
 
+/// A newly created HD wallet.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReturnHdWallet {
+    /// The identifier of the HD wallet, which is the address of its master key.
+    pub wallet_id: Address,
+    /// The mnemonic of the HD wallet. It must be backed up to be able to restore the wallet.
+    pub mnemonic: String,
+}
+
+/// An account that was derived from an HD wallet.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReturnDerivedAccount {
+    /// The address of the derived account.
+    pub address: Address,
+    /// The public key of the derived account.
+    pub public_key: Ed25519PublicKey,
+    /// The derivation path of the account.
+    pub path: String,
+}
+
+/// An address that is watched by the wallet without holding its key.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReturnWatchOnlyAddress {
+    /// The watched address.
+    pub address: Address,
+    /// The label of the address, if any.
+    pub label: Option<String>,
+    /// The current balance of the address. Only known to full nodes.
+    pub balance: Option<Coin>,
+}
+
 #[serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase", tag = "type")]
@@ -889,6 +960,123 @@ impl Validator {
     }
 }
 
+/// The tally of the validators signalling a given value through their `signal_data`. The stake is
+/// that of the active validators, the slots are those of the validators elected for the current
+/// epoch, including the ones that have been deactivated or jailed since.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignalTally {
+    pub signal: Blake2bHash,
+    /// The epoch the tally was computed in. Tallies are only available for the current epoch.
+    pub epoch_number: u32,
+    pub validators: Vec<Address>,
+    pub stake: Coin,
+    pub total_stake: Coin,
+    /// Percentage (0-100) of the active stake signalling the value.
+    pub stake_percentage: f64,
+    pub slots: u16,
+    /// Percentage (0-100) of the current epoch's slots signalling the value.
+    pub slot_percentage: f64,
+}
+
+impl From<nimiq_blockchain::SignalTally> for SignalTally {
+    fn from(tally: nimiq_blockchain::SignalTally) -> Self {
+        SignalTally {
+            stake_percentage: tally.stake_ratio() * 100.0,
+            slot_percentage: tally.slot_ratio() * 100.0,
+            signal: tally.signal,
+            epoch_number: tally.epoch_number,
+            validators: tally.validators,
+            stake: tally.stake,
+            total_stake: tally.total_stake,
+            slots: tally.slots,
+        }
+    }
+}
+
+/// Notification that the stake signalling a value crossed a threshold.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum SignalEvent {
+    ThresholdReached { tally: SignalTally },
+    ThresholdLost { signal: Blake2bHash },
+}
+
+impl From<nimiq_blockchain::SignalEvent> for SignalEvent {
+    fn from(event: nimiq_blockchain::SignalEvent) -> Self {
+        match event {
+            nimiq_blockchain::SignalEvent::ThresholdReached(tally) => {
+                SignalEvent::ThresholdReached {
+                    tally: tally.into(),
+                }
+            }
+            nimiq_blockchain::SignalEvent::ThresholdLost(signal) => {
+                SignalEvent::ThresholdLost { signal }
+            }
+        }
+    }
+}
+
+/// A payment of a batch payment. The optional data is given in hexadecimal format.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchPayment {
+    pub recipient: Address,
+    pub value: Coin,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<String>,
+}
+
+/// How the fee of each transaction of a batch payment is determined: Either the same fee for
+/// every transaction or a fee per byte of the serialized transaction.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type", content = "fee")]
+pub enum FeePolicy {
+    Fixed(Coin),
+    PerByte(Coin),
+}
+
+/// The state of a transaction of a batch payment.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum BatchTransactionState {
+    /// The transaction waits to be sent until fewer transactions of the batch are pending.
+    Queued,
+    /// The transaction was sent and waits to be included in a block.
+    Pending,
+    /// The transaction was included in a block.
+    Confirmed,
+    /// The transaction was included in a block, but failed. Only the fee was paid.
+    Failed,
+    /// The transaction wasn't included before the end of its validity window.
+    Expired,
+    /// The transaction couldn't be sent to the network, even after retrying.
+    Rejected,
+}
+
+impl BatchTransactionState {
+    /// Returns if the transaction won't change its state anymore, unless its block is reverted.
+    pub fn is_final(&self) -> bool {
+        !matches!(self, Self::Queued | Self::Pending)
+    }
+}
+
+/// The status of a transaction of a batch payment.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchTransactionStatus {
+    /// The index of the payment in the batch.
+    pub index: usize,
+    pub hash: Blake2bHash,
+    pub state: BatchTransactionState,
+    /// The block the transaction was included in.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_number: Option<u32>,
+    /// The reason why the transaction was rejected.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
 pub type RPCResult<T, S, E> = Result<RPCData<T, S>, E>;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -1095,10 +1283,15 @@ pub fn is_of_log_type_and_related_to_addresses(
 pub struct ZKPState {
     latest_block: Block,
     latest_proof: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    proving_job: Option<ProvingJob>,
 }
 
 impl ZKPState {
-    pub fn with_zkp_state(zkp_state: &nimiq_zkp_component::types::ZKPState) -> Self {
+    pub fn with_zkp_state(
+        zkp_state: &nimiq_zkp_component::types::ZKPState,
+        proving_progress: Option<&nimiq_zkp_component::proving_job::ProvingProgress>,
+    ) -> Self {
         let latest_block =
             Block::from_macro_block(None, zkp_state.latest_block.clone(), true).unwrap();
         let latest_proof = zkp_state
@@ -1109,10 +1302,73 @@ impl ZKPState {
         Self {
             latest_block,
             latest_proof,
+            proving_job: proving_progress.map(ProvingJob::from_proving_progress),
         }
     }
 }
 
+/// The zk proof for an election block.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ZKProof {
+    pub block_number: u32,
+    /// The compressed proof as hex.
+    pub proof: String,
+}
+
+impl ZKProof {
+    pub fn from_zk_proof(zk_proof: &nimiq_zkp_component::types::ZKProof) -> Option<Self> {
+        Some(ZKProof {
+            block_number: zk_proof.block_number,
+            proof: hex::encode(zk_proof.compressed_proof()?),
+        })
+    }
+}
+
+/// The proof generation of the local prover for an election block.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProvingJob {
+    pub previous_block_number: u32,
+    pub block_number: u32,
+    pub block_hash: Blake2bHash,
+    pub status: String,
+    pub attempts: u32,
+    pub sub_circuits: Vec<SubCircuitProgress>,
+}
+
+impl ProvingJob {
+    pub fn from_proving_progress(
+        progress: &nimiq_zkp_component::proving_job::ProvingProgress,
+    ) -> Self {
+        ProvingJob {
+            previous_block_number: progress.job.previous_block_number,
+            block_number: progress.job.block_number,
+            block_hash: progress.job.block_hash.clone(),
+            status: progress.job.status.to_string(),
+            attempts: progress.job.attempts,
+            sub_circuits: progress
+                .sub_circuits
+                .iter()
+                .map(|sub_circuit| SubCircuitProgress {
+                    circuit: sub_circuit.circuit.to_string(),
+                    completed: sub_circuit.completed as u32,
+                    total: sub_circuit.total as u32,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// The number of generated proofs of a sub-circuit of a proving job.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubCircuitProgress {
+    pub circuit: String,
+    pub completed: u32,
+    pub total: u32,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MempoolInfo {
@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use nimiq_hash::Blake2bHash;
 use nimiq_keys::Address;
 
 use crate::types::RPCResult;
@@ -23,6 +24,16 @@ pub trait ValidatorInterface {
         automatic_reactivate: bool,
     ) -> RPCResult<(), (), Self::Error>;
 
+    /// Returns the signal data our validator operator wants to signal for chain upgrades.
+    async fn get_signal_data(&mut self) -> RPCResult<Option<Blake2bHash>, (), Self::Error>;
+
+    /// Updates the signal data our validator operator wants to signal for chain upgrades.
+    /// This does not send an update validator transaction, which needs the validator's cold key.
+    async fn set_signal_data(
+        &mut self,
+        signal_data: Option<Blake2bHash>,
+    ) -> RPCResult<(), (), Self::Error>;
+
     /// Returns if our validator is currently elected.
     async fn is_validator_elected(&mut self) -> RPCResult<bool, (), Self::Error>;
 
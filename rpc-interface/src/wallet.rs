@@ -1,7 +1,12 @@
 use async_trait::async_trait;
+use futures::stream::BoxStream;
 use nimiq_keys::{Address, Ed25519PublicKey, Ed25519Signature};
+use nimiq_primitives::coin::Coin;
 
-use crate::types::{RPCResult, ReturnAccount, ReturnSignature};
+use crate::types::{
+    BlockLog, BlockchainState, LogType, RPCData, RPCResult, ReturnAccount, ReturnDerivedAccount,
+    ReturnHdWallet, ReturnSignature, ReturnWatchOnlyAddress, ValidityStartHeight,
+};
 
 #[nimiq_jsonrpc_derive::proxy(name = "WalletProxy", rename_all = "camelCase")]
 #[async_trait]
@@ -61,4 +66,160 @@ pub trait WalletInterface {
         signature: Ed25519Signature,
         is_hex: bool,
     ) -> RPCResult<bool, (), Self::Error>;
+
+    /// Signs the message with the account and returns a signed message envelope in hexadecimal
+    /// format. The envelope contains the signer's address, public key and signature, and can be
+    /// verified with `verifySignedMessage` or any other Nimiq implementation.
+    async fn create_signed_message(
+        &mut self,
+        message: String,
+        address: Address,
+        passphrase: Option<String>,
+        is_hex: bool,
+    ) -> RPCResult<String, (), Self::Error>;
+
+    /// Verifies a signed message envelope, in hexadecimal format, for the message. Envelopes of
+    /// all key types are supported, including Webauthn signatures of passkey-controlled accounts.
+    /// If an address is given, the envelope must also have been signed by it.
+    async fn verify_signed_message(
+        &mut self,
+        message: String,
+        signed_message: String,
+        address: Option<Address>,
+        is_hex: bool,
+    ) -> RPCResult<bool, (), Self::Error>;
+
+    /// Generates a new HD wallet from a random mnemonic and stores its seed locked with the
+    /// passphrase. Returns the identifier of the wallet and its mnemonic.
+    async fn create_hd_wallet(
+        &mut self,
+        passphrase: Option<String>,
+    ) -> RPCResult<ReturnHdWallet, (), Self::Error>;
+
+    /// Imports an HD wallet from a BIP39 mnemonic and stores its seed locked with the passphrase.
+    /// Returns the identifier of the wallet.
+    async fn import_mnemonic(
+        &mut self,
+        mnemonic: String,
+        passphrase: Option<String>,
+    ) -> RPCResult<Address, (), Self::Error>;
+
+    /// Derives an account from the HD wallet, either by its SLIP-10 `path` or by its `index` in
+    /// the default Nimiq path `m/44'/242'/0'/{index}'`. Without both, the account at index 0 is
+    /// derived. The account is imported locked with the passphrase of the HD wallet.
+    async fn derive_account(
+        &mut self,
+        wallet_id: Address,
+        path: Option<String>,
+        index: Option<u32>,
+        passphrase: Option<String>,
+    ) -> RPCResult<ReturnDerivedAccount, (), Self::Error>;
+
+    /// Returns the accounts that have been derived from the HD wallet.
+    async fn list_derived_accounts(
+        &mut self,
+        wallet_id: Address,
+    ) -> RPCResult<Vec<ReturnDerivedAccount>, (), Self::Error>;
+
+    /// Exports all accounts and HD wallets as a backup encrypted with the password, in
+    /// hexadecimal format. The accounts stay locked with their own passphrases inside the backup.
+    async fn export_wallet(&mut self, password: String) -> RPCResult<String, (), Self::Error>;
+
+    /// Imports a backup created by `exportWallet`, checking its integrity. By default the backup
+    /// is merged into the existing wallet data; with `replace` set, the existing data is removed
+    /// first. Returns the addresses of the imported accounts.
+    async fn import_wallet(
+        &mut self,
+        backup: String,
+        password: String,
+        replace: Option<bool>,
+    ) -> RPCResult<Vec<Address>, (), Self::Error>;
+
+    /// Adds an address to watch without holding its key, with an optional label. Adding an
+    /// address that is already watched updates its label.
+    async fn add_watch_only_address(
+        &mut self,
+        address: Address,
+        label: Option<String>,
+    ) -> RPCResult<(), (), Self::Error>;
+
+    /// Stops watching the address. Returns if the address was watched.
+    async fn remove_watch_only_address(
+        &mut self,
+        address: Address,
+    ) -> RPCResult<bool, (), Self::Error>;
+
+    /// Returns the watch-only addresses along with their current balances.
+    async fn list_watch_only_addresses(
+        &mut self,
+    ) -> RPCResult<Vec<ReturnWatchOnlyAddress>, BlockchainState, Self::Error>;
+
+    /// Creates an unsigned basic transaction from a watch-only address, serialized in hexadecimal
+    /// format. The transaction can be signed offline, e.g. with `nimiq-signtx --stdin`.
+    async fn create_unsigned_basic_transaction(
+        &mut self,
+        sender: Address,
+        recipient: Address,
+        value: Coin,
+        fee: Coin,
+        validity_start_height: ValidityStartHeight,
+    ) -> RPCResult<String, (), Self::Error>;
+
+    /// Creates a partially signed transaction for a basic transaction from the multisig address
+    /// of the `public_keys` with `min_signatures`, to be signed by the owners in `signers`. Returns
+    /// it in hexadecimal format, to be passed to the signers in turn or in parallel.
+    async fn create_partially_signed_transaction(
+        &mut self,
+        sender: Address,
+        recipient: Address,
+        value: Coin,
+        fee: Coin,
+        validity_start_height: ValidityStartHeight,
+        public_keys: Vec<Ed25519PublicKey>,
+        min_signatures: u8,
+        signers: Vec<Ed25519PublicKey>,
+    ) -> RPCResult<String, (), Self::Error>;
+
+    /// Adds new commitments of the account to the partially signed transaction and returns the
+    /// updated one. The secret nonces of the commitments are kept in memory until the account
+    /// signs the transaction with `signPartiallySignedTransaction`.
+    async fn add_multisig_commitments(
+        &mut self,
+        partially_signed_transaction: String,
+        address: Address,
+        passphrase: Option<String>,
+    ) -> RPCResult<String, (), Self::Error>;
+
+    /// Adds the partial signature of the account to the partially signed transaction and returns
+    /// the updated one. Requires the commitments of all signers, the account's own ones having
+    /// been added with `addMultisigCommitments` on this node.
+    async fn sign_partially_signed_transaction(
+        &mut self,
+        partially_signed_transaction: String,
+        address: Address,
+        passphrase: Option<String>,
+    ) -> RPCResult<String, (), Self::Error>;
+
+    /// Merges the commitments and partial signatures of partially signed transactions of the same
+    /// signing round.
+    async fn combine_partially_signed_transactions(
+        &mut self,
+        partially_signed_transactions: Vec<String>,
+    ) -> RPCResult<String, (), Self::Error>;
+
+    /// Combines the partial signatures of all signers and returns the signed transaction in
+    /// hexadecimal format, ready to be sent with `sendRawTransaction`.
+    async fn finalize_partially_signed_transaction(
+        &mut self,
+        partially_signed_transaction: String,
+    ) -> RPCResult<String, (), Self::Error>;
+
+    /// Subscribes to log events related to any of the watch-only addresses and of any of the log
+    /// types provided. If log_types is empty it won't filter by log types. Addresses that are
+    /// added or removed later are taken into account for the following blocks.
+    #[stream]
+    async fn subscribe_for_watch_only_addresses(
+        &mut self,
+        log_types: Vec<LogType>,
+    ) -> Result<BoxStream<'static, RPCData<BlockLog, BlockchainState>>, Self::Error>;
 }
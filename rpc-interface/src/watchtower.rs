@@ -0,0 +1,33 @@
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use nimiq_keys::Address;
+
+use crate::types::{RPCData, RPCResult, SeenEquivocation};
+
+#[nimiq_jsonrpc_derive::proxy(name = "WatchtowerProxy", rename_all = "camelCase")]
+#[async_trait]
+pub trait WatchtowerInterface {
+    type Error;
+
+    /// Returns all equivocations observed by the watchtower that can still be reported.
+    ///
+    /// The watchtower only detects forks of micro blocks by itself. Double votes are only included
+    /// if they were detected by the validator running on this node or gossiped by a peer, and
+    /// double proposals only if a peer gossiped a proof for them. An empty result therefore doesn't
+    /// mean that no validator equivocated.
+    async fn get_seen_equivocations(&mut self)
+        -> RPCResult<Vec<SeenEquivocation>, (), Self::Error>;
+
+    /// Returns the equivocations of the given validator observed by the watchtower that can
+    /// still be reported. See `getSeenEquivocations` for which equivocations are observed.
+    async fn get_seen_equivocations_by_validator(
+        &mut self,
+        address: Address,
+    ) -> RPCResult<Vec<SeenEquivocation>, (), Self::Error>;
+
+    /// Subscribes to equivocations newly observed by the watchtower.
+    #[stream]
+    async fn subscribe_for_equivocations(
+        &mut self,
+    ) -> Result<BoxStream<'static, RPCData<SeenEquivocation, ()>>, Self::Error>;
+}
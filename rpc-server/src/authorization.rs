@@ -0,0 +1,214 @@
+use std::{collections::HashSet, future::Future, net::IpAddr, sync::Arc};
+
+use async_trait::async_trait;
+use nimiq_jsonrpc_core::{Request, Response};
+use nimiq_jsonrpc_server::Dispatcher;
+use tokio::sync::mpsc;
+use warp::ws::Message;
+
+use crate::error::{error_response, Error};
+
+tokio::task_local! {
+    /// The caller of the request that is currently being dispatched.
+    static CALLER: Caller;
+}
+
+/// The authenticated caller of an RPC request.
+#[derive(Clone, Debug)]
+pub struct Caller {
+    /// The name of the user, or `None` for callers without credentials.
+    pub username: Option<String>,
+    /// The IP address the request was sent from, if known.
+    pub ip: Option<IpAddr>,
+    /// The methods the caller may call, or `None` if all methods are allowed.
+    pub allowed_methods: Option<Arc<HashSet<String>>>,
+}
+
+impl Caller {
+    /// Returns the caller of the request that is currently being dispatched.
+    pub fn current() -> Option<Caller> {
+        CALLER.try_with(Clone::clone).ok()
+    }
+
+    /// Runs the future, usually the dispatch of a request, on behalf of this caller.
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        CALLER.scope(self, future).await
+    }
+
+    /// The name of the caller in the logs.
+    pub fn name(&self) -> &str {
+        self.username.as_deref().unwrap_or("anonymous")
+    }
+
+    /// Returns if the caller may call the method.
+    pub fn is_allowed(&self, method: &str) -> bool {
+        self.allowed_methods
+            .as_ref()
+            .map_or(true, |allowed_methods| allowed_methods.contains(method))
+    }
+}
+
+/// A dispatcher that only dispatches the methods the caller is allowed to call, and logs the
+/// denied calls. Requests that are not dispatched on behalf of a caller are denied.
+#[derive(Clone)]
+pub struct AuthorizedDispatcher<D> {
+    inner: D,
+}
+
+impl<D: Dispatcher> AuthorizedDispatcher<D> {
+    pub fn new(inner: D) -> Self {
+        AuthorizedDispatcher { inner }
+    }
+}
+
+#[async_trait]
+impl<D: Dispatcher> Dispatcher for AuthorizedDispatcher<D> {
+    async fn dispatch(
+        &mut self,
+        request: Request,
+        tx: Option<&mpsc::Sender<Message>>,
+        id: u64,
+    ) -> Option<Response> {
+        let caller = Caller::current();
+        if !caller
+            .as_ref()
+            .map_or(false, |caller| caller.is_allowed(&request.method))
+        {
+            log::warn!(
+                username = caller.as_ref().map_or("none", Caller::name),
+                ip = ?caller.as_ref().and_then(|caller| caller.ip),
+                method = %request.method,
+                "RPC call denied: method not allowed"
+            );
+            return error_response(request.id, Error::MethodNotAllowed(request.method));
+        }
+
+        self.inner.dispatch(request, tx, id).await
+    }
+
+    fn match_method(&self, name: &str) -> bool {
+        self.inner.match_method(name)
+    }
+
+    fn method_names(&self) -> Vec<&str> {
+        self.inner.method_names()
+    }
+}
+
+/// The RPC methods of a dispatcher, grouped under the namespace used in method patterns, e.g.
+/// `wallet` for the methods of the wallet dispatcher.
+pub struct MethodNamespace {
+    pub name: &'static str,
+    pub methods: Vec<String>,
+}
+
+/// Returns if the method name matches the glob pattern, in which `*` matches any number of
+/// characters.
+pub fn matches_glob(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    // `split` always yields at least one part.
+    let first = parts.next().unwrap();
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+
+    let mut parts: Vec<&str> = parts.collect();
+    let Some(last) = parts.pop() else {
+        // There was no `*`, so the whole name must match.
+        return rest.is_empty();
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+/// Resolves method patterns to the names of the methods they allow.
+///
+/// A pattern is a glob like `get*`, matched against the methods of all namespaces, optionally
+/// prefixed with a namespace like `wallet.*` to only match the methods of that namespace.
+/// Patterns that don't match any method are returned separately, as they are most likely typos.
+pub fn resolve_method_patterns<'a>(
+    patterns: &'a [String],
+    namespaces: &[MethodNamespace],
+) -> (HashSet<String>, Vec<&'a str>) {
+    let mut allowed = HashSet::new();
+    let mut unmatched = vec![];
+
+    for pattern in patterns {
+        let (namespace, glob) = match pattern.split_once('.') {
+            Some((namespace, glob)) => (Some(namespace), glob),
+            None => (None, pattern.as_str()),
+        };
+
+        let mut matched = false;
+        for method in namespaces
+            .iter()
+            .filter(|ns| namespace.map_or(true, |namespace| ns.name == namespace))
+            .flat_map(|ns| &ns.methods)
+            .filter(|method| matches_glob(glob, method))
+        {
+            allowed.insert(method.clone());
+            matched = true;
+        }
+        if !matched {
+            unmatched.push(pattern.as_str());
+        }
+    }
+
+    (allowed, unmatched)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_matches_globs() {
+        assert!(matches_glob("getBlockNumber", "getBlockNumber"));
+        assert!(!matches_glob("getBlock", "getBlockNumber"));
+        assert!(matches_glob("get*", "getBlockNumber"));
+        assert!(matches_glob("*Number", "getBlockNumber"));
+        assert!(matches_glob("get*By*", "getBlockByHash"));
+        assert!(!matches_glob("get*By*Number", "getBlockByHash"));
+        assert!(matches_glob("*", "peerCount"));
+        assert!(!matches_glob("send*", "getBlockNumber"));
+    }
+
+    #[test]
+    fn it_resolves_patterns_per_namespace() {
+        let namespaces = vec![
+            MethodNamespace {
+                name: "blockchain",
+                methods: vec!["getBlockNumber".to_string(), "getAccount".to_string()],
+            },
+            MethodNamespace {
+                name: "wallet",
+                methods: vec!["getAccounts".to_string(), "unlockAccount".to_string()],
+            },
+        ];
+
+        let (allowed, unmatched) = resolve_method_patterns(&["get*".to_string()], &namespaces);
+        assert_eq!(allowed.len(), 3);
+        assert!(unmatched.is_empty());
+
+        let patterns = vec![
+            "wallet.*".to_string(),
+            "blockchain.getBlockNumber".to_string(),
+            "wallet.getBlockNumber".to_string(),
+        ];
+        let (allowed, unmatched) = resolve_method_patterns(&patterns, &namespaces);
+        assert_eq!(
+            allowed,
+            HashSet::from([
+                "getAccounts".to_string(),
+                "unlockAccount".to_string(),
+                "getBlockNumber".to_string(),
+            ])
+        );
+        assert_eq!(unmatched, vec!["wallet.getBlockNumber"]);
+    }
+}
@@ -1,6 +1,11 @@
 use async_trait::async_trait;
-use futures::{future, stream::BoxStream, StreamExt};
+use futures::{
+    future,
+    stream::{self, BoxStream},
+    StreamExt,
+};
 use nimiq_account::{BlockLog as BBlockLog, TransactionLog};
+use nimiq_blockchain::SignalThresholdTracker;
 use nimiq_blockchain_interface::{AbstractBlockchain, BlockchainEvent};
 use nimiq_blockchain_proxy::{BlockchainProxy, BlockchainReadProxy};
 use nimiq_hash::Blake2bHash;
@@ -10,21 +15,62 @@ use nimiq_rpc_interface::{
     blockchain::BlockchainInterface,
     types::{
         is_of_log_type_and_related_to_addresses, Account, Block, BlockLog, BlockchainState,
-        ExecutedTransaction, Inherent, LogType, PenalizedSlots, RPCData, RPCResult, Slot, Staker,
-        Validator,
+        ExecutedTransaction, Inherent, LogType, PenalizedSlots, RPCData, RPCResult, SignalEvent,
+        SignalTally, Slot, Staker, Validator,
     },
 };
 use tokio_stream::wrappers::BroadcastStream;
 
-use crate::error::Error;
+use crate::{error::Error, limits::check_deadline};
 
+#[derive(Clone)]
 pub struct BlockchainDispatcher {
     blockchain: BlockchainProxy,
+    /// The maximum number of items returned by methods that return lists.
+    max_result_size: Option<usize>,
 }
 
 impl BlockchainDispatcher {
-    pub fn new(blockchain: BlockchainProxy) -> Self {
-        Self { blockchain }
+    pub fn new(blockchain: BlockchainProxy, max_result_size: Option<usize>) -> Self {
+        Self {
+            blockchain,
+            max_result_size,
+        }
+    }
+
+    /// Fails if a result of `len` items exceeds the maximum result size.
+    fn check_result_size(&self, len: usize) -> Result<(), Error> {
+        match self.max_result_size {
+            Some(max_result_size) if len > max_result_size => {
+                Err(Error::ResultTooLarge(max_result_size))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the requested maximum number of items, which defaults to 500 but never exceeds the
+    /// maximum result size.
+    fn max_items(&self, max: Option<u16>) -> Result<u16, Error> {
+        let Some(max) = max else {
+            let max_result_size = self.max_result_size.unwrap_or(usize::MAX);
+            return Ok(500.min(max_result_size) as u16);
+        };
+        self.check_result_size(max as usize)?;
+        Ok(max)
+    }
+
+    /// Returns the number of items to fetch for a page with the requested limit. Without a limit,
+    /// one item more than the maximum result size is fetched, so that an oversized result fails.
+    fn page_size(&self, limit: Option<u16>) -> Result<usize, Error> {
+        match limit {
+            Some(limit) => {
+                self.check_result_size(limit as usize)?;
+                Ok(limit as usize)
+            }
+            None => Ok(self.max_result_size.map_or(usize::MAX, |max_result_size| {
+                max_result_size.saturating_add(1)
+            })),
+        }
     }
 }
 
@@ -45,6 +91,94 @@ fn get_block_by_hash(
         .map(|block| block.into())
 }
 
+/// Filters the logs of a block event down to those of any of the given log types that are related
+/// to any of the given addresses. Empty filters match all addresses or log types respectively.
+/// Returns `None` if the block has no transaction logs or inherent logs of interest.
+pub(crate) fn filter_block_log(
+    event: BBlockLog,
+    addresses: &[Address],
+    log_types: &[LogType],
+) -> Option<RPCData<BlockLog, BlockchainState>> {
+    // Since each TransactionLog has its own vec of logs, we iterate over each tx_logs and filter their logs,
+    // if a tx_log has no logs after filtering, it will be filtered out completely.
+    let filter_tx_logs = |tx_logs: Vec<TransactionLog>| -> Vec<TransactionLog> {
+        tx_logs
+            .into_iter()
+            .filter_map(|mut tx_log| {
+                tx_log.logs.retain(|log| {
+                    is_of_log_type_and_related_to_addresses(log, addresses, log_types)
+                });
+                if tx_log.logs.is_empty() {
+                    None
+                } else {
+                    Some(tx_log)
+                }
+            })
+            .collect()
+    };
+
+    match event {
+        BBlockLog::AppliedBlock {
+            mut inherent_logs,
+            block_hash,
+            block_number,
+            timestamp,
+            tx_logs,
+            total_tx_size: _,
+        } => {
+            // Collects the inherents that are related to any of the addresses specified and of any of the log types provided.
+            inherent_logs
+                .retain(|log| is_of_log_type_and_related_to_addresses(log, addresses, log_types));
+            let tx_logs = filter_tx_logs(tx_logs);
+
+            // If this block has no transaction logs or inherent logs of interest, we return None. Otherwise, we return the filtered BlockLog.
+            // This way the stream only emits an event if a block has at least one log fulfilling the specified criteria.
+            if !inherent_logs.is_empty() || !tx_logs.is_empty() {
+                Some(RPCData::new(
+                    BlockLog::AppliedBlock {
+                        inherent_logs,
+                        timestamp,
+                        tx_logs,
+                    },
+                    BlockchainState {
+                        block_number,
+                        block_hash,
+                    },
+                ))
+            } else {
+                None
+            }
+        }
+        BBlockLog::RevertedBlock {
+            mut inherent_logs,
+            block_hash,
+            block_number,
+            tx_logs,
+            total_tx_size: _,
+        } => {
+            // Filters the inherents and tx_logs the same way as the AppliedBlock
+            inherent_logs
+                .retain(|log| is_of_log_type_and_related_to_addresses(log, addresses, log_types));
+            let tx_logs = filter_tx_logs(tx_logs);
+
+            if !inherent_logs.is_empty() || !tx_logs.is_empty() {
+                Some(RPCData::new(
+                    BlockLog::RevertedBlock {
+                        inherent_logs,
+                        tx_logs,
+                    },
+                    BlockchainState {
+                        block_number,
+                        block_hash,
+                    },
+                ))
+            } else {
+                None
+            }
+        }
+    }
+}
+
 /// Tries to fetch a validator information given its address.
 /// This function requires the read lock acquisition prior to its execution.
 fn get_validator_by_address(
@@ -70,6 +204,15 @@ fn get_validator_by_address(
     }
 }
 
+/// Returns the error for a `start_after` cursor that isn't a transaction of the given address.
+fn unknown_cursor(address: &Address, start_after: Option<&Blake2bHash>) -> Error {
+    Error::InvalidArgument(format!(
+        "Unknown cursor: {} is not a transaction of {}",
+        start_after.map(ToString::to_string).unwrap_or_default(),
+        address.to_user_friendly_address(),
+    ))
+}
+
 #[nimiq_jsonrpc_derive::service(rename_all = "camelCase")]
 #[async_trait]
 impl BlockchainInterface for BlockchainDispatcher {
@@ -217,6 +360,7 @@ impl BlockchainInterface for BlockchainDispatcher {
                     ));
                 }
             }
+            self.check_result_size(transactions.len())?;
 
             Ok(transactions.into())
         } else {
@@ -242,6 +386,7 @@ impl BlockchainInterface for BlockchainDispatcher {
                     inherents.push(inherent);
                 }
             }
+            self.check_result_size(inherents.len())?;
 
             Ok(inherents.into())
         } else {
@@ -266,6 +411,7 @@ impl BlockchainInterface for BlockchainDispatcher {
             let mut transactions = vec![];
 
             for i in first_block..=last_block {
+                check_deadline()?;
                 let hist_txs = blockchain.history_store.get_block_transactions(i, None);
 
                 // Get the timestamp of the block from one of the historic transactions. This complicated
@@ -284,6 +430,7 @@ impl BlockchainInterface for BlockchainDispatcher {
                         ));
                     }
                 }
+                self.check_result_size(transactions.len())?;
             }
 
             Ok(transactions.into())
@@ -310,6 +457,7 @@ impl BlockchainInterface for BlockchainDispatcher {
             let last_micro_block = macro_block_number - 1;
 
             for i in first_micro_block..=last_micro_block {
+                check_deadline()?;
                 let micro_hist_tx_vec = blockchain.history_store.get_block_transactions(i, None);
 
                 for hist_tx in micro_hist_tx_vec {
@@ -317,6 +465,7 @@ impl BlockchainInterface for BlockchainDispatcher {
                         inherent_tx_vec.push(inherent);
                     }
                 }
+                self.check_result_size(inherent_tx_vec.len())?;
             }
 
             // Append inherents of the macro block (we do this after the micro blocks so the inherents are in order)
@@ -327,6 +476,7 @@ impl BlockchainInterface for BlockchainDispatcher {
                     .into_iter()
                     .filter_map(Inherent::try_from),
             );
+            self.check_result_size(inherent_tx_vec.len())?;
 
             Ok(inherent_tx_vec.into())
         } else {
@@ -338,12 +488,15 @@ impl BlockchainInterface for BlockchainDispatcher {
         &mut self,
         address: Address,
         max: Option<u16>,
+        start_after: Option<Blake2bHash>,
     ) -> RPCResult<Vec<Blake2bHash>, (), Self::Error> {
+        let max = self.max_items(max)?;
         if let BlockchainProxy::Full(blockchain) = &self.blockchain {
             Ok(blockchain
                 .read()
                 .history_store
-                .get_tx_hashes_by_address(&address, max.unwrap_or(500), None)
+                .get_tx_hashes_by_address(&address, max, start_after.as_ref(), None)
+                .ok_or_else(|| unknown_cursor(&address, start_after.as_ref()))?
                 .into())
         } else {
             Err(Error::NotSupportedForLightBlockchain)
@@ -354,18 +507,20 @@ impl BlockchainInterface for BlockchainDispatcher {
         &mut self,
         address: Address,
         max: Option<u16>,
+        start_after: Option<Blake2bHash>,
     ) -> RPCResult<Vec<ExecutedTransaction>, (), Self::Error> {
+        let max = self.max_items(max)?;
         if let BlockchainReadProxy::Full(blockchain) = self.blockchain.read() {
             // Get the transaction hashes for this address.
-            let tx_hashes = blockchain.history_store.get_tx_hashes_by_address(
-                &address,
-                max.unwrap_or(500),
-                None,
-            );
+            let tx_hashes = blockchain
+                .history_store
+                .get_tx_hashes_by_address(&address, max, start_after.as_ref(), None)
+                .ok_or_else(|| unknown_cursor(&address, start_after.as_ref()))?;
 
             let mut txs = vec![];
 
             for hash in tx_hashes {
+                check_deadline()?;
                 // Get all the historic transactions that correspond to this hash.
                 let mut historic_tx_vec = blockchain.history_store.get_hist_tx_by_hash(&hash, None);
 
@@ -420,19 +575,41 @@ impl BlockchainInterface for BlockchainDispatcher {
         }
     }
 
-    async fn get_accounts(&mut self) -> RPCResult<Vec<Account>, BlockchainState, Self::Error> {
+    async fn get_accounts(
+        &mut self,
+        start_after: Option<Address>,
+        limit: Option<u16>,
+    ) -> RPCResult<Vec<Account>, BlockchainState, Self::Error> {
+        let limit = self.page_size(limit)?;
         let blockchain_proxy = self.blockchain.read();
         if let BlockchainReadProxy::Full(ref blockchain) = blockchain_proxy {
             let db_txn = blockchain.read_transaction();
-            let mut start = Some(KeyNibbles::default());
+            // The accounts tree is ordered by key, so the page starts at the cursor's key, which
+            // itself is skipped.
+            let mut start = Some(
+                start_after
+                    .as_ref()
+                    .map_or_else(KeyNibbles::default, KeyNibbles::from),
+            );
             let mut accounts = vec![];
-            while start.is_some() {
-                let chunk = blockchain.get_accounts_chunk(Some(&db_txn), start.unwrap(), 1000);
+            while let Some(key) = start {
+                if accounts.len() >= limit {
+                    break;
+                }
+                check_deadline()?;
+                let chunk = blockchain.get_accounts_chunk(Some(&db_txn), key, 1000);
                 start = chunk.end_key;
-                for account in chunk.accounts {
-                    accounts.push(Account::from_account(account.0, account.1));
+                for (address, account) in chunk.accounts {
+                    if Some(&address) == start_after.as_ref() {
+                        continue;
+                    }
+                    if accounts.len() >= limit {
+                        break;
+                    }
+                    accounts.push(Account::from_account(address, account));
                 }
             }
+            self.check_result_size(accounts.len())?;
             Ok(RPCData::with_blockchain(accounts, &blockchain_proxy))
         } else {
             Err(Error::NotSupportedForLightBlockchain)
@@ -513,6 +690,38 @@ impl BlockchainInterface for BlockchainDispatcher {
         }
     }
 
+    async fn get_signal_tally(
+        &mut self,
+        signal: Blake2bHash,
+    ) -> RPCResult<SignalTally, BlockchainState, Self::Error> {
+        let blockchain_proxy = self.blockchain.read();
+        if let BlockchainReadProxy::Full(ref blockchain) = blockchain_proxy {
+            let tally = blockchain
+                .get_signal_tally(&signal)
+                .ok_or(Error::NoConsensus)?;
+
+            Ok(RPCData::with_blockchain(tally.into(), &blockchain_proxy))
+        } else {
+            Err(Error::NotSupportedForLightBlockchain)
+        }
+    }
+
+    async fn get_signal_tallies(
+        &mut self,
+    ) -> RPCResult<Vec<SignalTally>, BlockchainState, Self::Error> {
+        let blockchain_proxy = self.blockchain.read();
+        if let BlockchainReadProxy::Full(ref blockchain) = blockchain_proxy {
+            let tallies = blockchain.get_signal_tallies().ok_or(Error::NoConsensus)?;
+
+            Ok(RPCData::with_blockchain(
+                tallies.into_iter().map(Into::into).collect(),
+                &blockchain_proxy,
+            ))
+        } else {
+            Err(Error::NotSupportedForLightBlockchain)
+        }
+    }
+
     async fn get_validator_by_address(
         &mut self,
         address: Address,
@@ -520,7 +729,12 @@ impl BlockchainInterface for BlockchainDispatcher {
         get_validator_by_address(&self.blockchain.read(), &address)
     }
 
-    async fn get_validators(&mut self) -> RPCResult<Vec<Validator>, BlockchainState, Self::Error> {
+    async fn get_validators(
+        &mut self,
+        start_after: Option<Address>,
+        limit: Option<u16>,
+    ) -> RPCResult<Vec<Validator>, BlockchainState, Self::Error> {
+        let limit = self.page_size(limit)?;
         let blockchain_proxy = self.blockchain.read();
 
         if let BlockchainReadProxy::Full(ref blockchain) = blockchain_proxy {
@@ -529,7 +743,12 @@ impl BlockchainInterface for BlockchainDispatcher {
                 .ok_or(Error::NoConsensus)?;
             let data_store = blockchain.get_staking_contract_store();
             let db_txn = blockchain.read_transaction();
-            let validators = staking_contract.get_validators(&data_store.read(&db_txn));
+            let validators = staking_contract.get_validators_page(
+                &data_store.read(&db_txn),
+                start_after.as_ref(),
+                limit,
+            );
+            self.check_result_size(validators.len())?;
 
             Ok(RPCData::with_blockchain(
                 validators.iter().map(Validator::from_validator).collect(),
@@ -543,7 +762,10 @@ impl BlockchainInterface for BlockchainDispatcher {
     async fn get_stakers_by_validator_address(
         &mut self,
         address: Address,
+        start_after: Option<Address>,
+        limit: Option<u16>,
     ) -> RPCResult<Vec<Staker>, BlockchainState, Self::Error> {
+        let limit = self.page_size(limit)?;
         let blockchain_proxy = self.blockchain.read();
 
         if let BlockchainReadProxy::Full(ref blockchain) = blockchain_proxy {
@@ -552,8 +774,13 @@ impl BlockchainInterface for BlockchainDispatcher {
                 .ok_or(Error::NoConsensus)?;
             let data_store = blockchain.get_staking_contract_store();
             let db_txn = blockchain.read_transaction();
-            let staker =
-                staking_contract.get_stakers_for_validator(&data_store.read(&db_txn), &address);
+            let staker = staking_contract.get_stakers_for_validator_page(
+                &data_store.read(&db_txn),
+                &address,
+                start_after.as_ref(),
+                limit,
+            );
+            self.check_result_size(staker.len())?;
 
             Ok(RPCData::with_blockchain(
                 staker.iter().map(Staker::from_staker).collect(),
@@ -655,6 +882,48 @@ impl BlockchainInterface for BlockchainDispatcher {
             .boxed())
     }
 
+    #[stream]
+    async fn subscribe_for_signal_threshold(
+        &mut self,
+        threshold: f64,
+    ) -> Result<BoxStream<'static, RPCData<SignalEvent, BlockchainState>>, Self::Error> {
+        if !(0.0..=100.0).contains(&threshold) {
+            return Err(Error::InvalidArgument(
+                "Threshold must be a percentage between 0 and 100".to_string(),
+            ));
+        }
+        if !matches!(self.blockchain, BlockchainProxy::Full(_)) {
+            return Err(Error::NotSupportedForLightBlockchain);
+        }
+
+        let blockchain = self.blockchain.clone();
+        let stream = self.blockchain.read().notifier_as_stream();
+        let mut tracker = SignalThresholdTracker::new(threshold / 100.0);
+
+        Ok(stream
+            .flat_map(move |event| {
+                let events = match event {
+                    BlockchainEvent::Finalized(..) | BlockchainEvent::EpochFinalized(..) => {
+                        let blockchain_rg = blockchain.read();
+                        if let BlockchainReadProxy::Full(ref full_blockchain) = blockchain_rg {
+                            full_blockchain
+                                .get_signal_tallies()
+                                .map(|tallies| tracker.update(tallies))
+                                .unwrap_or_default()
+                                .into_iter()
+                                .map(|event| RPCData::with_blockchain(event.into(), &blockchain_rg))
+                                .collect()
+                        } else {
+                            vec![]
+                        }
+                    }
+                    _ => vec![],
+                };
+                stream::iter(events)
+            })
+            .boxed())
+    }
+
     #[stream]
     async fn subscribe_for_logs_by_addresses_and_types(
         &mut self,
@@ -675,104 +944,11 @@ impl BlockchainInterface for BlockchainDispatcher {
             } else {
                 Ok(stream
                     .filter_map(move |event| {
-                        let result = match event {
-                            Ok(BBlockLog::AppliedBlock {
-                                mut inherent_logs,
-                                block_hash,
-                                block_number,
-                                timestamp,
-                                tx_logs,
-                                total_tx_size: _,
-                            }) => {
-                                // Collects the inherents that are related to any of the addresses specified and of any of the log types provided.
-                                inherent_logs.retain(|log| {
-                                    is_of_log_type_and_related_to_addresses(
-                                        log, &addresses, &log_types,
-                                    )
-                                });
-                                // Since each TransactionLog has its own vec of logs, we iterate over each tx_logs and filter their logs,
-                                // if a tx_log has no logs after filtering, it will be filtered out completely.
-                                let tx_logs: Vec<TransactionLog> = tx_logs
-                                    .into_iter()
-                                    .filter_map(|mut tx_log| {
-                                        tx_log.logs.retain(|log| {
-                                            is_of_log_type_and_related_to_addresses(
-                                                log, &addresses, &log_types,
-                                            )
-                                        });
-                                        if tx_log.logs.is_empty() {
-                                            None
-                                        } else {
-                                            Some(tx_log)
-                                        }
-                                    })
-                                    .collect();
-
-                                // If this block has no transaction logs or inherent logs of interest, we return None. Otherwise, we return the filtered BlockLog.
-                                // This way the stream only emits an event if a block has at least one log fulfilling the specified criteria.
-                                if !inherent_logs.is_empty() || !tx_logs.is_empty() {
-                                    Some(RPCData::new(
-                                        BlockLog::AppliedBlock {
-                                            inherent_logs,
-                                            timestamp,
-                                            tx_logs,
-                                        },
-                                        BlockchainState {
-                                            block_number,
-                                            block_hash,
-                                        },
-                                    ))
-                                } else {
-                                    None
-                                }
-                            }
-                            Ok(BBlockLog::RevertedBlock {
-                                mut inherent_logs,
-                                block_hash,
-                                block_number,
-                                tx_logs,
-                                total_tx_size: _,
-                            }) => {
-                                // Filters the inherents and tx_logs the same way as the AppliedBlock
-                                inherent_logs.retain(|log| {
-                                    is_of_log_type_and_related_to_addresses(
-                                        log, &addresses, &log_types,
-                                    )
-                                });
-                                let tx_logs: Vec<TransactionLog> = tx_logs
-                                    .into_iter()
-                                    .filter_map(|mut tx_log| {
-                                        tx_log.logs.retain(|log| {
-                                            is_of_log_type_and_related_to_addresses(
-                                                log, &addresses, &log_types,
-                                            )
-                                        });
-                                        if tx_log.logs.is_empty() {
-                                            None
-                                        } else {
-                                            Some(tx_log)
-                                        }
-                                    })
-                                    .collect();
-
-                                if !inherent_logs.is_empty() || !tx_logs.is_empty() {
-                                    Some(RPCData::new(
-                                        BlockLog::RevertedBlock {
-                                            inherent_logs,
-                                            tx_logs,
-                                        },
-                                        BlockchainState {
-                                            block_number,
-                                            block_hash,
-                                        },
-                                    ))
-                                } else {
-                                    None
-                                }
-                            }
-                            Err(_) => None,
-                        };
-                        future::ready(result)
+                        future::ready(
+                            event
+                                .ok()
+                                .and_then(|event| filter_block_log(event, &addresses, &log_types)),
+                        )
                     })
                     .boxed())
             }
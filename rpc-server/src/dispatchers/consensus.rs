@@ -1,6 +1,8 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use async_trait::async_trait;
+use futures::stream::{self, BoxStream, StreamExt};
+use nimiq_account::BlockLog;
 use nimiq_blockchain_interface::AbstractBlockchain;
 use nimiq_blockchain_proxy::BlockchainReadProxy;
 use nimiq_bls::{KeyPair as BlsKeyPair, SecretKey as BlsSecretKey};
@@ -11,18 +13,27 @@ use nimiq_network_libp2p::Network;
 use nimiq_primitives::{coin::Coin, networks::NetworkId};
 use nimiq_rpc_interface::{
     consensus::ConsensusInterface,
-    types::{RPCResult, Transaction as RPCTransaction, ValidityStartHeight},
+    types::{
+        BatchPayment, BatchTransactionState, BatchTransactionStatus, BlockchainState, FeePolicy,
+        RPCData, RPCResult, Transaction as RPCTransaction, ValidityStartHeight,
+    },
 };
 use nimiq_serde::{Deserialize, Serialize};
 use nimiq_transaction::{
     account::htlc_contract::{AnyHash, PreImage},
+    historic_transaction::HistoricTransactionData,
     SignatureProof, Transaction,
 };
-use nimiq_transaction_builder::TransactionBuilder;
+use nimiq_transaction_builder::{
+    BatchPayment as BatchPaymentBuilder, ExternalSigner, FeePolicy as BuilderFeePolicy, Payment,
+    TransactionBuilder,
+};
 use parking_lot::RwLock;
+use tokio_stream::wrappers::BroadcastStream;
 
 use crate::{error::Error, wallets::UnlockedWallets};
 
+#[derive(Clone)]
 pub struct ConsensusDispatcher {
     consensus: ConsensusProxy<Network>,
     unlocked_wallets: Option<Arc<RwLock<UnlockedWallets>>>,
@@ -62,6 +73,42 @@ impl ConsensusDispatcher {
     fn validity_start_height(&self, validity_start_height: ValidityStartHeight) -> u32 {
         validity_start_height.block_number(self.consensus.blockchain.read().block_number())
     }
+
+    /// Builds and signs the transactions of a batch payment from the given wallet.
+    fn build_batch_payment(
+        &self,
+        wallet: &Address,
+        payments: Vec<BatchPayment>,
+        fee_policy: FeePolicy,
+        validity_start_height: ValidityStartHeight,
+    ) -> Result<Vec<Transaction>, Error> {
+        let payments = payments
+            .into_iter()
+            .map(|payment| {
+                Ok(Payment {
+                    recipient: payment.recipient,
+                    value: payment.value,
+                    data: payment
+                        .data
+                        .map(hex::decode)
+                        .transpose()?
+                        .unwrap_or_default(),
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let fee_policy = match fee_policy {
+            FeePolicy::Fixed(fee) => BuilderFeePolicy::Fixed(fee),
+            FeePolicy::PerByte(fee) => BuilderFeePolicy::PerByte(fee),
+        };
+
+        let batch = BatchPaymentBuilder::new(
+            payments,
+            fee_policy,
+            self.validity_start_height(validity_start_height),
+            self.get_network_id(),
+        );
+        Ok(batch.build(&self.get_wallet_keypair(wallet)?)?)
+    }
 }
 
 fn transaction_to_hex_string(transaction: &Transaction) -> String {
@@ -178,6 +225,106 @@ impl ConsensusInterface for ConsensusDispatcher {
         self.send_raw_transaction(raw_tx).await
     }
 
+    async fn create_batch_payment_transactions(
+        &mut self,
+        wallet: Address,
+        payments: Vec<BatchPayment>,
+        fee_policy: FeePolicy,
+        validity_start_height: ValidityStartHeight,
+    ) -> RPCResult<Vec<String>, (), Self::Error> {
+        let transactions =
+            self.build_batch_payment(&wallet, payments, fee_policy, validity_start_height)?;
+
+        Ok(transactions
+            .iter()
+            .map(transaction_to_hex_string)
+            .collect::<Vec<_>>()
+            .into())
+    }
+
+    #[stream]
+    async fn send_batch_payment(
+        &mut self,
+        wallet: Address,
+        payments: Vec<BatchPayment>,
+        fee_policy: FeePolicy,
+        validity_start_height: ValidityStartHeight,
+        max_pending: Option<u32>,
+    ) -> Result<BoxStream<'static, RPCData<BatchTransactionStatus, BlockchainState>>, Self::Error>
+    {
+        let max_pending = max_pending.unwrap_or(64);
+        if max_pending == 0 {
+            return Err(Error::InvalidArgument(
+                "maxPending must be greater than 0".to_string(),
+            ));
+        }
+        let transactions =
+            self.build_batch_payment(&wallet, payments, fee_policy, validity_start_height)?;
+
+        // Subscribe before sending anything, so no inclusion of a transaction is missed.
+        let block_logs = match self.consensus.blockchain.read() {
+            BlockchainReadProxy::Full(blockchain) => {
+                BroadcastStream::new(blockchain.log_notifier.subscribe())
+            }
+            _ => return Err(Error::NotSupportedForLightBlockchain),
+        };
+        let head = self.consensus.blockchain.read().block_number();
+        let tracker = BatchPaymentTracker::new(transactions, max_pending as usize, head);
+        let consensus = self.consensus.clone();
+
+        Ok(stream::unfold(
+            (tracker, block_logs, consensus),
+            |(mut tracker, mut block_logs, consensus)| async move {
+                let updates = loop {
+                    if tracker.is_finished() {
+                        return None;
+                    }
+
+                    // Send as many transactions as the window allows, and retry the ones that
+                    // couldn't be sent or weren't included for a while.
+                    let mut updates = vec![];
+                    for (index, transaction) in tracker.next_to_send() {
+                        let result = consensus
+                            .send_transaction(transaction)
+                            .await
+                            .map_err(|e| e.to_string());
+                        updates.push(tracker.sent(index, result));
+                    }
+                    if !updates.is_empty() {
+                        break updates;
+                    }
+
+                    // Otherwise, wait for the next block, which might change the status of a
+                    // transaction or allow to send one.
+                    let updates = match block_logs.next().await? {
+                        Ok(block_log) => tracker.apply_block_log(&block_log),
+                        Err(error) => {
+                            log::warn!(
+                                %error,
+                                "Batch payment missed block logs, checking the history"
+                            );
+                            check_history(&mut tracker, &consensus)
+                        }
+                    };
+                    if !updates.is_empty() {
+                        break updates;
+                    }
+                };
+
+                let updates = {
+                    let blockchain = consensus.blockchain.read();
+                    updates
+                        .into_iter()
+                        .map(|status| RPCData::with_blockchain(status, &blockchain))
+                        .collect::<Vec<_>>()
+                };
+                Some((stream::iter(updates), (tracker, block_logs, consensus)))
+            },
+        )
+        .flatten()
+        .boxed())
+    }
+
     async fn create_new_vesting_transaction(
         &mut self,
         wallet: Address,
@@ -615,7 +762,7 @@ impl ConsensusInterface for ConsensusDispatcher {
         };
 
         let transaction = TransactionBuilder::new_update_staker(
-            sender_key.as_ref(),
+            sender_key.as_ref().map(|key| key as &dyn ExternalSigner),
             &self.get_wallet_keypair(&staker_wallet)?,
             new_delegation,
             reactivate_all_stake,
@@ -664,7 +811,7 @@ impl ConsensusInterface for ConsensusDispatcher {
         };
 
         let transaction = TransactionBuilder::new_set_active_stake(
-            sender_key.as_ref(),
+            sender_key.as_ref().map(|key| key as &dyn ExternalSigner),
             &self.get_wallet_keypair(&staker_wallet)?,
             new_active_balance,
             fee,
@@ -710,7 +857,7 @@ impl ConsensusInterface for ConsensusDispatcher {
         };
 
         let transaction = TransactionBuilder::new_retire_stake(
-            sender_key.as_ref(),
+            sender_key.as_ref().map(|key| key as &dyn ExternalSigner),
             &self.get_wallet_keypair(&staker_wallet)?,
             retire_stake,
             fee,
@@ -1167,3 +1314,426 @@ impl ConsensusInterface for ConsensusDispatcher {
         self.send_raw_transaction(raw_tx).await
     }
 }
+
+/// Updates the transactions of a batch payment from the history, e.g. after block logs were missed.
+fn check_history(
+    tracker: &mut BatchPaymentTracker,
+    consensus: &ConsensusProxy<Network>,
+) -> Vec<BatchTransactionStatus> {
+    let BlockchainReadProxy::Full(blockchain) = consensus.blockchain.read() else {
+        return vec![];
+    };
+
+    tracker.apply_history(blockchain.block_number(), |hash| {
+        blockchain
+            .history_store
+            .get_hist_tx_by_hash(hash, None)
+            .into_iter()
+            .find_map(|historic_tx| match historic_tx.data {
+                HistoricTransactionData::Basic(tx) => Some((historic_tx.block_number, tx.failed())),
+                _ => None,
+            })
+    })
+}
+
+/// A transaction of a batch payment and when to send it.
+struct TrackedTransaction {
+    transaction: Transaction,
+    status: BatchTransactionStatus,
+    /// The number of times sending the transaction failed before it was pending.
+    send_failures: usize,
+    /// The block number from which on the transaction is (re)sent.
+    send_at: u32,
+}
+
+/// Tracks the transactions of a batch payment from sending them until they are final.
+struct BatchPaymentTracker {
+    transactions: Vec<TrackedTransaction>,
+    indices: HashMap<Blake2bHash, usize>,
+    /// The maximum number of sent transactions waiting to be included in a block.
+    max_pending: usize,
+    /// The block number of our head.
+    head: u32,
+}
+
+impl BatchPaymentTracker {
+    /// The number of times sending a transaction may fail before it is rejected. Failed sends are
+    /// retried with the next block, e.g. once the mempools have room for the transaction again.
+    const MAX_SEND_FAILURES: usize = 5;
+    /// The number of blocks after which a pending transaction is sent again. Full mempools evict
+    /// the transactions with the lowest fees, so it might have to be sent again to be included.
+    const RESEND_AFTER_BLOCKS: u32 = 10;
+
+    fn new(transactions: Vec<Transaction>, max_pending: usize, head: u32) -> Self {
+        let transactions: Vec<_> = transactions
+            .into_iter()
+            .enumerate()
+            .map(|(index, transaction)| TrackedTransaction {
+                status: BatchTransactionStatus {
+                    index,
+                    hash: transaction.hash(),
+                    state: BatchTransactionState::Queued,
+                    block_number: None,
+                    error: None,
+                },
+                transaction,
+                send_failures: 0,
+                send_at: head,
+            })
+            .collect();
+        let indices = transactions
+            .iter()
+            .map(|tracked| (tracked.status.hash.clone(), tracked.status.index))
+            .collect();
+
+        BatchPaymentTracker {
+            transactions,
+            indices,
+            max_pending,
+            head,
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.transactions
+            .iter()
+            .all(|tracked| tracked.status.state.is_final())
+    }
+
+    /// Returns the transactions due to be sent. These are the pending transactions that weren't
+    /// included for a while and the queued transactions that can be sent without exceeding the
+    /// maximum number of pending transactions.
+    fn next_to_send(&self) -> Vec<(usize, Transaction)> {
+        let is_due = |tracked: &&TrackedTransaction| tracked.send_at <= self.head;
+        let pending = self
+            .transactions
+            .iter()
+            .filter(|tracked| tracked.status.state == BatchTransactionState::Pending)
+            .count();
+
+        let resend = self
+            .transactions
+            .iter()
+            .filter(|tracked| tracked.status.state == BatchTransactionState::Pending)
+            .filter(is_due);
+        let send = self
+            .transactions
+            .iter()
+            .filter(|tracked| tracked.status.state == BatchTransactionState::Queued)
+            .take(self.max_pending.saturating_sub(pending))
+            .filter(is_due);
+
+        resend
+            .chain(send)
+            .map(|tracked| (tracked.status.index, tracked.transaction.clone()))
+            .collect()
+    }
+
+    fn sent(&mut self, index: usize, result: Result<(), String>) -> BatchTransactionStatus {
+        let tracked = &mut self.transactions[index];
+        match result {
+            Ok(()) => {
+                tracked.status.state = BatchTransactionState::Pending;
+                tracked.status.error = None;
+                tracked.send_at = self.head + Self::RESEND_AFTER_BLOCKS;
+            }
+            Err(error) => {
+                // Transactions that were sent already might still be included.
+                if tracked.status.state == BatchTransactionState::Queued {
+                    tracked.send_failures += 1;
+                    if tracked.send_failures >= Self::MAX_SEND_FAILURES {
+                        tracked.status.state = BatchTransactionState::Rejected;
+                    }
+                }
+                tracked.status.error = Some(error);
+                tracked.send_at = self.head + 1;
+            }
+        }
+        tracked.status.clone()
+    }
+
+    /// Updates the transactions included in or reverted from the block and expires the ones that
+    /// can't be included anymore. Returns the statuses that changed.
+    fn apply_block_log(&mut self, block_log: &BlockLog) -> Vec<BatchTransactionStatus> {
+        let mut updates = vec![];
+        match block_log {
+            BlockLog::AppliedBlock {
+                block_number,
+                tx_logs,
+                ..
+            } => {
+                self.head = *block_number;
+                for tx_log in tx_logs {
+                    if let Some(&index) = self.indices.get(&tx_log.tx_hash) {
+                        let status = &mut self.transactions[index].status;
+                        status.state = if tx_log.failed {
+                            BatchTransactionState::Failed
+                        } else {
+                            BatchTransactionState::Confirmed
+                        };
+                        status.block_number = Some(*block_number);
+                        updates.push(status.clone());
+                    }
+                }
+            }
+            BlockLog::RevertedBlock {
+                block_number,
+                tx_logs,
+                ..
+            } => {
+                self.head = block_number - 1;
+                // Reverted transactions are added back to the mempool.
+                for tx_log in tx_logs {
+                    if let Some(&index) = self.indices.get(&tx_log.tx_hash) {
+                        let tracked = &mut self.transactions[index];
+                        tracked.status.state = BatchTransactionState::Pending;
+                        tracked.status.block_number = None;
+                        tracked.send_at = self.head + Self::RESEND_AFTER_BLOCKS;
+                        updates.push(tracked.status.clone());
+                    }
+                }
+            }
+        }
+        updates.extend(self.expire());
+        updates
+    }
+
+    /// Updates the sent transactions from the history at the given head, using the given lookup
+    /// of the block number a transaction was included in and whether it failed. Returns the
+    /// statuses that changed.
+    fn apply_history(
+        &mut self,
+        head: u32,
+        lookup: impl Fn(&Blake2bHash) -> Option<(u32, bool)>,
+    ) -> Vec<BatchTransactionStatus> {
+        self.head = head;
+
+        let mut updates = vec![];
+        for tracked in &mut self.transactions {
+            let status = &mut tracked.status;
+            if !matches!(
+                status.state,
+                BatchTransactionState::Pending
+                    | BatchTransactionState::Confirmed
+                    | BatchTransactionState::Failed
+            ) {
+                continue;
+            }
+
+            let (state, block_number) = match lookup(&status.hash) {
+                Some((block_number, false)) => {
+                    (BatchTransactionState::Confirmed, Some(block_number))
+                }
+                Some((block_number, true)) => (BatchTransactionState::Failed, Some(block_number)),
+                // The transaction wasn't included yet or its block was reverted.
+                None => (BatchTransactionState::Pending, None),
+            };
+            if status.state != state || status.block_number != block_number {
+                if state == BatchTransactionState::Pending {
+                    tracked.send_at = head;
+                }
+                status.state = state;
+                status.block_number = block_number;
+                updates.push(status.clone());
+            }
+        }
+        updates.extend(self.expire());
+        updates
+    }
+
+    /// Expires the transactions that can't be included after our head anymore.
+    fn expire(&mut self) -> Vec<BatchTransactionStatus> {
+        let mut updates = vec![];
+        for tracked in &mut self.transactions {
+            if !tracked.status.state.is_final() && !tracked.transaction.is_valid_at(self.head + 1) {
+                tracked.status.state = BatchTransactionState::Expired;
+                updates.push(tracked.status.clone());
+            }
+        }
+        updates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nimiq_account::{BlockLog, TransactionLog};
+    use nimiq_hash::{Blake2bHash, Hash};
+    use nimiq_keys::Address;
+    use nimiq_primitives::{coin::Coin, networks::NetworkId, policy::Policy};
+    use nimiq_rpc_interface::types::BatchTransactionState;
+    use nimiq_test_log::test;
+    use nimiq_transaction::Transaction;
+
+    use super::BatchPaymentTracker;
+
+    fn transactions(count: u64) -> Vec<Transaction> {
+        (0..count)
+            .map(|i| {
+                Transaction::new_basic(
+                    Address::default(),
+                    Address::default(),
+                    Coin::from_u64_unchecked(i + 1),
+                    Coin::ZERO,
+                    1,
+                    NetworkId::UnitAlbatross,
+                )
+            })
+            .collect()
+    }
+
+    fn applied_block(block_number: u32, included: &[(&Transaction, bool)]) -> BlockLog {
+        BlockLog::AppliedBlock {
+            inherent_logs: vec![],
+            block_hash: Blake2bHash::default(),
+            block_number,
+            timestamp: 0,
+            tx_logs: included
+                .iter()
+                .map(|(transaction, failed)| {
+                    let mut tx_log = TransactionLog::new(transaction.hash(), vec![]);
+                    tx_log.failed = *failed;
+                    tx_log
+                })
+                .collect(),
+            total_tx_size: 0,
+        }
+    }
+
+    fn states(tracker: &BatchPaymentTracker) -> Vec<BatchTransactionState> {
+        tracker
+            .transactions
+            .iter()
+            .map(|tracked| tracked.status.state)
+            .collect()
+    }
+
+    fn send_all(tracker: &mut BatchPaymentTracker) -> Vec<usize> {
+        let indices: Vec<_> = tracker
+            .next_to_send()
+            .into_iter()
+            .map(|(index, _)| index)
+            .collect();
+        for &index in &indices {
+            tracker.sent(index, Ok(()));
+        }
+        indices
+    }
+
+    #[test]
+    fn it_limits_the_pending_transactions() {
+        let transactions = transactions(3);
+        let mut tracker = BatchPaymentTracker::new(transactions.clone(), 2, 1);
+
+        assert_eq!(send_all(&mut tracker), vec![0, 1]);
+        assert!(tracker.next_to_send().is_empty());
+
+        let updates = tracker.apply_block_log(&applied_block(2, &[(&transactions[0], false)]));
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].block_number, Some(2));
+        assert_eq!(send_all(&mut tracker), vec![2]);
+
+        tracker.apply_block_log(&applied_block(
+            3,
+            &[(&transactions[1], true), (&transactions[2], false)],
+        ));
+        assert_eq!(
+            states(&tracker),
+            vec![
+                BatchTransactionState::Confirmed,
+                BatchTransactionState::Failed,
+                BatchTransactionState::Confirmed,
+            ]
+        );
+        assert!(tracker.is_finished());
+    }
+
+    #[test]
+    fn it_retries_failed_sends_with_the_next_block() {
+        let mut tracker = BatchPaymentTracker::new(transactions(1), 1, 1);
+
+        for attempt in 1..=BatchPaymentTracker::MAX_SEND_FAILURES {
+            assert_eq!(tracker.next_to_send().len(), 1);
+            let status = tracker.sent(0, Err("mempool full".to_string()));
+            assert_eq!(status.error.as_deref(), Some("mempool full"));
+            if attempt < BatchPaymentTracker::MAX_SEND_FAILURES {
+                assert_eq!(status.state, BatchTransactionState::Queued);
+            }
+
+            // Sending is retried only once the next block arrived.
+            assert!(tracker.next_to_send().is_empty());
+            tracker.apply_block_log(&applied_block(attempt as u32 + 1, &[]));
+        }
+
+        assert_eq!(states(&tracker), vec![BatchTransactionState::Rejected]);
+        assert!(tracker.is_finished());
+    }
+
+    #[test]
+    fn it_resends_transactions_that_are_not_included() {
+        let transactions = transactions(1);
+        let mut tracker = BatchPaymentTracker::new(transactions.clone(), 1, 1);
+        assert_eq!(send_all(&mut tracker), vec![0]);
+
+        for block_number in 2..1 + BatchPaymentTracker::RESEND_AFTER_BLOCKS {
+            tracker.apply_block_log(&applied_block(block_number, &[]));
+            assert!(tracker.next_to_send().is_empty());
+        }
+        tracker.apply_block_log(&applied_block(
+            1 + BatchPaymentTracker::RESEND_AFTER_BLOCKS,
+            &[],
+        ));
+        assert_eq!(send_all(&mut tracker), vec![0]);
+
+        // Failing to resend a pending transaction doesn't reject it, since it might be included.
+        let block_number = 1 + 2 * BatchPaymentTracker::RESEND_AFTER_BLOCKS;
+        tracker.apply_block_log(&applied_block(block_number, &[]));
+        for _ in 0..BatchPaymentTracker::MAX_SEND_FAILURES {
+            tracker.sent(0, Err("mempool full".to_string()));
+        }
+        assert_eq!(states(&tracker), vec![BatchTransactionState::Pending]);
+
+        // Transactions that weren't included in their validity window expire.
+        let expired_at = transactions[0].validity_start_height
+            + Policy::transaction_validity_window_blocks()
+            - 1;
+        let updates = tracker.apply_block_log(&applied_block(expired_at, &[]));
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].state, BatchTransactionState::Expired);
+        assert!(tracker.is_finished());
+    }
+
+    #[test]
+    fn it_checks_the_history_after_missing_block_logs() {
+        let transactions = transactions(3);
+        let hashes: Vec<Blake2bHash> = transactions.iter().map(|tx| tx.hash()).collect();
+        let mut tracker = BatchPaymentTracker::new(transactions, 3, 1);
+        assert_eq!(send_all(&mut tracker), vec![0, 1, 2]);
+
+        let updates = tracker.apply_history(5, |hash| {
+            if *hash == hashes[0] {
+                Some((3, false))
+            } else if *hash == hashes[1] {
+                Some((4, true))
+            } else {
+                None
+            }
+        });
+        assert_eq!(updates.len(), 2);
+        assert_eq!(
+            states(&tracker),
+            vec![
+                BatchTransactionState::Confirmed,
+                BatchTransactionState::Failed,
+                BatchTransactionState::Pending,
+            ]
+        );
+        assert_eq!(tracker.transactions[0].status.block_number, Some(3));
+
+        // Transactions that are not in the history anymore were reverted and are sent again.
+        let updates = tracker.apply_history(6, |hash| (*hash == hashes[1]).then_some((4, true)));
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].index, 0);
+        assert_eq!(updates[0].state, BatchTransactionState::Pending);
+        assert_eq!(send_all(&mut tracker), vec![0]);
+    }
+}
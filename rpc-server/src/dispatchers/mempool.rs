@@ -13,13 +13,19 @@ use nimiq_transaction::Transaction;
 use crate::error::Error;
 
 #[allow(dead_code)]
+#[derive(Clone)]
 pub struct MempoolDispatcher {
     mempool: Arc<Mempool>,
+    /// The maximum number of items returned by methods that return lists.
+    max_result_size: Option<usize>,
 }
 
 impl MempoolDispatcher {
-    pub fn new(mempool: Arc<Mempool>) -> Self {
-        MempoolDispatcher { mempool }
+    pub fn new(mempool: Arc<Mempool>, max_result_size: Option<usize>) -> Self {
+        MempoolDispatcher {
+            mempool,
+            max_result_size,
+        }
     }
 }
 
@@ -61,18 +67,38 @@ impl MempoolInterface for MempoolDispatcher {
     async fn mempool_content(
         &mut self,
         include_transactions: bool,
+        start_after: Option<Blake2bHash>,
+        limit: Option<u16>,
     ) -> RPCResult<Vec<HashOrTx>, (), Self::Error> {
+        // The hashes are sorted, so that the cursor of a page stays valid while the mempool
+        // changes.
+        let mut hashes = self.mempool.get_transaction_hashes();
+        hashes.sort_unstable();
+
+        let page_start = match &start_after {
+            Some(start_after) => hashes.partition_point(|hash| hash <= start_after),
+            None => 0,
+        };
+        let page_end = match limit {
+            Some(limit) => hashes.len().min(page_start + limit as usize),
+            None => hashes.len(),
+        };
+        if let Some(max_result_size) = self.max_result_size {
+            if page_end - page_start > max_result_size {
+                return Err(Error::ResultTooLarge(max_result_size));
+            }
+        }
+
+        let page = &hashes[page_start..page_end];
         return match include_transactions {
-            true => Ok(self
-                .mempool
-                .get_transactions()
+            // Transactions that left the mempool since the hashes were fetched are skipped.
+            true => Ok(page
                 .iter()
-                .map(|tx| HashOrTx::from(tx.clone()))
+                .filter_map(|hash| self.mempool.get_transaction_by_hash(hash))
+                .map(HashOrTx::from)
                 .collect::<Vec<_>>()
                 .into()),
-            false => Ok(self
-                .mempool
-                .get_transaction_hashes()
+            false => Ok(page
                 .iter()
                 .map(|hash| HashOrTx::from(hash.clone()))
                 .collect::<Vec<_>>()
@@ -5,13 +5,15 @@ pub use network::NetworkDispatcher;
 pub use policy::PolicyDispatcher;
 pub use validator::ValidatorDispatcher;
 pub use wallet::WalletDispatcher;
+pub use watchtower::WatchtowerDispatcher;
 pub use zkp_component::ZKPComponentDispatcher;
 
-mod blockchain;
+pub(crate) mod blockchain;
 mod consensus;
 mod mempool;
 mod network;
 mod policy;
 mod validator;
 mod wallet;
+mod watchtower;
 mod zkp_component;
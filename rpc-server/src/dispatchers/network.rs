@@ -7,6 +7,7 @@ use nimiq_rpc_interface::{network::NetworkInterface, types::RPCResult};
 
 use crate::error::Error;
 
+#[derive(Clone)]
 pub struct NetworkDispatcher {
     network: Arc<Network>,
 }
@@ -7,6 +7,7 @@ use nimiq_rpc_interface::{
 
 use crate::error::Error;
 
+#[derive(Clone)]
 pub struct PolicyDispatcher {}
 
 #[nimiq_jsonrpc_derive::service(rename_all = "camelCase")]
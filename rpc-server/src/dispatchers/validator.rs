@@ -1,6 +1,7 @@
 use std::sync::atomic::Ordering;
 
 use async_trait::async_trait;
+use nimiq_hash::Blake2bHash;
 use nimiq_keys::Address;
 use nimiq_rpc_interface::{types::RPCResult, validator::ValidatorInterface};
 use nimiq_serde::Serialize;
@@ -8,6 +9,7 @@ use nimiq_validator::validator::ValidatorProxy;
 
 use crate::error::Error;
 
+#[derive(Clone)]
 pub struct ValidatorDispatcher {
     validator: ValidatorProxy,
 }
@@ -54,6 +56,19 @@ impl ValidatorInterface for ValidatorDispatcher {
         Ok(().into())
     }
 
+    async fn get_signal_data(&mut self) -> RPCResult<Option<Blake2bHash>, (), Self::Error> {
+        Ok(self.validator.signal_data.read().clone().into())
+    }
+
+    async fn set_signal_data(
+        &mut self,
+        signal_data: Option<Blake2bHash>,
+    ) -> RPCResult<(), (), Self::Error> {
+        log::debug!(?signal_data, "Signal data set.");
+        *self.validator.signal_data.write() = signal_data;
+        Ok(().into())
+    }
+
     async fn is_validator_elected(&mut self) -> RPCResult<bool, (), Self::Error> {
         let is_elected = self.validator.slot_band.read().is_some();
         Ok(is_elected.into())
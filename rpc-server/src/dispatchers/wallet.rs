@@ -1,18 +1,37 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use async_trait::async_trait;
+use futures::{future, stream::BoxStream, StreamExt};
+use nimiq_blockchain_interface::AbstractBlockchain;
+use nimiq_blockchain_proxy::{BlockchainProxy, BlockchainReadProxy};
 use nimiq_database::traits::WriteTransaction;
-use nimiq_keys::{Address, Ed25519PublicKey, Ed25519Signature, KeyPair, PrivateKey};
+use nimiq_hash::{Blake2bHash, Hash};
+use nimiq_keys::{
+    multisig::{commitment::CommitmentPair, MUSIG2_PARAMETER_V},
+    Address, Ed25519PublicKey, Ed25519Signature, KeyPair, PrivateKey, SecureGenerate,
+};
+use nimiq_mnemonic::Mnemonic;
+use nimiq_primitives::{coin::Coin, policy::Policy};
 use nimiq_rpc_interface::{
-    types::{RPCResult, ReturnAccount, ReturnSignature},
+    types::{
+        BlockLog, BlockchainState, LogType, RPCData, RPCResult, ReturnAccount,
+        ReturnDerivedAccount, ReturnHdWallet, ReturnSignature, ReturnWatchOnlyAddress,
+        ValidityStartHeight,
+    },
     wallet::WalletInterface,
 };
-use nimiq_serde::Deserialize;
+use nimiq_serde::{Deserialize, Serialize};
+use nimiq_transaction::{signed_message::SignedMessage, Transaction};
+use nimiq_transaction_builder::PartiallySignedTransaction;
 use nimiq_utils::otp::Locked;
-use nimiq_wallet::{WalletAccount, WalletStore};
+use nimiq_wallet::{
+    DerivedAccount, HdWallet, HdWalletError, WalletAccount, WalletBackup, WalletStore,
+    WatchOnlyAddress,
+};
 use parking_lot::RwLock;
+use tokio_stream::wrappers::BroadcastStream;
 
-use crate::{error::Error, wallets::UnlockedWallets};
+use crate::{dispatchers::blockchain::filter_block_log, error::Error, wallets::UnlockedWallets};
 
 fn message_from_maybe_hex(s: String, is_hex: bool) -> Result<Vec<u8>, Error> {
     if is_hex {
@@ -22,20 +41,86 @@ fn message_from_maybe_hex(s: String, is_hex: bool) -> Result<Vec<u8>, Error> {
     }
 }
 
+/// The maximum number of pending multisig signing rounds, which bounds the memory used by
+/// commitments that are never used for signing.
+const MAX_PENDING_MULTISIG_ROUNDS: usize = 1000;
+
+/// The secret commitment pairs of pending multisig signing rounds, see [`WalletDispatcher`].
+type MultisigCommitments =
+    HashMap<(Ed25519PublicKey, Blake2bHash), ([CommitmentPair; MUSIG2_PARAMETER_V], u32)>;
+
+#[derive(Clone)]
 pub struct WalletDispatcher {
     wallet_store: Arc<WalletStore>,
+    blockchain: BlockchainProxy,
     pub unlocked_wallets: Arc<RwLock<UnlockedWallets>>,
+    /// The secret commitment pairs of pending multisig signing rounds, by signer and transaction
+    /// hash, together with the block number at which the transaction expires. They are removed
+    /// once used for signing, as they must never be reused, or once the transaction expired.
+    /// They are shared between the clones of the dispatcher.
+    multisig_commitments: Arc<RwLock<MultisigCommitments>>,
 }
 
 impl WalletDispatcher {
-    pub fn new(wallet_store: Arc<WalletStore>) -> Self {
+    pub fn new(wallet_store: Arc<WalletStore>, blockchain: BlockchainProxy) -> Self {
         Self {
             wallet_store,
+            blockchain,
             unlocked_wallets: Arc::new(RwLock::new(UnlockedWallets::default())),
+            multisig_commitments: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Removes the commitments of signing rounds whose transaction can't be included in a block
+    /// anymore.
+    fn prune_multisig_commitments(&self) {
+        let block_number = self.blockchain.read().block_number();
+        self.multisig_commitments
+            .write()
+            .retain(|_, (_, expires_at)| *expires_at > block_number);
+    }
+
+    /// Returns the account, either from the unlocked accounts or by unlocking it with the
+    /// passphrase.
+    fn get_wallet_account(
+        &self,
+        address: Address,
+        passphrase: Option<String>,
+    ) -> Result<WalletAccount, Error> {
+        if let Some(wallet) = self.unlocked_wallets.read().get(&address) {
+            return Ok(wallet.clone());
         }
+
+        let passphrase = passphrase.unwrap_or_default();
+        Ok(self
+            .wallet_store
+            .get(&address, None)
+            .ok_or(Error::AccountNotFound(address))?
+            .unlock(passphrase.as_bytes())
+            .map_err(|_locked| Error::WrongPassphrase)?
+            .key_pair
+            .clone()
+            .into())
+    }
+
+    /// Stores the HD wallet locked with the passphrase and returns its identifier.
+    fn store_hd_wallet(&self, wallet: HdWallet, passphrase: &str) -> Result<Address, Error> {
+        let wallet_id = wallet.id.clone();
+        let locked_wallet = Locked::with_defaults(wallet, passphrase.as_bytes())?;
+
+        let mut txn = self.wallet_store.create_write_transaction();
+        self.wallet_store
+            .put_hd_wallet(&wallet_id, &locked_wallet, &mut txn);
+        txn.commit();
+
+        Ok(wallet_id)
     }
 }
 
+fn partially_signed_transaction_from_hex(s: String) -> Result<PartiallySignedTransaction, Error> {
+    Ok(PartiallySignedTransaction::from_bytes(&hex::decode(s)?)?)
+}
+
 #[nimiq_jsonrpc_derive::service(rename_all = "camelCase")]
 #[async_trait]
 impl WalletInterface for WalletDispatcher {
@@ -137,25 +222,7 @@ impl WalletInterface for WalletDispatcher {
     ) -> RPCResult<ReturnSignature, (), Self::Error> {
         let message = message_from_maybe_hex(message, is_hex)?;
 
-        let passphrase = passphrase.unwrap_or_default();
-
-        let wallet_account: WalletAccount;
-        let unlocked_wallets = self.unlocked_wallets.read();
-
-        let wallet = if let Some(wallet) = unlocked_wallets.get(&address) {
-            wallet
-        } else {
-            wallet_account = self
-                .wallet_store
-                .get(&address, None)
-                .ok_or(Error::AccountNotFound(address))?
-                .unlock(passphrase.as_bytes())
-                .map_err(|_locked| Error::WrongPassphrase)?
-                .key_pair
-                .clone()
-                .into();
-            &wallet_account
-        };
+        let wallet = self.get_wallet_account(address, passphrase)?;
 
         let (public_key, signature) = wallet.sign_message(&message);
 
@@ -176,4 +243,390 @@ impl WalletInterface for WalletDispatcher {
         let message = message_from_maybe_hex(message, is_hex)?;
         Ok(WalletAccount::verify_message(&public_key, &message, &signature).into())
     }
+
+    async fn create_signed_message(
+        &mut self,
+        message: String,
+        address: Address,
+        passphrase: Option<String>,
+        is_hex: bool,
+    ) -> RPCResult<String, (), Self::Error> {
+        let message = message_from_maybe_hex(message, is_hex)?;
+        let wallet = self.get_wallet_account(address, passphrase)?;
+
+        Ok(hex::encode(wallet.create_signed_message(&message).to_bytes()).into())
+    }
+
+    async fn verify_signed_message(
+        &mut self,
+        message: String,
+        signed_message: String,
+        address: Option<Address>,
+        is_hex: bool,
+    ) -> RPCResult<bool, (), Self::Error> {
+        let message = message_from_maybe_hex(message, is_hex)?;
+        let signed_message = SignedMessage::from_bytes(&hex::decode(signed_message)?)?;
+
+        let is_valid = signed_message.verify(&message).is_ok()
+            && address.map_or(true, |address| address == signed_message.signer);
+        Ok(is_valid.into())
+    }
+
+    async fn create_hd_wallet(
+        &mut self,
+        passphrase: Option<String>,
+    ) -> RPCResult<ReturnHdWallet, (), Self::Error> {
+        let passphrase = passphrase.unwrap_or_default();
+        let (wallet, mnemonic) = HdWallet::generate();
+        let wallet_id = self.store_hd_wallet(wallet, &passphrase)?;
+
+        Ok(ReturnHdWallet {
+            wallet_id,
+            mnemonic: mnemonic.to_string(),
+        }
+        .into())
+    }
+
+    async fn import_mnemonic(
+        &mut self,
+        mnemonic: String,
+        passphrase: Option<String>,
+    ) -> RPCResult<Address, (), Self::Error> {
+        let passphrase = passphrase.unwrap_or_default();
+
+        // Tolerate line breaks and repeated spaces between the words.
+        let mnemonic: Mnemonic = mnemonic
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .parse()
+            .map_err(|()| HdWalletError::InvalidMnemonic)?;
+        let wallet = HdWallet::from_mnemonic(&mnemonic)?;
+        let wallet_id = self.store_hd_wallet(wallet, &passphrase)?;
+
+        Ok(wallet_id.into())
+    }
+
+    async fn derive_account(
+        &mut self,
+        wallet_id: Address,
+        path: Option<String>,
+        index: Option<u32>,
+        passphrase: Option<String>,
+    ) -> RPCResult<ReturnDerivedAccount, (), Self::Error> {
+        let passphrase = passphrase.unwrap_or_default();
+        let path = match (path, index) {
+            (Some(_), Some(_)) => {
+                return Err(Error::InvalidArgument(
+                    "Either a path or an index can be given, not both".to_string(),
+                ))
+            }
+            (Some(path), None) => path,
+            (None, index) => HdWallet::path_for_index(index.unwrap_or_default()),
+        };
+
+        let wallet = self
+            .wallet_store
+            .get_hd_wallet(&wallet_id, None)
+            .ok_or(Error::HdWalletNotFound(wallet_id.clone()))?
+            .unlock(passphrase.as_bytes())
+            .map_err(|_locked| Error::WrongPassphrase)?;
+        let account = wallet.derive_account(&path)?;
+
+        let address = account.address.clone();
+        let derived_account = DerivedAccount {
+            wallet_id,
+            path,
+            public_key: account.key_pair.public,
+        };
+        let locked_account = Locked::with_defaults(account, passphrase.as_bytes())?;
+
+        let mut txn = self.wallet_store.create_write_transaction();
+        self.wallet_store.put(&address, &locked_account, &mut txn);
+        self.wallet_store
+            .put_derived_account(&address, &derived_account, &mut txn);
+        txn.commit();
+
+        Ok(ReturnDerivedAccount {
+            address,
+            public_key: derived_account.public_key,
+            path: derived_account.path,
+        }
+        .into())
+    }
+
+    async fn list_derived_accounts(
+        &mut self,
+        wallet_id: Address,
+    ) -> RPCResult<Vec<ReturnDerivedAccount>, (), Self::Error> {
+        if self.wallet_store.get_hd_wallet(&wallet_id, None).is_none() {
+            return Err(Error::HdWalletNotFound(wallet_id));
+        }
+
+        let accounts = self
+            .wallet_store
+            .list_derived_accounts(&wallet_id, None)
+            .into_iter()
+            .map(|(address, account)| ReturnDerivedAccount {
+                address,
+                public_key: account.public_key,
+                path: account.path,
+            })
+            .collect::<Vec<_>>();
+
+        Ok(accounts.into())
+    }
+
+    async fn export_wallet(&mut self, password: String) -> RPCResult<String, (), Self::Error> {
+        let contents = self.wallet_store.export_backup_contents();
+        let backup = WalletBackup::new(contents, password.as_bytes())?;
+
+        Ok(hex::encode(backup.to_bytes()).into())
+    }
+
+    async fn import_wallet(
+        &mut self,
+        backup: String,
+        password: String,
+        replace: Option<bool>,
+    ) -> RPCResult<Vec<Address>, (), Self::Error> {
+        let replace = replace.unwrap_or_default();
+        let contents =
+            WalletBackup::from_bytes(&hex::decode(backup)?)?.open(password.as_bytes())?;
+        let addresses = contents.addresses();
+
+        self.wallet_store.import_backup_contents(&contents, replace);
+        if replace {
+            // Lock the accounts that were removed by the import.
+            self.unlocked_wallets
+                .write()
+                .unlocked_wallets
+                .retain(|address, _| addresses.contains(address));
+        }
+
+        Ok(addresses.into())
+    }
+
+    async fn add_watch_only_address(
+        &mut self,
+        address: Address,
+        label: Option<String>,
+    ) -> RPCResult<(), (), Self::Error> {
+        let mut txn = self.wallet_store.create_write_transaction();
+        self.wallet_store
+            .put_watch_only(&address, &WatchOnlyAddress { label }, &mut txn);
+        txn.commit();
+
+        Ok(().into())
+    }
+
+    async fn remove_watch_only_address(
+        &mut self,
+        address: Address,
+    ) -> RPCResult<bool, (), Self::Error> {
+        if self.wallet_store.get_watch_only(&address, None).is_none() {
+            return Ok(false.into());
+        }
+
+        let mut txn = self.wallet_store.create_write_transaction();
+        self.wallet_store.remove_watch_only(&address, &mut txn);
+        txn.commit();
+
+        Ok(true.into())
+    }
+
+    async fn list_watch_only_addresses(
+        &mut self,
+    ) -> RPCResult<Vec<ReturnWatchOnlyAddress>, BlockchainState, Self::Error> {
+        let blockchain_proxy = self.blockchain.read();
+        let addresses = self
+            .wallet_store
+            .list_watch_only(None)
+            .into_iter()
+            .map(|(address, watch_only)| {
+                // Balances are only known if the node has the accounts tree.
+                let balance = match blockchain_proxy {
+                    BlockchainReadProxy::Full(ref blockchain) => blockchain
+                        .get_account_if_complete(&address)
+                        .map(|account| account.balance()),
+                    _ => None,
+                };
+                ReturnWatchOnlyAddress {
+                    address,
+                    label: watch_only.label,
+                    balance,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        Ok(RPCData::with_blockchain(addresses, &blockchain_proxy))
+    }
+
+    async fn create_unsigned_basic_transaction(
+        &mut self,
+        sender: Address,
+        recipient: Address,
+        value: Coin,
+        fee: Coin,
+        validity_start_height: ValidityStartHeight,
+    ) -> RPCResult<String, (), Self::Error> {
+        if self.wallet_store.get_watch_only(&sender, None).is_none() {
+            return Err(Error::WatchOnlyAddressNotFound(sender));
+        }
+
+        let blockchain = self.blockchain.read();
+        let transaction = Transaction::new_basic(
+            sender,
+            recipient,
+            value,
+            fee,
+            validity_start_height.block_number(blockchain.block_number()),
+            blockchain.network_id(),
+        );
+
+        Ok(hex::encode(transaction.serialize_to_vec()).into())
+    }
+
+    async fn create_partially_signed_transaction(
+        &mut self,
+        sender: Address,
+        recipient: Address,
+        value: Coin,
+        fee: Coin,
+        validity_start_height: ValidityStartHeight,
+        public_keys: Vec<Ed25519PublicKey>,
+        min_signatures: u8,
+        signers: Vec<Ed25519PublicKey>,
+    ) -> RPCResult<String, (), Self::Error> {
+        let blockchain = self.blockchain.read();
+        let transaction = Transaction::new_basic(
+            sender,
+            recipient,
+            value,
+            fee,
+            validity_start_height.block_number(blockchain.block_number()),
+            blockchain.network_id(),
+        );
+        let pst =
+            PartiallySignedTransaction::new(transaction, public_keys, min_signatures, signers)?;
+
+        Ok(hex::encode(pst.to_bytes()).into())
+    }
+
+    async fn add_multisig_commitments(
+        &mut self,
+        partially_signed_transaction: String,
+        address: Address,
+        passphrase: Option<String>,
+    ) -> RPCResult<String, (), Self::Error> {
+        let mut pst = partially_signed_transaction_from_hex(partially_signed_transaction)?;
+        let public_key = self
+            .get_wallet_account(address, passphrase)?
+            .key_pair
+            .public;
+
+        let key = (public_key, pst.transaction.hash::<Blake2bHash>());
+        self.prune_multisig_commitments();
+        let mut multisig_commitments = self.multisig_commitments.write();
+        if !multisig_commitments.contains_key(&key)
+            && multisig_commitments.len() >= MAX_PENDING_MULTISIG_ROUNDS
+        {
+            return Err(Error::TooManyMultisigRounds(MAX_PENDING_MULTISIG_ROUNDS));
+        }
+
+        let commitment_pairs: [CommitmentPair; MUSIG2_PARAMETER_V] =
+            std::array::from_fn(|_| CommitmentPair::generate_default_csprng());
+        pst.add_commitments(
+            public_key,
+            CommitmentPair::to_commitments(&commitment_pairs),
+        )?;
+        let expires_at =
+            pst.transaction.validity_start_height + Policy::transaction_validity_window_blocks();
+        multisig_commitments.insert(key, (commitment_pairs, expires_at));
+
+        Ok(hex::encode(pst.to_bytes()).into())
+    }
+
+    async fn sign_partially_signed_transaction(
+        &mut self,
+        partially_signed_transaction: String,
+        address: Address,
+        passphrase: Option<String>,
+    ) -> RPCResult<String, (), Self::Error> {
+        let mut pst = partially_signed_transaction_from_hex(partially_signed_transaction)?;
+        let wallet = self.get_wallet_account(address.clone(), passphrase)?;
+
+        let key = (
+            wallet.key_pair.public,
+            pst.transaction.hash::<Blake2bHash>(),
+        );
+        let mut multisig_commitments = self.multisig_commitments.write();
+        let (commitment_pairs, _) = multisig_commitments
+            .get(&key)
+            .ok_or(Error::MultisigCommitmentsNotFound(address))?;
+        pst.sign(&wallet.key_pair, commitment_pairs)?;
+        multisig_commitments.remove(&key);
+
+        Ok(hex::encode(pst.to_bytes()).into())
+    }
+
+    async fn combine_partially_signed_transactions(
+        &mut self,
+        partially_signed_transactions: Vec<String>,
+    ) -> RPCResult<String, (), Self::Error> {
+        let mut psts = partially_signed_transactions
+            .into_iter()
+            .map(partially_signed_transaction_from_hex);
+        let mut pst = psts.next().ok_or_else(|| {
+            Error::InvalidArgument("No partially signed transactions given".to_string())
+        })??;
+        for other in psts {
+            pst.combine(&other?)?;
+        }
+
+        Ok(hex::encode(pst.to_bytes()).into())
+    }
+
+    async fn finalize_partially_signed_transaction(
+        &mut self,
+        partially_signed_transaction: String,
+    ) -> RPCResult<String, (), Self::Error> {
+        let pst = partially_signed_transaction_from_hex(partially_signed_transaction)?;
+        let transaction = pst.finalize_transaction()?;
+
+        Ok(hex::encode(transaction.serialize_to_vec()).into())
+    }
+
+    #[stream]
+    async fn subscribe_for_watch_only_addresses(
+        &mut self,
+        log_types: Vec<LogType>,
+    ) -> Result<BoxStream<'static, RPCData<BlockLog, BlockchainState>>, Self::Error> {
+        if let BlockchainReadProxy::Full(blockchain) = self.blockchain.read() {
+            let wallet_store = Arc::clone(&self.wallet_store);
+            let stream = BroadcastStream::new(blockchain.log_notifier.subscribe());
+
+            Ok(stream
+                .filter_map(move |event| {
+                    // Read the watch-only addresses for every block to pick up changes.
+                    let addresses = wallet_store
+                        .list_watch_only(None)
+                        .into_iter()
+                        .map(|(address, _)| address)
+                        .collect::<Vec<_>>();
+
+                    // An empty address filter would match all addresses.
+                    let result = match event {
+                        Ok(event) if !addresses.is_empty() => {
+                            filter_block_log(event, &addresses, &log_types)
+                        }
+                        _ => None,
+                    };
+                    future::ready(result)
+                })
+                .boxed())
+        } else {
+            Err(Error::NotSupportedForLightBlockchain)
+        }
+    }
 }
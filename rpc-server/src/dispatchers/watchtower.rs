@@ -0,0 +1,69 @@
+use async_trait::async_trait;
+use futures::{future, stream::BoxStream, StreamExt};
+use nimiq_consensus::watchtower::ForkProofWatchtowerProxy;
+use nimiq_keys::Address;
+use nimiq_rpc_interface::{
+    types::{RPCData, RPCResult, SeenEquivocation},
+    watchtower::WatchtowerInterface,
+};
+
+use crate::error::Error;
+
+#[derive(Clone)]
+pub struct WatchtowerDispatcher {
+    watchtower: ForkProofWatchtowerProxy,
+}
+
+impl WatchtowerDispatcher {
+    pub fn new(watchtower: ForkProofWatchtowerProxy) -> Self {
+        WatchtowerDispatcher { watchtower }
+    }
+}
+
+#[nimiq_jsonrpc_derive::service(rename_all = "camelCase")]
+#[async_trait]
+impl WatchtowerInterface for WatchtowerDispatcher {
+    type Error = Error;
+
+    async fn get_seen_equivocations(
+        &mut self,
+    ) -> RPCResult<Vec<SeenEquivocation>, (), Self::Error> {
+        Ok(self
+            .watchtower
+            .get_seen_equivocations()
+            .into_iter()
+            .map(Into::into)
+            .collect::<Vec<_>>()
+            .into())
+    }
+
+    async fn get_seen_equivocations_by_validator(
+        &mut self,
+        address: Address,
+    ) -> RPCResult<Vec<SeenEquivocation>, (), Self::Error> {
+        Ok(self
+            .watchtower
+            .get_seen_equivocations_by_validator(&address)
+            .into_iter()
+            .map(Into::into)
+            .collect::<Vec<_>>()
+            .into())
+    }
+
+    #[stream]
+    async fn subscribe_for_equivocations(
+        &mut self,
+    ) -> Result<BoxStream<'static, RPCData<SeenEquivocation, ()>>, Self::Error> {
+        Ok(self
+            .watchtower
+            .subscribe()
+            .filter_map(|event| {
+                future::ready(
+                    event
+                        .ok()
+                        .map(|equivocation| SeenEquivocation::from(equivocation).into()),
+                )
+            })
+            .boxed())
+    }
+}
@@ -1,13 +1,14 @@
 use async_trait::async_trait;
 use nimiq_network_libp2p::Network;
 use nimiq_rpc_interface::{
-    types::{RPCResult, ZKPState},
+    types::{RPCResult, ZKPState, ZKProof},
     zkp_component::ZKPComponentInterface,
 };
 use nimiq_zkp_component::zkp_component::ZKPComponentProxy;
 
 use crate::error::Error;
 
+#[derive(Clone)]
 pub struct ZKPComponentDispatcher {
     zkp_component: ZKPComponentProxy<Network>,
 }
@@ -24,6 +25,24 @@ impl ZKPComponentInterface for ZKPComponentDispatcher {
     type Error = Error;
 
     async fn get_zkp_state(&mut self) -> RPCResult<ZKPState, (), Self::Error> {
-        Ok(ZKPState::with_zkp_state(&self.zkp_component.get_zkp_state()).into())
+        Ok(ZKPState::with_zkp_state(
+            &self.zkp_component.get_zkp_state(),
+            self.zkp_component.get_proving_progress().as_ref(),
+        )
+        .into())
+    }
+
+    async fn get_zkp_by_block_number(
+        &mut self,
+        block_number: u32,
+    ) -> RPCResult<ZKProof, (), Self::Error> {
+        let zk_proof = self
+            .zkp_component
+            .get_zkp_at(block_number)
+            .as_ref()
+            .and_then(ZKProof::from_zk_proof)
+            .ok_or(Error::ZKProofNotFound(block_number))?;
+
+        Ok(zk_proof.into())
     }
 }
@@ -1,7 +1,8 @@
 use nimiq_hash::Blake2bHash;
-use nimiq_jsonrpc_core::RpcError;
+use nimiq_jsonrpc_core::{Response, RpcError};
 use nimiq_keys::Address;
 use nimiq_mempool::verify::VerifyErr;
+use serde_json::Value;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -42,6 +43,9 @@ pub enum Error {
     #[error("Failed to build a transaction: {0}")]
     TransactionBuilder(#[from] nimiq_transaction_builder::TransactionBuilderError),
 
+    #[error("Failed to build a batch payment: {0}")]
+    BatchPayment(#[from] nimiq_transaction_builder::BatchPaymentError),
+
     #[error("No account with address: {0}")]
     AccountNotFound(Address),
 
@@ -63,6 +67,30 @@ pub enum Error {
     #[error("No unlocked wallet with address: {0}")]
     UnlockedWalletNotFound(Address),
 
+    #[error("No HD wallet with id: {0}")]
+    HdWalletNotFound(Address),
+
+    #[error("{0}")]
+    HdWallet(#[from] nimiq_wallet::HdWalletError),
+
+    #[error("No watch-only address: {0}")]
+    WatchOnlyAddressNotFound(Address),
+
+    #[error("{0}")]
+    SignedMessage(#[from] nimiq_transaction::signed_message::SignedMessageError),
+
+    #[error("{0}")]
+    PartiallySignedTransaction(#[from] nimiq_transaction_builder::PartiallySignedTransactionError),
+
+    #[error("No pending multisig commitments of {0} for this transaction")]
+    MultisigCommitmentsNotFound(Address),
+
+    #[error("Too many pending multisig signing rounds, at most {0} are allowed")]
+    TooManyMultisigRounds(usize),
+
+    #[error("{0}")]
+    WalletBackup(#[from] nimiq_wallet::WalletBackupError),
+
     #[error("Invalid hex: {0}")]
     HexError(#[from] hex::FromHexError),
 
@@ -81,11 +109,26 @@ pub enum Error {
     #[error("Invalid argument: {0}")]
     InvalidArgument(String),
 
+    #[error("No zk proof for block: {0}")]
+    ZKProofNotFound(u32),
+
     #[error("No consensus")]
     NoConsensus,
 
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
+
+    #[error("The result exceeds the maximum size of {0} items")]
+    ResultTooLarge(usize),
+
+    #[error("Rate limit exceeded")]
+    RateLimited,
+
+    #[error("Request timed out")]
+    Timeout,
+
+    #[error("Method not allowed: {0}")]
+    MethodNotAllowed(String),
 }
 
 impl From<Error> for RpcError {
@@ -93,3 +136,8 @@ impl From<Error> for RpcError {
         RpcError::internal_error(Some(serde_json::value::Value::String(e.to_string())))
     }
 }
+
+/// Returns the error response, unless the request is a notification that must not be answered.
+pub(crate) fn error_response(id: Option<Value>, error: Error) -> Option<Response> {
+    id.map(|id| Response::new_error(id, RpcError::from(error)))
+}
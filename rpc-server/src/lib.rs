@@ -1,6 +1,10 @@
 pub use error::Error;
 pub use nimiq_jsonrpc_server::{Config, Server};
 
+pub mod authorization;
 pub mod dispatchers;
 pub mod error;
+pub mod limits;
+pub mod modular;
+pub mod server;
 pub mod wallets;
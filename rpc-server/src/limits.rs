@@ -0,0 +1,380 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use nimiq_jsonrpc_core::{Request, Response};
+use nimiq_jsonrpc_server::Dispatcher;
+use parking_lot::Mutex;
+use tokio::sync::mpsc;
+use warp::ws::Message;
+
+use crate::{
+    authorization::Caller,
+    error::{error_response, Error},
+};
+
+/// The number of rate limiters above which the ones of idle clients are dropped.
+const MAX_IDLE_RATE_LIMITERS: usize = 1000;
+
+tokio::task_local! {
+    /// The time at which the request that is currently being dispatched was received.
+    static RECEIVED_AT: Instant;
+    /// The time at which the request that is currently being dispatched times out.
+    static DEADLINE: Instant;
+}
+
+/// Runs the future, usually the dispatch of a request, for a request received at the given time.
+///
+/// Timeouts are measured from this time, so that the time a request waits before it is dispatched
+/// counts towards its timeout. Without it, they are measured from the start of the dispatch.
+pub async fn scope_received_at<F: Future>(received_at: Instant, future: F) -> F::Output {
+    RECEIVED_AT.scope(received_at, future).await
+}
+
+/// Fails if the request that is currently being dispatched has timed out.
+///
+/// A timeout can only interrupt a request while it is waiting, so methods that scan the
+/// blockchain synchronously must call this regularly to stop once the request has timed out.
+pub fn check_deadline() -> Result<(), Error> {
+    match DEADLINE.try_with(|deadline| Instant::now() >= *deadline) {
+        Ok(true) => Err(Error::Timeout),
+        _ => Ok(()),
+    }
+}
+
+/// Limits on the requests of a client.
+#[derive(Clone, Debug, Default)]
+pub struct RequestLimits {
+    /// The cost of requests a client may spend per minute. Unlimited if not set.
+    pub rate_limit: Option<u32>,
+    /// The cost of each method. Methods that aren't listed cost 1.
+    pub method_costs: HashMap<String, u32>,
+    /// The maximum time between receiving a request and answering it.
+    pub timeout: Option<Duration>,
+}
+
+impl RequestLimits {
+    pub fn method_cost(&self, method: &str) -> u32 {
+        self.method_costs.get(method).copied().unwrap_or(1)
+    }
+}
+
+/// A token bucket that holds up to one minute worth of tokens and refills continuously.
+pub struct RateLimiter {
+    capacity: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    pub fn new(rate_per_minute: u32) -> Self {
+        RateLimiter {
+            capacity: rate_per_minute as f64,
+            tokens: rate_per_minute as f64,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Takes `cost` tokens from the bucket if there are enough. A cost higher than the rate per
+    /// minute is never granted.
+    pub fn try_acquire(&mut self, cost: u32) -> bool {
+        self.try_acquire_at(cost, Instant::now())
+    }
+
+    /// Returns if the bucket is full, i.e. the client hasn't sent a request for a while.
+    pub fn is_idle(&mut self) -> bool {
+        self.refill(Instant::now());
+        self.tokens >= self.capacity
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        self.tokens =
+            (self.tokens + elapsed.as_secs_f64() * self.capacity / 60.0).min(self.capacity);
+        self.last_refill = now;
+    }
+
+    fn try_acquire_at(&mut self, cost: u32, now: Instant) -> bool {
+        self.refill(now);
+
+        if self.tokens >= cost as f64 {
+            self.tokens -= cost as f64;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// A dispatcher that enforces the request limits of each client. Clients are identified by the
+/// username of the [`Caller`], or by its IP address if it didn't authenticate.
+///
+/// Clones share the rate limiters, so the limits hold across requests dispatched concurrently.
+#[derive(Clone)]
+pub struct LimitedDispatcher<D> {
+    inner: D,
+    limits: Arc<RequestLimits>,
+    rate_limiters: Arc<Mutex<HashMap<String, RateLimiter>>>,
+}
+
+impl<D: Dispatcher> LimitedDispatcher<D> {
+    pub fn new(inner: D, limits: RequestLimits) -> Self {
+        LimitedDispatcher {
+            inner,
+            limits: Arc::new(limits),
+            rate_limiters: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Takes the cost of the method from the rate limiter of the client.
+    fn try_acquire(&self, client: &str, method: &str) -> bool {
+        let Some(rate_limit) = self.limits.rate_limit else {
+            return true;
+        };
+
+        let mut rate_limiters = self.rate_limiters.lock();
+        if !rate_limiters.contains_key(client) && rate_limiters.len() >= MAX_IDLE_RATE_LIMITERS {
+            // A full bucket is the same as a new one, so the idle clients can be dropped.
+            rate_limiters.retain(|_, rate_limiter| !rate_limiter.is_idle());
+        }
+
+        rate_limiters
+            .entry(client.to_string())
+            .or_insert_with(|| RateLimiter::new(rate_limit))
+            .try_acquire(self.limits.method_cost(method))
+    }
+}
+
+/// Returns the name by which the client is rate limited and identified in the logs.
+fn client_name(caller: Option<&Caller>) -> String {
+    match caller {
+        Some(Caller {
+            username: Some(username),
+            ..
+        }) => username.clone(),
+        Some(Caller { ip: Some(ip), .. }) => ip.to_string(),
+        _ => "unknown".to_string(),
+    }
+}
+
+#[async_trait]
+impl<D: Dispatcher> Dispatcher for LimitedDispatcher<D> {
+    async fn dispatch(
+        &mut self,
+        request: Request,
+        tx: Option<&mpsc::Sender<Message>>,
+        id: u64,
+    ) -> Option<Response> {
+        let client = client_name(Caller::current().as_ref());
+        if !self.try_acquire(&client, &request.method) {
+            log::warn!(
+                %client,
+                method = %request.method,
+                "RPC request denied: rate limit exceeded"
+            );
+            return error_response(request.id, Error::RateLimited);
+        }
+
+        let Some(timeout) = self.limits.timeout else {
+            return self.inner.dispatch(request, tx, id).await;
+        };
+        let received_at = RECEIVED_AT
+            .try_with(|received_at| *received_at)
+            .unwrap_or_else(|_| Instant::now());
+        let deadline = received_at + timeout;
+        if Instant::now() >= deadline {
+            log::warn!(%client, method = %request.method, "RPC request timed out before dispatch");
+            return error_response(request.id, Error::Timeout);
+        }
+
+        let request_id = request.id.clone();
+        let method = request.method.clone();
+        let response = DEADLINE.scope(
+            deadline,
+            tokio::time::timeout_at(deadline.into(), self.inner.dispatch(request, tx, id)),
+        );
+        match response.await {
+            Ok(response) => response,
+            Err(_) => {
+                log::warn!(%client, %method, "RPC request timed out");
+                error_response(request_id, Error::Timeout)
+            }
+        }
+    }
+
+    fn match_method(&self, name: &str) -> bool {
+        self.inner.match_method(name)
+    }
+
+    fn method_names(&self) -> Vec<&str> {
+        self.inner.method_names()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nimiq_jsonrpc_core::RpcError;
+    use nimiq_test_log::test;
+    use serde_json::Value;
+
+    use super::*;
+
+    /// Answers `sleep` after a second, spins on `scan` until the request times out and answers any
+    /// other method immediately.
+    #[derive(Clone)]
+    struct TestDispatcher;
+
+    #[async_trait]
+    impl Dispatcher for TestDispatcher {
+        async fn dispatch(
+            &mut self,
+            request: Request,
+            _tx: Option<&mpsc::Sender<Message>>,
+            _id: u64,
+        ) -> Option<Response> {
+            let id = request.id?;
+            match request.method.as_str() {
+                "sleep" => tokio::time::sleep(Duration::from_secs(1)).await,
+                "scan" => loop {
+                    // A synchronous scan that never yields to the runtime.
+                    if let Err(error) = check_deadline() {
+                        return Some(Response::new_error(id, RpcError::from(error)));
+                    }
+                },
+                _ => {}
+            }
+            Some(Response::new_success(id, Value::String(request.method)))
+        }
+
+        fn match_method(&self, _name: &str) -> bool {
+            true
+        }
+
+        fn method_names(&self) -> Vec<&str> {
+            vec![]
+        }
+    }
+
+    fn caller(username: Option<&str>, ip: [u8; 4]) -> Caller {
+        Caller {
+            username: username.map(ToString::to_string),
+            ip: Some(ip.into()),
+            allowed_methods: None,
+        }
+    }
+
+    /// Dispatches the method on behalf of the caller and returns if it succeeded.
+    async fn call(
+        dispatcher: &mut LimitedDispatcher<TestDispatcher>,
+        caller: Caller,
+        method: &str,
+    ) -> bool {
+        let request = serde_json::from_value(serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": method,
+            "params": [],
+            "id": 1,
+        }))
+        .unwrap();
+        let response = caller
+            .scope(dispatcher.dispatch(request, None, 1))
+            .await
+            .unwrap();
+        serde_json::to_value(response)
+            .unwrap()
+            .get("error")
+            .is_none()
+    }
+
+    #[test(tokio::test)]
+    async fn it_limits_the_rate_per_client() {
+        let mut dispatcher = LimitedDispatcher::new(
+            TestDispatcher,
+            RequestLimits {
+                rate_limit: Some(3),
+                method_costs: HashMap::from([("expensive".to_string(), 2)]),
+                timeout: None,
+            },
+        );
+
+        let alice = caller(Some("alice"), [127, 0, 0, 1]);
+        assert!(call(&mut dispatcher, alice.clone(), "expensive").await);
+        assert!(call(&mut dispatcher, alice.clone(), "cheap").await);
+        assert!(!call(&mut dispatcher, alice.clone(), "cheap").await);
+
+        // Clones of the dispatcher share the rate limiters.
+        assert!(!call(&mut dispatcher.clone(), alice.clone(), "cheap").await);
+
+        // Other users and anonymous callers from other addresses have buckets of their own, even
+        // if they share an address.
+        let bob = caller(Some("bob"), [127, 0, 0, 1]);
+        assert!(call(&mut dispatcher, bob.clone(), "expensive").await);
+        assert!(!call(&mut dispatcher, bob, "expensive").await);
+
+        let anonymous = caller(None, [10, 0, 0, 1]);
+        assert!(call(&mut dispatcher, anonymous.clone(), "expensive").await);
+        assert!(call(&mut dispatcher, caller(None, [10, 0, 0, 2]), "expensive").await);
+        assert!(!call(&mut dispatcher, anonymous, "expensive").await);
+    }
+
+    #[test(tokio::test)]
+    async fn it_times_out_requests() {
+        let mut dispatcher = LimitedDispatcher::new(
+            TestDispatcher,
+            RequestLimits {
+                rate_limit: None,
+                method_costs: HashMap::new(),
+                timeout: Some(Duration::from_millis(50)),
+            },
+        );
+        let alice = caller(Some("alice"), [127, 0, 0, 1]);
+
+        assert!(call(&mut dispatcher, alice.clone(), "fast").await);
+        assert!(!call(&mut dispatcher, alice.clone(), "sleep").await);
+        assert!(!call(&mut dispatcher, alice.clone(), "scan").await);
+
+        // The time a request waited before being dispatched counts towards the timeout.
+        let request = serde_json::from_value(serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "fast",
+            "params": [],
+            "id": 1,
+        }))
+        .unwrap();
+        let response = scope_received_at(
+            Instant::now() - Duration::from_millis(100),
+            alice.scope(dispatcher.dispatch(request, None, 1)),
+        )
+        .await
+        .unwrap();
+        assert!(serde_json::to_value(response)
+            .unwrap()
+            .get("error")
+            .is_some());
+
+        // Without a deadline, there is nothing to check.
+        assert!(check_deadline().is_ok());
+    }
+
+    #[test]
+    fn it_limits_the_rate() {
+        let start = Instant::now();
+        let mut rate_limiter = RateLimiter::new(60);
+        rate_limiter.last_refill = start;
+
+        assert!(rate_limiter.try_acquire_at(50, start));
+        assert!(!rate_limiter.try_acquire_at(20, start));
+        assert!(rate_limiter.try_acquire_at(10, start));
+        assert!(!rate_limiter.try_acquire_at(1, start));
+
+        // One token is refilled per second, up to the capacity.
+        assert!(rate_limiter.try_acquire_at(5, start + Duration::from_secs(5)));
+        assert!(!rate_limiter.try_acquire_at(1, start + Duration::from_secs(5)));
+        assert!(!rate_limiter.try_acquire_at(61, start + Duration::from_secs(600)));
+        assert!(rate_limiter.try_acquire_at(60, start + Duration::from_secs(600)));
+    }
+}
@@ -0,0 +1,82 @@
+use async_trait::async_trait;
+use nimiq_jsonrpc_core::{Request, Response, RpcError};
+use nimiq_jsonrpc_server::Dispatcher;
+use serde_json::Value;
+use tokio::sync::mpsc;
+use warp::ws::Message;
+
+/// A dispatcher that can be cloned behind a trait object.
+trait CloneableDispatcher: Dispatcher {
+    fn clone_box(&self) -> Box<dyn CloneableDispatcher>;
+}
+
+impl<D: Dispatcher + Clone> CloneableDispatcher for D {
+    fn clone_box(&self) -> Box<dyn CloneableDispatcher> {
+        Box::new(self.clone())
+    }
+}
+
+/// Routes each request to the first of its dispatchers that handles the method.
+///
+/// Unlike [`nimiq_jsonrpc_server::ModularDispatcher`], it can be cloned, such that requests can be
+/// dispatched concurrently on clones of the dispatcher instead of one after the other. Dispatchers
+/// that keep state across requests must therefore share it between their clones.
+#[derive(Default)]
+pub struct ModularDispatcher {
+    dispatchers: Vec<Box<dyn CloneableDispatcher>>,
+}
+
+impl ModularDispatcher {
+    pub fn add<D: Dispatcher + Clone>(&mut self, dispatcher: D) {
+        self.dispatchers.push(Box::new(dispatcher));
+    }
+}
+
+impl Clone for ModularDispatcher {
+    fn clone(&self) -> Self {
+        ModularDispatcher {
+            dispatchers: self
+                .dispatchers
+                .iter()
+                .map(|dispatcher| dispatcher.clone_box())
+                .collect(),
+        }
+    }
+}
+
+#[async_trait]
+impl Dispatcher for ModularDispatcher {
+    async fn dispatch(
+        &mut self,
+        request: Request,
+        tx: Option<&mpsc::Sender<Message>>,
+        id: u64,
+    ) -> Option<Response> {
+        match self
+            .dispatchers
+            .iter_mut()
+            .find(|dispatcher| dispatcher.match_method(&request.method))
+        {
+            Some(dispatcher) => dispatcher.dispatch(request, tx, id).await,
+            None => request.id.map(|id| {
+                Response::new_error(
+                    id,
+                    RpcError::method_not_found(Some(Value::String(request.method))),
+                )
+            }),
+        }
+    }
+
+    fn match_method(&self, name: &str) -> bool {
+        self.dispatchers
+            .iter()
+            .any(|dispatcher| dispatcher.match_method(name))
+    }
+
+    fn method_names(&self) -> Vec<&str> {
+        self.dispatchers
+            .iter()
+            .flat_map(|dispatcher| dispatcher.method_names())
+            .collect()
+    }
+}
@@ -0,0 +1,473 @@
+use std::{
+    collections::HashSet,
+    net::{IpAddr, SocketAddr},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use nimiq_hash::{Blake2bHash, Hash};
+use nimiq_jsonrpc_core::{Request, Response, RpcError};
+use nimiq_jsonrpc_server::Dispatcher;
+use serde_json::Value;
+use subtle::ConstantTimeEq;
+use warp::{http::StatusCode, Filter, Reply};
+
+use crate::{authorization::Caller, limits::scope_received_at};
+
+/// The maximum size of a request body.
+const MAX_BODY_SIZE: u64 = 1024 * 1024;
+
+/// The realm sent to clients that failed to authenticate.
+const REALM: &str = "Basic realm=\"nimiq\"";
+
+/// An RPC user, authenticated with HTTP basic auth.
+#[derive(Clone, Debug)]
+pub struct RpcUser {
+    pub username: String,
+    pub password_hash: Blake2bHash,
+    /// The methods the user may call, or `None` if all methods are allowed.
+    pub allowed_methods: Option<HashSet<String>>,
+}
+
+/// The configuration of the RPC server.
+#[derive(Clone, Debug)]
+pub struct ServerConfig {
+    pub bind_to: SocketAddr,
+    pub users: Vec<RpcUser>,
+    /// Whether requests without credentials are accepted.
+    pub allow_anonymous: bool,
+    /// The methods anonymous callers may call, or `None` if all methods are allowed.
+    pub anonymous_methods: Option<HashSet<String>>,
+}
+
+struct User {
+    password_hash: Blake2bHash,
+    allowed_methods: Option<Arc<HashSet<String>>>,
+}
+
+/// A JSON-RPC server over HTTP that authenticates each request and dispatches it on behalf of the
+/// caller, see [`Caller`].
+///
+/// Each request is dispatched on its own clone of the dispatcher, so requests don't wait for each
+/// other.
+pub struct RpcServer<D> {
+    bind_to: SocketAddr,
+    users: Vec<(String, User)>,
+    anonymous: Option<Option<Arc<HashSet<String>>>>,
+    dispatcher: D,
+    next_id: AtomicU64,
+}
+
+impl<D: Dispatcher + Clone + 'static> RpcServer<D> {
+    pub fn new(config: ServerConfig, dispatcher: D) -> Self {
+        let users = config
+            .users
+            .into_iter()
+            .map(|user| {
+                (
+                    user.username,
+                    User {
+                        password_hash: user.password_hash,
+                        allowed_methods: user.allowed_methods.map(Arc::new),
+                    },
+                )
+            })
+            .collect();
+
+        RpcServer {
+            bind_to: config.bind_to,
+            users,
+            anonymous: config
+                .allow_anonymous
+                .then(|| config.anonymous_methods.map(Arc::new)),
+            dispatcher,
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    /// Runs the server until the task is dropped.
+    pub async fn run(self) {
+        let bind_to = self.bind_to;
+        let server = Arc::new(self);
+
+        let routes = warp::post()
+            .and(warp::path::end())
+            .and(warp::addr::remote())
+            .and(warp::header::optional::<String>("authorization"))
+            .and(warp::body::content_length_limit(MAX_BODY_SIZE))
+            .and(warp::body::bytes())
+            .then(
+                move |remote: Option<SocketAddr>,
+                      authorization: Option<String>,
+                      body: warp::hyper::body::Bytes| {
+                    let server = Arc::clone(&server);
+                    async move {
+                        let ip = remote.map(|remote| remote.ip());
+                        let Some(caller) = server.authenticate(ip, authorization.as_deref()) else {
+                            return warp::reply::with_header(
+                                StatusCode::UNAUTHORIZED,
+                                "www-authenticate",
+                                REALM,
+                            )
+                            .into_response();
+                        };
+
+                        match server.handle_request(caller, &body).await {
+                            Some(response) => warp::reply::with_header(
+                                response,
+                                "content-type",
+                                "application/json",
+                            )
+                            .into_response(),
+                            None => StatusCode::NO_CONTENT.into_response(),
+                        }
+                    }
+                },
+            );
+
+        warp::serve(routes).run(bind_to).await;
+    }
+
+    /// Authenticates the caller with the HTTP basic auth credentials. Returns `None` and logs the
+    /// attempt if the credentials are invalid, or missing and anonymous callers aren't allowed.
+    pub fn authenticate(&self, ip: Option<IpAddr>, authorization: Option<&str>) -> Option<Caller> {
+        let Some(authorization) = authorization else {
+            if let Some(allowed_methods) = &self.anonymous {
+                return Some(Caller {
+                    username: None,
+                    ip,
+                    allowed_methods: allowed_methods.clone(),
+                });
+            }
+            log::warn!(?ip, "RPC request denied: missing credentials");
+            return None;
+        };
+
+        let Some((username, password)) = parse_basic_auth(authorization) else {
+            log::warn!(?ip, "RPC request denied: malformed credentials");
+            return None;
+        };
+
+        let user = self
+            .users
+            .iter()
+            .find(|(name, _)| *name == username)
+            .map(|(_, user)| user)
+            .filter(|user| {
+                user.password_hash
+                    .ct_eq(&password.hash::<Blake2bHash>())
+                    .into()
+            });
+        let Some(user) = user else {
+            log::warn!(%username, ?ip, "RPC request denied: invalid credentials");
+            return None;
+        };
+
+        Some(Caller {
+            username: Some(username),
+            ip,
+            allowed_methods: user.allowed_methods.clone(),
+        })
+    }
+
+    /// Dispatches a single or batch request on behalf of the caller and returns the serialized
+    /// response, or `None` if the request only contained notifications.
+    pub async fn handle_request(&self, caller: Caller, body: &[u8]) -> Option<Vec<u8>> {
+        // Timeouts include the time a request waits, e.g. behind the other requests of a batch.
+        let received_at = Instant::now();
+        let response = match serde_json::from_slice::<Value>(body) {
+            Ok(Value::Array(requests)) if !requests.is_empty() => {
+                let mut responses = vec![];
+                for request in requests {
+                    if let Some(response) =
+                        self.dispatch(caller.clone(), request, received_at).await
+                    {
+                        responses.push(response);
+                    }
+                }
+                if responses.is_empty() {
+                    return None;
+                }
+                serde_json::to_vec(&responses)
+            }
+            Ok(Value::Array(_)) => serde_json::to_vec(&Response::new_error(
+                Value::Null,
+                RpcError::invalid_request(None),
+            )),
+            Ok(request) => serde_json::to_vec(&self.dispatch(caller, request, received_at).await?),
+            Err(error) => serde_json::to_vec(&Response::new_error(
+                Value::Null,
+                RpcError::parse_error(Some(Value::String(error.to_string()))),
+            )),
+        };
+
+        Some(response.expect("Failed to serialize RPC response"))
+    }
+
+    async fn dispatch(
+        &self,
+        caller: Caller,
+        request: Value,
+        received_at: Instant,
+    ) -> Option<Response> {
+        let request = match serde_json::from_value::<Request>(request) {
+            Ok(request) => request,
+            Err(error) => {
+                return Some(Response::new_error(
+                    Value::Null,
+                    RpcError::invalid_request(Some(Value::String(error.to_string()))),
+                ))
+            }
+        };
+
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let mut dispatcher = self.dispatcher.clone();
+        let response = dispatcher.dispatch(request, None, id);
+        caller.scope(scope_received_at(received_at, response)).await
+    }
+}
+
+/// Parses the username and password of a HTTP basic auth header.
+fn parse_basic_auth(authorization: &str) -> Option<(String, String)> {
+    let encoded = authorization.strip_prefix("Basic ")?;
+    let decoded = String::from_utf8(STANDARD.decode(encoded.trim()).ok()?).ok()?;
+    let (username, password) = decoded.split_once(':')?;
+    Some((username.to_string(), password.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use nimiq_test_log::test;
+    use tokio::sync::mpsc;
+    use warp::ws::Message;
+
+    use super::*;
+    use crate::authorization::AuthorizedDispatcher;
+
+    /// Answers every request with the name of the method.
+    #[derive(Clone)]
+    struct EchoDispatcher;
+
+    #[async_trait]
+    impl Dispatcher for EchoDispatcher {
+        async fn dispatch(
+            &mut self,
+            request: Request,
+            _tx: Option<&mpsc::Sender<Message>>,
+            _id: u64,
+        ) -> Option<Response> {
+            request
+                .id
+                .map(|id| Response::new_success(id, Value::String(request.method)))
+        }
+
+        fn match_method(&self, name: &str) -> bool {
+            ["getBlockNumber", "sendTransaction"].contains(&name)
+        }
+
+        fn method_names(&self) -> Vec<&str> {
+            vec!["getBlockNumber", "sendTransaction"]
+        }
+    }
+
+    fn server(allow_anonymous: bool) -> RpcServer<AuthorizedDispatcher<EchoDispatcher>> {
+        RpcServer::new(
+            ServerConfig {
+                bind_to: ([127, 0, 0, 1], 0).into(),
+                users: vec![
+                    RpcUser {
+                        username: "admin".to_string(),
+                        password_hash: "secret".hash(),
+                        allowed_methods: None,
+                    },
+                    RpcUser {
+                        username: "reader".to_string(),
+                        password_hash: "password".hash(),
+                        allowed_methods: Some(HashSet::from(["getBlockNumber".to_string()])),
+                    },
+                ],
+                allow_anonymous,
+                anonymous_methods: Some(HashSet::new()),
+            },
+            AuthorizedDispatcher::new(EchoDispatcher),
+        )
+    }
+
+    fn basic_auth(username: &str, password: &str) -> String {
+        format!(
+            "Basic {}",
+            STANDARD.encode(format!("{username}:{password}"))
+        )
+    }
+
+    async fn call(
+        server: &RpcServer<AuthorizedDispatcher<EchoDispatcher>>,
+        caller: Caller,
+        method: &str,
+    ) -> Value {
+        let request = format!(r#"{{"jsonrpc":"2.0","method":"{method}","params":[],"id":1}}"#);
+        let response = server
+            .handle_request(caller, request.as_bytes())
+            .await
+            .unwrap();
+        serde_json::from_slice(&response).unwrap()
+    }
+
+    #[test]
+    fn it_authenticates_users() {
+        let server = server(false);
+
+        let caller = server
+            .authenticate(None, Some(&basic_auth("reader", "password")))
+            .unwrap();
+        assert_eq!(caller.username.as_deref(), Some("reader"));
+        assert!(caller.is_allowed("getBlockNumber"));
+        assert!(!caller.is_allowed("sendTransaction"));
+
+        let caller = server
+            .authenticate(None, Some(&basic_auth("admin", "secret")))
+            .unwrap();
+        assert!(caller.is_allowed("sendTransaction"));
+
+        assert!(server
+            .authenticate(None, Some(&basic_auth("reader", "secret")))
+            .is_none());
+        assert!(server
+            .authenticate(None, Some(&basic_auth("unknown", "password")))
+            .is_none());
+        assert!(server.authenticate(None, Some("Basic ???")).is_none());
+        assert!(server.authenticate(None, None).is_none());
+
+        let caller = server(true).authenticate(None, None).unwrap();
+        assert!(caller.username.is_none());
+        assert!(!caller.is_allowed("getBlockNumber"));
+    }
+
+    #[test(tokio::test)]
+    async fn it_dispatches_only_the_allowed_methods() {
+        let server = server(false);
+        let caller = server
+            .authenticate(None, Some(&basic_auth("reader", "password")))
+            .unwrap();
+
+        let response = call(&server, caller.clone(), "getBlockNumber").await;
+        assert_eq!(response["result"], "getBlockNumber");
+        assert!(response.get("error").is_none());
+
+        let response = call(&server, caller, "sendTransaction").await;
+        assert!(response.get("result").is_none());
+        assert!(response["error"]["data"]
+            .as_str()
+            .unwrap()
+            .contains("sendTransaction"));
+
+        // Requests that aren't dispatched on behalf of a caller are denied.
+        let mut dispatcher = AuthorizedDispatcher::new(EchoDispatcher);
+        let request = serde_json::from_str(
+            r#"{"jsonrpc":"2.0","method":"getBlockNumber","params":[],"id":1}"#,
+        )
+        .unwrap();
+        let response = dispatcher.dispatch(request, None, 1).await.unwrap();
+        assert!(serde_json::to_value(response)
+            .unwrap()
+            .get("error")
+            .is_some());
+    }
+
+    /// Answers every request once the given number of requests is waiting.
+    #[derive(Clone)]
+    struct BarrierDispatcher(Arc<tokio::sync::Barrier>);
+
+    #[async_trait]
+    impl Dispatcher for BarrierDispatcher {
+        async fn dispatch(
+            &mut self,
+            request: Request,
+            _tx: Option<&mpsc::Sender<Message>>,
+            _id: u64,
+        ) -> Option<Response> {
+            self.0.wait().await;
+            request
+                .id
+                .map(|id| Response::new_success(id, Value::String(request.method)))
+        }
+
+        fn match_method(&self, _name: &str) -> bool {
+            true
+        }
+
+        fn method_names(&self) -> Vec<&str> {
+            vec![]
+        }
+    }
+
+    #[test(tokio::test)]
+    async fn it_dispatches_requests_concurrently() {
+        let server = RpcServer::new(
+            ServerConfig {
+                bind_to: ([127, 0, 0, 1], 0).into(),
+                users: vec![],
+                allow_anonymous: true,
+                anonymous_methods: None,
+            },
+            BarrierDispatcher(Arc::new(tokio::sync::Barrier::new(2))),
+        );
+        let caller = server.authenticate(None, None).unwrap();
+        let request = br#"{"jsonrpc":"2.0","method":"getBlockNumber","params":[],"id":1}"#;
+
+        // Both requests only complete if they are dispatched at the same time.
+        let responses = tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            futures::future::join(
+                server.handle_request(caller.clone(), request),
+                server.handle_request(caller, request),
+            ),
+        )
+        .await
+        .expect("Requests were dispatched one after the other");
+        assert!(responses.0.is_some());
+        assert!(responses.1.is_some());
+    }
+
+    #[test(tokio::test)]
+    async fn it_handles_batches_and_invalid_requests() {
+        let server = server(false);
+        let caller = server
+            .authenticate(None, Some(&basic_auth("reader", "password")))
+            .unwrap();
+
+        let request = r#"[
+            {"jsonrpc":"2.0","method":"getBlockNumber","params":[],"id":1},
+            {"jsonrpc":"2.0","method":"getBlockNumber","params":[]},
+            {"jsonrpc":"2.0","method":"sendTransaction","params":[],"id":2}
+        ]"#;
+        let response = server
+            .handle_request(caller.clone(), request.as_bytes())
+            .await
+            .unwrap();
+        let response: Vec<Value> = serde_json::from_slice(&response).unwrap();
+        assert_eq!(response.len(), 2);
+        assert_eq!(response[0]["result"], "getBlockNumber");
+        assert!(response[1].get("error").is_some());
+
+        // Notifications aren't answered.
+        let request = r#"{"jsonrpc":"2.0","method":"getBlockNumber","params":[]}"#;
+        assert!(server
+            .handle_request(caller.clone(), request.as_bytes())
+            .await
+            .is_none());
+
+        let response = server.handle_request(caller.clone(), b"{").await.unwrap();
+        let response: Value = serde_json::from_slice(&response).unwrap();
+        assert!(response.get("error").is_some());
+
+        let response = server.handle_request(caller, b"[]").await.unwrap();
+        let response: Value = serde_json::from_slice(&response).unwrap();
+        assert!(response.get("error").is_some());
+    }
+}
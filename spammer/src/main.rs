@@ -27,7 +27,7 @@ use nimiq_keys::{Address, KeyPair, PrivateKey, SecureGenerate};
 use nimiq_mempool::mempool::Mempool;
 use nimiq_primitives::{coin::Coin, networks::NetworkId, policy::Policy};
 use nimiq_transaction::Transaction;
-use nimiq_transaction_builder::TransactionBuilder;
+use nimiq_transaction_builder::{ExternalSigner, KeyFileSigner, TransactionBuilder};
 use rand::{
     distributions::{Distribution, WeightedIndex},
     thread_rng, Rng,
@@ -58,6 +58,17 @@ pub struct SpammerCommandLine {
     ///
     #[clap(long, short)]
     pub profile: Option<PathBuf>,
+
+    /// A file containing the private key, in hex format, used to sign the transactions that
+    /// spend the funds. It is read for every signature, like an external signer would.
+    /// Defaults to the fee key of the network.
+    ///
+    /// # Examples
+    ///
+    /// * `nimiq-spammer --key-file funding.key`
+    ///
+    #[clap(long)]
+    pub key_file: Option<PathBuf>,
 }
 
 pub struct SpammerAccounts {
@@ -140,6 +151,7 @@ async fn main_inner() -> Result<(), Error> {
         sync_mode: None,
         network: None,
         prove: false,
+        zkp_worker: None,
     };
 
     // Parse config file - this will obey the `--config` command line option.
@@ -190,10 +202,15 @@ async fn main_inner() -> Result<(), Error> {
         _ => panic!("Unsupported network"),
     };
 
-    let key_pair = KeyPair::from(PrivateKey::from_str(private_key).unwrap());
+    let signer: Arc<dyn ExternalSigner + Send + Sync> = match spammer_command_line.key_file {
+        Some(ref path) => {
+            Arc::new(KeyFileSigner::new(path).map_err(|e| Error::config_error(e.to_string()))?)
+        }
+        None => Arc::new(KeyPair::from(PrivateKey::from_str(private_key).unwrap())),
+    };
     log::info!(
         "Funds for txs will come from this address: {}",
-        Address::from(&key_pair)
+        signer.address()
     );
 
     // Create client from config.
@@ -227,6 +244,17 @@ async fn main_inner() -> Result<(), Error> {
     tokio::spawn(consensus);
     let consensus = client.consensus_proxy();
 
+    // Start Spammer
+    let (mempool, handel_metrics) = if let Some(validator) = client.take_validator() {
+        log::info!("Spawning spammer");
+        let mempool = Arc::clone(&validator.mempool);
+        let handel_metrics = validator.get_handel_metrics();
+        tokio::spawn(validator);
+        (mempool, handel_metrics)
+    } else {
+        panic!("Could not start spammer");
+    };
+
     // Initialize metrics server
     if let Some(metrics_config) = metrics_config {
         use nimiq::extras::metrics_server::start_metrics_server;
@@ -234,22 +262,13 @@ async fn main_inner() -> Result<(), Error> {
             metrics_config.addr,
             client.blockchain(),
             client.mempool(),
+            handel_metrics,
             client.consensus_proxy(),
             client.network(),
             &[],
         )
     }
 
-    // Start Spammer
-    let mempool = if let Some(validator) = client.take_validator() {
-        log::info!("Spawning spammer");
-        let mempool = Arc::clone(&validator.mempool);
-        tokio::spawn(validator);
-        mempool
-    } else {
-        panic!("Could not start spammer");
-    };
-
     let rolling_window = Policy::blocks_per_batch() as usize;
 
     let mut stat_exerts: VecDeque<StatsExert> = VecDeque::new();
@@ -298,7 +317,7 @@ async fn main_inner() -> Result<(), Error> {
                     spam(
                         Arc::clone(&mempool),
                         consensus.clone(),
-                        key_pair.clone(),
+                        Arc::clone(&signer),
                         conf_options.clone(),
                         Arc::clone(&state),
                     )
@@ -378,7 +397,7 @@ async fn main_inner() -> Result<(), Error> {
 async fn spam(
     mempool: Arc<Mempool>,
     consensus: ConsensusProxy,
-    key_pair: KeyPair,
+    signer: Arc<dyn ExternalSigner + Send + Sync>,
     config: Arc<SpammerGenerationOptions>,
     state: Arc<RwLock<SpammerState>>,
 ) {
@@ -401,7 +420,7 @@ async fn spam(
             SpamType::BaseBasicTransaction => {
                 new_count = config.tpb;
                 generate_basic_transactions(
-                    &key_pair,
+                    signer.as_ref(),
                     number,
                     net_id,
                     new_count,
@@ -412,7 +431,7 @@ async fn spam(
             SpamType::BurstBasicTransaction => {
                 new_count = rng.gen_range(config.tpb * 10..config.tpb * 20);
                 generate_basic_transactions(
-                    &key_pair,
+                    signer.as_ref(),
                     number,
                     net_id,
                     new_count,
@@ -422,7 +441,7 @@ async fn spam(
             }
             SpamType::Vesting => {
                 new_count = rng.gen_range(0..config.tpb);
-                generate_vesting_contracts(&key_pair, number, net_id, new_count, state)
+                generate_vesting_contracts(signer.as_ref(), number, net_id, new_count, state)
             }
         };
 
@@ -445,7 +464,7 @@ async fn spam(
 }
 
 fn generate_basic_transactions(
-    key_pair: &KeyPair,
+    signer: &dyn ExternalSigner,
     start_height: u32,
     network_id: NetworkId,
     count: usize,
@@ -522,7 +541,7 @@ fn generate_basic_transactions(
         }
 
         let tx = TransactionBuilder::new_basic(
-            key_pair,
+            signer,
             recipient,
             amount,
             Coin::ZERO,
@@ -537,7 +556,7 @@ fn generate_basic_transactions(
 }
 
 fn generate_vesting_contracts(
-    key_pair: &KeyPair,
+    signer: &dyn ExternalSigner,
     start_height: u32,
     network_id: NetworkId,
     count: usize,
@@ -574,7 +593,7 @@ fn generate_vesting_contracts(
         let recipient = Address::from(&new_kp);
 
         let tx = TransactionBuilder::new_create_vesting(
-            key_pair,
+            signer,
             recipient,
             1,
             1,
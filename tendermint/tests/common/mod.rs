@@ -13,6 +13,7 @@ use tokio::{sync::mpsc, time::timeout};
 use tokio_stream::wrappers::ReceiverStream;
 
 pub mod helper;
+pub mod simulation;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct TestProposal(pub u32);
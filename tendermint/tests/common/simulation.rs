@@ -0,0 +1,999 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures::{
+    future::{self, BoxFuture, FutureExt},
+    stream::{self, BoxStream, StreamExt},
+};
+use nimiq_collections::BitSet;
+use nimiq_handel::partitioner::{BinomialPartitioner, Partitioner};
+use nimiq_tendermint::*;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+
+/// Initial timeout of the simulated protocol in (virtual) milliseconds.
+const TIMEOUT_INIT: u64 = 500;
+/// Timeout increase per round of the simulated protocol in (virtual) milliseconds.
+const TIMEOUT_DELTA: u64 = 250;
+/// Resolution of the virtual clock in milliseconds.
+const TICK: u64 = 10;
+/// Interval in which the nodes send their level updates, mimicking Handel's periodic updates.
+const UPDATE_INTERVAL: u64 = 50;
+/// Number of peers contacted per level in every update, like Handel's default `update_count`.
+const UPDATE_COUNT: usize = 1;
+/// Maximum number of items a single node may produce within one tick before the run is considered stuck.
+const MAX_POLLS_PER_TICK: usize = 1_000;
+
+/// Returns the proposer for `round`.
+pub fn proposer(round: u32, offset: usize, validators: usize) -> u16 {
+    ((round as usize + offset) % validators) as u16
+}
+
+/// The behaviour of a simulated validator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Behaviour {
+    /// Follows the protocol.
+    Honest,
+    /// Follows the protocol, but all of its messages are delayed by the given amount of milliseconds.
+    Delayed(u64),
+    /// Crashed from the start, never sends anything.
+    Silent,
+    /// Follows the protocol, but never publishes its own proposals.
+    Withholding,
+    /// Sends conflicting proposals and conflicting votes to different peers.
+    Equivocating,
+}
+
+impl Behaviour {
+    /// Correct validators must never decide on conflicting values and must always decide eventually.
+    pub fn is_correct(&self) -> bool {
+        matches!(self, Behaviour::Honest | Behaviour::Delayed(_))
+    }
+
+    fn runs_tendermint(&self) -> bool {
+        !matches!(self, Behaviour::Silent | Behaviour::Equivocating)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SimulationConfig {
+    /// Seed for all randomness within the simulation, i.e. message delays, drops and byzantine choices.
+    pub seed: u64,
+    /// The behaviour of each validator. Validators without an entry are honest.
+    pub behaviours: BTreeMap<u16, Behaviour>,
+    /// Shifts the proposer schedule, such that validator `proposer_offset` proposes in round 0.
+    pub proposer_offset: usize,
+    /// Minimum delay of a message in milliseconds.
+    pub min_delay: u64,
+    /// Maximum delay of a message in milliseconds.
+    pub max_delay: u64,
+    /// Probability of a message getting lost before `gst`.
+    pub drop_probability: f64,
+    /// Global stabilization time in milliseconds. No messages get lost after it.
+    pub gst: u64,
+    /// Duration after which the run is stopped in milliseconds.
+    pub max_time: u64,
+}
+
+impl SimulationConfig {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            behaviours: BTreeMap::default(),
+            proposer_offset: 0,
+            min_delay: 1,
+            max_delay: 100,
+            drop_probability: 0.0,
+            gst: 0,
+            max_time: 60_000,
+        }
+    }
+
+    pub fn with_behaviour(mut self, validator: u16, behaviour: Behaviour) -> Self {
+        self.behaviours.insert(validator, behaviour);
+        self
+    }
+
+    /// Creates a random configuration for `validators` validators out of which at most f are byzantine.
+    pub fn random(seed: u64, validators: usize) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut config = Self::new(seed);
+
+        let mut ids: Vec<u16> = (0..validators as u16).collect();
+        ids.shuffle(&mut rng);
+        let faulty = rng.gen_range(0..=(validators - 1) / 3);
+
+        for (index, validator) in ids.into_iter().enumerate() {
+            let behaviour = if index < faulty {
+                *[
+                    Behaviour::Silent,
+                    Behaviour::Withholding,
+                    Behaviour::Equivocating,
+                ]
+                .choose(&mut rng)
+                .unwrap()
+            } else if rng.gen_bool(0.2) {
+                Behaviour::Delayed(rng.gen_range(50..=500))
+            } else {
+                Behaviour::Honest
+            };
+            config.behaviours.insert(validator, behaviour);
+        }
+
+        config.proposer_offset = rng.gen_range(0..validators);
+        config.min_delay = rng.gen_range(1..=20);
+        config.max_delay = config.min_delay + rng.gen_range(0..=300);
+        if rng.gen_bool(0.3) {
+            config.drop_probability = 0.1;
+            config.gst = rng.gen_range(0..=3_000);
+        }
+
+        config
+    }
+
+    fn behaviour(&self, validator: u16) -> Behaviour {
+        self.behaviours
+            .get(&validator)
+            .copied()
+            .unwrap_or(Behaviour::Honest)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SimProposal {
+    pub proposer: u16,
+    /// The round the proposal was created in.
+    pub round: u32,
+    /// Distinguishes the conflicting proposals of an equivocating proposer.
+    pub variant: u8,
+}
+
+impl Proposal<u32, u32> for SimProposal {
+    fn hash(&self) -> u32 {
+        (self.round << 12) | ((self.proposer as u32) << 2) | self.variant as u32
+    }
+    fn inherent_hash(&self) -> u32 {
+        self.hash()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SimInherent(pub u32);
+impl Inherent<u32> for SimInherent {
+    fn hash(&self) -> u32 {
+        self.0
+    }
+}
+
+/// Votes of a set of validators, keyed by the voted for proposal hash.
+pub type Contributions = BTreeMap<Option<u32>, BitSet>;
+
+fn merge(contributions: &mut Contributions, other: &Contributions) {
+    for (vote, contributors) in other.iter() {
+        *contributions.entry(*vote).or_default() |= contributors.clone();
+    }
+}
+
+fn all_contributors(contributions: &Contributions) -> BitSet {
+    let mut b = BitSet::default();
+    for c in contributions.iter() {
+        b |= c.1.clone();
+    }
+    b
+}
+
+fn contributors_for(contributions: &Contributions, vote: Option<&u32>) -> BitSet {
+    contributions
+        .get(&vote.cloned())
+        .cloned()
+        .unwrap_or_default()
+}
+
+fn proposals(contributions: &Contributions) -> Vec<(u32, usize)> {
+    contributions
+        .iter()
+        .filter_map(|(hash_opt, sig)| hash_opt.map(|hash| (hash, sig.len())))
+        .collect()
+}
+
+/// Returns the Handel level in which `peer` is contacted by the owner of `partitioner`.
+///
+/// The binomial partitioner is symmetric, i.e. this is also the level in which the peer contacts the owner.
+fn level_of(partitioner: &BinomialPartitioner, peer: usize) -> Option<usize> {
+    (1..partitioner.levels()).find(|level| {
+        partitioner
+            .range(*level)
+            .is_ok_and(|range| range.contains(&peer))
+    })
+}
+
+/// The votes a validator aggregated so far.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SimAggregate {
+    pub contributions: Contributions,
+}
+
+impl Aggregation<u32> for SimAggregate {
+    fn all_contributors(&self) -> BitSet {
+        all_contributors(&self.contributions)
+    }
+    fn contributors_for(&self, vote: Option<&u32>) -> BitSet {
+        contributors_for(&self.contributions, vote)
+    }
+    fn proposals(&self) -> Vec<(u32, usize)> {
+        proposals(&self.contributions)
+    }
+}
+
+/// A Handel level update, carrying the votes of all levels below `level` of the sender.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SimLevelUpdate {
+    pub sender: u16,
+    pub level: usize,
+    pub contributions: Contributions,
+}
+
+impl Aggregation<u32> for SimLevelUpdate {
+    fn all_contributors(&self) -> BitSet {
+        all_contributors(&self.contributions)
+    }
+    fn contributors_for(&self, vote: Option<&u32>) -> BitSet {
+        contributors_for(&self.contributions, vote)
+    }
+    fn proposals(&self) -> Vec<(u32, usize)> {
+        proposals(&self.contributions)
+    }
+}
+
+impl AggregationMessage<u32> for SimLevelUpdate {
+    fn sender(&self) -> u16 {
+        self.sender
+    }
+}
+
+/// A single Handel aggregation, i.e. the votes received per level.
+///
+/// The peers are partitioned into levels by Handel's own binomial partitioner. Level 0 holds the own
+/// vote, every other level the votes of the peers in its range. Signatures are not modelled, thus the
+/// votes received for a level are combined instead of only keeping the best contribution.
+struct SimHandel {
+    partitioner: BinomialPartitioner,
+    levels: Vec<Contributions>,
+}
+
+impl SimHandel {
+    fn new(id: u16, validators: usize, vote: Option<u32>) -> Self {
+        let partitioner = BinomialPartitioner::new(id as usize, validators);
+        let mut levels = vec![Contributions::default(); partitioner.levels()];
+        levels[0].insert(vote, BitSet::from_iter([id as usize]));
+
+        Self {
+            partitioner,
+            levels,
+        }
+    }
+
+    /// Adds the votes of an update to its level. Updates sent at the wrong level are ignored, and only
+    /// votes of validators within the range of the level are taken into account.
+    fn apply(&mut self, update: &SimLevelUpdate) {
+        let level = update.level;
+        if level_of(&self.partitioner, update.sender as usize) != Some(level) {
+            return;
+        }
+        let range = self.partitioner.range(level).expect("Level must exist");
+
+        let contributions: Contributions = update
+            .contributions
+            .iter()
+            .map(|(vote, contributors)| {
+                let contributors: BitSet = contributors
+                    .iter()
+                    .filter(|contributor| range.contains(contributor))
+                    .collect();
+                (*vote, contributors)
+            })
+            .collect();
+        merge(&mut self.levels[level], &contributions);
+    }
+
+    /// The votes of all levels combined.
+    fn aggregate(&self) -> SimAggregate {
+        let mut contributions = Contributions::default();
+        for level in self.levels.iter() {
+            merge(&mut contributions, level);
+        }
+        SimAggregate { contributions }
+    }
+
+    /// The update for the peers of `level`, containing the votes of all levels below it.
+    fn level_update(&self, sender: u16, level: usize) -> SimLevelUpdate {
+        let mut contributions = Contributions::default();
+        for lower_level in self.levels[..level].iter() {
+            merge(&mut contributions, lower_level);
+        }
+        SimLevelUpdate {
+            sender,
+            level,
+            contributions,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SimDecision {
+    pub proposal: SimProposal,
+    pub round: u32,
+    pub signers: BitSet,
+}
+
+#[derive(Clone, Debug)]
+enum Message {
+    Proposal(SignedProposalMessage<SimProposal, u16>),
+    Aggregate(TaggedAggregationMessage<SimLevelUpdate>),
+}
+
+struct Envelope {
+    to: u16,
+    message: Message,
+}
+
+/// Delivers messages between the simulated validators in virtual time.
+pub struct SimNetwork {
+    rng: StdRng,
+    now: u64,
+    next_seq: u64,
+    validators: u16,
+    min_delay: u64,
+    max_delay: u64,
+    drop_probability: f64,
+    gst: u64,
+    extra_delays: BTreeMap<u16, u64>,
+    /// Messages in flight, keyed by their delivery time and a sequence number.
+    queue: BTreeMap<(u64, u64), Envelope>,
+    /// All proposals sent to at least one peer, keyed by round and proposal hash.
+    published_proposals: BTreeMap<(u32, u32), SignedProposalMessage<SimProposal, u16>>,
+    messages_sent: usize,
+    messages_dropped: usize,
+}
+
+impl SimNetwork {
+    fn new(config: &SimulationConfig, validators: u16) -> Self {
+        let extra_delays = config
+            .behaviours
+            .iter()
+            .filter_map(|(validator, behaviour)| match behaviour {
+                Behaviour::Delayed(delay) => Some((*validator, *delay)),
+                _ => None,
+            })
+            .collect();
+
+        Self {
+            rng: StdRng::seed_from_u64(config.seed),
+            now: 0,
+            next_seq: 0,
+            validators,
+            min_delay: config.min_delay,
+            max_delay: config.max_delay,
+            drop_probability: config.drop_probability,
+            gst: config.gst,
+            extra_delays,
+            queue: BTreeMap::default(),
+            published_proposals: BTreeMap::default(),
+            messages_sent: 0,
+            messages_dropped: 0,
+        }
+    }
+
+    fn random_delay(&mut self) -> u64 {
+        self.rng.gen_range(self.min_delay..=self.max_delay)
+    }
+
+    fn send(&mut self, from: u16, to: u16, message: Message) {
+        self.messages_sent += 1;
+        if self.now < self.gst && self.rng.gen_bool(self.drop_probability) {
+            self.messages_dropped += 1;
+            return;
+        }
+
+        let delay = self.random_delay() + self.extra_delays.get(&from).copied().unwrap_or(0);
+        self.queue
+            .insert((self.now + delay, self.next_seq), Envelope { to, message });
+        self.next_seq += 1;
+    }
+
+    fn broadcast(&mut self, from: u16, message: Message) {
+        for to in 0..self.validators {
+            if to != from {
+                self.send(from, to, message.clone());
+            }
+        }
+    }
+
+    fn publish_proposal(&mut self, proposal: SignedProposalMessage<SimProposal, u16>) {
+        self.published_proposals.insert(
+            (proposal.message.round, proposal.message.proposal.hash()),
+            proposal,
+        );
+    }
+
+    fn proposal_hashes(&self, round: u32) -> Vec<u32> {
+        self.published_proposals
+            .range((round, 0)..=(round, u32::MAX))
+            .map(|((_round, hash), _proposal)| *hash)
+            .collect()
+    }
+
+    /// Removes all messages which are due at `now` from the queue and returns them in delivery order.
+    fn take_due(&mut self) -> Vec<Envelope> {
+        let later = self.queue.split_off(&(self.now + 1, 0));
+        std::mem::replace(&mut self.queue, later)
+            .into_values()
+            .collect()
+    }
+}
+
+/// The protocol of a single simulated validator. `N` is the total number of validators.
+#[derive(Clone)]
+pub struct SimProtocol<const N: usize> {
+    id: u16,
+    proposer_offset: usize,
+    withhold_proposals: bool,
+    network: Arc<Mutex<SimNetwork>>,
+    /// The Handel instance of every aggregation this validator started.
+    aggregations: Arc<Mutex<BTreeMap<(u32, Step), SimHandel>>>,
+}
+
+impl<const N: usize> SimProtocol<N> {
+    fn proposer(&self, round: u32) -> u16 {
+        proposer(round, self.proposer_offset, N)
+    }
+}
+
+impl<const N: usize> Protocol for SimProtocol<N> {
+    type Proposal = SimProposal;
+    type ProposalHash = u32;
+    type InherentHash = u32;
+    type ProposalSignature = u16;
+    type Inherent = SimInherent;
+    type Aggregation = SimAggregate;
+    type AggregationMessage = SimLevelUpdate;
+    type Decision = SimDecision;
+
+    const F_PLUS_ONE: usize = (N - 1) / 3 + 1;
+    const TWO_F_PLUS_ONE: usize = N - (N - 1) / 3;
+    const TIMEOUT_INIT: u64 = TIMEOUT_INIT;
+    const TIMEOUT_DELTA: u64 = TIMEOUT_DELTA;
+
+    fn is_proposer(&self, round: u32) -> Result<bool, ProtocolError> {
+        Ok(self.proposer(round) == self.id)
+    }
+
+    fn create_proposal(
+        &self,
+        round: u32,
+    ) -> Result<(ProposalMessage<Self::Proposal>, Self::Inherent), ProtocolError> {
+        let proposal = SimProposal {
+            proposer: self.id,
+            round,
+            variant: 0,
+        };
+        let inherent = SimInherent(proposal.hash());
+        Ok((
+            ProposalMessage {
+                round,
+                valid_round: None,
+                proposal,
+            },
+            inherent,
+        ))
+    }
+
+    fn sign_proposal(
+        &self,
+        _proposal_message: &ProposalMessage<Self::Proposal>,
+    ) -> Self::ProposalSignature {
+        self.id
+    }
+
+    fn verify_proposal(
+        &self,
+        proposal: &SignedProposalMessage<Self::Proposal, Self::ProposalSignature>,
+        precalculated_inherent: Option<Self::Inherent>,
+    ) -> Result<Self::Inherent, ProposalError> {
+        let message = &proposal.message;
+        // The message must be signed by the proposer of its round, while the proposal itself must have
+        // been created by the proposer of the round it was created in.
+        if proposal.signature != self.proposer(message.round)
+            || message.proposal.proposer != self.proposer(message.proposal.round)
+            || message.proposal.round > message.round
+        {
+            return Err(ProposalError::InvalidProposal);
+        }
+
+        Ok(precalculated_inherent.unwrap_or_else(|| SimInherent(message.proposal.hash())))
+    }
+
+    fn broadcast_proposal(
+        &self,
+        proposal: SignedProposalMessage<Self::Proposal, Self::ProposalSignature>,
+    ) {
+        if self.withhold_proposals {
+            return;
+        }
+
+        let mut network = self.network.lock().unwrap();
+        network.publish_proposal(proposal.clone());
+        network.broadcast(self.id, Message::Proposal(proposal));
+    }
+
+    fn request_proposal(
+        &self,
+        proposal_hash: Self::ProposalHash,
+        round: u32,
+        _candidates: BitSet,
+    ) -> BoxFuture<'static, Option<SignedProposalMessage<Self::Proposal, Self::ProposalSignature>>>
+    {
+        let mut network = self.network.lock().unwrap();
+        let proposal = network
+            .published_proposals
+            .get(&(round, proposal_hash))
+            .cloned();
+        // A request takes a round trip.
+        let delay = network.random_delay() + network.random_delay();
+
+        tokio::time::sleep(Duration::from_millis(delay))
+            .map(move |()| proposal)
+            .boxed()
+    }
+
+    fn create_decision(
+        &self,
+        proposal: Self::Proposal,
+        _inherent: Self::Inherent,
+        aggregation: Self::Aggregation,
+        round: u32,
+    ) -> Self::Decision {
+        let signers = aggregation.contributors_for(Some(&proposal.hash()));
+        SimDecision {
+            proposal,
+            round,
+            signers,
+        }
+    }
+
+    fn create_aggregation(
+        &self,
+        round: u32,
+        step: Step,
+        vote: Option<Self::ProposalHash>,
+        update_stream: BoxStream<'static, Self::AggregationMessage>,
+    ) -> BoxStream<'static, Self::Aggregation> {
+        let handel = SimHandel::new(self.id, N, vote);
+        let own = handel.aggregate();
+
+        // The level updates are picked up from here to be sent to the other validators periodically.
+        let aggregations = Arc::clone(&self.aggregations);
+        aggregations.lock().unwrap().insert((round, step), handel);
+
+        stream::once(future::ready(own))
+            .chain(update_stream.map(move |update| {
+                let mut aggregations = aggregations.lock().unwrap();
+                let handel = aggregations
+                    .get_mut(&(round, step))
+                    .expect("Aggregation must exist");
+                handel.apply(&update);
+                handel.aggregate()
+            }))
+            .boxed()
+    }
+
+    fn verify_aggregation_message(
+        &self,
+        _round: u32,
+        _step: Step,
+        _message: Self::AggregationMessage,
+    ) -> BoxFuture<'static, Result<(), ()>> {
+        future::ready(Ok(())).boxed()
+    }
+}
+
+struct SimNode<const N: usize> {
+    id: u16,
+    behaviour: Behaviour,
+    tendermint: Option<Tendermint<SimProtocol<N>>>,
+    proposal_sender: Option<mpsc::UnboundedSender<SignedProposalMessage<SimProposal, u16>>>,
+    message_sender: Option<mpsc::UnboundedSender<TaggedAggregationMessage<SimLevelUpdate>>>,
+    aggregations: Arc<Mutex<BTreeMap<(u32, Step), SimHandel>>>,
+    current_round: u32,
+    decision: Option<SimDecision>,
+    /// Rounds in which an equivocating node already sent its conflicting proposals.
+    proposed_rounds: BTreeSet<u32>,
+}
+
+impl<const N: usize> SimNode<N> {
+    fn new(id: u16, config: &SimulationConfig, network: &Arc<Mutex<SimNetwork>>) -> Self {
+        let behaviour = config.behaviour(id);
+        let aggregations = Arc::new(Mutex::new(BTreeMap::default()));
+
+        let mut node = Self {
+            id,
+            behaviour,
+            tendermint: None,
+            proposal_sender: None,
+            message_sender: None,
+            aggregations: Arc::clone(&aggregations),
+            current_round: 0,
+            decision: None,
+            proposed_rounds: BTreeSet::default(),
+        };
+
+        if behaviour.runs_tendermint() {
+            let protocol = SimProtocol {
+                id,
+                proposer_offset: config.proposer_offset,
+                withhold_proposals: behaviour == Behaviour::Withholding,
+                network: Arc::clone(network),
+                aggregations,
+            };
+
+            let (proposal_sender, proposal_receiver) = mpsc::unbounded_channel();
+            let (message_sender, message_receiver) = mpsc::unbounded_channel();
+
+            node.tendermint = Some(Tendermint::new(
+                protocol,
+                None,
+                UnboundedReceiverStream::new(proposal_receiver).boxed(),
+                UnboundedReceiverStream::new(message_receiver).boxed(),
+            ));
+            node.proposal_sender = Some(proposal_sender);
+            node.message_sender = Some(message_sender);
+        }
+
+        node
+    }
+
+    fn deliver(&self, message: Message) {
+        // Sending fails once the node terminated, which is fine.
+        match message {
+            Message::Proposal(proposal) => {
+                if let Some(sender) = &self.proposal_sender {
+                    let _ = sender.send(proposal);
+                }
+            }
+            Message::Aggregate(aggregate) => {
+                if let Some(sender) = &self.message_sender {
+                    let _ = sender.send(aggregate);
+                }
+            }
+        }
+    }
+
+    /// Polls the Tendermint instance of this node until it returns `Poll::Pending` or terminates.
+    fn poll(&mut self, cx: &mut Context<'_>, seed: u64) {
+        let mut polls = 0;
+        while let Some(tendermint) = self.tendermint.as_mut() {
+            polls += 1;
+            assert!(
+                polls <= MAX_POLLS_PER_TICK,
+                "seed {}: validator {} does not return Poll::Pending",
+                seed,
+                self.id,
+            );
+
+            match tendermint.poll_next_unpin(cx) {
+                Poll::Ready(Some(Return::Update(state))) => {
+                    self.current_round = state.current_round;
+                }
+                Poll::Ready(Some(Return::Decision(decision))) => {
+                    self.current_round = decision.round;
+                    self.decision = Some(decision);
+                }
+                Poll::Ready(Some(_proposal)) => {}
+                Poll::Ready(None) => self.tendermint = None,
+                Poll::Pending => break,
+            }
+        }
+    }
+}
+
+/// The result of a simulation run.
+#[derive(Debug)]
+pub struct SimulationOutcome {
+    pub seed: u64,
+    /// The validators which are honest or only delayed.
+    pub correct: BTreeSet<u16>,
+    /// The decisions of the correct validators.
+    pub decisions: BTreeMap<u16, SimDecision>,
+    /// The number of votes needed for a decision, i.e. 2f+1.
+    pub quorum: usize,
+    /// Elapsed virtual time in milliseconds.
+    pub elapsed: u64,
+    pub messages_sent: usize,
+    pub messages_dropped: usize,
+}
+
+impl SimulationOutcome {
+    /// Checks that no two correct validators decided on conflicting proposals and that every decision
+    /// is backed by a quorum.
+    pub fn check_safety(&self) -> Result<(), String> {
+        let mut first: Option<(u16, &SimDecision)> = None;
+        for (validator, decision) in self.decisions.iter() {
+            if decision.signers.len() < self.quorum {
+                return Err(format!(
+                    "seed {}: validator {} decided with only {} signers",
+                    self.seed,
+                    validator,
+                    decision.signers.len(),
+                ));
+            }
+
+            match first {
+                Some((other, other_decision)) if other_decision.proposal != decision.proposal => {
+                    return Err(format!(
+                        "seed {}: validator {} decided {:?} in round {}, but validator {} decided {:?} in round {}",
+                        self.seed,
+                        other,
+                        other_decision.proposal,
+                        other_decision.round,
+                        validator,
+                        decision.proposal,
+                        decision.round,
+                    ));
+                }
+                Some(_) => {}
+                None => first = Some((*validator, decision)),
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that every correct validator decided.
+    pub fn check_liveness(&self) -> Result<(), String> {
+        let undecided: Vec<_> = self
+            .correct
+            .iter()
+            .filter(|validator| !self.decisions.contains_key(validator))
+            .collect();
+
+        if undecided.is_empty() {
+            Ok(())
+        } else {
+            Err(format!(
+                "seed {}: validators {:?} did not decide within {}ms",
+                self.seed, undecided, self.elapsed,
+            ))
+        }
+    }
+}
+
+/// Simulates a single Tendermint height with `N` validators.
+///
+/// All validators run in a single task on a paused tokio clock which is advanced in steps of `TICK`,
+/// such that timeouts elapse in virtual time. Messages are scheduled by a seeded random number generator,
+/// thus a run can be replayed using its seed. The only source of randomness outside of the seed is
+/// Tendermint itself, which picks the order in which future round messages are verified at random.
+///
+/// Votes are aggregated along Handel's levels: every `UPDATE_INTERVAL`, each node sends the votes of
+/// its lower levels to `UPDATE_COUNT` random peers of every level. Signatures and Handel's scoring are
+/// not modelled. Equivocating validators send different votes to different validators.
+pub fn simulate<const N: usize>(config: SimulationConfig) -> SimulationOutcome {
+    let _reporter = SeedReporter(config.seed);
+
+    tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .start_paused(true)
+        .build()
+        .expect("Failed to build the runtime")
+        .block_on(Simulation::<N>::new(config).run())
+}
+
+/// Prints the seed of a run which panicked, such that it can be replayed.
+struct SeedReporter(u64);
+
+impl Drop for SeedReporter {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            eprintln!("Tendermint simulation with seed {} panicked", self.0);
+        }
+    }
+}
+
+struct Simulation<const N: usize> {
+    config: SimulationConfig,
+    network: Arc<Mutex<SimNetwork>>,
+    nodes: Vec<SimNode<N>>,
+    /// Randomness used by byzantine validators.
+    rng: StdRng,
+}
+
+impl<const N: usize> Simulation<N> {
+    fn new(config: SimulationConfig) -> Self {
+        let network = Arc::new(Mutex::new(SimNetwork::new(&config, N as u16)));
+        let nodes = (0..N as u16)
+            .map(|id| SimNode::new(id, &config, &network))
+            .collect();
+        let rng = StdRng::seed_from_u64(config.seed.wrapping_add(1));
+
+        Self {
+            config,
+            network,
+            nodes,
+            rng,
+        }
+    }
+
+    async fn run(mut self) -> SimulationOutcome {
+        let waker = futures::task::noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        let seed = self.config.seed;
+        let mut now = 0;
+
+        loop {
+            let due = self.network.lock().unwrap().take_due();
+            for envelope in due {
+                self.nodes[envelope.to as usize].deliver(envelope.message);
+            }
+
+            if now % UPDATE_INTERVAL == 0 {
+                self.send_updates();
+                self.act_byzantine();
+            }
+
+            for node in self.nodes.iter_mut() {
+                node.poll(&mut cx, seed);
+            }
+
+            let all_decided = self
+                .nodes
+                .iter()
+                .all(|node| !node.behaviour.is_correct() || node.decision.is_some());
+            if all_decided || now >= self.config.max_time {
+                break;
+            }
+
+            tokio::time::advance(Duration::from_millis(TICK)).await;
+            now += TICK;
+            self.network.lock().unwrap().now = now;
+        }
+
+        let network = self.network.lock().unwrap();
+        SimulationOutcome {
+            seed,
+            correct: self
+                .nodes
+                .iter()
+                .filter(|node| node.behaviour.is_correct())
+                .map(|node| node.id)
+                .collect(),
+            decisions: self
+                .nodes
+                .iter()
+                .filter(|node| node.behaviour.is_correct())
+                .filter_map(|node| node.decision.clone().map(|decision| (node.id, decision)))
+                .collect(),
+            quorum: SimProtocol::<N>::TWO_F_PLUS_ONE,
+            elapsed: now,
+            messages_sent: network.messages_sent,
+            messages_dropped: network.messages_dropped,
+        }
+    }
+
+    /// Sends the level updates of the aggregations of the current and the previous round of every node
+    /// to `UPDATE_COUNT` random peers per level.
+    ///
+    /// Nodes keep doing so after they decided, which allows the others to learn about the decision.
+    fn send_updates(&mut self) {
+        let mut network = self.network.lock().unwrap();
+        for node in self.nodes.iter() {
+            if !node.behaviour.runs_tendermint() {
+                continue;
+            }
+
+            let first_round = node.current_round.saturating_sub(1);
+            let aggregations = node.aggregations.lock().unwrap();
+            for ((round, step), handel) in aggregations.range((first_round, Step::Propose)..) {
+                for level in 1..handel.partitioner.levels() {
+                    let Ok(range) = handel.partitioner.range(level) else {
+                        continue;
+                    };
+                    let peers: Vec<usize> = range
+                        .collect::<Vec<_>>()
+                        .choose_multiple(&mut network.rng, UPDATE_COUNT)
+                        .copied()
+                        .collect();
+                    for peer in peers {
+                        network.send(
+                            node.id,
+                            peer as u16,
+                            Message::Aggregate(TaggedAggregationMessage {
+                                tag: (*round, *step),
+                                aggregation: handel.level_update(node.id, level),
+                            }),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// Lets the equivocating validators send conflicting proposals and votes for the rounds the correct
+    /// validators are currently in.
+    fn act_byzantine(&mut self) {
+        let max_round = self
+            .nodes
+            .iter()
+            .filter(|node| node.behaviour.is_correct())
+            .map(|node| node.current_round)
+            .max()
+            .unwrap_or(0);
+
+        let mut network = self.network.lock().unwrap();
+        for node in self.nodes.iter_mut() {
+            if node.behaviour != Behaviour::Equivocating {
+                continue;
+            }
+
+            let mut peers: Vec<u16> = (0..N as u16).filter(|peer| *peer != node.id).collect();
+            let partitioner = BinomialPartitioner::new(node.id as usize, N);
+
+            for round in max_round.saturating_sub(1)..=max_round {
+                // Send one proposal to half of the peers and a different one to the other half.
+                if proposer(round, self.config.proposer_offset, N) == node.id
+                    && node.proposed_rounds.insert(round)
+                {
+                    peers.shuffle(&mut self.rng);
+                    for (index, peer) in peers.iter().enumerate() {
+                        let proposal = SignedProposalMessage {
+                            message: ProposalMessage {
+                                round,
+                                valid_round: None,
+                                proposal: SimProposal {
+                                    proposer: node.id,
+                                    round,
+                                    variant: (2 * index / peers.len()) as u8,
+                                },
+                            },
+                            signature: node.id,
+                        };
+                        network.publish_proposal(proposal.clone());
+                        network.send(node.id, *peer, Message::Proposal(proposal));
+                    }
+                }
+
+                // Vote for a random option for every peer.
+                let mut options = vec![None];
+                options.extend(network.proposal_hashes(round).into_iter().map(Some));
+
+                for step in [Step::Prevote, Step::Precommit] {
+                    for peer in peers.iter() {
+                        let level = level_of(&partitioner, *peer as usize)
+                            .expect("Every peer is on some level");
+                        let mut contributions = Contributions::default();
+                        contributions.insert(
+                            *options.choose(&mut self.rng).unwrap(),
+                            BitSet::from_iter([node.id as usize]),
+                        );
+
+                        network.send(
+                            node.id,
+                            *peer,
+                            Message::Aggregate(TaggedAggregationMessage {
+                                tag: (round, step),
+                                aggregation: SimLevelUpdate {
+                                    sender: node.id,
+                                    level,
+                                    contributions,
+                                },
+                            }),
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
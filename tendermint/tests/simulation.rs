@@ -0,0 +1,150 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ops::Range,
+};
+
+use nimiq_collections::BitSet;
+
+pub mod common;
+
+use self::common::simulation::*;
+
+/// The seeds every scenario is run with. They are fixed, such that failures can be reproduced.
+const SEEDS: Range<u64> = 0..20;
+
+/// Number of runs of the soak test. Can be changed using the `TENDERMINT_SIMULATION_RUNS` environment
+/// variable.
+fn soak_runs() -> u64 {
+    std::env::var("TENDERMINT_SIMULATION_RUNS")
+        .ok()
+        .and_then(|runs| runs.parse().ok())
+        .unwrap_or(1_000)
+}
+
+/// The seed of the first run of the soak test. It is random, unless set using the
+/// `TENDERMINT_SIMULATION_SEED` environment variable, e.g. to replay a failed run.
+fn soak_seed() -> u64 {
+    std::env::var("TENDERMINT_SIMULATION_SEED")
+        .ok()
+        .and_then(|seed| seed.parse().ok())
+        .unwrap_or_else(rand::random)
+}
+
+fn assert_safe_and_live(outcome: &SimulationOutcome) {
+    if let Err(error) = outcome.check_safety() {
+        panic!("Safety violated: {}", error);
+    }
+    if let Err(error) = outcome.check_liveness() {
+        panic!("Liveness violated: {}", error);
+    }
+}
+
+#[test]
+fn honest_validators_decide() {
+    for seed in SEEDS {
+        let outcome = simulate::<4>(SimulationConfig::new(seed));
+        assert_safe_and_live(&outcome);
+        assert_eq!(outcome.decisions.len(), 4);
+    }
+}
+
+#[test]
+fn it_tolerates_a_silent_proposer() {
+    for seed in SEEDS {
+        let config = SimulationConfig::new(seed).with_behaviour(0, Behaviour::Silent);
+        let outcome = simulate::<4>(config);
+        assert_safe_and_live(&outcome);
+        // Validator 0 would have proposed in round 0.
+        assert!(outcome
+            .decisions
+            .values()
+            .all(|decision| decision.round > 0));
+    }
+}
+
+#[test]
+fn it_tolerates_an_equivocating_proposer() {
+    for seed in SEEDS {
+        let config = SimulationConfig::new(seed).with_behaviour(0, Behaviour::Equivocating);
+        assert_safe_and_live(&simulate::<4>(config));
+    }
+}
+
+#[test]
+fn it_tolerates_withholding_validators() {
+    for seed in SEEDS {
+        let config = SimulationConfig::new(seed)
+            .with_behaviour(0, Behaviour::Withholding)
+            .with_behaviour(1, Behaviour::Withholding);
+        assert_safe_and_live(&simulate::<7>(config));
+    }
+}
+
+#[test]
+fn it_tolerates_delays_and_message_loss() {
+    for seed in SEEDS {
+        let mut config = SimulationConfig::new(seed)
+            .with_behaviour(2, Behaviour::Delayed(400))
+            .with_behaviour(5, Behaviour::Equivocating);
+        config.max_delay = 300;
+        config.drop_probability = 0.2;
+        config.gst = 5_000;
+        assert_safe_and_live(&simulate::<7>(config));
+    }
+}
+
+#[test]
+fn randomized_runs_are_safe_and_live() {
+    for seed in SEEDS {
+        assert_safe_and_live(&simulate::<7>(SimulationConfig::random(seed, 7)));
+    }
+}
+
+/// Runs randomized configurations with varying numbers of validators. Run it with `--ignored`.
+#[test]
+#[ignore]
+fn randomized_soak() {
+    let first_seed = soak_seed();
+    println!("Running the soak test starting with seed {}", first_seed);
+
+    for run in 0..soak_runs() {
+        let seed = first_seed.wrapping_add(run);
+        assert_safe_and_live(&simulate::<7>(SimulationConfig::random(seed, 7)));
+        assert_safe_and_live(&simulate::<10>(SimulationConfig::random(seed, 10)));
+    }
+}
+
+#[test]
+fn safety_check_detects_conflicting_decisions() {
+    let mut signers = BitSet::default();
+    for validator in 0..3 {
+        signers.insert(validator);
+    }
+    let decision = |proposer| SimDecision {
+        proposal: SimProposal {
+            proposer,
+            round: 0,
+            variant: 0,
+        },
+        round: 0,
+        signers: signers.clone(),
+    };
+
+    let mut outcome = SimulationOutcome {
+        seed: 0,
+        correct: BTreeSet::from([0, 1]),
+        decisions: BTreeMap::from([(0, decision(0)), (1, decision(0))]),
+        quorum: 3,
+        elapsed: 0,
+        messages_sent: 0,
+        messages_dropped: 0,
+    };
+    assert!(outcome.check_safety().is_ok());
+    assert!(outcome.check_liveness().is_ok());
+
+    outcome.decisions.insert(1, decision(1));
+    assert!(outcome.check_safety().is_err());
+
+    outcome.decisions.remove(&1);
+    assert!(outcome.check_liveness().is_err());
+}
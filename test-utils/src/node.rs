@@ -88,6 +88,8 @@ impl<N: NetworkInterface + TestNetwork> Node<N> {
             is_prover_active,
             prover_path,
             PathBuf::from(ZKP_TEST_KEYS_PATH),
+            vec![],
+            Default::default(),
             zkp_storage,
         )
         .await;
@@ -51,10 +51,12 @@ where
             validator_network,
             validator_address,
             automatic_reactivate,
+            None,
             signing_key,
             voting_key,
             fee_key,
             MempoolConfig::default(),
+            Default::default(),
         ),
         consensus,
     )
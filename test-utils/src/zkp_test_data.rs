@@ -1,12 +1,13 @@
 use std::{fs::File, path::Path, sync::Arc};
 
 use ark_ff::ToConstraintField;
+use ark_groth16::Proof;
 use ark_mnt6_753::MNT6_753;
 use ark_serialize::CanonicalDeserialize;
 use nimiq_blockchain::Blockchain;
 use nimiq_blockchain_interface::AbstractBlockchain;
 use nimiq_genesis::NetworkInfo;
-use nimiq_hash::HashOutput;
+use nimiq_hash::{Blake2sHash, HashOutput};
 use nimiq_zkp_circuits::test_setup::ToxicWaste;
 use nimiq_zkp_component::types::ZKProof;
 use nimiq_zkp_primitives::VerifyingData;
@@ -72,12 +73,30 @@ pub fn simulate_merger_wrapper(
     let network_info = NetworkInfo::from_network_id(blockchain.read().network_id());
     let genesis_block = network_info.genesis_block().unwrap_macro();
 
-    let mut genesis_header_hash = genesis_block
-        .hash_blake2s()
-        .as_bytes()
-        .to_field_elements()
-        .unwrap();
-    let mut final_header_hash = block.hash_blake2s().as_bytes().to_field_elements().unwrap();
+    let proof = simulate_proof(
+        path,
+        &genesis_block.hash_blake2s(),
+        &block.hash_blake2s(),
+        verifying_data,
+        rng,
+    );
+    ZKProof {
+        block_number: block.block_number(),
+        proof: Some(proof),
+    }
+}
+
+/// Simulates a proof for the Merger Wrapper circuit attesting the transition between the blocks
+/// with the given header hashes.
+pub fn simulate_proof(
+    path: &Path,
+    genesis_header_hash: &Blake2sHash,
+    final_header_hash: &Blake2sHash,
+    verifying_data: &VerifyingData,
+    rng: &mut impl Rng,
+) -> Proof<MNT6_753> {
+    let mut genesis_header_hash = genesis_header_hash.as_bytes().to_field_elements().unwrap();
+    let mut final_header_hash = final_header_hash.as_bytes().to_field_elements().unwrap();
 
     // Prepare the inputs.
     let mut inputs = vec![];
@@ -89,9 +108,5 @@ pub fn simulate_merger_wrapper(
 
     // Simulate proof.
     let toxic_waste = load_merger_wrapper_simulator(path).expect("Missing toxic waste.");
-    let proof = toxic_waste.simulate_proof(&inputs, rng);
-    ZKProof {
-        block_number: block.block_number(),
-        proof: Some(proof),
-    }
+    toxic_waste.simulate_proof(&inputs, rng)
 }
@@ -0,0 +1,144 @@
+use std::process::exit;
+
+use anyhow::Error;
+use clap::{crate_authors, crate_version, Arg, ArgAction, ArgMatches, Command};
+use nimiq_keys::{Address, KeyPair, PrivateKey, PublicKey};
+use nimiq_serde::Deserialize;
+use nimiq_transaction::{
+    signed_message::{SignedMessage, SignedMessageError},
+    SignatureProof,
+};
+use serde_json::json;
+
+fn message_args(command: Command) -> Command {
+    command
+        .arg(
+            Arg::new("message")
+                .short('m')
+                .long("message")
+                .value_name("MESSAGE")
+                .required(true)
+                .help("The message."),
+        )
+        .arg(
+            Arg::new("hex")
+                .long("hex")
+                .action(ArgAction::SetTrue)
+                .help("The message is given as hex."),
+        )
+}
+
+fn read_message(matches: &ArgMatches) -> Result<Vec<u8>, Error> {
+    let message = matches.get_one::<String>("message").unwrap();
+    if matches.get_flag("hex") {
+        Ok(hex::decode(message)?)
+    } else {
+        Ok(message.as_bytes().to_vec())
+    }
+}
+
+fn run_app() -> Result<serde_json::Value, Error> {
+    let matches = Command::new("nimiq-signmsg")
+        .version(crate_version!())
+        .author(crate_authors!())
+        .about("Signs messages and verifies signed messages of any key type")
+        .subcommand_required(true)
+        .subcommand(message_args(
+            Command::new("sign")
+                .about("Signs a message and prints the signed message as hex")
+                .arg(
+                    Arg::new("secret_key")
+                        .short('k')
+                        .long("secret-key")
+                        .value_name("SECRET_KEY")
+                        .required(true)
+                        .help("The secret key to sign the message with."),
+                ),
+        ))
+        .subcommand(message_args(
+            Command::new("verify")
+                .about("Verifies a signed message, including Webauthn signatures")
+                .arg(
+                    Arg::new("signed_message")
+                        .short('s')
+                        .long("signed-message")
+                        .value_name("HEX")
+                        .required(true)
+                        .help("The signed message as hex."),
+                )
+                .arg(
+                    Arg::new("address")
+                        .short('a')
+                        .long("address")
+                        .value_name("ADDRESS")
+                        .help("Require the message to be signed by ADDRESS."),
+                ),
+        ))
+        .get_matches();
+
+    match matches.subcommand() {
+        Some(("sign", matches)) => {
+            let message = read_message(matches)?;
+            let raw_secret_key = hex::decode(matches.get_one::<String>("secret_key").unwrap())?;
+            let key_pair: KeyPair = PrivateKey::deserialize_from_vec(&raw_secret_key)?.into();
+
+            let hash = SignedMessage::message_hash(&message);
+            let signature = key_pair.sign(hash.as_slice());
+            let signed_message = SignedMessage::new(
+                Address::from(&key_pair),
+                SignatureProof::from_ed25519(key_pair.public, signature),
+            );
+
+            Ok(json!({
+                "signer": signed_message.signer.to_user_friendly_address(),
+                "signedMessage": hex::encode(signed_message.to_bytes()),
+            }))
+        }
+        Some(("verify", matches)) => {
+            let message = read_message(matches)?;
+            let signed_message = SignedMessage::from_bytes(&hex::decode(
+                matches.get_one::<String>("signed_message").unwrap(),
+            )?)?;
+            let address = matches
+                .get_one::<String>("address")
+                .map(|address| Address::from_any_str(address))
+                .transpose()?;
+
+            let result = signed_message.verify(&message).and_then(|_| match address {
+                Some(ref address) if *address != signed_message.signer => {
+                    Err(SignedMessageError::SignerMismatch)
+                }
+                _ => Ok(()),
+            });
+            let key_type = match signed_message.public_key() {
+                PublicKey::Ed25519(_) => "ed25519",
+                PublicKey::ES256(_) => "es256",
+            };
+
+            Ok(json!({
+                "valid": result.is_ok(),
+                "error": result.err().map(|e| e.to_string()),
+                "version": signed_message.version(),
+                "signer": signed_message.signer.to_user_friendly_address(),
+                "keyType": key_type,
+                "webauthn": signed_message.proof.webauthn_fields.is_some(),
+            }))
+        }
+        _ => unreachable!(),
+    }
+}
+
+fn main() {
+    // The result is always printed as JSON. For verifications, the exit code is only zero if the
+    // signed message is valid.
+    let (result, code) = match run_app() {
+        Ok(result) => {
+            let code = if result["valid"] == false { 1 } else { 0 };
+            (result, code)
+        }
+        Err(e) => (json!({ "error": e.to_string() }), 2),
+    };
+
+    println!("{result}");
+    exit(code);
+}
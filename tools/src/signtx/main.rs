@@ -2,26 +2,21 @@ use std::{io::stdin, process::exit, str::FromStr};
 
 use anyhow::Error;
 use clap::{
-    crate_authors, crate_description, crate_version, value_parser, Arg, ArgAction, Command,
+    crate_authors, crate_description, crate_version, value_parser, Arg, ArgAction, ArgMatches,
+    Command,
+};
+use nimiq_keys::{
+    multisig::{commitment::CommitmentPair, MUSIG2_PARAMETER_V},
+    Address, Ed25519PublicKey, KeyPair, PrivateKey, SecureGenerate,
 };
-use nimiq_keys::{Address, KeyPair, PrivateKey};
 use nimiq_primitives::{coin::Coin, networks::NetworkId};
 use nimiq_serde::{Deserialize, Serialize};
 use nimiq_transaction::Transaction;
+use nimiq_transaction_builder::PartiallySignedTransaction;
 use thiserror::Error;
 
-fn run_app() -> Result<(), Error> {
-    let matches = Command::new("Sign transaction")
-        .version(crate_version!())
-        .author(crate_authors!())
-        .about(crate_description!())
-        .arg(
-            Arg::new("secret_key")
-                .short('k')
-                .long("secret-key")
-                .value_name("SECRET_KEY")
-                .help("Specify the secret key to be used to sign the transaction."),
-        )
+fn transaction_args(command: Command) -> Command {
+    command
         .arg(
             Arg::new("tx_from_stdin")
                 .long("stdin")
@@ -71,54 +66,235 @@ fn run_app() -> Result<(), Error> {
                 .value_name("NETWORK")
                 .help("Set network ID"),
         )
-        .get_matches();
+}
+
+fn secret_key_arg(required: bool) -> Arg {
+    Arg::new("secret_key")
+        .short('k')
+        .long("secret-key")
+        .value_name("SECRET_KEY")
+        .required(required)
+        .help("Specify the secret key to be used to sign the transaction.")
+}
 
+fn pst_arg() -> Arg {
+    Arg::new("pst")
+        .short('p')
+        .long("pst")
+        .value_name("HEX")
+        .required(true)
+        .help("The partially signed transaction as hex.")
+}
+
+fn read_transaction(matches: &ArgMatches) -> Result<Transaction, Error> {
     // read transaction either from arguments or stdin
-    let tx = if matches.get_flag("tx_from_stdin") {
+    if matches.get_flag("tx_from_stdin") {
         let mut line = String::new();
         stdin().read_line(&mut line)?;
-        Transaction::deserialize_from_vec(&hex::decode(line.trim_end())?)?
-    } else {
-        let from_address = Address::from_user_friendly_address(
-            matches
-                .get_one::<String>("from_address")
-                .ok_or(AppError::SenderAddress)?,
-        )?;
-        let to_address = Address::from_user_friendly_address(
-            matches
-                .get_one::<String>("to_address")
-                .ok_or(AppError::RecipientAddress)?,
-        )?;
-        let value = Coin::from_str(matches.get_one::<String>("value").ok_or(AppError::Value)?)?;
-        let fee = Coin::from_str(matches.get_one::<String>("fee").ok_or(AppError::Fee)?)?;
-        let validity_start_height = matches
-            .get_one::<u32>("validity_start_height")
-            .ok_or(AppError::ValidityStartHeight)?;
-        let network_id = match matches.get_one::<String>("network_id") {
-            Some(s) => NetworkId::from_str(s)?,
-            None => NetworkId::Main,
-        };
-        Transaction::new_basic(
-            from_address,
-            to_address,
-            value,
-            fee,
-            *validity_start_height,
-            network_id,
-        )
+        return Ok(Transaction::deserialize_from_vec(&hex::decode(
+            line.trim_end(),
+        )?)?);
+    }
+
+    let from_address = Address::from_user_friendly_address(
+        matches
+            .get_one::<String>("from_address")
+            .ok_or(AppError::SenderAddress)?,
+    )?;
+    let to_address = Address::from_user_friendly_address(
+        matches
+            .get_one::<String>("to_address")
+            .ok_or(AppError::RecipientAddress)?,
+    )?;
+    let value = Coin::from_str(matches.get_one::<String>("value").ok_or(AppError::Value)?)?;
+    let fee = Coin::from_str(matches.get_one::<String>("fee").ok_or(AppError::Fee)?)?;
+    let validity_start_height = matches
+        .get_one::<u32>("validity_start_height")
+        .ok_or(AppError::ValidityStartHeight)?;
+    let network_id = match matches.get_one::<String>("network_id") {
+        Some(s) => NetworkId::from_str(s)?,
+        None => NetworkId::Main,
     };
+    Ok(Transaction::new_basic(
+        from_address,
+        to_address,
+        value,
+        fee,
+        *validity_start_height,
+        network_id,
+    ))
+}
+
+fn read_key_pair(matches: &ArgMatches) -> Result<KeyPair, Error> {
+    let hex_secret_key = matches
+        .get_one::<String>("secret_key")
+        .ok_or(AppError::SecretKey)?;
+    let raw_secret_key = hex::decode(hex_secret_key)?;
+    Ok(PrivateKey::deserialize_from_vec(&raw_secret_key)?.into())
+}
+
+fn read_pst(hex_pst: &str) -> Result<PartiallySignedTransaction, Error> {
+    Ok(PartiallySignedTransaction::from_bytes(&hex::decode(
+        hex_pst,
+    )?)?)
+}
+
+fn read_public_keys(matches: &ArgMatches, id: &str) -> Result<Vec<Ed25519PublicKey>, Error> {
+    matches
+        .get_many::<String>(id)
+        .unwrap_or_default()
+        .map(|public_key| Ok(Ed25519PublicKey::from_str(public_key)?))
+        .collect()
+}
+
+fn run_app() -> Result<(), Error> {
+    let matches = transaction_args(
+        Command::new("Sign transaction")
+            .version(crate_version!())
+            .author(crate_authors!())
+            .about(crate_description!())
+            .arg(secret_key_arg(false)),
+    )
+    .subcommand(
+        Command::new("multisig")
+            .about("Signs a transaction from a multisig address in rounds")
+            .subcommand_required(true)
+            .subcommand(transaction_args(
+                Command::new("create")
+                    .about("Creates a partially signed transaction and prints it as hex")
+                    .arg(
+                        Arg::new("public_keys")
+                            .short('P')
+                            .long("public-keys")
+                            .value_name("PUBLIC_KEY")
+                            .num_args(1..)
+                            .required(true)
+                            .help("The public keys of all owners of the multisig address."),
+                    )
+                    .arg(
+                        Arg::new("min_signatures")
+                            .short('m')
+                            .long("min-signatures")
+                            .value_name("NUM")
+                            .value_parser(value_parser!(u8))
+                            .required(true)
+                            .help("The minimum number of signatures of the multisig address."),
+                    )
+                    .arg(
+                        Arg::new("signers")
+                            .short('s')
+                            .long("signers")
+                            .value_name("PUBLIC_KEY")
+                            .num_args(1..)
+                            .required(true)
+                            .help("The public keys of the owners signing the transaction."),
+                    ),
+            ))
+            .subcommand(
+                Command::new("commit")
+                    .about(
+                        "Adds new commitments of the signer. Prints the updated partially signed \
+                         transaction and, on a second line, the secret commitment pairs that must \
+                         be kept for signing",
+                    )
+                    .arg(pst_arg())
+                    .arg(secret_key_arg(true)),
+            )
+            .subcommand(
+                Command::new("sign")
+                    .about(
+                        "Adds the partial signature of the signer once all commitments are known",
+                    )
+                    .arg(pst_arg())
+                    .arg(secret_key_arg(true))
+                    .arg(
+                        Arg::new("commitment_pairs")
+                            .short('c')
+                            .long("commitment-pairs")
+                            .value_name("HEX")
+                            .required(true)
+                            .help("The secret commitment pairs printed by the commit step."),
+                    ),
+            )
+            .subcommand(
+                Command::new("combine")
+                    .about("Merges partially signed transactions of the same signing round")
+                    .arg(pst_arg().num_args(1..)),
+            )
+            .subcommand(
+                Command::new("finalize")
+                    .about(
+                        "Combines the partial signatures and prints the signed transaction as hex",
+                    )
+                    .arg(pst_arg()),
+            ),
+    )
+    .get_matches();
+
+    if let Some(("multisig", matches)) = matches.subcommand() {
+        return run_multisig(matches);
+    }
 
     // sign transaction
-    if let Some(hex_secret_key) = matches.get_one::<String>("secret_key") {
-        let raw_secret_key = hex::decode(hex_secret_key)?;
-        let key_pair: KeyPair = PrivateKey::deserialize_from_vec(&raw_secret_key)?.into();
-        let signature = key_pair.sign(tx.serialize_content().as_slice());
-        let raw_signature = signature.serialize_to_vec();
-        println!("{}", hex::encode(raw_signature));
-        Ok(())
-    } else {
-        Err(AppError::SecretKey.into())
+    let tx = read_transaction(&matches)?;
+    let key_pair = read_key_pair(&matches)?;
+    let signature = key_pair.sign(tx.serialize_content().as_slice());
+    let raw_signature = signature.serialize_to_vec();
+    println!("{}", hex::encode(raw_signature));
+    Ok(())
+}
+
+fn run_multisig(matches: &ArgMatches) -> Result<(), Error> {
+    match matches.subcommand() {
+        Some(("create", matches)) => {
+            let pst = PartiallySignedTransaction::new(
+                read_transaction(matches)?,
+                read_public_keys(matches, "public_keys")?,
+                *matches.get_one::<u8>("min_signatures").unwrap(),
+                read_public_keys(matches, "signers")?,
+            )?;
+            println!("{}", hex::encode(pst.to_bytes()));
+        }
+        Some(("commit", matches)) => {
+            let mut pst = read_pst(matches.get_one::<String>("pst").unwrap())?;
+            let key_pair = read_key_pair(matches)?;
+            let commitment_pairs = CommitmentPair::generate_all(&mut rand::thread_rng());
+            pst.add_commitments(
+                key_pair.public,
+                CommitmentPair::to_commitments(&commitment_pairs),
+            )?;
+            println!("{}", hex::encode(pst.to_bytes()));
+            println!("{}", hex::encode(commitment_pairs.serialize_to_vec()));
+        }
+        Some(("sign", matches)) => {
+            let mut pst = read_pst(matches.get_one::<String>("pst").unwrap())?;
+            let key_pair = read_key_pair(matches)?;
+            let commitment_pairs: [CommitmentPair; MUSIG2_PARAMETER_V] =
+                Deserialize::deserialize_from_vec(&hex::decode(
+                    matches.get_one::<String>("commitment_pairs").unwrap(),
+                )?)?;
+            pst.sign(&key_pair, &commitment_pairs)?;
+            println!("{}", hex::encode(pst.to_bytes()));
+        }
+        Some(("combine", matches)) => {
+            let mut psts = matches
+                .get_many::<String>("pst")
+                .unwrap()
+                .map(|hex_pst| read_pst(hex_pst));
+            let mut pst = psts.next().unwrap()?;
+            for other in psts {
+                pst.combine(&other?)?;
+            }
+            println!("{}", hex::encode(pst.to_bytes()));
+        }
+        Some(("finalize", matches)) => {
+            let pst = read_pst(matches.get_one::<String>("pst").unwrap())?;
+            let tx = pst.finalize_transaction()?;
+            println!("{}", hex::encode(tx.serialize_to_vec()));
+        }
+        _ => unreachable!(),
     }
+    Ok(())
 }
 
 fn main() {
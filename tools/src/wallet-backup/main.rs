@@ -0,0 +1,168 @@
+use std::{
+    env, fs,
+    io::{stderr, stdin, IsTerminal, Write},
+    process::exit,
+};
+
+use anyhow::{bail, Error};
+use clap::{crate_authors, crate_version, Arg, ArgAction, ArgMatches, Command};
+use nimiq_database::mdbx::MdbxDatabase;
+use nimiq_wallet::{WalletBackup, WalletStore};
+use serde_json::json;
+
+/// Initial database size, see the default of the client's database settings.
+const DATABASE_SIZE: usize = 1024 * 1024 * 1024 * 1024;
+/// Maximum number of tables, see the default of the client's database settings.
+const DATABASE_MAX_TABLES: u32 = 20;
+/// Environment variable the backup password can be passed in.
+const PASSWORD_ENV: &str = "NIMIQ_WALLET_BACKUP_PASSWORD";
+
+fn database_arg() -> Arg {
+    Arg::new("database")
+        .short('d')
+        .long("database")
+        .value_name("PATH")
+        .required(true)
+        .help("Path to the database of the (stopped) client.")
+}
+
+fn file_arg() -> Arg {
+    Arg::new("file")
+        .short('f')
+        .long("file")
+        .value_name("FILE")
+        .required(true)
+        .help("The backup file, containing the backup as hex like the `exportWallet` RPC call.")
+}
+
+/// Reads the password the backup is encrypted with from the environment or, if it is not set
+/// there, from the first line of stdin. Unlike an argument, it thus doesn't end up in the process
+/// list or the shell history.
+fn read_password() -> Result<String, Error> {
+    if let Ok(password) = env::var(PASSWORD_ENV) {
+        return Ok(password);
+    }
+
+    if stdin().is_terminal() {
+        eprint!("Password: ");
+        stderr().flush()?;
+    }
+    let mut password = String::new();
+    stdin().read_line(&mut password)?;
+    let password = password.trim_end_matches(['\r', '\n']).to_owned();
+
+    if password.is_empty() {
+        bail!("No password given, pass it on stdin or in {PASSWORD_ENV}");
+    }
+    Ok(password)
+}
+
+fn read_backup(matches: &ArgMatches) -> Result<WalletBackup, Error> {
+    let file = matches.get_one::<String>("file").unwrap();
+    let backup = hex::decode(fs::read_to_string(file)?.trim())?;
+    Ok(WalletBackup::from_bytes(&backup)?)
+}
+
+fn open_wallet_store(matches: &ArgMatches) -> Result<WalletStore, Error> {
+    let env = MdbxDatabase::new(
+        matches.get_one::<String>("database").unwrap(),
+        DATABASE_SIZE,
+        DATABASE_MAX_TABLES,
+    )?;
+    Ok(WalletStore::new(env))
+}
+
+fn run_app() -> Result<serde_json::Value, Error> {
+    let matches = Command::new("nimiq-wallet-backup")
+        .version(crate_version!())
+        .author(crate_authors!())
+        .about("Exports, imports and inspects encrypted backups of a client's wallet")
+        .after_help(format!(
+            "The password of the backup is read from the {PASSWORD_ENV} environment variable or stdin."
+        ))
+        .subcommand_required(true)
+        .subcommand(
+            Command::new("export")
+                .about("Writes all wallet accounts of the database to an encrypted backup file")
+                .arg(database_arg())
+                .arg(file_arg()),
+        )
+        .subcommand(
+            Command::new("import")
+                .about("Restores the wallet accounts of a backup file into the database")
+                .arg(database_arg())
+                .arg(file_arg())
+                .arg(
+                    Arg::new("replace")
+                        .long("replace")
+                        .action(ArgAction::SetTrue)
+                        .help("Remove the existing wallet data instead of merging the backup into it."),
+                ),
+        )
+        .subcommand(
+            Command::new("inspect")
+                .about("Decrypts a backup file, checks its integrity and lists its accounts")
+                .arg(file_arg()),
+        )
+        .get_matches();
+
+    match matches.subcommand() {
+        Some(("export", matches)) => {
+            let wallet_store = open_wallet_store(matches)?;
+            let contents = wallet_store.export_backup_contents();
+            let addresses = contents.addresses();
+
+            let password = read_password()?;
+            let backup = WalletBackup::new(contents, password.as_bytes())?;
+            fs::write(
+                matches.get_one::<String>("file").unwrap(),
+                hex::encode(backup.to_bytes()),
+            )?;
+
+            Ok(json!({ "version": backup.version(), "accounts": addresses }))
+        }
+        Some(("import", matches)) => {
+            let backup = read_backup(matches)?;
+            let version = backup.version();
+            let password = read_password()?;
+            let contents = backup.open(password.as_bytes())?;
+
+            let wallet_store = open_wallet_store(matches)?;
+            wallet_store.import_backup_contents(&contents, matches.get_flag("replace"));
+
+            Ok(json!({ "version": version, "accounts": contents.addresses() }))
+        }
+        Some(("inspect", matches)) => {
+            let backup = read_backup(matches)?;
+            let version = backup.version();
+            let password = read_password()?;
+            let contents = backup.open(password.as_bytes())?;
+
+            Ok(json!({
+                "version": version,
+                "accounts": contents.addresses(),
+                "hdWallets": contents
+                    .hd_wallets
+                    .iter()
+                    .map(|(id, _)| id.clone())
+                    .collect::<Vec<_>>(),
+                "watchOnly": contents
+                    .watch_only
+                    .iter()
+                    .map(|(address, _)| address.clone())
+                    .collect::<Vec<_>>(),
+            }))
+        }
+        _ => unreachable!(),
+    }
+}
+
+fn main() {
+    match run_app() {
+        Ok(result) => println!("{result}"),
+        Err(e) => {
+            eprintln!("Error: {e}");
+            exit(1);
+        }
+    }
+}
@@ -0,0 +1,199 @@
+use std::{process::exit, str::FromStr};
+
+use anyhow::Error;
+use clap::{crate_authors, crate_version, Arg, Command};
+use nimiq_block::MacroHeader;
+use nimiq_hash::{Blake2sHash, Hash};
+use nimiq_primitives::networks::NetworkId;
+use nimiq_serde::Deserialize;
+use nimiq_zkp::{
+    verify::{deserialize_proof, verify_election_headers},
+    ZKPVerifyingKey, ZKP_NETWORKS,
+};
+use serde_json::json;
+use thiserror::Error;
+
+/// Verifies the hex encoded proof for the transition between the hex encoded election headers and
+/// returns the verdict. Malformed inputs are reported as errors, while a proof that doesn't verify
+/// results in an invalid verdict.
+fn verify(
+    network_id: NetworkId,
+    proof: &str,
+    initial_header: &str,
+    final_header: &str,
+) -> Result<serde_json::Value, Error> {
+    if !ZKP_NETWORKS.contains(&network_id) {
+        return Err(AppError::UnsupportedNetwork(network_id).into());
+    }
+
+    let proof = deserialize_proof(&hex::decode(proof)?)?;
+    let initial_header = MacroHeader::deserialize_from_vec(&hex::decode(initial_header)?)?;
+    let final_header = MacroHeader::deserialize_from_vec(&hex::decode(final_header)?)?;
+
+    let verifying_key = ZKPVerifyingKey::new();
+    verifying_key.init_with_network_id(network_id);
+    let valid = verify_election_headers(
+        network_id,
+        &initial_header,
+        &final_header,
+        proof,
+        &verifying_key,
+    )?;
+
+    Ok(json!({
+        "valid": valid,
+        "network": network_id.to_string(),
+        "initialBlockNumber": initial_header.block_number,
+        "initialHeaderHash": initial_header.hash::<Blake2sHash>().to_hex(),
+        "finalBlockNumber": final_header.block_number,
+        "finalHeaderHash": final_header.hash::<Blake2sHash>().to_hex(),
+    }))
+}
+
+fn run_app() -> Result<serde_json::Value, Error> {
+    let matches = Command::new("nimiq-zkp-verify")
+        .version(crate_version!())
+        .author(crate_authors!())
+        .about("Verifies a zero-knowledge proof of an election block transition without a node")
+        .arg(
+            Arg::new("network_id")
+                .short('N')
+                .long("network")
+                .value_name("NETWORK")
+                .required(true)
+                .help("Network the proof was generated for, e.g. test-albatross."),
+        )
+        .arg(
+            Arg::new("proof")
+                .short('p')
+                .long("proof")
+                .value_name("HEX")
+                .required(true)
+                .help("The compressed proof as hex."),
+        )
+        .arg(
+            Arg::new("initial_header")
+                .short('i')
+                .long("initial-header")
+                .value_name("HEX")
+                .required(true)
+                .help("The serialized header of the initial election block as hex, usually the genesis block."),
+        )
+        .arg(
+            Arg::new("final_header")
+                .short('f')
+                .long("final-header")
+                .value_name("HEX")
+                .required(true)
+                .help("The serialized header of the election block the proof was generated for as hex."),
+        )
+        .get_matches();
+
+    let network_id = NetworkId::from_str(matches.get_one::<String>("network_id").unwrap())?;
+    verify(
+        network_id,
+        matches.get_one::<String>("proof").unwrap(),
+        matches.get_one::<String>("initial_header").unwrap(),
+        matches.get_one::<String>("final_header").unwrap(),
+    )
+}
+
+fn main() {
+    // The verdict is always printed as JSON. The exit code is only zero if the proof is valid.
+    let (verdict, code) = match run_app() {
+        Ok(verdict) => {
+            let code = if verdict["valid"] == true { 0 } else { 1 };
+            (verdict, code)
+        }
+        Err(e) => (json!({ "valid": false, "error": e.to_string() }), 2),
+    };
+
+    println!("{verdict}");
+    exit(code);
+}
+
+#[derive(Debug, Error)]
+enum AppError {
+    #[error("No verifying key for network {0}")]
+    UnsupportedNetwork(NetworkId),
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use ark_serialize::CanonicalSerialize;
+    use nimiq_primitives::policy::Policy;
+    use nimiq_serde::Serialize;
+    use nimiq_test_utils::zkp_test_data::{get_base_seed, simulate_proof, ZKP_TEST_KEYS_PATH};
+    use nimiq_zkp::ZKP_VERIFYING_DATA;
+
+    use super::*;
+
+    fn election_header(epoch: u32) -> MacroHeader {
+        MacroHeader {
+            network: NetworkId::UnitAlbatross,
+            block_number: Policy::genesis_block_number() + epoch * Policy::blocks_per_epoch(),
+            ..Default::default()
+        }
+    }
+
+    fn proof(initial_header: &MacroHeader, final_header: &MacroHeader) -> String {
+        let proof = simulate_proof(
+            Path::new(ZKP_TEST_KEYS_PATH),
+            &initial_header.hash::<Blake2sHash>(),
+            &final_header.hash::<Blake2sHash>(),
+            &ZKP_VERIFYING_DATA,
+            &mut get_base_seed(),
+        );
+        let mut bytes = vec![];
+        proof.serialize_compressed(&mut bytes).unwrap();
+        hex::encode(bytes)
+    }
+
+    #[test]
+    fn it_reports_the_verdict() {
+        let genesis = election_header(0);
+        let election = election_header(1);
+        let proof = proof(&genesis, &election);
+
+        let verdict = verify(
+            NetworkId::UnitAlbatross,
+            &proof,
+            &hex::encode(genesis.serialize_to_vec()),
+            &hex::encode(election.serialize_to_vec()),
+        )
+        .unwrap();
+        assert_eq!(verdict["valid"], true);
+        assert_eq!(verdict["initialBlockNumber"], genesis.block_number);
+        assert_eq!(verdict["finalBlockNumber"], election.block_number);
+        assert_eq!(
+            verdict["finalHeaderHash"],
+            election.hash::<Blake2sHash>().to_hex()
+        );
+
+        let verdict = verify(
+            NetworkId::UnitAlbatross,
+            &proof,
+            &hex::encode(genesis.serialize_to_vec()),
+            &hex::encode(election_header(2).serialize_to_vec()),
+        )
+        .unwrap();
+        assert_eq!(verdict["valid"], false);
+    }
+
+    #[test]
+    fn it_rejects_malformed_inputs() {
+        let genesis = hex::encode(election_header(0).serialize_to_vec());
+        let election = hex::encode(election_header(1).serialize_to_vec());
+        let proof = proof(&election_header(0), &election_header(1));
+
+        // Unsupported network.
+        assert!(verify(NetworkId::MainAlbatross, &proof, &genesis, &election).is_err());
+        // Invalid hex and truncated proofs.
+        assert!(verify(NetworkId::UnitAlbatross, "xyz", &genesis, &election).is_err());
+        assert!(verify(NetworkId::UnitAlbatross, &proof[2..], &genesis, &election).is_err());
+        // Headers in the wrong order.
+        assert!(verify(NetworkId::UnitAlbatross, &proof, &election, &genesis).is_err());
+    }
+}
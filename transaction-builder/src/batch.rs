@@ -0,0 +1,194 @@
+use std::str::FromStr;
+
+use nimiq_keys::{Address, Ed25519PublicKey, Ed25519Signature};
+use nimiq_primitives::{coin::Coin, networks::NetworkId};
+use nimiq_serde::Serialize;
+use nimiq_transaction::{SignatureProof, Transaction};
+use thiserror::Error;
+
+use crate::{
+    proof::BasicProofBuilder, ExternalSigner, Recipient, Sender, TransactionBuilder,
+    TransactionBuilderError,
+};
+
+/// Errors that can occur while parsing or building a [`BatchPayment`].
+#[derive(Debug, Error)]
+pub enum BatchPaymentError {
+    /// A line of a CSV payment list couldn't be parsed.
+    #[error("Invalid payment on line {line}: {reason}")]
+    InvalidCsv { line: usize, reason: String },
+    /// The fee of a transaction isn't representable.
+    #[error("The fee is too large.")]
+    FeeOverflow,
+    #[error("{0}")]
+    TransactionBuilder(#[from] TransactionBuilderError),
+}
+
+/// A single payment of a [`BatchPayment`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Payment {
+    /// The basic account receiving the payment.
+    pub recipient: Address,
+    /// The value of the payment.
+    pub value: Coin,
+    /// Arbitrary data attached to the payment. It may be empty.
+    pub data: Vec<u8>,
+}
+
+/// How the fee of each transaction of a [`BatchPayment`] is determined.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FeePolicy {
+    /// Every transaction pays the same fee.
+    Fixed(Coin),
+    /// Every transaction pays the given fee for each byte of its serialized size.
+    PerByte(Coin),
+}
+
+impl FeePolicy {
+    fn fee_for_size(&self, size: usize) -> Result<Coin, BatchPaymentError> {
+        match self {
+            FeePolicy::Fixed(fee) => Ok(*fee),
+            FeePolicy::PerByte(fee_per_byte) => fee_per_byte
+                .checked_mul(size as u64)
+                .ok_or(BatchPaymentError::FeeOverflow),
+        }
+    }
+}
+
+/// Builds the transactions to pay many recipients from the same basic account.
+///
+/// All transactions share the fee policy, the validity start height and the network ID, so the
+/// whole batch expires at the same block.
+#[derive(Clone, Debug)]
+pub struct BatchPayment {
+    pub payments: Vec<Payment>,
+    pub fee_policy: FeePolicy,
+    pub validity_start_height: u32,
+    pub network_id: NetworkId,
+}
+
+impl BatchPayment {
+    pub fn new(
+        payments: Vec<Payment>,
+        fee_policy: FeePolicy,
+        validity_start_height: u32,
+        network_id: NetworkId,
+    ) -> Self {
+        BatchPayment {
+            payments,
+            fee_policy,
+            validity_start_height,
+            network_id,
+        }
+    }
+
+    /// Parses a list of payments with one `recipient,value[,data]` entry per line. The value is
+    /// given in NIM and the optional data in hex format. Empty lines, lines starting with `#` and
+    /// a `recipient,...` header line are skipped.
+    pub fn parse_csv(csv: &str) -> Result<Vec<Payment>, BatchPaymentError> {
+        let mut payments = vec![];
+        for (i, line) in csv.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with("recipient") {
+                continue;
+            }
+
+            let invalid = |reason: String| BatchPaymentError::InvalidCsv {
+                line: i + 1,
+                reason,
+            };
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            if !(2..=3).contains(&fields.len()) {
+                return Err(invalid(format!(
+                    "expected 2 or 3 fields, got {}",
+                    fields.len()
+                )));
+            }
+
+            payments.push(Payment {
+                recipient: Address::from_any_str(fields[0]).map_err(|e| invalid(e.to_string()))?,
+                value: Coin::from_str(fields[1]).map_err(|e| invalid(e.to_string()))?,
+                data: match fields.get(2) {
+                    Some(data) => hex::decode(data).map_err(|e| invalid(e.to_string()))?,
+                    None => vec![],
+                },
+            });
+        }
+        Ok(payments)
+    }
+
+    /// Builds and signs the transactions of all payments, in order.
+    ///
+    /// With [`FeePolicy::PerByte`], the fee is first estimated for an Ed25519 signature proof. If
+    /// the signer returns a larger proof, the transaction is signed again with the higher fee.
+    pub fn build(
+        &self,
+        signer: &dyn ExternalSigner,
+    ) -> Result<Vec<Transaction>, BatchPaymentError> {
+        let sender = signer.address();
+        self.payments
+            .iter()
+            .map(|payment| self.build_payment(signer, &sender, payment))
+            .collect()
+    }
+
+    fn build_payment(
+        &self,
+        signer: &dyn ExternalSigner,
+        sender: &Address,
+        payment: &Payment,
+    ) -> Result<Transaction, BatchPaymentError> {
+        let mut fee = match self.fee_policy {
+            FeePolicy::Fixed(fee) => fee,
+            FeePolicy::PerByte(_) => {
+                let mut estimate = self.proof_builder(sender, payment, Coin::ZERO)?.transaction;
+                estimate.proof = SignatureProof::from_ed25519(
+                    Ed25519PublicKey::default(),
+                    Ed25519Signature::default(),
+                )
+                .serialize_to_vec();
+                self.fee_policy.fee_for_size(estimate.serialized_size())?
+            }
+        };
+
+        loop {
+            let mut proof_builder = self.proof_builder(sender, payment, fee)?;
+            proof_builder
+                .sign_with(signer)
+                .map_err(TransactionBuilderError::from)?;
+            let transaction = proof_builder.generate().unwrap();
+
+            let required_fee = self
+                .fee_policy
+                .fee_for_size(transaction.serialized_size())?;
+            if fee >= required_fee {
+                return Ok(transaction);
+            }
+            fee = required_fee;
+        }
+    }
+
+    fn proof_builder(
+        &self,
+        sender: &Address,
+        payment: &Payment,
+        fee: Coin,
+    ) -> Result<BasicProofBuilder, TransactionBuilderError> {
+        let recipient = if payment.data.is_empty() {
+            Recipient::new_basic(payment.recipient.clone())
+        } else {
+            Recipient::new_basic_with_data(payment.recipient.clone(), payment.data.clone())
+        };
+
+        let mut builder = TransactionBuilder::new();
+        builder
+            .with_sender(Sender::new_basic(sender.clone()))
+            .with_recipient(recipient)
+            .with_value(payment.value)
+            .with_fee(fee)
+            .with_validity_start_height(self.validity_start_height)
+            .with_network_id(self.network_id);
+
+        Ok(builder.generate()?.unwrap_basic())
+    }
+}
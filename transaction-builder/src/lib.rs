@@ -8,11 +8,21 @@ use nimiq_transaction::{
 };
 use thiserror::Error;
 
-pub use crate::{proof::TransactionProofBuilder, recipient::Recipient, sender::Sender};
+pub use crate::{
+    batch::{BatchPayment, BatchPaymentError, FeePolicy, Payment},
+    partially_signed::{PartiallySignedTransaction, PartiallySignedTransactionError},
+    proof::TransactionProofBuilder,
+    recipient::Recipient,
+    sender::Sender,
+    signer::{ExternalSigner, ExternalSignerError, KeyFileSigner},
+};
 
+pub mod batch;
+pub mod partially_signed;
 pub mod proof;
 pub mod recipient;
 pub mod sender;
+pub mod signer;
 
 /// Building a transaction can fail if mandatory fields are not set.
 /// In these cases, a `TransactionBuilderError` is returned.
@@ -68,6 +78,11 @@ pub enum TransactionBuilderError {
     /// [`signaling transaction`]: struct.TransactionBuilder.html#method.with_value
     #[error("The value must be zero for signaling transactions and cannot be zero for others.")]
     InvalidValue,
+    /// The [`ExternalSigner`] failed to sign the transaction.
+    ///
+    /// [`ExternalSigner`]: signer/trait.ExternalSigner.html
+    #[error("Failed to sign the transaction: {0}")]
+    Signer(#[from] ExternalSignerError),
 }
 
 /// A helper to build arbitrary transactions.
@@ -417,14 +432,17 @@ impl TransactionBuilder {
 }
 
 // Convenience functionality.
+//
+// Transactions are signed by an `ExternalSigner`, e.g. a hardware wallet. A `KeyPair` can be
+// passed directly.
 impl TransactionBuilder {
-    /// Creates a basic transaction from the address of a given `key_pair` to a basic `recipient`.
+    /// Creates a basic transaction from the address of a given `signer` to a basic `recipient`.
     ///
     /// # Arguments
     ///
-    ///  - `key_pair`:              The key pair used to sign the outgoing transaction. The
+    ///  - `signer`:                The signer used to sign the outgoing transaction. The
     ///                             transaction value is sent from the basic account belonging to
-    ///                             this key pair.
+    ///                             this signer.
     ///  - `recipient`:             The address of the basic account that will receive the funds.
     ///  - `value`:                 The value that will be sent to the recipient account.
     ///  - `fee`:                   Transaction fee.
@@ -436,7 +454,7 @@ impl TransactionBuilder {
     /// The finalized transaction.
     ///
     pub fn new_basic(
-        key_pair: &KeyPair,
+        signer: &dyn ExternalSigner,
         recipient: Address,
         value: Coin,
         fee: Coin,
@@ -445,7 +463,7 @@ impl TransactionBuilder {
     ) -> Result<Transaction, TransactionBuilderError> {
         let mut builder = Self::new();
         builder
-            .with_sender(Sender::new_basic(Address::from(key_pair)))
+            .with_sender(Sender::new_basic(signer.address()))
             .with_recipient(Recipient::new_basic(recipient))
             .with_value(value)
             .with_fee(fee)
@@ -455,7 +473,7 @@ impl TransactionBuilder {
         let proof_builder = builder.generate()?;
         match proof_builder {
             TransactionProofBuilder::Basic(mut builder) => {
-                builder.sign_with_key_pair(key_pair);
+                builder.sign_with(signer)?;
                 Ok(builder.generate().unwrap())
             }
             _ => unreachable!(),
@@ -466,9 +484,9 @@ impl TransactionBuilder {
     ///
     /// # Arguments
     ///
-    ///  - `key_pair`:              The key pair used to sign the outgoing transaction. The
+    ///  - `signer`:                The signer used to sign the outgoing transaction. The
     ///                             transaction value is sent from the basic account belonging to
-    ///                             this key pair.
+    ///                             this signer.
     ///  - `recipient`:             The address of the basic account that will receive the funds.
     ///  - `data`:                  The data that will be stored in the transaction data field.
     ///  - `value`:                 The value that will be sent to the recipient account.
@@ -481,7 +499,7 @@ impl TransactionBuilder {
     /// The finalized transaction.
     ///
     pub fn new_basic_with_data(
-        key_pair: &KeyPair,
+        signer: &dyn ExternalSigner,
         recipient: Address,
         data: Vec<u8>,
         value: Coin,
@@ -491,7 +509,7 @@ impl TransactionBuilder {
     ) -> Result<Transaction, TransactionBuilderError> {
         let mut builder = Self::new();
         builder
-            .with_sender(Sender::new_basic(Address::from(key_pair)))
+            .with_sender(Sender::new_basic(signer.address()))
             .with_recipient(Recipient::new_basic_with_data(recipient, data))
             .with_value(value)
             .with_fee(fee)
@@ -501,7 +519,7 @@ impl TransactionBuilder {
         let proof_builder = builder.generate()?;
         match proof_builder {
             TransactionProofBuilder::Basic(mut builder) => {
-                builder.sign_with_key_pair(key_pair);
+                builder.sign_with(signer)?;
                 Ok(builder.generate().unwrap())
             }
             _ => unreachable!(),
@@ -512,16 +530,16 @@ impl TransactionBuilder {
     ///
     /// # Arguments
     ///
-    ///  - `key_pair`:              The key pair used to sign the outgoing transaction. The vesting
+    ///  - `signer`:                The signer used to sign the outgoing transaction. The vesting
     ///                             contract value is sent from the basic account belonging to this
-    ///                             key pair.
+    ///                             signer.
     ///  - `owner`:                 The address of the owner of the vesting contract.
     ///  - `start_time`,
     ///    `time_step`,
     ///    `num_steps`:             Create a release schedule of `num_steps` payouts of value
     ///                             starting at `start_time + time_step`.
     ///  - `value`:                 The value for the vesting contract. This is sent from the
-    ///                             account belonging to `key_pair`.
+    ///                             account belonging to `signer`.
     ///  - `fee`:                   Transaction fee.
     ///  - `validity_start_height`: Block height from which this transaction is valid.
     ///  - `network_id`:            ID of network for which the transaction is meant.
@@ -531,7 +549,7 @@ impl TransactionBuilder {
     /// The finalized transaction.
     ///
     pub fn new_create_vesting(
-        key_pair: &KeyPair,
+        signer: &dyn ExternalSigner,
         owner: Address,
         start_time: u64,
         time_step: u64,
@@ -546,7 +564,7 @@ impl TransactionBuilder {
 
         let mut builder = Self::new();
         builder
-            .with_sender(Sender::new_basic(Address::from(key_pair)))
+            .with_sender(Sender::new_basic(signer.address()))
             .with_recipient(recipient.generate().unwrap())
             .with_value(value)
             .with_fee(fee)
@@ -556,7 +574,7 @@ impl TransactionBuilder {
         let proof_builder = builder.generate()?;
         match proof_builder {
             TransactionProofBuilder::Basic(mut builder) => {
-                builder.sign_with_key_pair(key_pair);
+                builder.sign_with(signer)?;
                 Ok(builder.generate().unwrap())
             }
             _ => unreachable!(),
@@ -567,7 +585,7 @@ impl TransactionBuilder {
     ///
     /// # Arguments
     ///
-    ///  - `key_pair`:              The key pair used to sign the transaction. This key pair
+    ///  - `signer`:                The signer used to sign the transaction. This signer
     ///                             corresponds to the owner of the vesting contract
     ///  - `contract_address`:      The address of the vesting contract.
     ///  - `recipient`:             The address of the basic account that will receive the funds.
@@ -581,7 +599,7 @@ impl TransactionBuilder {
     /// The finalized transaction.
     ///
     pub fn new_redeem_vesting(
-        key_pair: &KeyPair,
+        signer: &dyn ExternalSigner,
         contract_address: Address,
         recipient: Address,
         value: Coin,
@@ -601,7 +619,7 @@ impl TransactionBuilder {
         let proof_builder = builder.generate()?;
         match proof_builder {
             TransactionProofBuilder::Vesting(mut builder) => {
-                builder.sign_with_key_pair(key_pair);
+                builder.sign_with(signer)?;
                 Ok(builder.generate().unwrap())
             }
             _ => unreachable!(),
@@ -612,9 +630,9 @@ impl TransactionBuilder {
     ///
     /// # Arguments
     ///
-    ///  - `key_pair`:              The key pair used to sign the outgoing transaction. The HTLC
+    ///  - `signer`:                The signer used to sign the outgoing transaction. The HTLC
     ///                             contract value is sent from the basic account belonging to this
-    ///                             key pair.
+    ///                             signer.
     ///  - `htlc_sender`:           The address of the sender in the HTLC contract.
     ///  - `htlc_recipient`:        The address of the recipient in the HTLC contract.
     ///  - `hash_root`,
@@ -622,7 +640,7 @@ impl TransactionBuilder {
     ///  - `timeout`:               Sets the blockchain height at which the `htlc_sender`
     ///                             automatically gains control over the funds.
     ///  - `value`:                 The value for the vesting contract. This is sent from the
-    ///                             account belonging to `key_pair`.
+    ///                             account belonging to `signer`.
     ///  - `fee`:                   Transaction fee.
     ///  - `validity_start_height`: Block height from which this transaction is valid.
     ///  - `network_id`:            ID of network for which the transaction is meant.
@@ -632,7 +650,7 @@ impl TransactionBuilder {
     /// The finalized transaction.
     ///
     pub fn new_create_htlc(
-        key_pair: &KeyPair,
+        signer: &dyn ExternalSigner,
         htlc_sender: Address,
         htlc_recipient: Address,
         hash_root: AnyHash,
@@ -652,7 +670,7 @@ impl TransactionBuilder {
 
         let mut builder = Self::new();
         builder
-            .with_sender(Sender::new_basic(Address::from(key_pair)))
+            .with_sender(Sender::new_basic(signer.address()))
             .with_recipient(recipient.generate().unwrap())
             .with_value(value)
             .with_fee(fee)
@@ -662,7 +680,7 @@ impl TransactionBuilder {
         let proof_builder = builder.generate()?;
         match proof_builder {
             TransactionProofBuilder::Basic(mut builder) => {
-                builder.sign_with_key_pair(key_pair);
+                builder.sign_with(signer)?;
                 Ok(builder.generate().unwrap())
             }
             _ => unreachable!(),
@@ -679,7 +697,7 @@ impl TransactionBuilder {
     ///
     /// # Arguments
     ///
-    ///  - `key_pair`:              The key pair used to sign the transaction. This key pair
+    ///  - `signer`:                The signer used to sign the transaction. This signer
     ///                             corresponds to the `htlc_recipient` in the HTLC contract
     ///  - `contract_address`:      The address of the HTLC contract.
     ///  - `recipient`:             The address of the basic account that will receive the funds.
@@ -696,7 +714,7 @@ impl TransactionBuilder {
     /// The finalized transaction.
     ///
     pub fn new_redeem_htlc_regular(
-        key_pair: &KeyPair,
+        signer: &dyn ExternalSigner,
         contract_address: Address,
         recipient: Address,
         pre_image: PreImage,
@@ -719,7 +737,7 @@ impl TransactionBuilder {
         let proof_builder = builder.generate()?;
         match proof_builder {
             TransactionProofBuilder::Htlc(mut builder) => {
-                let sig = builder.signature_with_key_pair(key_pair);
+                let sig = builder.signature_with(signer)?;
                 builder.regular_transfer(pre_image, hash_count, hash_root, sig);
                 Ok(builder.generate().unwrap())
             }
@@ -733,7 +751,7 @@ impl TransactionBuilder {
     ///
     /// # Arguments
     ///
-    ///  - `key_pair`:              The key pair used to sign the transaction. This key pair
+    ///  - `signer`:                The signer used to sign the transaction. This signer
     ///                             corresponds to the `htlc_sender` in the HTLC contract.
     ///  - `contract_address`:      The address of the HTLC contract.
     ///  - `recipient`:             The address of the basic account that will receive the funds.
@@ -747,7 +765,7 @@ impl TransactionBuilder {
     /// The finalized transaction.
     ///
     pub fn new_redeem_htlc_timeout(
-        key_pair: &KeyPair,
+        signer: &dyn ExternalSigner,
         contract_address: Address,
         recipient: Address,
         value: Coin,
@@ -767,7 +785,7 @@ impl TransactionBuilder {
         let proof_builder = builder.generate()?;
         match proof_builder {
             TransactionProofBuilder::Htlc(mut builder) => {
-                let sig = builder.signature_with_key_pair(key_pair);
+                let sig = builder.signature_with(signer)?;
                 builder.timeout_resolve(sig);
                 Ok(builder.generate().unwrap())
             }
@@ -827,11 +845,11 @@ impl TransactionBuilder {
 
     /// Creates a signature that can be used to redeem funds from a HTLC contract using the
     /// `EarlyResolve` method. This can be used with both the `htlc_sender` and `htlc_recipient`
-    ///  key pairs.
+    ///  signers.
     ///
     /// # Arguments
     ///
-    ///  - `key_pair`:              The key pair used to sign the transaction. This key pair
+    ///  - `signer`:                The signer used to sign the transaction. This signer
     ///                             corresponds either to the `htlc_sender` or the `htlc_recipient`
     ///                             in the HTLC contract.
     ///  - `contract_address`:      The address of the HTLC contract.
@@ -846,7 +864,7 @@ impl TransactionBuilder {
     /// The signature proof.
     ///
     pub fn sign_htlc_early(
-        key_pair: &KeyPair,
+        signer: &dyn ExternalSigner,
         contract_address: Address,
         recipient: Address,
         value: Coin,
@@ -865,7 +883,7 @@ impl TransactionBuilder {
 
         let proof_builder = builder.generate()?;
         match proof_builder {
-            TransactionProofBuilder::Htlc(builder) => Ok(builder.signature_with_key_pair(key_pair)),
+            TransactionProofBuilder::Htlc(builder) => Ok(builder.signature_with(signer)?),
             _ => unreachable!(),
         }
     }
@@ -874,13 +892,13 @@ impl TransactionBuilder {
     ///
     /// # Arguments
     ///
-    ///  - `key_pair`:              The key pair used to sign the outgoing transaction. The initial
-    ///                             stake is sent from the basic account belonging to this key pair.
-    ///  - `staker_key_pair`:       The key pair used to sign the incoming transaction. The staker
-    ///                             address will be derived from this key pair.
+    ///  - `signer`:                The signer used to sign the outgoing transaction. The initial
+    ///                             stake is sent from the basic account belonging to this signer.
+    ///  - `staker_signer`:         The signer used to sign the incoming transaction. The staker
+    ///                             address will be derived from this signer.
     ///  - `delegation`:            The (optional) delegation to a validator.
     ///  - `value`:                 The value for the initial stake. This is sent from the account
-    ///                             belonging to `key_pair`. Value must be >= minimum stake.
+    ///                             belonging to `signer`. Value must be >= minimum stake.
     ///  - `fee`:                   Transaction fee.
     ///  - `validity_start_height`: Block height from which this transaction is valid.
     ///  - `network_id`:            ID of network for which the transaction is meant.
@@ -890,8 +908,8 @@ impl TransactionBuilder {
     /// The finalized transaction.
     ///
     pub fn new_create_staker(
-        key_pair: &KeyPair,
-        staker_key_pair: &KeyPair,
+        signer: &dyn ExternalSigner,
+        staker_signer: &dyn ExternalSigner,
         delegation: Option<Address>,
         value: Coin,
         fee: Coin,
@@ -903,7 +921,7 @@ impl TransactionBuilder {
 
         let mut builder = Self::new();
         builder
-            .with_sender(Sender::new_basic(Address::from(key_pair)))
+            .with_sender(Sender::new_basic(signer.address()))
             .with_recipient(recipient.generate().unwrap())
             .with_value(value)
             .with_fee(fee)
@@ -913,25 +931,25 @@ impl TransactionBuilder {
         let proof_builder = builder.generate()?;
         match proof_builder {
             TransactionProofBuilder::InStaking(mut builder) => {
-                builder.sign_with_key_pair(staker_key_pair);
+                builder.sign_with(staker_signer)?;
                 let mut builder = builder.generate().unwrap().unwrap_basic();
-                builder.sign_with_key_pair(key_pair);
+                builder.sign_with(signer)?;
                 Ok(builder.generate().unwrap())
             }
             _ => unreachable!(),
         }
     }
 
-    /// Creates a transaction to add stake from the address of a given `key_pair` to a specified
+    /// Creates a transaction to add stake from the address of a given `signer` to a specified
     /// `staker_address`.
     ///
     /// # Arguments
     ///
-    ///  - `key_pair`:              The key pair used to sign the outgoing transaction. The
-    ///                             stake is sent from the basic account belonging to this key pair.
+    ///  - `signer`:                The signer used to sign the outgoing transaction. The
+    ///                             stake is sent from the basic account belonging to this signer.
     ///  - `staker_address`:        The address of the staker that we are sending the stake to.
     ///  - `value`:                 The value of the stake. This is sent from the account
-    ///                             belonging to `key_pair`. This transaction can fail if the resulting staker
+    ///                             belonging to `signer`. This transaction can fail if the resulting staker
     ///                             violates the minimum non-retired stake.
     ///  - `fee`:                   Transaction fee.
     ///  - `validity_start_height`: Block height from which this transaction is valid.
@@ -942,7 +960,7 @@ impl TransactionBuilder {
     /// The finalized transaction.
     ///
     pub fn new_add_stake(
-        key_pair: &KeyPair,
+        signer: &dyn ExternalSigner,
         staker_address: Address,
         value: Coin,
         fee: Coin,
@@ -954,7 +972,7 @@ impl TransactionBuilder {
 
         let mut builder = Self::new();
         builder
-            .with_sender(Sender::new_basic(Address::from(key_pair)))
+            .with_sender(Sender::new_basic(signer.address()))
             .with_recipient(recipient.generate().unwrap())
             .with_value(value)
             .with_fee(fee)
@@ -966,7 +984,7 @@ impl TransactionBuilder {
             TransactionProofBuilder::InStaking(mut builder) => {
                 builder.sign_with_key_pair(&KeyPair::default());
                 let mut builder = builder.generate().unwrap().unwrap_basic();
-                builder.sign_with_key_pair(key_pair);
+                builder.sign_with(signer)?;
                 Ok(builder.generate().unwrap())
             }
             _ => unreachable!(),
@@ -977,11 +995,11 @@ impl TransactionBuilder {
     ///
     /// # Arguments
     ///
-    ///  - `key_pair`:              The optional key pair used to sign the outgoing transaction. If
+    ///  - `signer`:                The optional signer used to sign the outgoing transaction. If
     ///                             it is given, the fee will be paid from the basic account
-    ///                             belonging to this key pair.
-    ///  - `staker_key_pair`:       The key pair used to sign the incoming transaction. The staker
-    ///                             address will be derived from this key pair.
+    ///                             belonging to this signer.
+    ///  - `staker_signer`:         The signer used to sign the incoming transaction. The staker
+    ///                             address will be derived from this signer.
     ///  - `delegation`:            The new delegation.
     ///  - `reactivate_all_stake`:  If it should activate all inactive stake to the new delegation.
     ///  - `fee`:                   Transaction fee.
@@ -997,8 +1015,8 @@ impl TransactionBuilder {
     /// This is a *signaling transaction*.
     ///
     pub fn new_update_staker(
-        key_pair: Option<&KeyPair>,
-        staker_key_pair: &KeyPair,
+        signer: Option<&dyn ExternalSigner>,
+        staker_signer: &dyn ExternalSigner,
         new_delegation: Option<Address>,
         reactivate_all_stake: bool,
         fee: Coin,
@@ -1016,22 +1034,22 @@ impl TransactionBuilder {
             .with_validity_start_height(validity_start_height)
             .with_network_id(network_id);
 
-        match key_pair {
+        match signer {
             None => {
-                builder.with_sender(Sender::new_basic(Address::from(staker_key_pair)));
+                builder.with_sender(Sender::new_basic(staker_signer.address()));
             }
             Some(key) => {
-                builder.with_sender(Sender::new_basic(Address::from(key)));
+                builder.with_sender(Sender::new_basic(key.address()));
             }
         }
 
         let proof_builder = builder.generate()?;
         let mut staking_data_builder = proof_builder.unwrap_in_staking();
-        staking_data_builder.sign_with_key_pair(staker_key_pair);
+        staking_data_builder.sign_with(staker_signer)?;
         let mut builder = staking_data_builder.generate().unwrap().unwrap_basic();
-        match key_pair {
-            None => builder.sign_with_key_pair(staker_key_pair),
-            Some(key) => builder.sign_with_key_pair(key),
+        match signer {
+            None => builder.sign_with(staker_signer)?,
+            Some(key) => builder.sign_with(key)?,
         };
         Ok(builder.generate().unwrap())
     }
@@ -1040,11 +1058,11 @@ impl TransactionBuilder {
     ///
     /// # Arguments
     ///
-    ///  - `key_pair`:              The optional key pair used to sign the outgoing transaction. If
+    ///  - `signer`:                The optional signer used to sign the outgoing transaction. If
     ///                             it is given, the fee will be paid from the basic account
-    ///                             belonging to this key pair.
-    ///  - `staker_key_pair`:       The key pair used to sign the incoming transaction. The staker
-    ///                             address will be derived from this key pair.
+    ///                             belonging to this signer.
+    ///  - `staker_signer`:         The signer used to sign the incoming transaction. The staker
+    ///                             address will be derived from this signer.
     ///  - `new_active_balance`:    The portion of the total stake to be set as the active stake. Can
     ///                             be at most the total stake. The difference between this value and
     ///                             the total stake is set as inactive stake. To inactivate all stake,
@@ -1062,8 +1080,8 @@ impl TransactionBuilder {
     /// This is a *signaling transaction*.
     ///
     pub fn new_set_active_stake(
-        key_pair: Option<&KeyPair>,
-        staker_key_pair: &KeyPair,
+        signer: Option<&dyn ExternalSigner>,
+        staker_signer: &dyn ExternalSigner,
         new_active_balance: Coin,
         fee: Coin,
         validity_start_height: u32,
@@ -1080,22 +1098,22 @@ impl TransactionBuilder {
             .with_validity_start_height(validity_start_height)
             .with_network_id(network_id);
 
-        match key_pair {
+        match signer {
             None => {
-                builder.with_sender(Sender::new_basic(Address::from(staker_key_pair)));
+                builder.with_sender(Sender::new_basic(staker_signer.address()));
             }
             Some(key) => {
-                builder.with_sender(Sender::new_basic(Address::from(key)));
+                builder.with_sender(Sender::new_basic(key.address()));
             }
         }
 
         let proof_builder = builder.generate()?;
         let mut staking_data_builder = proof_builder.unwrap_in_staking();
-        staking_data_builder.sign_with_key_pair(staker_key_pair);
+        staking_data_builder.sign_with(staker_signer)?;
         let mut builder = staking_data_builder.generate().unwrap().unwrap_basic();
-        match key_pair {
-            None => builder.sign_with_key_pair(staker_key_pair),
-            Some(key) => builder.sign_with_key_pair(key),
+        match signer {
+            None => builder.sign_with(staker_signer)?,
+            Some(key) => builder.sign_with(key)?,
         };
         Ok(builder.generate().unwrap())
     }
@@ -1104,11 +1122,11 @@ impl TransactionBuilder {
     ///
     /// # Arguments
     ///
-    ///  - `key_pair`:              The optional key pair used to sign the outgoing transaction. If
+    ///  - `signer`:                The optional signer used to sign the outgoing transaction. If
     ///                             it is given, the fee will be paid from the basic account
-    ///                             belonging to this key pair.
-    ///  - `staker_key_pair`:       The key pair used to sign the incoming transaction. The staker
-    ///                             address will be derived from this key pair.
+    ///                             belonging to this signer.
+    ///  - `staker_signer`:         The signer used to sign the incoming transaction. The staker
+    ///                             address will be derived from this signer.
     ///  - `retire_stake`:          The portion of the inactive stake to be retired.
     ///  - `fee`:                   Transaction fee.
     ///  - `validity_start_height`: Block height from which this transaction is valid.
@@ -1123,8 +1141,8 @@ impl TransactionBuilder {
     /// This is a *signaling transaction*.
     ///
     pub fn new_retire_stake(
-        key_pair: Option<&KeyPair>,
-        staker_key_pair: &KeyPair,
+        signer: Option<&dyn ExternalSigner>,
+        staker_signer: &dyn ExternalSigner,
         retire_stake: Coin,
         fee: Coin,
         validity_start_height: u32,
@@ -1141,22 +1159,22 @@ impl TransactionBuilder {
             .with_validity_start_height(validity_start_height)
             .with_network_id(network_id);
 
-        match key_pair {
+        match signer {
             None => {
-                builder.with_sender(Sender::new_basic(Address::from(staker_key_pair)));
+                builder.with_sender(Sender::new_basic(staker_signer.address()));
             }
             Some(key) => {
-                builder.with_sender(Sender::new_basic(Address::from(key)));
+                builder.with_sender(Sender::new_basic(key.address()));
             }
         }
 
         let proof_builder = builder.generate()?;
         let mut staking_data_builder = proof_builder.unwrap_in_staking();
-        staking_data_builder.sign_with_key_pair(staker_key_pair);
+        staking_data_builder.sign_with(staker_signer)?;
         let mut builder = staking_data_builder.generate().unwrap().unwrap_basic();
-        match key_pair {
-            None => builder.sign_with_key_pair(staker_key_pair),
-            Some(key) => builder.sign_with_key_pair(key),
+        match signer {
+            None => builder.sign_with(staker_signer)?,
+            Some(key) => builder.sign_with(key)?,
         };
         Ok(builder.generate().unwrap())
     }
@@ -1166,8 +1184,8 @@ impl TransactionBuilder {
     ///
     /// # Arguments
     ///
-    ///  - `key_pair`:              The key pair used to sign the outgoing transaction. The staker
-    ///                             address will be derived from this key pair.
+    ///  - `signer`:                The signer used to sign the outgoing transaction. The staker
+    ///                             address will be derived from this signer.
     ///  - `recipient`:             The basic address that will receive the removed funds.
     ///  - `value`:                 The value to be removed from the staker. Must be equal to retired balance.
     ///  - `fee`:                   Transaction fee.
@@ -1179,7 +1197,7 @@ impl TransactionBuilder {
     /// The finalized transaction.
     ///
     pub fn new_remove_stake(
-        key_pair: &KeyPair,
+        signer: &dyn ExternalSigner,
         recipient: Address,
         value: Coin,
         fee: Coin,
@@ -1204,7 +1222,7 @@ impl TransactionBuilder {
         let proof_builder = builder.generate()?;
         match proof_builder {
             TransactionProofBuilder::OutStaking(mut builder) => {
-                builder.sign_with_key_pair(key_pair);
+                builder.sign_with(signer)?;
                 Ok(builder.generate().unwrap())
             }
             _ => unreachable!(),
@@ -1215,10 +1233,10 @@ impl TransactionBuilder {
     ///
     /// # Arguments
     ///
-    ///  - `key_pair`:              The key pair used to sign the transaction. The initial stake is
-    ///                             sent from the account belonging to this key pair.
-    ///  - `cold_key_pair`:         The key pair that will become the validator address. The data is
-    ///                             signed using this key pair.
+    ///  - `signer`:                The signer used to sign the transaction. The initial stake is
+    ///                             sent from the account belonging to this signer.
+    ///  - `cold_signer`:           The signer that will become the validator address. The data is
+    ///                             signed using this signer.
     ///  - `signing_key` :          The Schnorr signing key used by the validator.
     ///  - `voting_key_pair`:       The BLS key pair used by the validator.
     ///  - `reward_address`:        The address to which the staking rewards are sent.
@@ -1232,8 +1250,8 @@ impl TransactionBuilder {
     /// The finalized transaction.
     ///
     pub fn new_create_validator(
-        key_pair: &KeyPair,
-        cold_key_pair: &KeyPair,
+        signer: &dyn ExternalSigner,
+        cold_signer: &dyn ExternalSigner,
         signing_key: Ed25519PublicKey,
         voting_key_pair: &BlsKeyPair,
         reward_address: Address,
@@ -1247,7 +1265,7 @@ impl TransactionBuilder {
 
         let mut builder = Self::new();
         builder
-            .with_sender(Sender::new_basic(Address::from(key_pair)))
+            .with_sender(Sender::new_basic(signer.address()))
             .with_recipient(recipient.generate().unwrap())
             .with_value(Coin::from_u64_unchecked(Policy::VALIDATOR_DEPOSIT))
             .with_fee(fee)
@@ -1257,9 +1275,9 @@ impl TransactionBuilder {
         let proof_builder = builder.generate()?;
         match proof_builder {
             TransactionProofBuilder::InStaking(mut builder) => {
-                builder.sign_with_key_pair(cold_key_pair);
+                builder.sign_with(cold_signer)?;
                 let mut builder = builder.generate().unwrap().unwrap_basic();
-                builder.sign_with_key_pair(key_pair);
+                builder.sign_with(signer)?;
                 Ok(builder.generate().unwrap())
             }
             _ => unreachable!(),
@@ -1270,10 +1288,10 @@ impl TransactionBuilder {
     ///
     /// # Arguments
     ///
-    ///  - `key_pair`:                 The key pair used to sign the transaction. The transaction
-    ///                                fee is taken from the account belonging to this key pair.
-    ///  - `cold_key_pair`:            The key pair that corresponds to the validator address. The
-    ///                                data is signed using this key pair.
+    ///  - `signer`:                   The signer used to sign the transaction. The transaction
+    ///                                fee is taken from the account belonging to this signer.
+    ///  - `cold_signer`:              The signer that corresponds to the validator address. The
+    ///                                data is signed using this signer.
     ///  - `new_signing_key`:          The new Schnorr signing key used by the validator.
     ///  - `new_reward_address`:       The new address to which the staking reward is sent.
     ///  - `new_signal_data`:          The new signal data showed by the validator.
@@ -1291,8 +1309,8 @@ impl TransactionBuilder {
     /// This is a *signaling transaction*.
     ///
     pub fn new_update_validator(
-        key_pair: &KeyPair,
-        cold_key_pair: &KeyPair,
+        signer: &dyn ExternalSigner,
+        cold_signer: &dyn ExternalSigner,
         new_signing_key: Option<Ed25519PublicKey>,
         new_voting_key_pair: Option<&BlsKeyPair>,
         new_reward_address: Option<Address>,
@@ -1311,7 +1329,7 @@ impl TransactionBuilder {
 
         let mut builder = Self::new();
         builder
-            .with_sender(Sender::new_basic(Address::from(key_pair)))
+            .with_sender(Sender::new_basic(signer.address()))
             .with_recipient(recipient.generate().unwrap())
             .with_value(Coin::ZERO)
             .with_fee(fee)
@@ -1321,9 +1339,9 @@ impl TransactionBuilder {
         let proof_builder = builder.generate()?;
         match proof_builder {
             TransactionProofBuilder::InStaking(mut builder) => {
-                builder.sign_with_key_pair(cold_key_pair);
+                builder.sign_with(cold_signer)?;
                 let mut builder = builder.generate().unwrap().unwrap_basic();
-                builder.sign_with_key_pair(key_pair);
+                builder.sign_with(signer)?;
                 Ok(builder.generate().unwrap())
             }
             _ => unreachable!(),
@@ -1334,8 +1352,8 @@ impl TransactionBuilder {
     ///
     /// # Arguments
     ///
-    ///  - `key_pair`:              The key pair used to sign the transaction. The transaction fee
-    ///                             is taken from the account belonging to this key pair.
+    ///  - `signer`:                The signer used to sign the transaction. The transaction fee
+    ///                             is taken from the account belonging to this signer.
     ///  - `validator_address`:     The validator address.
     ///  - `signing_key_pair`:      The key pair that corresponds to the validator's signing key.
     ///                             The data is signed using this key pair.
@@ -1352,7 +1370,7 @@ impl TransactionBuilder {
     /// This is a *signaling transaction*.
     ///
     pub fn new_deactivate_validator(
-        key_pair: &KeyPair,
+        signer: &dyn ExternalSigner,
         validator_address: Address,
         signing_key_pair: &KeyPair,
         fee: Coin,
@@ -1364,7 +1382,7 @@ impl TransactionBuilder {
 
         let mut builder = Self::new();
         builder
-            .with_sender(Sender::new_basic(Address::from(key_pair)))
+            .with_sender(Sender::new_basic(signer.address()))
             .with_recipient(recipient.generate().unwrap())
             .with_value(Coin::ZERO)
             .with_fee(fee)
@@ -1376,7 +1394,7 @@ impl TransactionBuilder {
             TransactionProofBuilder::InStaking(mut builder) => {
                 builder.sign_with_key_pair(signing_key_pair);
                 let mut builder = builder.generate().unwrap().unwrap_basic();
-                builder.sign_with_key_pair(key_pair);
+                builder.sign_with(signer)?;
                 Ok(builder.generate().unwrap())
             }
             _ => unreachable!(),
@@ -1387,8 +1405,8 @@ impl TransactionBuilder {
     ///
     /// # Arguments
     ///
-    ///  - `key_pair`:              The key pair used to sign the transaction. The transaction fee
-    ///                             is taken from the account belonging to this key pair.
+    ///  - `signer`:                The signer used to sign the transaction. The transaction fee
+    ///                             is taken from the account belonging to this signer.
     ///  - `validator_address`:     The validator address.
     ///  - `signing_key_pair`:      The key pair that corresponds to the validator's signing key.
     ///                             The data is signed using this key pair.
@@ -1405,7 +1423,7 @@ impl TransactionBuilder {
     /// This is a *signaling transaction*.
     ///
     pub fn new_reactivate_validator(
-        key_pair: &KeyPair,
+        signer: &dyn ExternalSigner,
         validator_address: Address,
         signing_key_pair: &KeyPair,
         fee: Coin,
@@ -1417,7 +1435,7 @@ impl TransactionBuilder {
 
         let mut builder = Self::new();
         builder
-            .with_sender(Sender::new_basic(Address::from(key_pair)))
+            .with_sender(Sender::new_basic(signer.address()))
             .with_recipient(recipient.generate().unwrap())
             .with_value(Coin::ZERO)
             .with_fee(fee)
@@ -1429,7 +1447,7 @@ impl TransactionBuilder {
             TransactionProofBuilder::InStaking(mut builder) => {
                 builder.sign_with_key_pair(signing_key_pair);
                 let mut builder = builder.generate().unwrap().unwrap_basic();
-                builder.sign_with_key_pair(key_pair);
+                builder.sign_with(signer)?;
                 Ok(builder.generate().unwrap())
             }
             _ => unreachable!(),
@@ -1440,10 +1458,10 @@ impl TransactionBuilder {
     ///
     /// # Arguments
     ///
-    ///  - `key_pair`:                 The key pair used to sign the transaction. The transaction
-    ///                                fee is taken from the account belonging to this key pair.
-    ///  - `cold_key_pair`:            The key pair that corresponds to the validator address. The
-    ///                                data is signed using this key pair.
+    ///  - `signer`:                   The signer used to sign the transaction. The transaction
+    ///                                fee is taken from the account belonging to this signer.
+    ///  - `cold_signer`:              The signer that corresponds to the validator address. The
+    ///                                data is signed using this signer.
     ///  - `fee`:                      Transaction fee.
     ///  - `validity_start_height`:    Block height from which this transaction is valid.
     ///  - `network_id`:               ID of network for which the transaction is valid.
@@ -1457,8 +1475,8 @@ impl TransactionBuilder {
     /// This is a *signaling transaction*.
     ///
     pub fn new_retire_validator(
-        key_pair: &KeyPair,
-        cold_key_pair: &KeyPair,
+        signer: &dyn ExternalSigner,
+        cold_signer: &dyn ExternalSigner,
         fee: Coin,
         validity_start_height: u32,
         network_id: NetworkId,
@@ -1468,7 +1486,7 @@ impl TransactionBuilder {
 
         let mut builder = Self::new();
         builder
-            .with_sender(Sender::new_basic(Address::from(key_pair)))
+            .with_sender(Sender::new_basic(signer.address()))
             .with_recipient(recipient.generate().unwrap())
             .with_value(Coin::ZERO)
             .with_fee(fee)
@@ -1478,9 +1496,9 @@ impl TransactionBuilder {
         let proof_builder = builder.generate()?;
         match proof_builder {
             TransactionProofBuilder::InStaking(mut builder) => {
-                builder.sign_with_key_pair(cold_key_pair);
+                builder.sign_with(cold_signer)?;
                 let mut builder = builder.generate().unwrap().unwrap_basic();
-                builder.sign_with_key_pair(key_pair);
+                builder.sign_with(signer)?;
                 Ok(builder.generate().unwrap())
             }
             _ => unreachable!(),
@@ -1493,8 +1511,8 @@ impl TransactionBuilder {
     /// # Arguments
     ///
     ///  - `recipient`:             The recipient of the staked funds.
-    ///  - `cold_key_pair`:         The key pair that corresponds to the validator address. The
-    ///                             transaction is signed using this key pair.
+    ///  - `cold_signer`:           The signer that corresponds to the validator address. The
+    ///                             transaction is signed using this signer.
     ///  - `value`:                 The value to be removed from the validator. Must be equal to the validator's deposit.
     ///  - `fee`:                   Transaction fee. The fee is subtracted from the staked funds.
     ///  - `validity_start_height`: Block height from which this transaction is valid.
@@ -1506,7 +1524,7 @@ impl TransactionBuilder {
     ///
     pub fn new_delete_validator(
         recipient: Address,
-        cold_key_pair: &KeyPair,
+        cold_signer: &dyn ExternalSigner,
         fee: Coin,
         value: Coin,
         validity_start_height: u32,
@@ -1531,7 +1549,7 @@ impl TransactionBuilder {
         let proof_builder = builder.generate()?;
         match proof_builder {
             TransactionProofBuilder::OutStaking(mut builder) => {
-                builder.sign_with_key_pair(cold_key_pair);
+                builder.sign_with(cold_signer)?;
                 Ok(builder.generate().unwrap())
             }
             _ => unreachable!(),
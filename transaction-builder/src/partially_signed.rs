@@ -0,0 +1,439 @@
+use nimiq_hash::Blake2bHasher;
+use nimiq_keys::{
+    multisig::{
+        address::{combine_public_keys, compute_address},
+        commitment::{Commitment, CommitmentPair},
+        error::PartialSignatureError,
+        partial_signature::PartialSignature,
+        CommitmentsBuilder, CommitmentsData, MUSIG2_PARAMETER_V,
+    },
+    Ed25519PublicKey, KeyPair, PublicKey, Signature,
+};
+use nimiq_serde::{Deserialize, DeserializeError, Serialize};
+use nimiq_transaction::{SignatureProof, Transaction};
+use nimiq_utils::merkle::Blake2bMerklePath;
+use thiserror::Error;
+
+/// Errors that can occur while coordinating the signing of a [`PartiallySignedTransaction`].
+#[derive(Debug, Error)]
+pub enum PartiallySignedTransactionError {
+    /// The transaction sender is not the multisig address of the public keys.
+    #[error("The transaction sender is not the multisig address of the public keys.")]
+    InvalidSender,
+    /// The number of signers doesn't match the minimum number of signatures, or the signers are
+    /// not distinct owners of the multisig address.
+    #[error("The signers must be {0} distinct owners of the multisig address.")]
+    InvalidSigners(u8),
+    /// The public key is not one of the signers of this signing round.
+    #[error("The public key is not one of the signers.")]
+    UnknownSigner,
+    /// The signer already provided different commitments.
+    #[error("The signer already provided different commitments.")]
+    ConflictingCommitments,
+    /// The signer provided more than one partial signature.
+    #[error("The signer provided more than one partial signature.")]
+    ConflictingPartialSignatures,
+    /// The public keys of the multisig address are not sorted or not distinct.
+    #[error("The public keys must be sorted and distinct.")]
+    InvalidPublicKeys,
+    /// The commitments of some of the signers are still missing.
+    #[error("The commitments of some signers are missing.")]
+    MissingCommitments,
+    /// The partial signatures of some of the signers are still missing.
+    #[error("The partial signatures of some signers are missing.")]
+    MissingPartialSignatures,
+    /// The own commitments don't match the commitments provided for the signer.
+    #[error("The commitments don't match the ones provided for the signer.")]
+    CommitmentsMismatch,
+    /// The partial signature doesn't verify.
+    #[error("Invalid partial signature.")]
+    InvalidPartialSignature,
+    /// The combined signature doesn't verify.
+    #[error("The combined signature is invalid.")]
+    InvalidSignature,
+    /// The partially signed transactions to combine are for different transactions or signers.
+    #[error("The partially signed transactions don't match.")]
+    Mismatch,
+    #[error("{0}")]
+    PartialSignature(#[from] PartialSignatureError),
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] DeserializeError),
+}
+
+/// A container to coordinate the signing of a transaction from a multisig address.
+///
+/// Signing a transaction with MuSig2 takes two rounds: First, each signer creates commitments
+/// with [`CommitmentPair`]s they keep secret and adds the public commitments via
+/// [`add_commitments`]. Once the commitments of all signers are known, each signer creates their
+/// partial signature with [`sign`] using the same secret commitment pairs. Containers passed
+/// around independently can be merged with [`combine`]. After all partial signatures were
+/// collected, [`finalize`] creates the signature proof.
+///
+/// [`add_commitments`]: PartiallySignedTransaction::add_commitments
+/// [`sign`]: PartiallySignedTransaction::sign
+/// [`combine`]: PartiallySignedTransaction::combine
+/// [`finalize`]: PartiallySignedTransaction::finalize
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(try_from = "PartiallySignedTransactionFields")]
+pub struct PartiallySignedTransaction {
+    /// The unsigned transaction.
+    pub transaction: Transaction,
+    /// The public keys of all owners of the multisig address.
+    pub public_keys: Vec<Ed25519PublicKey>,
+    /// The minimum number of signatures of the multisig address.
+    pub min_signatures: u8,
+    /// The public keys of the owners signing in this round.
+    pub signers: Vec<Ed25519PublicKey>,
+    /// The public commitments collected from the signers.
+    pub commitments: Vec<(Ed25519PublicKey, [Commitment; MUSIG2_PARAMETER_V])>,
+    /// The partial signatures collected from the signers.
+    pub partial_signatures: Vec<(Ed25519PublicKey, PartialSignature)>,
+}
+
+/// The serialized form of a [`PartiallySignedTransaction`], which is only turned into one after
+/// validating it.
+#[derive(Deserialize)]
+struct PartiallySignedTransactionFields {
+    transaction: Transaction,
+    public_keys: Vec<Ed25519PublicKey>,
+    min_signatures: u8,
+    signers: Vec<Ed25519PublicKey>,
+    commitments: Vec<(Ed25519PublicKey, [Commitment; MUSIG2_PARAMETER_V])>,
+    partial_signatures: Vec<(Ed25519PublicKey, PartialSignature)>,
+}
+
+impl TryFrom<PartiallySignedTransactionFields> for PartiallySignedTransaction {
+    type Error = PartiallySignedTransactionError;
+
+    fn try_from(fields: PartiallySignedTransactionFields) -> Result<Self, Self::Error> {
+        let pst = PartiallySignedTransaction {
+            transaction: fields.transaction,
+            public_keys: fields.public_keys,
+            min_signatures: fields.min_signatures,
+            signers: fields.signers,
+            commitments: fields.commitments,
+            partial_signatures: fields.partial_signatures,
+        };
+        pst.validate()?;
+        Ok(pst)
+    }
+}
+
+impl PartiallySignedTransaction {
+    /// Creates a container for the transaction, which must be sent from the multisig address of
+    /// the `public_keys` with `min_signatures`. The `signers` are the owners signing it.
+    pub fn new(
+        transaction: Transaction,
+        public_keys: Vec<Ed25519PublicKey>,
+        min_signatures: u8,
+        mut signers: Vec<Ed25519PublicKey>,
+    ) -> Result<Self, PartiallySignedTransactionError> {
+        let mut public_keys = public_keys;
+        public_keys.sort();
+        public_keys.dedup();
+        signers.sort();
+        signers.dedup();
+
+        let pst = PartiallySignedTransaction {
+            transaction,
+            public_keys,
+            min_signatures,
+            signers,
+            commitments: vec![],
+            partial_signatures: vec![],
+        };
+        pst.validate()?;
+        Ok(pst)
+    }
+
+    /// Checks that the signers are distinct owners of the multisig address that sends the
+    /// transaction, and that all commitments and partial signatures are from distinct signers and
+    /// valid. This is required for containers received from others, as they can't be trusted.
+    pub fn validate(&self) -> Result<(), PartiallySignedTransactionError> {
+        if !is_sorted_and_distinct(&self.signers)
+            || self.min_signatures == 0
+            || self.signers.len() != self.min_signatures as usize
+            || !self
+                .signers
+                .iter()
+                .all(|signer| self.public_keys.contains(signer))
+        {
+            return Err(PartiallySignedTransactionError::InvalidSigners(
+                self.min_signatures,
+            ));
+        }
+        if !is_sorted_and_distinct(&self.public_keys) {
+            return Err(PartiallySignedTransactionError::InvalidPublicKeys);
+        }
+
+        let combined_public_keys =
+            combine_public_keys(self.public_keys.clone(), self.min_signatures as usize);
+        if compute_address(&combined_public_keys) != self.transaction.sender {
+            return Err(PartiallySignedTransactionError::InvalidSender);
+        }
+
+        for (index, (signer, _)) in self.commitments.iter().enumerate() {
+            if !self.signers.contains(signer) {
+                return Err(PartiallySignedTransactionError::UnknownSigner);
+            }
+            if self.commitments[..index]
+                .iter()
+                .any(|(public_key, _)| public_key == signer)
+            {
+                return Err(PartiallySignedTransactionError::ConflictingCommitments);
+            }
+        }
+
+        for (index, (signer, partial_signature)) in self.partial_signatures.iter().enumerate() {
+            if !self.signers.contains(signer) {
+                return Err(PartiallySignedTransactionError::UnknownSigner);
+            }
+            if self.partial_signatures[..index]
+                .iter()
+                .any(|(public_key, _)| public_key == signer)
+            {
+                return Err(PartiallySignedTransactionError::ConflictingPartialSignatures);
+            }
+            self.verify_partial_signature(signer, partial_signature)?;
+        }
+
+        Ok(())
+    }
+
+    /// Adds the public commitments of a signer.
+    pub fn add_commitments(
+        &mut self,
+        signer: Ed25519PublicKey,
+        commitments: [Commitment; MUSIG2_PARAMETER_V],
+    ) -> Result<(), PartiallySignedTransactionError> {
+        if !self.signers.contains(&signer) {
+            return Err(PartiallySignedTransactionError::UnknownSigner);
+        }
+
+        match self.commitments_of(&signer) {
+            Some(existing) if *existing == commitments => Ok(()),
+            Some(_) => Err(PartiallySignedTransactionError::ConflictingCommitments),
+            None => {
+                self.commitments.push((signer, commitments));
+                Ok(())
+            }
+        }
+    }
+
+    /// Adds the partial signature of a signer after verifying it. Requires the commitments of all
+    /// signers.
+    pub fn add_partial_signature(
+        &mut self,
+        signer: Ed25519PublicKey,
+        partial_signature: PartialSignature,
+    ) -> Result<(), PartiallySignedTransactionError> {
+        if !self.signers.contains(&signer) {
+            return Err(PartiallySignedTransactionError::UnknownSigner);
+        }
+        self.verify_partial_signature(&signer, &partial_signature)?;
+
+        self.partial_signatures
+            .retain(|(public_key, _)| *public_key != signer);
+        self.partial_signatures.push((signer, partial_signature));
+        Ok(())
+    }
+
+    /// Creates and adds the partial signature of the key pair. The commitment pairs must be the
+    /// secret counterparts of the commitments added for the signer, and must never be reused.
+    pub fn sign(
+        &mut self,
+        key_pair: &KeyPair,
+        commitment_pairs: &[CommitmentPair; MUSIG2_PARAMETER_V],
+    ) -> Result<PartialSignature, PartiallySignedTransactionError> {
+        let own_commitments = CommitmentPair::to_commitments(commitment_pairs);
+        match self.commitments_of(&key_pair.public) {
+            Some(commitments) if *commitments == own_commitments => {}
+            Some(_) => return Err(PartiallySignedTransactionError::CommitmentsMismatch),
+            None => return Err(PartiallySignedTransactionError::MissingCommitments),
+        }
+
+        let commitments_data = self.commitments_data(
+            CommitmentsBuilder::with_private_commitments(key_pair.public, *commitment_pairs),
+            &key_pair.public,
+        )?;
+        let partial_signature =
+            key_pair.partial_sign(&commitments_data, &self.transaction.serialize_content())?;
+
+        self.add_partial_signature(key_pair.public, partial_signature)?;
+        Ok(partial_signature)
+    }
+
+    /// Merges the commitments and partial signatures of another container for the same
+    /// transaction and signers into this one.
+    pub fn combine(&mut self, other: &Self) -> Result<(), PartiallySignedTransactionError> {
+        if self.transaction != other.transaction
+            || self.public_keys != other.public_keys
+            || self.min_signatures != other.min_signatures
+            || self.signers != other.signers
+        {
+            return Err(PartiallySignedTransactionError::Mismatch);
+        }
+
+        for (signer, commitments) in &other.commitments {
+            self.add_commitments(*signer, *commitments)?;
+        }
+        for (signer, partial_signature) in &other.partial_signatures {
+            if !self
+                .partial_signatures
+                .contains(&(*signer, *partial_signature))
+            {
+                self.add_partial_signature(*signer, *partial_signature)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns if the commitments of all signers have been collected.
+    pub fn has_all_commitments(&self) -> bool {
+        !self.signers.is_empty()
+            && self
+                .signers
+                .iter()
+                .all(|signer| self.commitments_of(signer).is_some())
+    }
+
+    /// Returns if the partial signatures of all signers have been collected.
+    pub fn is_complete(&self) -> bool {
+        !self.signers.is_empty()
+            && self
+                .signers
+                .iter()
+                .all(|signer| self.partial_signature_of(signer).is_some())
+    }
+
+    /// Combines the partial signatures into the signature proof of the transaction.
+    pub fn finalize(&self) -> Result<SignatureProof, PartiallySignedTransactionError> {
+        if !self.is_complete() {
+            return Err(PartiallySignedTransactionError::MissingPartialSignatures);
+        }
+
+        // The aggregate commitment is the same for all signers, so any signer can be used.
+        let signer =
+            *self
+                .signers
+                .first()
+                .ok_or(PartiallySignedTransactionError::InvalidSigners(
+                    self.min_signatures,
+                ))?;
+        let commitments_data = self.commitments_data(
+            CommitmentsBuilder::with_public_commitments(
+                signer,
+                *self
+                    .commitments_of(&signer)
+                    .ok_or(PartiallySignedTransactionError::MissingCommitments)?,
+            ),
+            &signer,
+        )?;
+
+        let aggregated_signature: PartialSignature = self
+            .signers
+            .iter()
+            .filter_map(|signer| self.partial_signature_of(signer))
+            .sum();
+        let signature = aggregated_signature.to_signature(&commitments_data.aggregate_commitment);
+
+        let combined_public_keys =
+            combine_public_keys(self.public_keys.clone(), self.min_signatures as usize);
+        let proof = SignatureProof {
+            merkle_path: Blake2bMerklePath::new::<Blake2bHasher, _>(
+                &combined_public_keys,
+                &commitments_data.aggregate_public_key,
+            ),
+            public_key: PublicKey::Ed25519(commitments_data.aggregate_public_key),
+            signature: Signature::Ed25519(signature),
+            webauthn_fields: None,
+        };
+
+        if !proof.is_signed_by(&self.transaction.sender)
+            || !proof.verify(&self.transaction.serialize_content())
+        {
+            return Err(PartiallySignedTransactionError::InvalidSignature);
+        }
+        Ok(proof)
+    }
+
+    /// Finalizes the signature proof and returns the signed transaction.
+    pub fn finalize_transaction(&self) -> Result<Transaction, PartiallySignedTransactionError> {
+        let proof = self.finalize()?;
+        let mut transaction = self.transaction.clone();
+        transaction.proof = proof.serialize_to_vec();
+        Ok(transaction)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.serialize_to_vec()
+    }
+
+    /// Deserializes and validates a container.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, PartiallySignedTransactionError> {
+        PartiallySignedTransactionFields::deserialize_from_vec(bytes)?.try_into()
+    }
+
+    fn commitments_of(
+        &self,
+        signer: &Ed25519PublicKey,
+    ) -> Option<&[Commitment; MUSIG2_PARAMETER_V]> {
+        self.commitments
+            .iter()
+            .find(|(public_key, _)| public_key == signer)
+            .map(|(_, commitments)| commitments)
+    }
+
+    fn partial_signature_of(&self, signer: &Ed25519PublicKey) -> Option<&PartialSignature> {
+        self.partial_signatures
+            .iter()
+            .find(|(public_key, _)| public_key == signer)
+            .map(|(_, partial_signature)| partial_signature)
+    }
+
+    /// Verifies the partial signature of a signer. Requires the commitments of all signers.
+    fn verify_partial_signature(
+        &self,
+        signer: &Ed25519PublicKey,
+        partial_signature: &PartialSignature,
+    ) -> Result<(), PartiallySignedTransactionError> {
+        let own_commitments = *self
+            .commitments_of(signer)
+            .ok_or(PartiallySignedTransactionError::MissingCommitments)?;
+        let commitments_data = self.commitments_data(
+            CommitmentsBuilder::with_public_commitments(*signer, own_commitments),
+            signer,
+        )?;
+
+        if !signer.verify_partial(
+            &commitments_data,
+            partial_signature,
+            &self.transaction.serialize_content(),
+        ) {
+            return Err(PartiallySignedTransactionError::InvalidPartialSignature);
+        }
+        Ok(())
+    }
+
+    /// Adds the commitments of all other signers to the builder and computes the commitments data.
+    fn commitments_data(
+        &self,
+        mut builder: CommitmentsBuilder,
+        own_public_key: &Ed25519PublicKey,
+    ) -> Result<CommitmentsData, PartiallySignedTransactionError> {
+        if !self.has_all_commitments() {
+            return Err(PartiallySignedTransactionError::MissingCommitments);
+        }
+
+        for (signer, commitments) in &self.commitments {
+            if signer != own_public_key {
+                builder.push_signer(*signer, *commitments);
+            }
+        }
+        Ok(builder.build(&self.transaction.serialize_content()))
+    }
+}
+
+fn is_sorted_and_distinct(public_keys: &[Ed25519PublicKey]) -> bool {
+    public_keys.windows(2).all(|pair| pair[0] < pair[1])
+}
@@ -6,6 +6,8 @@ use nimiq_transaction::{
     SignatureProof, Transaction,
 };
 
+use crate::signer::{sign_transaction, ExternalSigner, ExternalSignerError};
+
 /// The `HtlcProofBuilder` can be used to build proofs for transactions
 /// that originate in a HTLC contract.
 #[derive(Clone, Debug)]
@@ -69,6 +71,17 @@ impl HtlcProofBuilder {
         SignatureProof::from_ed25519(key_pair.public, signature)
     }
 
+    /// This method creates a signature proof by letting an external `signer`, e.g. a hardware
+    /// wallet, sign the transaction. It can be used instead of [`signature_with_key_pair`].
+    ///
+    /// [`signature_with_key_pair`]: struct.HtlcProofBuilder.html#method.signature_with_key_pair
+    pub fn signature_with(
+        &self,
+        signer: &dyn ExternalSigner,
+    ) -> Result<SignatureProof, ExternalSignerError> {
+        sign_transaction(signer, &self.transaction)
+    }
+
     /// This method creates a proof for the `TimeoutResolve` case, i.e.,
     /// after a blockchain height called `timeout` is reached, the `sender` can withdraw the funds.
     ///
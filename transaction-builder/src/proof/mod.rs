@@ -6,9 +6,12 @@ use nimiq_primitives::account::AccountType;
 use nimiq_serde::Serialize;
 use nimiq_transaction::{SignatureProof, Transaction};
 
-use crate::proof::{
-    htlc_contract::HtlcProofBuilder,
-    staking_contract::{StakingDataBuilder, StakingProofBuilder},
+use crate::{
+    proof::{
+        htlc_contract::HtlcProofBuilder,
+        staking_contract::{StakingDataBuilder, StakingProofBuilder},
+    },
+    signer::{sign_transaction, ExternalSigner, ExternalSignerError},
 };
 
 pub mod htlc_contract;
@@ -358,6 +361,16 @@ impl BasicProofBuilder {
         self
     }
 
+    /// This method sets the required `signature` proof by letting an external `signer`, e.g. a
+    /// hardware wallet, sign the transaction.
+    pub fn sign_with(
+        &mut self,
+        signer: &dyn ExternalSigner,
+    ) -> Result<&mut Self, ExternalSignerError> {
+        self.signature = Some(sign_transaction(signer, &self.transaction)?);
+        Ok(self)
+    }
+
     /// This method generates the final transaction if the signature has been set correctly.
     /// Otherwise, it returns `None`.
     pub fn generate(self) -> Option<Transaction> {
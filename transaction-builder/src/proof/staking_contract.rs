@@ -4,7 +4,10 @@ use nimiq_transaction::{
     account::staking_contract::IncomingStakingTransactionData, SignatureProof, Transaction,
 };
 
-use crate::proof::TransactionProofBuilder;
+use crate::{
+    proof::TransactionProofBuilder,
+    signer::{sign_transaction, ExternalSigner, ExternalSignerError},
+};
 
 /// The `StakingDataBuilder` can be used to build the data for incoming staking transactions.
 ///
@@ -30,6 +33,16 @@ impl StakingDataBuilder {
     /// This method sets the required `signature` proof by signing the transaction
     /// using a key pair.
     pub fn sign_with_key_pair(&mut self, key_pair: &KeyPair) -> &mut Self {
+        self.sign_with(key_pair)
+            .expect("Signing with a key pair can't fail")
+    }
+
+    /// This method sets the required `signature` proof by letting an external `signer`, e.g. a
+    /// hardware wallet, sign the transaction.
+    pub fn sign_with(
+        &mut self,
+        signer: &dyn ExternalSigner,
+    ) -> Result<&mut Self, ExternalSignerError> {
         // Deserialize the data.
         let mut data: IncomingStakingTransactionData =
             Deserialize::deserialize_from_vec(&self.transaction.recipient_data[..]).unwrap();
@@ -37,15 +50,11 @@ impl StakingDataBuilder {
         // If this is a stake transaction, we don't need to sign it.
         match data {
             IncomingStakingTransactionData::AddStake { .. } => {}
-            _ => {
-                let signature = key_pair.sign(self.transaction.serialize_content().as_slice());
-                let proof = SignatureProof::from_ed25519(key_pair.public, signature);
-                data.set_signature(proof);
-            }
+            _ => data.set_signature(sign_transaction(signer, &self.transaction)?),
         }
 
         self.data = Some(data);
-        self
+        Ok(self)
     }
 
     /// This method returns the next proof builder to be used if the staking data signature
@@ -87,6 +96,16 @@ impl StakingProofBuilder {
         self
     }
 
+    /// This method sets the required `signature` proof by letting an external `signer`, e.g. a
+    /// hardware wallet, sign the transaction.
+    pub fn sign_with(
+        &mut self,
+        signer: &dyn ExternalSigner,
+    ) -> Result<&mut Self, ExternalSignerError> {
+        self.proof = Some(sign_transaction(signer, &self.transaction)?);
+        Ok(self)
+    }
+
     /// This method generates the final transaction if the proof has been set correctly.
     /// Otherwise, it returns `None`.
     pub fn generate(self) -> Option<Transaction> {
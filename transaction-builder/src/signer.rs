@@ -0,0 +1,107 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use nimiq_keys::{Address, KeyPair, PrivateKey};
+use nimiq_transaction::{SignatureProof, Transaction};
+use thiserror::Error;
+
+/// Errors that can occur when signing with an [`ExternalSigner`].
+#[derive(Debug, Error)]
+pub enum ExternalSignerError {
+    /// The signer can't be reached or failed to load its key.
+    #[error("The signer is unavailable: {0}")]
+    Unavailable(String),
+    /// The signer declined to sign, e.g. because the user rejected it on the device.
+    #[error("The signer rejected the signing request.")]
+    Rejected,
+    /// The returned signature proof isn't a valid signature of the signer.
+    #[error("The signature proof doesn't match the signer.")]
+    InvalidProof,
+}
+
+/// A signer that signs transactions without handing out its keys, such as a hardware wallet or a
+/// passkey.
+///
+/// The signer receives the serialized content of a transaction and returns the signature proof,
+/// which can be an Ed25519 or an ES256 (Webauthn) signature. Any [`KeyPair`] is a signer itself.
+pub trait ExternalSigner {
+    /// Returns the address of the account the signer signs for.
+    fn address(&self) -> Address;
+
+    /// Signs the serialized transaction content.
+    fn sign_transaction_content(
+        &self,
+        content: &[u8],
+    ) -> Result<SignatureProof, ExternalSignerError>;
+}
+
+impl ExternalSigner for KeyPair {
+    fn address(&self) -> Address {
+        Address::from(self)
+    }
+
+    fn sign_transaction_content(
+        &self,
+        content: &[u8],
+    ) -> Result<SignatureProof, ExternalSignerError> {
+        Ok(SignatureProof::from_ed25519(
+            self.public,
+            self.sign(content),
+        ))
+    }
+}
+
+/// A signer that loads its private key, stored in hexadecimal format, from a file whenever it
+/// signs. It behaves like an external device and is mostly useful for testing.
+#[derive(Clone, Debug)]
+pub struct KeyFileSigner {
+    path: PathBuf,
+    address: Address,
+}
+
+impl KeyFileSigner {
+    /// Creates a signer for the key file at `path`, which must contain a valid private key.
+    pub fn new<P: Into<PathBuf>>(path: P) -> Result<Self, ExternalSignerError> {
+        let path = path.into();
+        let address = Address::from(&Self::load_key_pair(&path)?);
+        Ok(KeyFileSigner { path, address })
+    }
+
+    fn load_key_pair(path: &Path) -> Result<KeyPair, ExternalSignerError> {
+        let hex_private_key = fs::read_to_string(path)
+            .map_err(|e| ExternalSignerError::Unavailable(e.to_string()))?;
+        let private_key = PrivateKey::from_str(hex_private_key.trim())
+            .map_err(|e| ExternalSignerError::Unavailable(e.to_string()))?;
+        Ok(KeyPair::from(private_key))
+    }
+}
+
+impl ExternalSigner for KeyFileSigner {
+    fn address(&self) -> Address {
+        self.address.clone()
+    }
+
+    fn sign_transaction_content(
+        &self,
+        content: &[u8],
+    ) -> Result<SignatureProof, ExternalSignerError> {
+        Self::load_key_pair(&self.path)?.sign_transaction_content(content)
+    }
+}
+
+/// Signs the transaction with the signer and checks that the returned proof is a valid signature
+/// of the signer's address.
+pub(crate) fn sign_transaction(
+    signer: &dyn ExternalSigner,
+    transaction: &Transaction,
+) -> Result<SignatureProof, ExternalSignerError> {
+    let content = transaction.serialize_content();
+    let proof = signer.sign_transaction_content(&content)?;
+    if !proof.is_signed_by(&signer.address()) || !proof.verify(&content) {
+        return Err(ExternalSignerError::InvalidProof);
+    }
+    Ok(proof)
+}
@@ -0,0 +1,108 @@
+use nimiq_keys::{Address, KeyPair, SecureGenerate};
+use nimiq_primitives::{coin::Coin, networks::NetworkId};
+use nimiq_serde::Serialize;
+use nimiq_test_log::test;
+use nimiq_transaction_builder::{BatchPayment, BatchPaymentError, FeePolicy, Payment};
+
+#[test]
+fn it_can_parse_csv_payments() {
+    let recipient = Address::from([1u8; 20]);
+    let csv = format!(
+        "recipient,value,data\n# Monthly payout\n\n{},12.5\n{}, 0.00001, cafe\n",
+        recipient.to_user_friendly_address(),
+        recipient.to_hex(),
+    );
+
+    let payments = BatchPayment::parse_csv(&csv).unwrap();
+    assert_eq!(
+        payments,
+        vec![
+            Payment {
+                recipient: recipient.clone(),
+                value: Coin::from_u64_unchecked(1_250_000),
+                data: vec![],
+            },
+            Payment {
+                recipient,
+                value: Coin::from_u64_unchecked(1),
+                data: vec![0xca, 0xfe],
+            },
+        ]
+    );
+
+    assert!(matches!(
+        BatchPayment::parse_csv("NQ00 invalid,1"),
+        Err(BatchPaymentError::InvalidCsv { line: 1, .. })
+    ));
+    assert!(matches!(
+        BatchPayment::parse_csv(&format!("\n{},1,00,00", recipient.to_hex())),
+        Err(BatchPaymentError::InvalidCsv { line: 2, .. })
+    ));
+}
+
+#[test]
+fn it_can_build_a_batch_payment() {
+    let key_pair = KeyPair::generate_default_csprng();
+    let payments = vec![
+        Payment {
+            recipient: Address::from([1u8; 20]),
+            value: Coin::from_u64_unchecked(100),
+            data: vec![],
+        },
+        Payment {
+            recipient: Address::from([2u8; 20]),
+            value: Coin::from_u64_unchecked(200),
+            data: b"invoice 42".to_vec(),
+        },
+    ];
+
+    let batch = BatchPayment::new(
+        payments.clone(),
+        FeePolicy::Fixed(Coin::from_u64_unchecked(10)),
+        5,
+        NetworkId::Dummy,
+    );
+    let transactions = batch.build(&key_pair).unwrap();
+    assert_eq!(transactions.len(), 2);
+    for (transaction, payment) in transactions.iter().zip(&payments) {
+        assert_eq!(transaction.sender, Address::from(&key_pair));
+        assert_eq!(transaction.recipient, payment.recipient);
+        assert_eq!(transaction.value, payment.value);
+        assert_eq!(transaction.recipient_data, payment.data);
+        assert_eq!(transaction.fee, Coin::from_u64_unchecked(10));
+        assert_eq!(transaction.validity_start_height, 5);
+        assert_eq!(transaction.verify(NetworkId::Dummy), Ok(()));
+    }
+}
+
+#[test]
+fn it_pays_fees_per_byte() {
+    let key_pair = KeyPair::generate_default_csprng();
+    let batch = BatchPayment::new(
+        vec![
+            Payment {
+                recipient: Address::from([1u8; 20]),
+                value: Coin::from_u64_unchecked(100),
+                data: vec![],
+            },
+            Payment {
+                recipient: Address::from([2u8; 20]),
+                value: Coin::from_u64_unchecked(100),
+                data: vec![0; 32],
+            },
+        ],
+        FeePolicy::PerByte(Coin::from_u64_unchecked(2)),
+        1,
+        NetworkId::Dummy,
+    );
+
+    let transactions = batch.build(&key_pair).unwrap();
+    for transaction in &transactions {
+        assert_eq!(
+            u64::from(transaction.fee),
+            2 * transaction.serialized_size() as u64
+        );
+        assert_eq!(transaction.verify(NetworkId::Dummy), Ok(()));
+    }
+    assert!(transactions[1].fee > transactions[0].fee);
+}
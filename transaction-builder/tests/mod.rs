@@ -1,3 +1,6 @@
+mod batch;
 mod htlc_contract;
+mod partially_signed;
+mod signer;
 mod staking_contract;
 mod vesting_contract;
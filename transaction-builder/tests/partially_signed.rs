@@ -0,0 +1,199 @@
+use nimiq_keys::{
+    multisig::{
+        address::{combine_public_keys, compute_address},
+        commitment::CommitmentPair,
+    },
+    Address, KeyPair, SecureGenerate,
+};
+use nimiq_primitives::{coin::Coin, networks::NetworkId};
+use nimiq_test_log::test;
+use nimiq_transaction::Transaction;
+use nimiq_transaction_builder::{PartiallySignedTransaction, PartiallySignedTransactionError};
+
+fn prepare_multisig() -> (Vec<KeyPair>, PartiallySignedTransaction) {
+    let key_pairs: Vec<KeyPair> = (0..3).map(|_| KeyPair::generate_default_csprng()).collect();
+    let mut public_keys: Vec<_> = key_pairs.iter().map(|key_pair| key_pair.public).collect();
+    public_keys.sort();
+    let sender = compute_address(&combine_public_keys(public_keys.clone(), 2));
+
+    let transaction = Transaction::new_basic(
+        sender,
+        Address::from([1u8; 20]),
+        Coin::from_u64_unchecked(1000),
+        Coin::from_u64_unchecked(10),
+        1,
+        NetworkId::Dummy,
+    );
+    let pst = PartiallySignedTransaction::new(
+        transaction,
+        public_keys,
+        2,
+        vec![key_pairs[0].public, key_pairs[2].public],
+    )
+    .unwrap();
+
+    (key_pairs, pst)
+}
+
+#[test]
+fn it_can_sign_in_rounds() {
+    let (key_pairs, mut pst) = prepare_multisig();
+    let commitments_a = CommitmentPair::generate_all(&mut rand::thread_rng());
+    let commitments_b = CommitmentPair::generate_all(&mut rand::thread_rng());
+
+    // Signing requires the commitments of all signers.
+    pst.add_commitments(
+        key_pairs[0].public,
+        CommitmentPair::to_commitments(&commitments_a),
+    )
+    .unwrap();
+    assert!(matches!(
+        pst.sign(&key_pairs[0], &commitments_a),
+        Err(PartiallySignedTransactionError::MissingCommitments)
+    ));
+    pst.add_commitments(
+        key_pairs[2].public,
+        CommitmentPair::to_commitments(&commitments_b),
+    )
+    .unwrap();
+
+    // Each signer works on a serialized copy.
+    let mut pst_a = PartiallySignedTransaction::from_bytes(&pst.to_bytes()).unwrap();
+    let mut pst_b = PartiallySignedTransaction::from_bytes(&pst.to_bytes()).unwrap();
+    pst_a.sign(&key_pairs[0], &commitments_a).unwrap();
+    pst_b.sign(&key_pairs[2], &commitments_b).unwrap();
+    assert!(matches!(
+        pst_a.finalize(),
+        Err(PartiallySignedTransactionError::MissingPartialSignatures)
+    ));
+
+    pst_a.combine(&pst_b).unwrap();
+    assert!(pst_a.is_complete());
+
+    let transaction = pst_a.finalize_transaction().unwrap();
+    assert_eq!(transaction.verify(NetworkId::Dummy), Ok(()));
+}
+
+#[test]
+fn it_rejects_invalid_signers() {
+    let (key_pairs, pst) = prepare_multisig();
+    let outsider = KeyPair::generate_default_csprng();
+
+    assert!(matches!(
+        PartiallySignedTransaction::new(
+            pst.transaction.clone(),
+            pst.public_keys.clone(),
+            2,
+            vec![key_pairs[0].public],
+        ),
+        Err(PartiallySignedTransactionError::InvalidSigners(2))
+    ));
+    assert!(matches!(
+        PartiallySignedTransaction::new(
+            pst.transaction.clone(),
+            pst.public_keys.clone(),
+            2,
+            vec![key_pairs[0].public, outsider.public],
+        ),
+        Err(PartiallySignedTransactionError::InvalidSigners(2))
+    ));
+    assert!(matches!(
+        PartiallySignedTransaction::new(
+            pst.transaction.clone(),
+            pst.public_keys.clone(),
+            1,
+            vec![key_pairs[0].public],
+        ),
+        Err(PartiallySignedTransactionError::InvalidSender)
+    ));
+}
+
+#[test]
+fn it_rejects_invalid_partial_signatures() {
+    let (key_pairs, mut pst) = prepare_multisig();
+    let commitments_a = CommitmentPair::generate_all(&mut rand::thread_rng());
+    let commitments_b = CommitmentPair::generate_all(&mut rand::thread_rng());
+    pst.add_commitments(
+        key_pairs[0].public,
+        CommitmentPair::to_commitments(&commitments_a),
+    )
+    .unwrap();
+    pst.add_commitments(
+        key_pairs[2].public,
+        CommitmentPair::to_commitments(&commitments_b),
+    )
+    .unwrap();
+
+    // The commitments were provided for another signer.
+    assert!(matches!(
+        pst.sign(&key_pairs[2], &commitments_a),
+        Err(PartiallySignedTransactionError::CommitmentsMismatch)
+    ));
+    assert!(matches!(
+        pst.add_commitments(
+            key_pairs[0].public,
+            CommitmentPair::to_commitments(&commitments_b)
+        ),
+        Err(PartiallySignedTransactionError::ConflictingCommitments)
+    ));
+
+    // A partial signature of one signer isn't valid for another.
+    let partial_signature = pst.clone().sign(&key_pairs[0], &commitments_a).unwrap();
+    assert!(matches!(
+        pst.add_partial_signature(key_pairs[2].public, partial_signature),
+        Err(PartiallySignedTransactionError::InvalidPartialSignature)
+    ));
+    pst.add_partial_signature(key_pairs[0].public, partial_signature)
+        .unwrap();
+}
+
+#[test]
+fn it_validates_deserialized_containers() {
+    let (key_pairs, mut pst) = prepare_multisig();
+    let commitments_a = CommitmentPair::generate_all(&mut rand::thread_rng());
+    pst.add_commitments(
+        key_pairs[0].public,
+        CommitmentPair::to_commitments(&commitments_a),
+    )
+    .unwrap();
+
+    // Without signers, the container would be complete without any partial signature.
+    let mut crafted = pst.clone();
+    crafted.signers.clear();
+    crafted.commitments.clear();
+    assert!(matches!(
+        PartiallySignedTransaction::from_bytes(&crafted.to_bytes()),
+        Err(PartiallySignedTransactionError::InvalidSigners(2))
+    ));
+    assert!(!crafted.is_complete());
+    assert!(crafted.finalize().is_err());
+
+    let mut crafted = pst.clone();
+    crafted.commitments.push(crafted.commitments[0]);
+    assert!(matches!(
+        PartiallySignedTransaction::from_bytes(&crafted.to_bytes()),
+        Err(PartiallySignedTransactionError::ConflictingCommitments)
+    ));
+
+    let mut crafted = pst.clone();
+    crafted.commitments.push((
+        key_pairs[1].public,
+        CommitmentPair::to_commitments(&commitments_a),
+    ));
+    assert!(matches!(
+        PartiallySignedTransaction::from_bytes(&crafted.to_bytes()),
+        Err(PartiallySignedTransactionError::UnknownSigner)
+    ));
+
+    let mut crafted = pst.clone();
+    crafted.transaction.sender = Address::from([2u8; 20]);
+    assert!(matches!(
+        PartiallySignedTransaction::from_bytes(&crafted.to_bytes()),
+        Err(PartiallySignedTransactionError::InvalidSender)
+    ));
+
+    assert_eq!(
+        PartiallySignedTransaction::from_bytes(&pst.to_bytes()).unwrap(),
+        pst
+    );
+}
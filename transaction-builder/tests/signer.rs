@@ -0,0 +1,182 @@
+use std::fs;
+
+use base64::{prelude::BASE64_URL_SAFE_NO_PAD, Engine};
+use nimiq_hash::{Blake2bHash, Hash, Sha256Hash};
+use nimiq_keys::{
+    Address, ES256PublicKey, ES256Signature, KeyPair, PublicKey, SecureGenerate, Signature,
+};
+use nimiq_primitives::{coin::Coin, networks::NetworkId};
+use nimiq_serde::Deserialize;
+use nimiq_test_log::test;
+use nimiq_transaction::SignatureProof;
+use nimiq_transaction_builder::{
+    ExternalSigner, ExternalSignerError, KeyFileSigner, TransactionBuilder, TransactionBuilderError,
+};
+use p256::ecdsa::{signature::Signer, SigningKey};
+
+/// A signer that claims the address of one key pair, but signs with another one.
+struct WrongKeySigner {
+    claimed: KeyPair,
+    actual: KeyPair,
+}
+
+impl ExternalSigner for WrongKeySigner {
+    fn address(&self) -> Address {
+        Address::from(&self.claimed)
+    }
+
+    fn sign_transaction_content(
+        &self,
+        content: &[u8],
+    ) -> Result<SignatureProof, ExternalSignerError> {
+        self.actual.sign_transaction_content(content)
+    }
+}
+
+/// A passkey that signs like a browser does for a Webauthn request of `ORIGIN`.
+struct WebauthnSigner {
+    signing_key: SigningKey,
+    public_key: PublicKey,
+}
+
+impl WebauthnSigner {
+    const HOSTNAME: &'static str = "wallet.nimiq.com";
+    const ORIGIN: &'static str = "https://wallet.nimiq.com";
+
+    fn new() -> Self {
+        let signing_key = SigningKey::random(&mut rand::thread_rng());
+        let public_key = ES256PublicKey::from_bytes(
+            signing_key
+                .verifying_key()
+                .to_encoded_point(true)
+                .as_bytes(),
+        )
+        .unwrap();
+
+        WebauthnSigner {
+            signing_key,
+            public_key: PublicKey::ES256(public_key),
+        }
+    }
+}
+
+impl ExternalSigner for WebauthnSigner {
+    fn address(&self) -> Address {
+        Address::from(&self.public_key)
+    }
+
+    fn sign_transaction_content(
+        &self,
+        content: &[u8],
+    ) -> Result<SignatureProof, ExternalSignerError> {
+        let challenge: Blake2bHash = content.hash();
+        let client_data_json = format!(
+            r#"{{"type":"webauthn.get","challenge":"{}","origin":"{}","crossOrigin":false}}"#,
+            BASE64_URL_SAFE_NO_PAD.encode(challenge.as_slice()),
+            Self::ORIGIN,
+        );
+
+        // The authenticator data consists of the RP ID hash, the flags and the signature counter.
+        let rp_id: Sha256Hash = Self::HOSTNAME.hash();
+        let mut authenticator_data = rp_id.as_slice().to_vec();
+        authenticator_data.extend_from_slice(&[0x05, 0, 0, 0, 1]);
+
+        let client_data_hash: Sha256Hash = client_data_json.hash();
+        let mut signed_data = authenticator_data.clone();
+        signed_data.extend_from_slice(client_data_hash.as_slice());
+        let signature: p256::ecdsa::Signature = self.signing_key.sign(&signed_data);
+
+        SignatureProof::try_from_webauthn(
+            self.public_key.clone(),
+            None,
+            Signature::ES256(ES256Signature::from_bytes(&signature.to_bytes()).unwrap()),
+            &authenticator_data,
+            &client_data_json,
+        )
+        .map_err(|error| ExternalSignerError::Unavailable(error.to_string()))
+    }
+}
+
+#[test]
+fn it_can_sign_with_a_key_file_signer() {
+    let key_pair = KeyPair::generate_default_csprng();
+    let path = std::env::temp_dir().join(format!(
+        "nimiq-signer-{}.key",
+        Address::from(&key_pair).to_hex()
+    ));
+    fs::write(&path, key_pair.private.to_hex()).unwrap();
+
+    let signer = KeyFileSigner::new(&path).unwrap();
+    assert_eq!(signer.address(), Address::from(&key_pair));
+
+    let tx = TransactionBuilder::new_basic(
+        &signer,
+        Address::from([1u8; 20]),
+        Coin::from_u64_unchecked(100),
+        Coin::ZERO,
+        1,
+        NetworkId::Dummy,
+    )
+    .unwrap();
+    assert_eq!(tx.sender, Address::from(&key_pair));
+    assert_eq!(tx.verify(NetworkId::Dummy), Ok(()));
+
+    // The key is read for every signature, like from a device that has been disconnected.
+    fs::remove_file(&path).unwrap();
+    assert!(matches!(
+        TransactionBuilder::new_basic(
+            &signer,
+            Address::from([1u8; 20]),
+            Coin::from_u64_unchecked(100),
+            Coin::ZERO,
+            1,
+            NetworkId::Dummy,
+        ),
+        Err(TransactionBuilderError::Signer(
+            ExternalSignerError::Unavailable(_)
+        ))
+    ));
+}
+
+#[test]
+fn it_rejects_proofs_of_other_signers() {
+    let signer = WrongKeySigner {
+        claimed: KeyPair::generate_default_csprng(),
+        actual: KeyPair::generate_default_csprng(),
+    };
+
+    assert!(matches!(
+        TransactionBuilder::new_basic(
+            &signer,
+            Address::from([1u8; 20]),
+            Coin::from_u64_unchecked(100),
+            Coin::ZERO,
+            1,
+            NetworkId::Dummy,
+        ),
+        Err(TransactionBuilderError::Signer(
+            ExternalSignerError::InvalidProof
+        ))
+    ));
+}
+
+#[test]
+fn it_can_sign_with_a_webauthn_signer() {
+    let signer = WebauthnSigner::new();
+
+    let tx = TransactionBuilder::new_basic(
+        &signer,
+        Address::from([1u8; 20]),
+        Coin::from_u64_unchecked(100),
+        Coin::ZERO,
+        1,
+        NetworkId::Dummy,
+    )
+    .unwrap();
+    assert_eq!(tx.sender, signer.address());
+    assert_eq!(tx.verify(NetworkId::Dummy), Ok(()));
+
+    let proof = SignatureProof::deserialize_from_vec(&tx.proof).unwrap();
+    assert_eq!(proof.public_key, signer.public_key);
+    assert!(proof.webauthn_fields.is_some());
+}
@@ -249,6 +249,7 @@ impl SkipBlockAggregation {
         validator_id: u16,
         active_validators: Validators,
         network: Arc<N>,
+        config: Config,
     ) -> (SkipBlockInfo, SkipBlockProof) {
         // TODO expose this somewehere else so we don't need to clone here.
         let weights = Arc::new(ValidatorRegistry::new(active_validators.clone()));
@@ -303,7 +304,7 @@ impl SkipBlockAggregation {
 
             let aggregation = Aggregation::new(
                 protocol,
-                Config::default(),
+                config.clone(),
                 own_contribution,
                 Box::pin(input_switch),
                 NetworkWrapper::new(skip_block_info.clone(), Arc::clone(&network)),
@@ -8,8 +8,9 @@ use nimiq_primitives::TendermintIdentifier;
 use parking_lot::RwLock;
 
 use super::{
-    super::registry::ValidatorRegistry, contribution::TendermintContribution,
-    verifier::TendermintVerifier,
+    super::registry::ValidatorRegistry,
+    contribution::TendermintContribution,
+    verifier::{DoubleVoteReporter, TendermintVerifier},
 };
 
 #[derive(std::fmt::Debug)]
@@ -30,6 +31,7 @@ impl TendermintAggregationProtocol {
         node_id: usize,
         threshold: usize,
         id: TendermintIdentifier,
+        double_votes: DoubleVoteReporter,
     ) -> Self {
         let partitioner = Arc::new(BinomialPartitioner::new(node_id, validators.len()));
 
@@ -44,7 +46,11 @@ impl TendermintAggregationProtocol {
             threshold,
         ));
 
-        let verifier = Arc::new(TendermintVerifier::new(validators.clone(), id.clone()));
+        let verifier = Arc::new(TendermintVerifier::new(
+            validators.clone(),
+            id.clone(),
+            double_votes,
+        ));
 
         Self {
             verifier,
@@ -1,29 +1,73 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use nimiq_block::EquivocationProof;
 use nimiq_bls::AggregatePublicKey;
+use nimiq_consensus::watchtower::DoubleVoteDetector;
 use nimiq_handel::{
     identity::IdentityRegistry,
     verifier::{VerificationResult, Verifier},
 };
 use nimiq_hash::Hash;
 use nimiq_primitives::{TendermintIdentifier, TendermintVote};
+use parking_lot::Mutex;
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
-use tokio::task;
+use tokio::{sync::mpsc, task};
 
 use super::contribution::TendermintContribution;
 
+/// Checks the verified contributions for double votes and sends the proofs of the offending
+/// validators to the given channel.
+#[derive(Debug)]
+pub(crate) struct DoubleVoteReporter {
+    detector: Mutex<DoubleVoteDetector>,
+    sender: mpsc::UnboundedSender<EquivocationProof>,
+}
+
+impl DoubleVoteReporter {
+    pub(crate) fn new(
+        detector: DoubleVoteDetector,
+        sender: mpsc::UnboundedSender<EquivocationProof>,
+    ) -> Self {
+        Self {
+            detector: Mutex::new(detector),
+            sender,
+        }
+    }
+
+    fn report(&self, contribution: &TendermintContribution) {
+        let mut detector = self.detector.lock();
+        for (hash, multi_sig) in &contribution.contributions {
+            for proof in detector.observe(hash, multi_sig) {
+                info!(
+                    validator_address = %proof.validator_address(),
+                    block_number = proof.block_number(),
+                    "Detected double vote"
+                );
+                // The validator might be shutting down, in which case there is nobody to include the proof.
+                _ = self.sender.send(proof);
+            }
+        }
+    }
+}
+
 #[derive(Debug)]
 pub(crate) struct TendermintVerifier<I: IdentityRegistry> {
     identity_registry: Arc<I>,
     id: TendermintIdentifier,
+    double_votes: DoubleVoteReporter,
 }
 
 impl<I: IdentityRegistry> TendermintVerifier<I> {
-    pub(crate) fn new(identity_registry: Arc<I>, id: TendermintIdentifier) -> Self {
+    pub(crate) fn new(
+        identity_registry: Arc<I>,
+        id: TendermintIdentifier,
+        double_votes: DoubleVoteReporter,
+    ) -> Self {
         Self {
             identity_registry,
             id,
+            double_votes,
         }
     }
 }
@@ -76,7 +120,11 @@ impl<I: IdentityRegistry + Sync + Send + 'static> Verifier for TendermintVerifie
 
         match result {
             // All results were Ok. Verification is Ok.
-            Ok(()) => VerificationResult::Ok,
+            Ok(()) => {
+                // Only verified votes can prove that a validator voted twice.
+                self.double_votes.report(contribution);
+                VerificationResult::Ok
+            }
             Err(()) => VerificationResult::Forged,
         }
     }
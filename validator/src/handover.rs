@@ -0,0 +1,457 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use nimiq_utils::time::systemtime_to_timestamp;
+
+/// Configuration of the lease based handover between a primary validator and hot-standbys
+/// running with the same keys.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct HandoverConfig {
+    /// The lease file shared by all nodes running the validator. All nodes must see the same
+    /// file (e.g. on the same host or on a shared file system) and have synchronized clocks.
+    pub lease_file: PathBuf,
+
+    /// The identifier of this node in the lease file, which must be unique among the nodes sharing
+    /// the lease file. It can't be derived from the keys, as the primary and its standbys share them.
+    pub holder_id: String,
+
+    /// For how long a lease is valid after it has been renewed. The lease holder stops signing
+    /// as soon as it was not able to renew its lease in time.
+    pub lease_duration: Duration,
+
+    /// How long an expired lease must stay expired before another node may take it over.
+    /// This must exceed the clock skew between the nodes and the time it takes to write the lease file.
+    pub takeover_delay: Duration,
+
+    /// Whether this node is a standby. A standby does not claim a lease that has never been
+    /// written before the lease duration has passed, giving a starting primary precedence.
+    pub standby: bool,
+}
+
+/// The content of the lease file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct LeaseRecord {
+    holder: String,
+    expires_at: u64,
+}
+
+impl LeaseRecord {
+    fn parse(content: &str) -> io::Result<Self> {
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, "Malformed lease file");
+        let (holder, expires_at) = content.trim().rsplit_once(' ').ok_or_else(invalid)?;
+        Ok(LeaseRecord {
+            holder: holder.to_string(),
+            expires_at: expires_at.parse().map_err(|_| invalid())?,
+        })
+    }
+
+    fn to_file_content(&self) -> String {
+        format!("{} {}\n", self.holder, self.expires_at)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum LeaseState {
+    /// Another node holds the lease or nobody held it for long enough.
+    Standby,
+    /// We wrote our claim at the given time and wait for the takeover delay to pass before
+    /// checking that no other node claimed the lease concurrently.
+    Claimed { claimed_at: u64 },
+    /// We hold the lease until the given time.
+    Holding { expires_at: u64 },
+}
+
+/// A lease stored in a file that grants the exclusive right to sign as the validator.
+///
+/// The holder renews the lease periodically and never signs past the expiry it wrote. Other nodes
+/// only claim the lease once it has been expired for the takeover delay and only start to sign after
+/// verifying that their claim survived another takeover delay. Thus, as long as the clocks of the
+/// nodes differ by less than the takeover delay, at most one node signs at any time.
+pub struct HandoverLease {
+    config: HandoverConfig,
+    holder_id: String,
+    started_at: u64,
+    state: LeaseState,
+}
+
+impl HandoverLease {
+    /// Creates a lease for the node identified by the configured holder id.
+    ///
+    /// Fails if the holder id is invalid, or if the lease file contains an unexpired lease of the
+    /// same holder id. Then another node is running with the same holder id, or this node was
+    /// restarted before its lease expired. Starting anyway could let both nodes sign.
+    pub fn new(config: HandoverConfig) -> io::Result<Self> {
+        let holder_id = &config.holder_id;
+        if holder_id.is_empty() || holder_id.contains(char::is_whitespace) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Invalid lease holder id: {holder_id:?}"),
+            ));
+        }
+
+        let now = now();
+        let lease = Self::new_at(config, now);
+        if let Some(record) = lease.read()? {
+            if record.holder == lease.holder_id && now < record.expires_at {
+                return Err(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    format!(
+                        "The validator lease is held by a node with the holder id {:?}. Holder ids \
+                         must be unique, and a restarted node must wait for its lease to expire.",
+                        record.holder
+                    ),
+                ));
+            }
+        }
+        Ok(lease)
+    }
+
+    fn new_at(config: HandoverConfig, now: u64) -> Self {
+        HandoverLease {
+            holder_id: config.holder_id.clone(),
+            config,
+            started_at: now,
+            state: LeaseState::Standby,
+        }
+    }
+
+    /// Moves the lease to a thread that keeps it updated, such that the file I/O never blocks the
+    /// validator. The thread releases the lease and stops once the returned handle is dropped.
+    pub fn spawn(mut self) -> io::Result<HandoverLeaseHandle> {
+        let expires_at = Arc::new(AtomicU64::new(0));
+        let shared_expires_at = Arc::downgrade(&expires_at);
+        let update_interval = self.update_interval();
+
+        thread::Builder::new()
+            .name("validator-lease".to_string())
+            .spawn(move || {
+                while let Some(expires_at) = shared_expires_at.upgrade() {
+                    if let Err(error) = self.update() {
+                        warn!(%error, "Failed to update the validator lease");
+                    }
+                    expires_at.store(self.expires_at(), Ordering::Release);
+                    drop(expires_at);
+                    thread::sleep(update_interval);
+                }
+                if let Err(error) = self.release() {
+                    warn!(%error, "Failed to release the validator lease");
+                }
+            })?;
+
+        Ok(HandoverLeaseHandle {
+            expires_at,
+            update_interval,
+        })
+    }
+
+    /// The expiry of the lease we hold, or zero if we don't hold it.
+    fn expires_at(&self) -> u64 {
+        match self.state {
+            LeaseState::Holding { expires_at } => expires_at,
+            _ => 0,
+        }
+    }
+
+    /// The interval in which [`update`](Self::update) should be called.
+    pub fn update_interval(&self) -> Duration {
+        (self.config.lease_duration / 4).min(self.config.takeover_delay / 2)
+    }
+
+    /// Whether we currently hold the lease and are allowed to sign.
+    pub fn is_holder(&self) -> bool {
+        self.is_holder_at(now())
+    }
+
+    fn is_holder_at(&self, now: u64) -> bool {
+        matches!(self.state, LeaseState::Holding { expires_at } if now < expires_at)
+    }
+
+    /// Renews, claims or gives up the lease depending on the current content of the lease file.
+    /// Returns whether we hold the lease afterwards.
+    ///
+    /// If the lease file can't be accessed, the state is left unchanged. A holder thus stops signing
+    /// once its lease expires.
+    pub fn update(&mut self) -> io::Result<bool> {
+        self.update_at(now())
+    }
+
+    fn update_at(&mut self, now: u64) -> io::Result<bool> {
+        let record = self.read()?;
+        let is_ours = record
+            .as_ref()
+            .map_or(false, |record| record.holder == self.holder_id);
+
+        self.state = match self.state {
+            LeaseState::Holding { .. } | LeaseState::Claimed { .. } if !is_ours => {
+                if let Some(record) = &record {
+                    warn!(holder = %record.holder, "Lost the validator lease");
+                }
+                LeaseState::Standby
+            }
+            LeaseState::Holding { expires_at } if now >= expires_at => {
+                // Our lease expired without being renewed, so another node might be about to claim it.
+                // Claim it again like any other node would.
+                warn!("Validator lease expired before it could be renewed");
+                self.claim(now)?
+            }
+            LeaseState::Holding { .. } => LeaseState::Holding {
+                expires_at: self.write(now)?,
+            },
+            LeaseState::Claimed { claimed_at }
+                if now >= claimed_at + self.config.takeover_delay.as_millis() as u64 =>
+            {
+                info!("Acquired the validator lease");
+                LeaseState::Holding {
+                    expires_at: self.write(now)?,
+                }
+            }
+            LeaseState::Claimed { claimed_at } => LeaseState::Claimed { claimed_at },
+            LeaseState::Standby if self.may_claim(record.as_ref(), now) => self.claim(now)?,
+            LeaseState::Standby => LeaseState::Standby,
+        };
+
+        Ok(self.is_holder_at(now))
+    }
+
+    /// Releases the lease such that a standby can take over without waiting for it to expire.
+    pub fn release(&mut self) -> io::Result<()> {
+        if self.state == LeaseState::Standby {
+            return Ok(());
+        }
+        self.state = LeaseState::Standby;
+        if self
+            .read()?
+            .map_or(false, |record| record.holder == self.holder_id)
+        {
+            self.write_record(&LeaseRecord {
+                holder: self.holder_id.clone(),
+                expires_at: 0,
+            })?;
+        }
+        Ok(())
+    }
+
+    fn may_claim(&self, record: Option<&LeaseRecord>, now: u64) -> bool {
+        let takeover_delay = self.config.takeover_delay.as_millis() as u64;
+        match record {
+            // A lease we released ourselves can be claimed right away.
+            Some(record) if record.holder == self.holder_id => now >= record.expires_at,
+            Some(record) => now >= record.expires_at + takeover_delay,
+            None if self.config.standby => {
+                now >= self.started_at + self.config.lease_duration.as_millis() as u64
+            }
+            None => true,
+        }
+    }
+
+    fn claim(&self, now: u64) -> io::Result<LeaseState> {
+        info!(
+            lease_file = %self.config.lease_file.display(),
+            "Claiming the validator lease"
+        );
+        self.write(now)?;
+        Ok(LeaseState::Claimed { claimed_at: now })
+    }
+
+    fn read(&self) -> io::Result<Option<LeaseRecord>> {
+        match fs::read_to_string(&self.config.lease_file) {
+            Ok(content) => LeaseRecord::parse(&content).map(Some),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Writes a lease record for us that is valid for the lease duration and returns its expiry.
+    fn write(&self, now: u64) -> io::Result<u64> {
+        let expires_at = now + self.config.lease_duration.as_millis() as u64;
+        self.write_record(&LeaseRecord {
+            holder: self.holder_id.clone(),
+            expires_at,
+        })?;
+        Ok(expires_at)
+    }
+
+    /// Replaces the lease file atomically, such that other nodes never read a partial record.
+    fn write_record(&self, record: &LeaseRecord) -> io::Result<()> {
+        let temp_file = temp_file_path(&self.config.lease_file, &self.holder_id);
+        fs::write(&temp_file, record.to_file_content())?;
+        fs::rename(&temp_file, &self.config.lease_file)
+    }
+}
+
+/// A handle to a lease that is kept updated by a separate thread, see [`HandoverLease::spawn`].
+pub struct HandoverLeaseHandle {
+    expires_at: Arc<AtomicU64>,
+    update_interval: Duration,
+}
+
+impl HandoverLeaseHandle {
+    /// Whether we currently hold the lease and are allowed to sign. This never blocks, and a lease
+    /// that could not be renewed stops being held once it expires.
+    pub fn is_holder(&self) -> bool {
+        now() < self.expires_at.load(Ordering::Acquire)
+    }
+
+    /// The interval in which the lease is updated.
+    pub fn update_interval(&self) -> Duration {
+        self.update_interval
+    }
+}
+
+fn temp_file_path(lease_file: &Path, holder_id: &str) -> PathBuf {
+    let mut file_name = lease_file.file_name().unwrap_or_default().to_os_string();
+    file_name.push(format!(".{holder_id}.tmp"));
+    lease_file.with_file_name(file_name)
+}
+
+fn now() -> u64 {
+    systemtime_to_timestamp(SystemTime::now())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEASE_DURATION: u64 = 10_000;
+    const TAKEOVER_DELAY: u64 = 2_000;
+
+    fn lease_file(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "nimiq-validator-lease-{}-{name}",
+            std::process::id()
+        ));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    fn lease(lease_file: &Path, holder_id: &str, standby: bool, now: u64) -> HandoverLease {
+        HandoverLease::new_at(config(lease_file, holder_id, standby), now)
+    }
+
+    fn config(lease_file: &Path, holder_id: &str, standby: bool) -> HandoverConfig {
+        HandoverConfig {
+            lease_file: lease_file.to_path_buf(),
+            holder_id: holder_id.to_string(),
+            lease_duration: Duration::from_millis(LEASE_DURATION),
+            takeover_delay: Duration::from_millis(TAKEOVER_DELAY),
+            standby,
+        }
+    }
+
+    #[test]
+    fn primary_acquires_free_lease_after_takeover_delay() {
+        let file = lease_file("primary");
+        let mut primary = lease(&file, "primary", false, 0);
+
+        assert!(!primary.update_at(0).unwrap());
+        assert!(!primary.update_at(TAKEOVER_DELAY - 1).unwrap());
+        assert!(primary.update_at(TAKEOVER_DELAY).unwrap());
+        assert!(primary.is_holder_at(TAKEOVER_DELAY + LEASE_DURATION - 1));
+        assert!(!primary.is_holder_at(TAKEOVER_DELAY + LEASE_DURATION));
+
+        let _ = fs::remove_file(file);
+    }
+
+    #[test]
+    fn standby_only_takes_over_expired_lease() {
+        let file = lease_file("takeover");
+        let mut primary = lease(&file, "primary", false, 0);
+        let mut standby = lease(&file, "standby", true, 0);
+
+        primary.update_at(0).unwrap();
+        assert!(primary.update_at(TAKEOVER_DELAY).unwrap());
+        let mut now = TAKEOVER_DELAY;
+
+        // While the primary renews its lease, the standby never claims it.
+        for _ in 0..10 {
+            now += LEASE_DURATION / 2;
+            assert!(primary.update_at(now).unwrap());
+            assert!(!standby.update_at(now).unwrap());
+        }
+
+        // The primary goes offline. The standby waits for the lease to expire and the takeover delay.
+        let expires_at = now + LEASE_DURATION;
+        assert!(!standby.update_at(expires_at + TAKEOVER_DELAY - 1).unwrap());
+        assert!(!standby.update_at(expires_at + TAKEOVER_DELAY).unwrap());
+        assert!(!primary.is_holder_at(expires_at));
+        assert!(standby.update_at(expires_at + 2 * TAKEOVER_DELAY).unwrap());
+
+        // The primary comes back and notices that it lost the lease.
+        assert!(!primary.update_at(expires_at + 2 * TAKEOVER_DELAY).unwrap());
+        assert!(!primary.update_at(expires_at + 3 * TAKEOVER_DELAY).unwrap());
+        assert!(standby.update_at(expires_at + 3 * TAKEOVER_DELAY).unwrap());
+
+        let _ = fs::remove_file(file);
+    }
+
+    #[test]
+    fn concurrent_claims_yield_a_single_holder() {
+        let file = lease_file("concurrent");
+        let mut first = lease(&file, "first", false, 0);
+        let mut second = lease(&file, "second", false, 0);
+
+        // A claimed lease is respected by other nodes.
+        first.update_at(0).unwrap();
+        assert!(!second.update_at(1).unwrap());
+
+        // Both nodes read the lease before either of them wrote its claim.
+        second.state = second.claim(1).unwrap();
+        assert!(!first.update_at(TAKEOVER_DELAY).unwrap());
+        assert!(second.update_at(TAKEOVER_DELAY + 1).unwrap());
+        assert!(!first.update_at(TAKEOVER_DELAY + 1).unwrap());
+
+        let _ = fs::remove_file(file);
+    }
+
+    #[test]
+    fn standby_gives_starting_primary_precedence() {
+        let file = lease_file("precedence");
+        let mut standby = lease(&file, "standby", true, 0);
+        assert!(!standby.update_at(LEASE_DURATION - 1).unwrap());
+
+        let mut primary = lease(&file, "primary", false, LEASE_DURATION - 1);
+        primary.update_at(LEASE_DURATION - 1).unwrap();
+        assert!(!standby.update_at(LEASE_DURATION).unwrap());
+        assert!(primary.update_at(LEASE_DURATION + TAKEOVER_DELAY).unwrap());
+
+        let _ = fs::remove_file(file);
+    }
+
+    #[test]
+    fn released_lease_is_taken_over() {
+        let file = lease_file("release");
+        let mut primary = lease(&file, "primary", false, 0);
+        let mut standby = lease(&file, "standby", true, 0);
+
+        primary.update_at(0).unwrap();
+        assert!(primary.update_at(TAKEOVER_DELAY).unwrap());
+        primary.release().unwrap();
+        assert!(!primary.is_holder_at(TAKEOVER_DELAY));
+
+        standby.update_at(2 * TAKEOVER_DELAY).unwrap();
+        assert!(standby.update_at(3 * TAKEOVER_DELAY).unwrap());
+
+        let _ = fs::remove_file(file);
+    }
+
+    #[test]
+    fn refuses_to_start_with_the_holder_id_of_the_current_lease() {
+        let file = lease_file("collision");
+        let mut primary = lease(&file, "node", false, now());
+        primary.update_at(now()).unwrap();
+
+        assert!(HandoverLease::new(config(&file, "node", true)).is_err());
+        assert!(HandoverLease::new(config(&file, "other", true)).is_ok());
+        assert!(HandoverLease::new(config(&file, "with space", true)).is_err());
+
+        let _ = fs::remove_file(file);
+    }
+}
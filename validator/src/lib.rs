@@ -2,6 +2,7 @@
 extern crate log;
 
 pub mod aggregation;
+pub mod handover;
 mod jail;
 mod r#macro;
 mod micro;
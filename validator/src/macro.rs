@@ -9,14 +9,16 @@ use futures::{
     future,
     stream::{BoxStream, Stream, StreamExt},
 };
-use nimiq_block::MacroBlock;
+use nimiq_block::{EquivocationProof, MacroBlock};
 use nimiq_blockchain::{BlockProducer, Blockchain};
+use nimiq_handel::config::Config as HandelConfig;
 use nimiq_keys::Ed25519Signature as SchnorrSignature;
 use nimiq_network_interface::network::Topic;
 use nimiq_primitives::{networks::NetworkId, slots_allocation::Validators};
 use nimiq_tendermint::{Return as TendermintReturn, SignedProposalMessage, Tendermint};
 use nimiq_validator_network::{PubsubId, ValidatorNetwork};
 use parking_lot::RwLock;
+use tokio::sync::mpsc;
 
 use crate::{
     aggregation::tendermint::{
@@ -84,6 +86,8 @@ where
             'static,
             SignedProposalMessage<Header<PubsubId<TValidatorNetwork>>, (SchnorrSignature, u16)>,
         >,
+        handel_config: HandelConfig,
+        equivocation_tx: mpsc::UnboundedSender<EquivocationProof>,
     ) -> Self {
         let input = network
             .receive::<TendermintUpdate>()
@@ -108,6 +112,8 @@ where
             validator_slot_band,
             network_id,
             block_height,
+            handel_config,
+            equivocation_tx,
         );
 
         // create the Tendermint instance, which implements Stream
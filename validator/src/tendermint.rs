@@ -4,13 +4,14 @@ use futures::{
     future::{self, BoxFuture, FutureExt},
     stream::{BoxStream, StreamExt},
 };
-use nimiq_block::{Block, MacroBlock, TendermintProof};
+use nimiq_block::{Block, EquivocationProof, MacroBlock, TendermintProof};
 use nimiq_blockchain::{BlockProducer, Blockchain};
 use nimiq_blockchain_interface::AbstractBlockchain;
 use nimiq_collections::BitSet;
+use nimiq_consensus::watchtower::DoubleVoteDetector;
 use nimiq_handel::{
-    aggregation::Aggregation, identity::IdentityRegistry, protocol::Protocol as _,
-    verifier::VerificationResult,
+    aggregation::Aggregation, config::Config as HandelConfig, identity::IdentityRegistry,
+    protocol::Protocol as _, verifier::VerificationResult,
 };
 use nimiq_hash::{Blake2sHash, Hash};
 use nimiq_keys::Ed25519Signature as SchnorrSignature;
@@ -27,6 +28,7 @@ use nimiq_validator_network::{
     single_response_requester::SingleResponseRequester, PubsubId, ValidatorNetwork,
 };
 use parking_lot::RwLock;
+use tokio::sync::mpsc;
 
 use crate::{
     aggregation::{
@@ -36,6 +38,7 @@ use crate::{
             proposal::{Body, Header, RequestProposal, SignedProposal},
             protocol::TendermintAggregationProtocol,
             update_message::TendermintUpdate,
+            verifier::DoubleVoteReporter,
         },
     },
     r#macro::ProposalTopic,
@@ -115,6 +118,10 @@ pub struct TendermintProtocol<TValidatorNetwork: ValidatorNetwork> {
     pub blockchain: Arc<RwLock<Blockchain>>,
     // Validator registry on the heap for easy cloning into handel protocol.
     validator_registry: Arc<ValidatorRegistry>,
+    // The configuration used for the handel aggregations of this protocol.
+    handel_config: HandelConfig,
+    // The channel to which the proofs of the double votes we detect are sent.
+    equivocation_tx: mpsc::UnboundedSender<EquivocationProof>,
 }
 
 impl<TValidatorNetwork: ValidatorNetwork> Clone for TendermintProtocol<TValidatorNetwork> {
@@ -128,6 +135,8 @@ impl<TValidatorNetwork: ValidatorNetwork> Clone for TendermintProtocol<TValidato
             current_validators: self.current_validators.clone(),
             blockchain: Arc::clone(&self.blockchain),
             validator_registry: Arc::clone(&self.validator_registry),
+            handel_config: self.handel_config.clone(),
+            equivocation_tx: self.equivocation_tx.clone(),
         }
     }
 }
@@ -144,6 +153,8 @@ where
         validator_slot_band: u16,
         network_id: NetworkId,
         block_height: u32,
+        handel_config: HandelConfig,
+        equivocation_tx: mpsc::UnboundedSender<EquivocationProof>,
     ) -> Self {
         Self {
             block_producer,
@@ -154,8 +165,17 @@ where
             validator_registry: Arc::new(ValidatorRegistry::new(current_validators.clone())),
             current_validators,
             network,
+            handel_config,
+            equivocation_tx,
         }
     }
+
+    fn double_vote_reporter(&self, id: TendermintIdentifier) -> DoubleVoteReporter {
+        DoubleVoteReporter::new(
+            DoubleVoteDetector::new(id, self.current_validators.clone()),
+            self.equivocation_tx.clone(),
+        )
+    }
 }
 
 impl<TValidatorNetwork: ValidatorNetwork + 'static> Protocol
@@ -400,12 +420,13 @@ where
             Arc::clone(&self.validator_registry),
             self.validator_slot_band as usize,
             1, // to be removed
-            id,
+            id.clone(),
+            self.double_vote_reporter(id),
         );
 
         Aggregation::new(
             protocol,
-            nimiq_handel::config::Config::default(),
+            self.handel_config.clone(),
             own_contribution,
             update_stream.map(|item| item.0).boxed(),
             network,
@@ -436,7 +457,8 @@ where
             Arc::clone(&self.validator_registry),
             self.validator_slot_band as usize,
             1,
-            id,
+            id.clone(),
+            self.double_vote_reporter(id),
         );
 
         async move {
@@ -15,11 +15,17 @@ use nimiq_block::{Block, BlockHeaderTopic, BlockTopic, BlockType, EquivocationPr
 use nimiq_blockchain::{BlockProducer, Blockchain};
 use nimiq_blockchain_interface::{AbstractBlockchain, BlockchainEvent, ForkEvent, PushResult};
 use nimiq_bls::{lazy::LazyPublicKey, KeyPair as BlsKeyPair};
-use nimiq_consensus::{Consensus, ConsensusEvent, ConsensusProxy};
+use nimiq_consensus::{
+    watchtower::{ForkProofWatchtowerProxy, SeenEquivocation},
+    Consensus, ConsensusEvent, ConsensusProxy,
+};
 use nimiq_database::{
     traits::{Database, ReadTransaction, WriteTransaction},
     DatabaseProxy, TableProxy,
 };
+use nimiq_handel::config::Config as HandelConfig;
+#[cfg(feature = "metrics")]
+use nimiq_handel::metrics::HandelMetrics;
 use nimiq_hash::{Blake2bHash, Hash};
 use nimiq_keys::{Address, KeyPair as SchnorrKeyPair};
 use nimiq_mempool::{config::MempoolConfig, mempool::Mempool};
@@ -31,12 +37,17 @@ use nimiq_primitives::{coin::Coin, policy::Policy};
 use nimiq_transaction_builder::TransactionBuilder;
 use nimiq_validator_network::{PubsubId, ValidatorNetwork};
 use parking_lot::RwLock;
+use tokio::{
+    sync::mpsc,
+    time::{interval, Interval, MissedTickBehavior},
+};
 #[cfg(feature = "metrics")]
 use tokio_metrics::TaskMonitor;
 use tokio_stream::wrappers::BroadcastStream;
 
 use crate::{
     aggregation::tendermint::{proposal::RequestProposal, state::MacroState},
+    handover::HandoverLeaseHandle,
     jail::EquivocationProofPool,
     micro::{ProduceMicroBlock, ProduceMicroBlockEvent},
     proposal_buffer::{ProposalBuffer, ProposalReceiver},
@@ -69,6 +80,7 @@ pub struct ValidatorProxy {
     pub voting_key: Arc<RwLock<BlsKeyPair>>,
     pub fee_key: Arc<RwLock<SchnorrKeyPair>>,
     pub automatic_reactivate: Arc<AtomicBool>,
+    pub signal_data: Arc<RwLock<Option<Blake2bHash>>>,
     pub slot_band: Arc<RwLock<Option<u16>>>,
     pub consensus_state: Arc<RwLock<ConsensusState>>,
 }
@@ -81,6 +93,7 @@ impl Clone for ValidatorProxy {
             voting_key: Arc::clone(&self.voting_key),
             fee_key: Arc::clone(&self.fee_key),
             automatic_reactivate: Arc::clone(&self.automatic_reactivate),
+            signal_data: Arc::clone(&self.signal_data),
             slot_band: Arc::clone(&self.slot_band),
             consensus_state: Arc::clone(&self.consensus_state),
         }
@@ -109,17 +122,29 @@ where
     blockchain_event_rx: BoxStream<'static, BlockchainEvent>,
     network_event_rx: SubscribeEvents<<TValidatorNetwork::NetworkType as Network>::PeerId>,
     fork_event_rx: BroadcastStream<ForkEvent>,
+    equivocation_rx: Option<BroadcastStream<SeenEquivocation>>,
+    watchtower: Option<ForkProofWatchtowerProxy>,
+    double_vote_tx: mpsc::UnboundedSender<EquivocationProof>,
+    double_vote_rx: mpsc::UnboundedReceiver<EquivocationProof>,
 
     slot_band: Arc<RwLock<Option<u16>>>,
     consensus_state: Arc<RwLock<ConsensusState>>,
     validator_state: Option<InactivityState>,
     automatic_reactivate: Arc<AtomicBool>,
+    signal_data: Arc<RwLock<Option<Blake2bHash>>>,
 
     macro_producer: Option<ProduceMacroBlock<TValidatorNetwork>>,
     macro_state: Arc<RwLock<Option<MacroState>>>,
 
     micro_producer: Option<ProduceMicroBlock<TValidatorNetwork>>,
 
+    handel_config: HandelConfig,
+
+    handover_lease: Option<HandoverLeaseHandle>,
+    handover_interval: Option<Interval>,
+    lease_held: bool,
+    dht_ready: bool,
+
     pub mempool: Arc<Mempool>,
     mempool_active: bool,
     #[cfg(feature = "metrics")]
@@ -145,10 +170,12 @@ where
         network: Arc<TValidatorNetwork>,
         validator_address: Address,
         automatic_reactivate: bool,
+        signal_data: Option<Blake2bHash>,
         signing_key: SchnorrKeyPair,
         voting_key: BlsKeyPair,
         fee_key: SchnorrKeyPair,
         mempool_config: MempoolConfig,
+        handel_config: HandelConfig,
     ) -> Self {
         let consensus_event_rx = consensus.subscribe_events();
 
@@ -158,6 +185,7 @@ where
         drop(blockchain_rg);
 
         let network_event_rx = network.subscribe_events();
+        let (double_vote_tx, double_vote_rx) = mpsc::unbounded_channel();
 
         let blockchain_state = ConsensusState {
             equivocation_proofs: EquivocationProofPool::new(),
@@ -184,6 +212,13 @@ where
 
         let automatic_reactivate = Arc::new(AtomicBool::new(automatic_reactivate));
 
+        // Always collect handel metrics if they are compiled in, such that they can be served.
+        #[cfg(feature = "metrics")]
+        let handel_config = HandelConfig {
+            metrics: Some(handel_config.metrics.unwrap_or_default()),
+            ..handel_config
+        };
+
         Self::init_network_request_receivers(&consensus.network, &macro_state);
 
         let network1 = Arc::clone(&network);
@@ -215,17 +250,29 @@ where
             blockchain_event_rx,
             network_event_rx,
             fork_event_rx,
+            equivocation_rx: None,
+            watchtower: None,
+            double_vote_tx,
+            double_vote_rx,
 
             slot_band: Arc::new(RwLock::new(None)),
             consensus_state: Arc::new(RwLock::new(blockchain_state)),
             validator_state: None,
             automatic_reactivate,
+            signal_data: Arc::new(RwLock::new(signal_data)),
 
             macro_producer: None,
             macro_state: Arc::clone(&macro_state),
 
             micro_producer: None,
 
+            handel_config,
+
+            handover_lease: None,
+            handover_interval: None,
+            lease_held: false,
+            dht_ready: false,
+
             mempool: Arc::clone(&mempool),
             mempool_active,
             #[cfg(feature = "metrics")]
@@ -279,6 +326,8 @@ where
             }
         }
 
+        self.check_signal_data(&blockchain);
+
         let validators = blockchain.current_validators().unwrap();
 
         *self.slot_band.write() = validators.get_slot_band_by_address(&self.validator_address());
@@ -317,7 +366,7 @@ where
         self.macro_producer = None;
         self.micro_producer = None;
 
-        if !self.is_elected() || !self.is_synced() {
+        if !self.is_elected() || !self.is_synced() || !self.is_lease_holder() {
             return;
         }
 
@@ -357,6 +406,8 @@ where
                     next_block_number,
                     self.macro_state.read().clone(),
                     proposal_stream,
+                    self.handel_config.clone(),
+                    self.double_vote_tx.clone(),
                 ));
             }
             BlockType::Micro => {
@@ -380,6 +431,7 @@ where
                     next_block_number,
                     Self::PRODUCER_TIMEOUT,
                     Self::BLOCK_SEPARATION_TIME,
+                    self.handel_config.clone(),
                 ));
             }
         }
@@ -419,6 +471,13 @@ where
         self.mempool_active = true;
     }
 
+    /// Puts the validator into standby. It stays synced and keeps its mempool, but stops producing
+    /// blocks until it holds the handover lease again.
+    fn stand_by(&mut self) {
+        self.macro_producer = None;
+        self.micro_producer = None;
+    }
+
     fn pause(&mut self) {
         *self.slot_band.write() = None;
         self.macro_producer = None;
@@ -547,6 +606,56 @@ where
         }
     }
 
+    /// Feeds the equivocation proofs observed by the watchtower into our equivocation proof pool,
+    /// such that we include them in the micro blocks we produce. In turn, the double votes we
+    /// detect are reported to the watchtower, which gossips them to the network.
+    pub fn subscribe_equivocations(&mut self, watchtower: &ForkProofWatchtowerProxy) {
+        self.equivocation_rx = Some(watchtower.subscribe());
+        self.watchtower = Some(watchtower.clone());
+    }
+
+    /// Runs the validator as part of a primary/hot-standby setup. The validator only signs while it
+    /// holds the given lease and stays in standby otherwise.
+    ///
+    /// After acquiring the lease, the validator only starts producing with the block following the
+    /// one that is currently being produced, as it does not know what the previous holder signed for it.
+    /// The lease must have been spawned, such that accessing the lease file never blocks the validator.
+    pub fn enable_handover(&mut self, lease: HandoverLeaseHandle) {
+        let mut handover_interval = interval(lease.update_interval());
+        handover_interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        self.stand_by();
+        self.lease_held = false;
+        self.handover_lease = Some(lease);
+        self.handover_interval = Some(handover_interval);
+    }
+
+    /// Checks whether we are allowed to sign, which is always the case if handover is not enabled.
+    fn is_lease_holder(&self) -> bool {
+        self.handover_lease
+            .as_ref()
+            .map_or(true, |lease| lease.is_holder())
+    }
+
+    fn update_handover_lease(&mut self) {
+        let lease_held = self.is_lease_holder();
+        if lease_held == self.lease_held {
+            return;
+        }
+        self.lease_held = lease_held;
+
+        if lease_held {
+            info!("Validator lease acquired, leaving standby");
+            // Point the DHT record of our validator to this node.
+            if self.dht_ready {
+                self.publish_dht();
+            }
+        } else {
+            info!("Validator lease not held, entering standby");
+            self.stand_by();
+        }
+    }
+
     fn on_equivocation_proof(&mut self, proof: EquivocationProof) {
         // Keep the lock until the proof is added to the proof pool.
         let blockchain = self.blockchain.read();
@@ -759,6 +868,35 @@ where
             )
     }
 
+    /// Checks whether the signal data of our validator in the staking contract matches the one
+    /// configured by the operator. Updating the signal data requires the validator's cold key,
+    /// so we can only remind the operator to send the update transaction.
+    fn check_signal_data(&self, blockchain: &Blockchain) {
+        let signal_data = self.signal_data.read().clone();
+        if signal_data.is_none() {
+            return;
+        }
+
+        let staking_contract = match blockchain.get_staking_contract_if_complete(None) {
+            Some(contract) => contract,
+            None => return,
+        };
+        let data_store = blockchain.get_staking_contract_store();
+        let txn = blockchain.read_transaction();
+        let on_chain_signal_data = staking_contract
+            .get_validator(&data_store.read(&txn), &self.validator_address())
+            .and_then(|validator| validator.signal_data);
+
+        if on_chain_signal_data != signal_data {
+            warn!(
+                validator_address = %self.validator_address(),
+                configured = ?signal_data,
+                on_chain = ?on_chain_signal_data,
+                "Validator signal data differs from the configured one, send an update validator transaction to signal it"
+            );
+        }
+    }
+
     fn reactivate(&self, blockchain: &Blockchain) -> InactivityState {
         let validity_start_height = blockchain.block_number();
 
@@ -818,6 +956,7 @@ where
             voting_key: Arc::clone(&self.voting_key),
             fee_key: Arc::clone(&self.fee_key),
             automatic_reactivate: Arc::clone(&self.automatic_reactivate),
+            signal_data: Arc::clone(&self.signal_data),
             slot_band: Arc::clone(&self.slot_band),
             consensus_state: Arc::clone(&self.consensus_state),
         }
@@ -832,6 +971,11 @@ where
     pub fn get_control_mempool_monitor(&self) -> TaskMonitor {
         self.control_mempool_monitor.clone()
     }
+
+    #[cfg(feature = "metrics")]
+    pub fn get_handel_metrics(&self) -> Option<Arc<HandelMetrics>> {
+        self.handel_config.metrics.clone()
+    }
 }
 
 impl<TValidatorNetwork: ValidatorNetwork> Future for Validator<TValidatorNetwork>
@@ -841,6 +985,15 @@ where
     type Output = ();
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // Check for a change of the handover lease before doing anything that requires signing.
+        while let Some(Poll::Ready(_)) = self
+            .handover_interval
+            .as_mut()
+            .map(|handover_interval| handover_interval.poll_tick(cx))
+        {
+            self.update_handover_lease();
+        }
+
         // Process consensus updates.
         while let Poll::Ready(Some(event)) = self.consensus_event_rx.poll_next_unpin(cx) {
             match event {
@@ -865,8 +1018,32 @@ where
             }
         }
 
+        // Process equivocations relayed by the watchtower.
+        while let Some(Poll::Ready(Some(Ok(equivocation)))) = self
+            .equivocation_rx
+            .as_mut()
+            .map(|equivocation_rx| equivocation_rx.poll_next_unpin(cx))
+        {
+            if self.consensus.is_established() {
+                self.on_equivocation_proof(equivocation.proof);
+            }
+        }
+
+        // Process the double votes detected during our Tendermint aggregations.
+        while let Poll::Ready(Some(proof)) = self.double_vote_rx.poll_recv(cx) {
+            if let Some(watchtower) = &self.watchtower {
+                watchtower.report(proof.clone());
+            }
+            self.on_equivocation_proof(proof);
+        }
+
+        // A lease might expire in between its updates, so stop producing right away.
+        if !self.is_lease_holder() {
+            self.stand_by();
+        }
+
         // If we are an active validator, participate in block production.
-        if self.is_synced() && self.is_elected() {
+        if self.is_synced() && self.is_elected() && self.is_lease_holder() {
             if self.macro_producer.is_some() {
                 self.poll_macro(cx);
             }
@@ -876,7 +1053,8 @@ where
         }
 
         // Once the validator can be active is established, check the validator staking state.
-        if self.is_synced() {
+        // A standby leaves reactivating the validator to the lease holder.
+        if self.is_synced() && self.is_lease_holder() {
             let blockchain = self.blockchain.read();
             match self.get_staking_state(&blockchain) {
                 ValidatorStakingState::Active => {
@@ -908,7 +1086,11 @@ where
         while let Poll::Ready(Some(result)) = self.network_event_rx.poll_next_unpin(cx) {
             match result {
                 Ok(NetworkEvent::DhtReady) => {
-                    self.publish_dht();
+                    self.dht_ready = true;
+                    // A standby must not redirect the validator's traffic to itself.
+                    if self.is_lease_holder() {
+                        self.publish_dht();
+                    }
                 }
                 Ok(_) => {}
                 Err(e) => error!("{}", e),
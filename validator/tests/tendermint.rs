@@ -9,6 +9,7 @@ use nimiq_test_log::test;
 use nimiq_test_utils::{block_production::TemporaryBlockProducer, test_network::TestNetwork};
 use nimiq_validator::{aggregation::tendermint::proposal::Header, tendermint::TendermintProtocol};
 use nimiq_validator_network::network_impl::ValidatorNetworkImpl;
+use tokio::sync::mpsc;
 
 #[test(tokio::test)]
 async fn it_verifies_inferior_chain_proposals() {
@@ -125,6 +126,8 @@ async fn it_verifies_inferior_chain_proposals() {
         0,
         NetworkId::UnitAlbatross,
         blockchain2.read().head().block_number() + 1,
+        Default::default(),
+        mpsc::unbounded_channel().0,
     );
 
     // Make sure the main chain proposal is acceptable.
@@ -0,0 +1,180 @@
+use nimiq_hash::{argon2kdf::Argon2Error, Blake2bHash, Blake2bHasher, Hasher};
+use nimiq_keys::Address;
+use nimiq_serde::{Deserialize, DeserializeError, Serialize};
+use nimiq_utils::otp::{Locked, Unlocked, Verify};
+use thiserror::Error;
+
+use crate::{
+    hd_wallet::{DerivedAccount, HdWallet},
+    wallet_account::WalletAccount,
+    watch_only::WatchOnlyAddress,
+};
+
+/// The version of the backup format written by this implementation.
+pub const WALLET_BACKUP_VERSION: u8 = 2;
+
+/// The wallet data contained in a backup. The accounts and HD wallets stay locked with their
+/// own passphrases, the backup password only protects the backup as a whole.
+#[derive(Default, Serialize, Deserialize)]
+pub struct WalletBackupContents {
+    pub accounts: Vec<(Address, Locked<WalletAccount>)>,
+    pub hd_wallets: Vec<(Address, Locked<HdWallet>)>,
+    pub derived_accounts: Vec<(Address, DerivedAccount)>,
+    pub watch_only: Vec<(Address, WatchOnlyAddress)>,
+    /// The hash of the entries above, used to check the integrity of the backup.
+    checksum: Blake2bHash,
+}
+
+impl WalletBackupContents {
+    pub fn new(
+        accounts: Vec<(Address, Locked<WalletAccount>)>,
+        hd_wallets: Vec<(Address, Locked<HdWallet>)>,
+        derived_accounts: Vec<(Address, DerivedAccount)>,
+        watch_only: Vec<(Address, WatchOnlyAddress)>,
+    ) -> Self {
+        let mut contents = WalletBackupContents {
+            accounts,
+            hd_wallets,
+            derived_accounts,
+            watch_only,
+            checksum: Blake2bHash::default(),
+        };
+        contents.checksum = contents.compute_checksum();
+        contents
+    }
+
+    /// Returns the addresses of all accounts in the backup.
+    pub fn addresses(&self) -> Vec<Address> {
+        self.accounts
+            .iter()
+            .map(|(address, _)| address.clone())
+            .collect()
+    }
+
+    fn compute_checksum(&self) -> Blake2bHash {
+        let entries = (
+            &self.accounts,
+            &self.hd_wallets,
+            &self.derived_accounts,
+            &self.watch_only,
+        );
+        Blake2bHasher::default().digest(&entries.serialize_to_vec())
+    }
+}
+
+impl Verify for WalletBackupContents {
+    fn verify(&self) -> bool {
+        self.compute_checksum() == self.checksum
+    }
+}
+
+/// The wallet data contained in backups of version 1, which did not include watch-only addresses.
+#[derive(Default, Serialize, Deserialize)]
+struct WalletBackupContentsV1 {
+    accounts: Vec<(Address, Locked<WalletAccount>)>,
+    hd_wallets: Vec<(Address, Locked<HdWallet>)>,
+    derived_accounts: Vec<(Address, DerivedAccount)>,
+    checksum: Blake2bHash,
+}
+
+impl Verify for WalletBackupContentsV1 {
+    fn verify(&self) -> bool {
+        let entries = (&self.accounts, &self.hd_wallets, &self.derived_accounts);
+        Blake2bHasher::default().digest(&entries.serialize_to_vec()) == self.checksum
+    }
+}
+
+impl From<WalletBackupContentsV1> for WalletBackupContents {
+    fn from(contents: WalletBackupContentsV1) -> Self {
+        WalletBackupContents::new(
+            contents.accounts,
+            contents.hd_wallets,
+            contents.derived_accounts,
+            vec![],
+        )
+    }
+}
+
+/// The encrypted contents of a backup, in the format of the backup's version.
+enum VersionedContents {
+    V1(Locked<WalletBackupContentsV1>),
+    V2(Locked<WalletBackupContents>),
+}
+
+/// A versioned, password encrypted backup of the wallet.
+pub struct WalletBackup {
+    contents: VersionedContents,
+}
+
+impl WalletBackup {
+    /// Encrypts the given contents with the password.
+    /// Calling code should make sure to clear the password from memory after use.
+    pub fn new(contents: WalletBackupContents, password: &[u8]) -> Result<Self, WalletBackupError> {
+        Ok(WalletBackup {
+            contents: VersionedContents::V2(Locked::with_defaults(contents, password)?),
+        })
+    }
+
+    /// Returns the version of the backup format.
+    pub fn version(&self) -> u8 {
+        match self.contents {
+            VersionedContents::V1(_) => 1,
+            VersionedContents::V2(_) => WALLET_BACKUP_VERSION,
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match &self.contents {
+            VersionedContents::V1(contents) => (self.version(), contents).serialize_to_vec(),
+            VersionedContents::V2(contents) => (self.version(), contents).serialize_to_vec(),
+        }
+    }
+
+    /// Parses a backup, rejecting backups of unsupported versions.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, WalletBackupError> {
+        let contents = match bytes.first() {
+            Some(1) => {
+                let (_, contents): (u8, _) = Deserialize::deserialize_from_vec(bytes)?;
+                VersionedContents::V1(contents)
+            }
+            Some(&WALLET_BACKUP_VERSION) => {
+                let (_, contents): (u8, _) = Deserialize::deserialize_from_vec(bytes)?;
+                VersionedContents::V2(contents)
+            }
+            Some(&version) => return Err(WalletBackupError::UnsupportedVersion(version)),
+            None => return Err(WalletBackupError::Corrupted),
+        };
+        Ok(WalletBackup { contents })
+    }
+
+    /// Decrypts the backup and checks its integrity. Backups of older versions are converted to
+    /// the current contents, e.g. version 1 backups contain no watch-only addresses.
+    /// Calling code should make sure to clear the password from memory after use.
+    pub fn open(self, password: &[u8]) -> Result<WalletBackupContents, WalletBackupError> {
+        match self.contents {
+            VersionedContents::V1(contents) => contents
+                .unlock(password)
+                .map(|contents| Unlocked::into_unlocked_data(contents).into())
+                .map_err(|_| WalletBackupError::WrongPassword),
+            VersionedContents::V2(contents) => contents
+                .unlock(password)
+                .map(Unlocked::into_unlocked_data)
+                .map_err(|_| WalletBackupError::WrongPassword),
+        }
+    }
+}
+
+/// Possible wallet backup errors.
+#[derive(Debug, Error)]
+pub enum WalletBackupError {
+    #[error("Unsupported backup version: {0}")]
+    UnsupportedVersion(u8),
+    #[error("Wrong password or corrupted backup")]
+    WrongPassword,
+    #[error("Corrupted backup")]
+    Corrupted,
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] DeserializeError),
+    #[error("{0}")]
+    Argon2(#[from] Argon2Error),
+}
@@ -0,0 +1,208 @@
+use std::{fmt, io};
+
+use nimiq_database_value::{FromDatabaseValue, IntoDatabaseValue};
+use nimiq_key_derivation::ExtendedPrivateKey;
+use nimiq_keys::{Address, Ed25519PublicKey, KeyPair};
+use nimiq_mnemonic::{Entropy, Mnemonic, MnemonicType, WORDLIST_EN};
+use nimiq_serde::{Deserialize, Serialize};
+use nimiq_utils::otp::Verify;
+use rand::{rngs::OsRng, RngCore};
+use thiserror::Error;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::wallet_account::WalletAccount;
+
+/// The SLIP-10 path prefix of Nimiq accounts. The account index is appended as the last,
+/// hardened segment.
+pub const NIMIQ_DERIVATION_PATH_PREFIX: &str = "m/44'/242'/0'";
+
+/// A hierarchical deterministic wallet that derives accounts from the seed of a mnemonic by
+/// SLIP-10 path.
+///
+/// The seed is stored inline, such that it is cleared along with a locked wallet, and it is
+/// zeroed whenever a wallet is dropped.
+#[derive(Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct HdWallet {
+    /// The BIP39 seed of the mnemonic.
+    #[serde(with = "seed_serde")]
+    pub seed: [u8; HdWallet::SEED_SIZE],
+    /// The identifier of the wallet, which is the address of its master key.
+    pub id: Address,
+}
+
+impl Default for HdWallet {
+    fn default() -> Self {
+        HdWallet {
+            seed: [0; HdWallet::SEED_SIZE],
+            id: Address::default(),
+        }
+    }
+}
+
+impl fmt::Debug for HdWallet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HdWallet")
+            .field("id", &self.id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for HdWallet {
+    fn drop(&mut self) {
+        self.seed.zeroize();
+    }
+}
+
+/// Serializes the seed like a `Vec<u8>`, as which it was stored before.
+mod seed_serde {
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+    use zeroize::Zeroizing;
+
+    use super::HdWallet;
+
+    pub fn serialize<S: Serializer>(
+        seed: &[u8; HdWallet::SEED_SIZE],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        seed.as_slice().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<[u8; HdWallet::SEED_SIZE], D::Error> {
+        let bytes = Zeroizing::new(Vec::<u8>::deserialize(deserializer)?);
+        let mut seed = [0; HdWallet::SEED_SIZE];
+        if bytes.len() != seed.len() {
+            return Err(D::Error::invalid_length(bytes.len(), &"a 64 byte seed"));
+        }
+        seed.copy_from_slice(&bytes);
+        Ok(seed)
+    }
+}
+
+impl Verify for HdWallet {
+    fn verify(&self) -> bool {
+        // Check that the identifier corresponds to the seed.
+        self.master_key().to_address() == self.id
+    }
+}
+
+impl HdWallet {
+    /// The size of a BIP39 seed.
+    pub const SEED_SIZE: usize = 64;
+
+    /// Generates a new wallet from a random 24 word mnemonic, which is returned along with it.
+    pub fn generate() -> (Self, Mnemonic) {
+        let mut entropy = [0u8; Entropy::SIZE];
+        OsRng.fill_bytes(&mut entropy);
+        let mnemonic = Entropy::from(entropy).to_mnemonic(WORDLIST_EN);
+        entropy.zeroize();
+
+        // A freshly generated mnemonic is always valid.
+        let wallet = HdWallet::from_mnemonic(&mnemonic).unwrap();
+        (wallet, mnemonic)
+    }
+
+    /// Creates the wallet of a BIP39 mnemonic using the English wordlist.
+    pub fn from_mnemonic(mnemonic: &Mnemonic) -> Result<Self, HdWalletError> {
+        match mnemonic.get_type(WORDLIST_EN) {
+            MnemonicType::BIP39 | MnemonicType::UNKNOWN => {}
+            MnemonicType::LEGACY | MnemonicType::INVALID => {
+                return Err(HdWalletError::InvalidMnemonic)
+            }
+        }
+
+        let seed = Zeroizing::new(
+            mnemonic
+                .to_seed(None)
+                .map_err(|_| HdWalletError::InvalidMnemonic)?,
+        );
+        if seed.len() != HdWallet::SEED_SIZE {
+            return Err(HdWalletError::InvalidMnemonic);
+        }
+
+        let mut wallet = HdWallet::default();
+        wallet.seed.copy_from_slice(&seed);
+        wallet.id = wallet.master_key().to_address();
+        Ok(wallet)
+    }
+
+    /// Returns the derivation path of the account with the given index.
+    pub fn path_for_index(index: u32) -> String {
+        format!("{}/{}'", NIMIQ_DERIVATION_PATH_PREFIX, index)
+    }
+
+    /// Derives the account at the given path.
+    pub fn derive_account(&self, path: &str) -> Result<WalletAccount, HdWalletError> {
+        let key = self
+            .master_key()
+            .derive_path(path)
+            .ok_or_else(|| HdWalletError::InvalidPath(path.to_string()))?;
+        Ok(WalletAccount::from(KeyPair::from(key.into_private_key())))
+    }
+
+    fn master_key(&self) -> ExtendedPrivateKey {
+        ExtendedPrivateKey::from_seed_bytes(&self.seed)
+    }
+}
+
+impl IntoDatabaseValue for HdWallet {
+    fn database_byte_size(&self) -> usize {
+        self.serialized_size()
+    }
+
+    fn copy_into_database(&self, mut bytes: &mut [u8]) {
+        Serialize::serialize_to_writer(&self, &mut bytes).unwrap();
+    }
+}
+
+impl FromDatabaseValue for HdWallet {
+    fn copy_from_database(bytes: &[u8]) -> io::Result<Self>
+    where
+        Self: Sized,
+    {
+        Deserialize::deserialize_from_vec(bytes)
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+    }
+}
+
+/// An account that was derived from an HD wallet. Only the public information is stored; the
+/// account's key is stored as a locked `WalletAccount`.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct DerivedAccount {
+    /// The identifier of the HD wallet the account was derived from.
+    pub wallet_id: Address,
+    /// The derivation path of the account.
+    pub path: String,
+    /// The public key of the account.
+    pub public_key: Ed25519PublicKey,
+}
+
+impl IntoDatabaseValue for DerivedAccount {
+    fn database_byte_size(&self) -> usize {
+        self.serialized_size()
+    }
+
+    fn copy_into_database(&self, mut bytes: &mut [u8]) {
+        Serialize::serialize_to_writer(&self, &mut bytes).unwrap();
+    }
+}
+
+impl FromDatabaseValue for DerivedAccount {
+    fn copy_from_database(bytes: &[u8]) -> io::Result<Self>
+    where
+        Self: Sized,
+    {
+        Deserialize::deserialize_from_vec(bytes)
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+    }
+}
+
+/// Possible HD wallet errors.
+#[derive(Debug, Error)]
+pub enum HdWalletError {
+    #[error("Invalid mnemonic")]
+    InvalidMnemonic,
+    #[error("Invalid derivation path: {0}")]
+    InvalidPath(String),
+}
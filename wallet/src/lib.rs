@@ -1,9 +1,15 @@
+pub use backup::{WalletBackup, WalletBackupContents, WalletBackupError, WALLET_BACKUP_VERSION};
+pub use hd_wallet::{DerivedAccount, HdWallet, HdWalletError};
 pub use multisig_account::MultiSigAccount;
 pub use wallet_account::WalletAccount;
 #[cfg(feature = "store")]
 pub use wallet_store::WalletStore;
+pub use watch_only::WatchOnlyAddress;
 
+mod backup;
+mod hd_wallet;
 mod multisig_account;
 mod wallet_account;
 #[cfg(feature = "store")]
 mod wallet_store;
+mod watch_only;
@@ -1,15 +1,13 @@
 use std::io;
 
 use nimiq_database_value::{FromDatabaseValue, IntoDatabaseValue};
-use nimiq_hash::{Hash, HashOutput, Sha256Hash};
+use nimiq_hash::HashOutput;
 use nimiq_keys::{Address, Ed25519PublicKey, Ed25519Signature, KeyPair, SecureGenerate};
 use nimiq_primitives::{coin::Coin, networks::NetworkId};
 use nimiq_serde::{Deserialize, Serialize};
-use nimiq_transaction::{SignatureProof, Transaction};
+use nimiq_transaction::{signed_message::SignedMessage, SignatureProof, Transaction};
 use nimiq_utils::otp::Verify;
 
-pub const NIMIQ_SIGN_MESSAGE_PREFIX: &[u8] = b"\x16Nimiq Signed Message:\n";
-
 #[derive(Default, Debug, Clone, Serialize, Eq, PartialEq)]
 pub struct WalletAccount {
     pub key_pair: KeyPair,
@@ -61,29 +59,8 @@ impl WalletAccount {
         SignatureProof::from_ed25519(self.key_pair.public, signature)
     }
 
-    fn prepare_message_for_signature(message: &[u8]) -> Sha256Hash {
-        /*
-         * Adding a prefix to the message makes the calculated signature recognisable as
-         * a Nimiq specific signature. This and the hashing prevents misuse where a malicious
-         * request can sign arbitrary data (e.g. a transaction) and use the signature to
-         * impersonate the victim.
-         *
-         * See also
-         * https://github.com/ethereum/EIPs/blob/af249ed715879ca2d77c6b43ed331c9c0ab8f6cb/EIPS/eip-191.md#specification.
-         */
-        let mut buffer = NIMIQ_SIGN_MESSAGE_PREFIX.to_vec();
-        // Append length of message as encoded string.
-        let mut encoded_len = message.len().to_string().into_bytes();
-        buffer.append(&mut encoded_len);
-        // Append actual message.
-        buffer.extend_from_slice(message);
-
-        // Hash and sign.
-        buffer.hash::<Sha256Hash>()
-    }
-
     pub fn sign_message(&self, message: &[u8]) -> (Ed25519PublicKey, Ed25519Signature) {
-        let hash = Self::prepare_message_for_signature(message);
+        let hash = SignedMessage::message_hash(message);
         (self.key_pair.public, self.key_pair.sign(hash.as_bytes()))
     }
 
@@ -92,9 +69,18 @@ impl WalletAccount {
         message: &[u8],
         signature: &Ed25519Signature,
     ) -> bool {
-        let hash = Self::prepare_message_for_signature(message);
+        let hash = SignedMessage::message_hash(message);
         public_key.verify(signature, hash.as_bytes())
     }
+
+    /// Signs the message and wraps the signature into a signed message envelope.
+    pub fn create_signed_message(&self, message: &[u8]) -> SignedMessage {
+        let (public_key, signature) = self.sign_message(message);
+        SignedMessage::new(
+            self.address.clone(),
+            SignatureProof::from_ed25519(public_key, signature),
+        )
+    }
 }
 
 impl<'de> serde::Deserialize<'de> for WalletAccount {
@@ -5,20 +5,40 @@ use nimiq_database::{
 use nimiq_keys::Address;
 use nimiq_utils::otp::Locked;
 
-use crate::wallet_account::WalletAccount;
+use crate::{
+    backup::WalletBackupContents,
+    hd_wallet::{DerivedAccount, HdWallet},
+    wallet_account::WalletAccount,
+    watch_only::WatchOnlyAddress,
+};
 
 #[derive(Debug)]
 pub struct WalletStore {
     env: DatabaseProxy,
     wallet_db: TableProxy,
+    hd_wallet_db: TableProxy,
+    derived_account_db: TableProxy,
+    watch_only_db: TableProxy,
 }
 
 impl WalletStore {
     const WALLET_DB_NAME: &'static str = "Wallet";
+    const HD_WALLET_DB_NAME: &'static str = "HdWallet";
+    const DERIVED_ACCOUNT_DB_NAME: &'static str = "DerivedAccount";
+    const WATCH_ONLY_DB_NAME: &'static str = "WatchOnly";
 
     pub fn new(env: DatabaseProxy) -> Self {
         let wallet_db = env.open_table(Self::WALLET_DB_NAME.to_string());
-        WalletStore { env, wallet_db }
+        let hd_wallet_db = env.open_table(Self::HD_WALLET_DB_NAME.to_string());
+        let derived_account_db = env.open_table(Self::DERIVED_ACCOUNT_DB_NAME.to_string());
+        let watch_only_db = env.open_table(Self::WATCH_ONLY_DB_NAME.to_string());
+        WalletStore {
+            env,
+            wallet_db,
+            hd_wallet_db,
+            derived_account_db,
+            watch_only_db,
+        }
     }
 
     pub fn create_read_transaction(&self) -> TransactionProxy {
@@ -65,4 +85,150 @@ impl WalletStore {
     ) {
         txn.put_reserve(&self.wallet_db, address, wallet);
     }
+
+    pub fn get_hd_wallet(
+        &self,
+        id: &Address,
+        txn_option: Option<&TransactionProxy>,
+    ) -> Option<Locked<HdWallet>> {
+        match txn_option {
+            Some(txn) => txn.get(&self.hd_wallet_db, id),
+            None => self.env.read_transaction().get(&self.hd_wallet_db, id),
+        }
+    }
+
+    pub fn put_hd_wallet(
+        &self,
+        id: &Address,
+        wallet: &Locked<HdWallet>,
+        txn: &mut WriteTransactionProxy,
+    ) {
+        txn.put_reserve(&self.hd_wallet_db, id, wallet);
+    }
+
+    /// Returns the addresses and derivation information of the accounts that were derived from
+    /// the given HD wallet.
+    pub fn list_derived_accounts(
+        &self,
+        wallet_id: &Address,
+        txn_option: Option<&TransactionProxy>,
+    ) -> Vec<(Address, DerivedAccount)> {
+        let read_txn;
+        let txn = match txn_option {
+            Some(txn) => txn,
+            None => {
+                read_txn = self.env.read_transaction();
+                &read_txn
+            }
+        };
+
+        let cursor = txn.cursor(&self.derived_account_db);
+        cursor
+            .into_iter_start::<Address, DerivedAccount>()
+            .filter(|(_, account)| account.wallet_id == *wallet_id)
+            .collect()
+    }
+
+    pub fn put_derived_account(
+        &self,
+        address: &Address,
+        account: &DerivedAccount,
+        txn: &mut WriteTransactionProxy,
+    ) {
+        txn.put_reserve(&self.derived_account_db, address, account);
+    }
+
+    /// Returns the watch-only addresses along with their information.
+    pub fn list_watch_only(
+        &self,
+        txn_option: Option<&TransactionProxy>,
+    ) -> Vec<(Address, WatchOnlyAddress)> {
+        let read_txn;
+        let txn = match txn_option {
+            Some(txn) => txn,
+            None => {
+                read_txn = self.env.read_transaction();
+                &read_txn
+            }
+        };
+
+        let cursor = txn.cursor(&self.watch_only_db);
+        cursor
+            .into_iter_start::<Address, WatchOnlyAddress>()
+            .collect()
+    }
+
+    pub fn get_watch_only(
+        &self,
+        address: &Address,
+        txn_option: Option<&TransactionProxy>,
+    ) -> Option<WatchOnlyAddress> {
+        match txn_option {
+            Some(txn) => txn.get(&self.watch_only_db, address),
+            None => self
+                .env
+                .read_transaction()
+                .get(&self.watch_only_db, address),
+        }
+    }
+
+    pub fn put_watch_only(
+        &self,
+        address: &Address,
+        watch_only: &WatchOnlyAddress,
+        txn: &mut WriteTransactionProxy,
+    ) {
+        txn.put_reserve(&self.watch_only_db, address, watch_only);
+    }
+
+    pub fn remove_watch_only(&self, address: &Address, txn: &mut WriteTransactionProxy) {
+        txn.remove(&self.watch_only_db, address);
+    }
+
+    /// Collects all accounts, HD wallets, derivation information and watch-only addresses for a
+    /// backup.
+    pub fn export_backup_contents(&self) -> WalletBackupContents {
+        let txn = self.env.read_transaction();
+        let accounts = txn
+            .cursor(&self.wallet_db)
+            .into_iter_start::<Address, Locked<WalletAccount>>()
+            .collect();
+        let hd_wallets = txn
+            .cursor(&self.hd_wallet_db)
+            .into_iter_start::<Address, Locked<HdWallet>>()
+            .collect();
+        let derived_accounts = txn
+            .cursor(&self.derived_account_db)
+            .into_iter_start::<Address, DerivedAccount>()
+            .collect();
+        let watch_only = self.list_watch_only(Some(&txn));
+        WalletBackupContents::new(accounts, hd_wallets, derived_accounts, watch_only)
+    }
+
+    /// Restores the contents of a backup. If `replace` is set, all existing wallet data is
+    /// removed first, otherwise the backup is merged into it, overwriting entries with the
+    /// same address.
+    pub fn import_backup_contents(&self, contents: &WalletBackupContents, replace: bool) {
+        let mut txn = self.env.write_transaction();
+        if replace {
+            txn.clear_database(&self.wallet_db);
+            txn.clear_database(&self.hd_wallet_db);
+            txn.clear_database(&self.derived_account_db);
+            txn.clear_database(&self.watch_only_db);
+        }
+
+        for (address, account) in &contents.accounts {
+            self.put(address, account, &mut txn);
+        }
+        for (id, wallet) in &contents.hd_wallets {
+            self.put_hd_wallet(id, wallet, &mut txn);
+        }
+        for (address, account) in &contents.derived_accounts {
+            self.put_derived_account(address, account, &mut txn);
+        }
+        for (address, watch_only) in &contents.watch_only {
+            self.put_watch_only(address, watch_only, &mut txn);
+        }
+        txn.commit();
+    }
 }
@@ -0,0 +1,32 @@
+use std::io;
+
+use nimiq_database_value::{FromDatabaseValue, IntoDatabaseValue};
+use nimiq_serde::{Deserialize, Serialize};
+
+/// An address that is watched by the wallet without holding its key. Transactions of watch-only
+/// addresses can be created by the wallet, but must be signed elsewhere.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct WatchOnlyAddress {
+    /// An optional label to identify the address.
+    pub label: Option<String>,
+}
+
+impl IntoDatabaseValue for WatchOnlyAddress {
+    fn database_byte_size(&self) -> usize {
+        self.serialized_size()
+    }
+
+    fn copy_into_database(&self, mut bytes: &mut [u8]) {
+        Serialize::serialize_to_writer(&self, &mut bytes).unwrap();
+    }
+}
+
+impl FromDatabaseValue for WatchOnlyAddress {
+    fn copy_from_database(bytes: &[u8]) -> io::Result<Self>
+    where
+        Self: Sized,
+    {
+        Deserialize::deserialize_from_vec(bytes)
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+    }
+}
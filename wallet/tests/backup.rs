@@ -0,0 +1,146 @@
+use nimiq_database::volatile::VolatileDatabase;
+use nimiq_hash::{Blake2bHash, Blake2bHasher, Hasher};
+use nimiq_keys::Address;
+use nimiq_serde::{Deserialize, Serialize};
+use nimiq_test_log::test;
+use nimiq_utils::otp::Locked;
+use nimiq_wallet::{
+    DerivedAccount, HdWallet, WalletAccount, WalletBackup, WalletBackupContents, WalletBackupError,
+    WalletStore, WALLET_BACKUP_VERSION,
+};
+
+fn store_with_account(passphrase: &[u8]) -> (WalletStore, WalletAccount) {
+    let store = WalletStore::new(VolatileDatabase::new(20).unwrap());
+    let account = WalletAccount::generate();
+    let locked = Locked::with_defaults(account.clone(), passphrase).unwrap();
+
+    let mut txn = store.create_write_transaction();
+    store.put(&account.address, &locked, &mut txn);
+    txn.commit();
+
+    (store, account)
+}
+
+#[test]
+fn it_can_export_and_import_backups() {
+    let (store, account) = store_with_account(b"passphrase");
+    let backup = WalletBackup::new(store.export_backup_contents(), b"password").unwrap();
+    let bytes = backup.to_bytes();
+
+    let restored_store = WalletStore::new(VolatileDatabase::new(20).unwrap());
+    let contents = WalletBackup::from_bytes(&bytes)
+        .unwrap()
+        .open(b"password")
+        .unwrap();
+    assert_eq!(contents.addresses(), vec![account.address.clone()]);
+    restored_store.import_backup_contents(&contents, false);
+
+    // The restored account is still locked with its own passphrase.
+    let Ok(unlocked) = restored_store
+        .get(&account.address, None)
+        .unwrap()
+        .unlock(b"passphrase")
+    else {
+        panic!("Failed to unlock the restored account");
+    };
+    assert_eq!(*unlocked, account);
+}
+
+#[test]
+fn it_merges_or_replaces_on_import() {
+    let (store, account) = store_with_account(b"");
+    let contents = store.export_backup_contents();
+
+    let (other_store, other_account) = store_with_account(b"");
+    other_store.import_backup_contents(&contents, false);
+    let mut addresses = other_store.list(None);
+    addresses.sort();
+    let mut expected = vec![account.address.clone(), other_account.address.clone()];
+    expected.sort();
+    assert_eq!(addresses, expected);
+
+    other_store.import_backup_contents(&contents, true);
+    assert_eq!(other_store.list(None), vec![account.address]);
+}
+
+#[test]
+fn it_rejects_wrong_passwords_and_unsupported_versions() {
+    let (wallet, _) = HdWallet::generate();
+    let contents = WalletBackupContents::new(
+        vec![],
+        vec![(
+            wallet.id.clone(),
+            Locked::with_defaults(wallet, b"").unwrap(),
+        )],
+        vec![],
+        vec![],
+    );
+    let mut bytes = WalletBackup::new(contents, b"password").unwrap().to_bytes();
+    assert_eq!(bytes[0], WALLET_BACKUP_VERSION);
+
+    let backup = WalletBackup::from_bytes(&bytes).unwrap();
+    assert!(matches!(
+        backup.open(b"wrong password"),
+        Err(WalletBackupError::WrongPassword)
+    ));
+
+    bytes[0] = WALLET_BACKUP_VERSION + 1;
+    assert!(matches!(
+        WalletBackup::from_bytes(&bytes),
+        Err(WalletBackupError::UnsupportedVersion(_))
+    ));
+}
+
+/// The contents of a backup as written by version 1, which had no watch-only addresses.
+#[derive(Default, Serialize, Deserialize)]
+struct WalletBackupContentsV1 {
+    accounts: Vec<(Address, Locked<WalletAccount>)>,
+    hd_wallets: Vec<(Address, Locked<HdWallet>)>,
+    derived_accounts: Vec<(Address, DerivedAccount)>,
+    checksum: Blake2bHash,
+}
+
+#[test]
+fn it_reads_version_1_backups() {
+    let (store, account) = store_with_account(b"passphrase");
+    let WalletBackupContents {
+        accounts,
+        hd_wallets,
+        derived_accounts,
+        ..
+    } = store.export_backup_contents();
+
+    let mut contents_v1 = WalletBackupContentsV1 {
+        accounts,
+        hd_wallets,
+        derived_accounts,
+        checksum: Blake2bHash::default(),
+    };
+    let entries = (
+        &contents_v1.accounts,
+        &contents_v1.hd_wallets,
+        &contents_v1.derived_accounts,
+    );
+    contents_v1.checksum = Blake2bHasher::default().digest(&entries.serialize_to_vec());
+    let bytes = (
+        1u8,
+        Locked::with_defaults(contents_v1, b"password").unwrap(),
+    )
+        .serialize_to_vec();
+
+    let backup = WalletBackup::from_bytes(&bytes).unwrap();
+    assert_eq!(backup.version(), 1);
+    assert_eq!(backup.to_bytes(), bytes);
+
+    let contents = backup.open(b"password").unwrap();
+    assert_eq!(contents.addresses(), vec![account.address]);
+    assert!(contents.watch_only.is_empty());
+
+    // The converted contents can be written to a backup of the current version.
+    let bytes = WalletBackup::new(contents, b"password").unwrap().to_bytes();
+    assert_eq!(bytes[0], WALLET_BACKUP_VERSION);
+    assert!(WalletBackup::from_bytes(&bytes)
+        .unwrap()
+        .open(b"password")
+        .is_ok());
+}
@@ -0,0 +1,82 @@
+use nimiq_mnemonic::Mnemonic;
+use nimiq_serde::{Deserialize, Serialize};
+use nimiq_test_log::test;
+use nimiq_utils::otp::Locked;
+use nimiq_wallet::{HdWallet, HdWalletError};
+
+const MNEMONIC: &str = "void come effort suffer camp survey warrior heavy shoot primary clutch crush open amazing screen patrol group space point ten exist slush involve unfold";
+
+#[test]
+fn it_derives_accounts_deterministically() {
+    let wallet = HdWallet::from_mnemonic(&Mnemonic::from(MNEMONIC)).unwrap();
+    let restored = HdWallet::from_mnemonic(&Mnemonic::from(MNEMONIC)).unwrap();
+    assert_eq!(wallet.id, restored.id);
+
+    let path = HdWallet::path_for_index(0);
+    assert_eq!(path, "m/44'/242'/0'/0'");
+
+    let account = wallet.derive_account(&path).unwrap();
+    assert_eq!(account, restored.derive_account(&path).unwrap());
+    assert_ne!(
+        account.address,
+        wallet
+            .derive_account(&HdWallet::path_for_index(1))
+            .unwrap()
+            .address
+    );
+}
+
+#[test]
+fn it_generates_restorable_wallets() {
+    let (wallet, mnemonic) = HdWallet::generate();
+    let restored = HdWallet::from_mnemonic(&mnemonic).unwrap();
+    assert_eq!(wallet, restored);
+}
+
+#[test]
+fn it_rejects_invalid_mnemonics_and_paths() {
+    let legacy = Mnemonic::from(
+        "refuse walk suggest raven cheese gate eye divert base slot fossil lock oven fuel thank need unit oak image spike vehicle grace citizen expose",
+    );
+    assert!(matches!(
+        HdWallet::from_mnemonic(&legacy),
+        Err(HdWalletError::InvalidMnemonic)
+    ));
+    assert!(matches!(
+        HdWallet::from_mnemonic(&Mnemonic::from("not a mnemonic")),
+        Err(HdWalletError::InvalidMnemonic)
+    ));
+
+    let wallet = HdWallet::from_mnemonic(&Mnemonic::from(MNEMONIC)).unwrap();
+    assert!(matches!(
+        wallet.derive_account("m/44'/242'/0'/0"),
+        Err(HdWalletError::InvalidPath(_))
+    ));
+}
+
+#[test]
+fn it_can_be_locked_and_unlocked() {
+    let wallet = HdWallet::from_mnemonic(&Mnemonic::from(MNEMONIC)).unwrap();
+    let locked = Locked::with_defaults(wallet.clone(), b"password").unwrap();
+
+    let Err(locked) = locked.unlock(b"wrong password") else {
+        panic!("Unlocked with a wrong password");
+    };
+    let Ok(unlocked) = locked.unlock(b"password") else {
+        panic!("Failed to unlock with the right password");
+    };
+    assert_eq!(*unlocked, wallet);
+}
+
+#[test]
+fn it_serializes_the_seed_like_before() {
+    let wallet = HdWallet::from_mnemonic(&Mnemonic::from(MNEMONIC)).unwrap();
+
+    // Wallets used to store the seed as a `Vec<u8>`.
+    let bytes = (wallet.seed.to_vec(), wallet.id.clone()).serialize_to_vec();
+    assert_eq!(wallet.serialize_to_vec(), bytes);
+    assert_eq!(HdWallet::deserialize_from_vec(&bytes).unwrap(), wallet);
+
+    let bytes = (wallet.seed[..32].to_vec(), wallet.id.clone()).serialize_to_vec();
+    assert!(HdWallet::deserialize_from_vec(&bytes).is_err());
+}
@@ -0,0 +1,35 @@
+use nimiq_database::volatile::VolatileDatabase;
+use nimiq_keys::Address;
+use nimiq_test_log::test;
+use nimiq_wallet::{WalletStore, WatchOnlyAddress};
+
+#[test]
+fn it_can_add_and_remove_watch_only_addresses() {
+    let store = WalletStore::new(VolatileDatabase::new(20).unwrap());
+    let address = Address::from_any_str("NQ09 VF5Y 1PKV MRM4 5LE1 55KV P6R2 GXYJ XYQF").unwrap();
+    let watch_only = WatchOnlyAddress {
+        label: Some("cold storage".to_string()),
+    };
+
+    let mut txn = store.create_write_transaction();
+    store.put_watch_only(&address, &watch_only, &mut txn);
+    txn.commit();
+
+    assert_eq!(
+        store.get_watch_only(&address, None),
+        Some(watch_only.clone())
+    );
+    assert_eq!(
+        store.list_watch_only(None),
+        vec![(address.clone(), watch_only)]
+    );
+    // Watch-only addresses are not listed as accounts.
+    assert!(store.list(None).is_empty());
+
+    let mut txn = store.create_write_transaction();
+    store.remove_watch_only(&address, &mut txn);
+    txn.commit();
+
+    assert_eq!(store.get_watch_only(&address, None), None);
+    assert!(store.list_watch_only(None).is_empty());
+}
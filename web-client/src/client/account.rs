@@ -241,6 +241,72 @@ impl From<&nimiq_account::Validator> for PlainValidator {
     }
 }
 
+/// The block against whose state root the returned accounts or stakers were verified with an
+/// accounts trie proof.
+#[derive(serde::Serialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub struct PlainTrieProofBlock {
+    /// The hash of the block the proof was created for.
+    block_hash: String,
+    /// The block number of the block the proof was created for.
+    block_number: u32,
+    /// The state root the proven values were verified against.
+    state_root: String,
+    /// Whether the block was the head of the client's blockchain at the time of the verification.
+    is_head: bool,
+}
+
+impl From<nimiq_consensus::consensus::consensus_proxy::TrieProofBlock> for PlainTrieProofBlock {
+    fn from(block: nimiq_consensus::consensus::consensus_proxy::TrieProofBlock) -> Self {
+        PlainTrieProofBlock {
+            block_hash: block.block_hash.to_hex(),
+            block_number: block.block_number,
+            state_root: block.state_root.to_hex(),
+            is_head: block.is_head,
+        }
+    }
+}
+
+/// An account along with the block it was proven against.
+#[derive(serde::Serialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub struct PlainProvenAccount {
+    pub account: PlainAccount,
+    pub proof: PlainTrieProofBlock,
+}
+
+/// Accounts along with the block they were proven against. All accounts were proven by a
+/// single accounts trie multi-proof.
+#[derive(serde::Serialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub struct PlainProvenAccounts {
+    /// The accounts in the order of the requested addresses.
+    pub accounts: Vec<PlainAccount>,
+    pub proof: PlainTrieProofBlock,
+}
+
+/// A staker along with the block it was proven against.
+#[derive(serde::Serialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub struct PlainProvenStaker {
+    /// The staker, `undefined` if there is no staker for the address.
+    #[tsify(type = "PlainStaker | undefined")]
+    pub staker: Option<PlainStaker>,
+    pub proof: PlainTrieProofBlock,
+}
+
+/// Stakers along with the block they were proven against. All stakers were proven by a single
+/// accounts trie multi-proof.
+#[derive(serde::Serialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub struct PlainProvenStakers {
+    /// The stakers in the order of the requested addresses. Addresses without a staker are
+    /// `undefined`.
+    #[tsify(type = "(PlainStaker | undefined)[]")]
+    pub stakers: Vec<Option<PlainStaker>>,
+    pub proof: PlainTrieProofBlock,
+}
+
 #[wasm_bindgen]
 extern "C" {
     #[wasm_bindgen(typescript_type = "PlainAccount")]
@@ -260,4 +326,16 @@ extern "C" {
 
     #[wasm_bindgen(typescript_type = "(PlainValidator | undefined)[]")]
     pub type PlainValidatorArrayType;
+
+    #[wasm_bindgen(typescript_type = "PlainProvenAccount")]
+    pub type PlainProvenAccountType;
+
+    #[wasm_bindgen(typescript_type = "PlainProvenAccounts")]
+    pub type PlainProvenAccountsType;
+
+    #[wasm_bindgen(typescript_type = "PlainProvenStaker")]
+    pub type PlainProvenStakerType;
+
+    #[wasm_bindgen(typescript_type = "PlainProvenStakers")]
+    pub type PlainProvenStakersType;
 }
@@ -23,14 +23,17 @@ pub use nimiq::{
     },
     extras::{panic::initialize_panic_reporting, web_logging::initialize_web_logging},
 };
+use nimiq_block::Block;
 use nimiq_blockchain_interface::{AbstractBlockchain, BlockchainEvent};
+use nimiq_blockchain_proxy::BlockchainProxy;
 use nimiq_consensus::ConsensusEvent;
 use nimiq_hash::Blake2bHash;
+use nimiq_light_blockchain::LightBlockchain;
 use nimiq_network_interface::{
     network::{CloseReason, Network, NetworkEvent},
     Multiaddr,
 };
-use nimiq_primitives::policy::Policy;
+use nimiq_primitives::{coin::Coin, policy::Policy};
 use tokio::sync::oneshot;
 use tsify::Tsify;
 use wasm_bindgen::{prelude::*, JsCast};
@@ -41,22 +44,27 @@ use crate::{
     address::{Address, AddressAnyArrayType, AddressAnyType},
     client::{
         account::{
-            PlainAccount, PlainAccountArrayType, PlainAccountType, PlainStaker,
-            PlainStakerArrayType, PlainStakerType, PlainValidator, PlainValidatorArrayType,
-            PlainValidatorType,
+            PlainAccount, PlainAccountArrayType, PlainAccountType, PlainProvenAccount,
+            PlainProvenAccountType, PlainProvenAccounts, PlainProvenAccountsType,
+            PlainProvenStaker, PlainProvenStakerType, PlainProvenStakers, PlainProvenStakersType,
+            PlainStaker, PlainStakerArrayType, PlainStakerType, PlainTrieProofBlock,
+            PlainValidator, PlainValidatorArrayType, PlainValidatorType,
         },
         block::{PlainBlock, PlainBlockType},
         peer_info::PlainPeerInfo,
+        persistence::{ClientStore, IndexedDbBackend},
+        staking::{self, PlainStakingPlan, PlainStakingPlanType, StakingPlan},
+        transaction_history::{transaction_addresses, TransactionHistory},
     },
     client_configuration::{
         ClientConfiguration, PlainClientConfiguration, PlainClientConfigurationType,
     },
     transaction::{
         PlainTransactionDetails, PlainTransactionDetailsArrayType, PlainTransactionDetailsType,
-        PlainTransactionReceipt, PlainTransactionReceiptArrayType, PlainTransactionRecipientData,
-        Transaction, TransactionAnyType, TransactionState,
+        PlainTransactionReceipt, PlainTransactionReceiptArrayType, Transaction, TransactionAnyType,
+        TransactionState,
     },
-    utils::from_network_id,
+    utils::{from_network_id, to_network_id},
 };
 
 /// Maximum number of transactions that can be requested by address
@@ -113,6 +121,12 @@ pub struct Client {
     /// Map from transaction hash as hex string to oneshot sender.
     /// Used to await transaction events in `send_transaction`.
     transaction_oneshots: Rc<RefCell<HashMap<String, oneshot::Sender<PlainTransactionDetails>>>>,
+
+    /// Cache of verified transactions, used by `get_transaction_history`.
+    transaction_history: Rc<RefCell<TransactionHistory>>,
+
+    /// The store the client state is persisted in, if persistence is enabled.
+    store: Option<ClientStore>,
 }
 
 #[wasm_bindgen]
@@ -162,20 +176,82 @@ impl Client {
         config.network_id = web_config.network_id;
         config.network.desired_peer_count = 6;
 
+        // Load the state persisted by a previous run of the client.
+        let persisted = if web_config.persistence {
+            match IndexedDbBackend::open(web_config.network_id).await {
+                Ok(backend) => Some(ClientStore::load(backend).await),
+                Err(error) => {
+                    log::warn!(?error, "Failed to open the client state storage");
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        // Also dial the peers we knew before.
+        if let Some((_, state)) = &persisted {
+            for address in &state.peers {
+                if let Ok(address) = Multiaddr::from_str(address) {
+                    if !config
+                        .network
+                        .seeds
+                        .iter()
+                        .any(|seed| seed.address == address)
+                    {
+                        config.network.seeds.push(Seed { address });
+                    }
+                }
+            }
+        }
+
         log::info!(?config, "Final configuration");
 
         // Create client from config.
         log::info!("Initializing light client");
-        let mut client = nimiq::client::Client::from_config(
-            config,
-            Box::new(|fut| {
-                spawn_local(fut);
-            }),
-        )
-        .await
+        let mut client = match &persisted {
+            Some((store, _)) => {
+                nimiq::client::Client::from_config_with_proof_store(
+                    config,
+                    Box::new(|fut| {
+                        spawn_local(fut);
+                    }),
+                    Box::new(store.clone()),
+                )
+                .await
+            }
+            None => {
+                nimiq::client::Client::from_config(
+                    config,
+                    Box::new(|fut| {
+                        spawn_local(fut);
+                    }),
+                )
+                .await
+            }
+        }
         .expect("Client initialization failed");
         log::info!("Web client initialized");
 
+        // The client resumed from the persisted ZKP, catch up to the persisted macro head as well.
+        let (store, macro_head) = match persisted {
+            Some((store, state)) => (Some(store), state.macro_head),
+            None => (None, None),
+        };
+        if let Some(macro_head) = macro_head {
+            #[allow(unreachable_patterns)]
+            match client.blockchain() {
+                BlockchainProxy::Light(blockchain) => {
+                    let result = LightBlockchain::push_macro(
+                        blockchain.upgradable_read(),
+                        Block::Macro(macro_head),
+                    );
+                    log::debug!(?result, "Resumed the persisted macro head");
+                }
+                _ => {}
+            }
+        }
+
         // Start consensus.
         let consensus = client.take_consensus().unwrap();
         log::info!("Spawning consensus");
@@ -194,12 +270,16 @@ impl Client {
             peer_changed_listeners: Rc::new(RefCell::new(HashMap::with_capacity(1))),
             transaction_listeners: Rc::new(RefCell::new(HashMap::new())),
             transaction_oneshots: Rc::new(RefCell::new(HashMap::new())),
+            transaction_history: Rc::new(RefCell::new(TransactionHistory::default())),
+            store,
         };
 
         client.setup_offline_online_event_handlers();
         client.setup_consensus_events();
         client.setup_blockchain_events();
         client.setup_network_events();
+        client.setup_persistence_events();
+        client.setup_transaction_history_events();
         client.setup_transaction_events().await;
 
         Ok(client)
@@ -432,11 +512,14 @@ impl Client {
 
     /// Fetches the account for the provided address from the network.
     ///
+    /// The account is verified with an accounts trie proof against the state root of a block of
+    /// the client's blockchain. Use {@link getAccountWithProof} to learn which block that was.
+    ///
     /// Throws if the address cannot be parsed and on network errors.
     #[wasm_bindgen(js_name = getAccount)]
     pub async fn get_account(&self, address: &AddressAnyType) -> Result<PlainAccountType, JsError> {
         let address = Address::from_any(address)?.take_native();
-        let plain_accounts = self.get_plain_accounts(vec![address]).await?;
+        let (plain_accounts, _) = self.get_plain_accounts(vec![address]).await?;
         let account = plain_accounts.first().unwrap();
         Ok(serde_wasm_bindgen::to_value(account)?.into())
     }
@@ -450,17 +533,54 @@ impl Client {
         addresses: &AddressAnyArrayType,
     ) -> Result<PlainAccountArrayType, JsError> {
         let addresses = Client::unpack_addresses(addresses)?;
-        let plain_accounts = self.get_plain_accounts(addresses).await?;
+        let (plain_accounts, _) = self.get_plain_accounts(addresses).await?;
         Ok(serde_wasm_bindgen::to_value(&plain_accounts)?.into())
     }
 
+    /// Fetches the account for the provided address from the network, along with the block whose
+    /// state root the account was verified against.
+    ///
+    /// Throws if the address cannot be parsed and on network errors.
+    #[wasm_bindgen(js_name = getAccountWithProof)]
+    pub async fn get_account_with_proof(
+        &self,
+        address: &AddressAnyType,
+    ) -> Result<PlainProvenAccountType, JsError> {
+        let address = Address::from_any(address)?.take_native();
+        let (mut plain_accounts, proof) = self.get_plain_accounts(vec![address]).await?;
+        let proven_account = PlainProvenAccount {
+            account: plain_accounts.pop().unwrap(),
+            proof,
+        };
+        Ok(serde_wasm_bindgen::to_value(&proven_account)?.into())
+    }
+
+    /// Fetches the accounts for the provided addresses from the network, along with the block
+    /// whose state root the accounts were verified against. All accounts are fetched with a
+    /// single accounts trie multi-proof, so this is the preferred way to poll many addresses.
+    ///
+    /// Throws if an address cannot be parsed and on network errors.
+    #[wasm_bindgen(js_name = getAccountsWithProof)]
+    pub async fn get_accounts_with_proof(
+        &self,
+        addresses: &AddressAnyArrayType,
+    ) -> Result<PlainProvenAccountsType, JsError> {
+        let addresses = Client::unpack_addresses(addresses)?;
+        let (accounts, proof) = self.get_plain_accounts(addresses).await?;
+        let proven_accounts = PlainProvenAccounts { accounts, proof };
+        Ok(serde_wasm_bindgen::to_value(&proven_accounts)?.into())
+    }
+
     /// Fetches the staker for the provided address from the network.
     ///
+    /// The staker is verified with an accounts trie proof against the state root of a block of
+    /// the client's blockchain. Use {@link getStakerWithProof} to learn which block that was.
+    ///
     /// Throws if the address cannot be parsed and on network errors.
     #[wasm_bindgen(js_name = getStaker)]
     pub async fn get_staker(&self, address: &AddressAnyType) -> Result<PlainStakerType, JsError> {
         let address = Address::from_any(address)?.take_native();
-        let plain_stakers = self.get_plain_stakers(vec![address]).await?;
+        let (plain_stakers, _) = self.get_plain_stakers(vec![address]).await?;
         let staker = plain_stakers.first().unwrap();
         Ok(serde_wasm_bindgen::to_value(staker)?.into())
     }
@@ -474,10 +594,44 @@ impl Client {
         addresses: &AddressAnyArrayType,
     ) -> Result<PlainStakerArrayType, JsError> {
         let addresses = Client::unpack_addresses(addresses)?;
-        let plain_stakers = self.get_plain_stakers(addresses).await?;
+        let (plain_stakers, _) = self.get_plain_stakers(addresses).await?;
         Ok(serde_wasm_bindgen::to_value(&plain_stakers)?.into())
     }
 
+    /// Fetches the staker for the provided address from the network, along with the block whose
+    /// state root the staker was verified against.
+    ///
+    /// Throws if the address cannot be parsed and on network errors.
+    #[wasm_bindgen(js_name = getStakerWithProof)]
+    pub async fn get_staker_with_proof(
+        &self,
+        address: &AddressAnyType,
+    ) -> Result<PlainProvenStakerType, JsError> {
+        let address = Address::from_any(address)?.take_native();
+        let (mut plain_stakers, proof) = self.get_plain_stakers(vec![address]).await?;
+        let proven_staker = PlainProvenStaker {
+            staker: plain_stakers.pop().unwrap(),
+            proof,
+        };
+        Ok(serde_wasm_bindgen::to_value(&proven_staker)?.into())
+    }
+
+    /// Fetches the stakers for the provided addresses from the network, along with the block
+    /// whose state root the stakers were verified against. All stakers are fetched with a single
+    /// accounts trie multi-proof.
+    ///
+    /// Throws if an address cannot be parsed and on network errors.
+    #[wasm_bindgen(js_name = getStakersWithProof)]
+    pub async fn get_stakers_with_proof(
+        &self,
+        addresses: &AddressAnyArrayType,
+    ) -> Result<PlainProvenStakersType, JsError> {
+        let addresses = Client::unpack_addresses(addresses)?;
+        let (stakers, proof) = self.get_plain_stakers(addresses).await?;
+        let proven_stakers = PlainProvenStakers { stakers, proof };
+        Ok(serde_wasm_bindgen::to_value(&proven_stakers)?.into())
+    }
+
     /// Fetches the validator for the provided address from the network.
     ///
     /// Throws if the address cannot be parsed and on network errors.
@@ -505,6 +659,119 @@ impl Client {
         Ok(serde_wasm_bindgen::to_value(&plain_validators)?.into())
     }
 
+    /// Creates the transactions to stake `value` luna from the staker address for the given
+    /// validator. A staker is created if the address is not a staker yet.
+    ///
+    /// The returned transactions are not yet signed and must be sent in order. Throws if the stake
+    /// is delegated to a different validator, the validator does not exist or is retired, or the
+    /// stake would be below the minimum stake.
+    #[wasm_bindgen(js_name = stake)]
+    pub async fn stake(
+        &self,
+        staker_address: &AddressAnyType,
+        validator_address: &AddressAnyType,
+        value: u64,
+        fee: Option<u64>,
+    ) -> Result<PlainStakingPlanType, JsError> {
+        let staker_address = Address::from_any(staker_address)?.take_native();
+        let validator_address = Address::from_any(validator_address)?.take_native();
+        let staker = self.get_native_staker(&staker_address).await?;
+        let validator = self.get_native_validator(&validator_address).await?;
+
+        let plan = staking::plan_stake(
+            staker.as_ref(),
+            &validator_address,
+            validator.as_ref(),
+            Coin::try_from(value)?,
+        )
+        .map_err(|error| JsError::new(&error.to_string()))?;
+
+        self.to_plain_staking_plan(&staker_address, plan, fee).await
+    }
+
+    /// Creates the transactions to delegate the stake of the staker address to a different
+    /// validator.
+    ///
+    /// Stake can only be delegated to a different validator once all of it is inactive and
+    /// released. If the staker still has active stake, the returned transaction deactivates it,
+    /// and `nextStepAt` is the block height from which on `switchValidator` can be called again to
+    /// finish the switch. Throws with the block height to wait for if the inactive stake is still
+    /// locked.
+    #[wasm_bindgen(js_name = switchValidator)]
+    pub async fn switch_validator(
+        &self,
+        staker_address: &AddressAnyType,
+        validator_address: &AddressAnyType,
+        fee: Option<u64>,
+    ) -> Result<PlainStakingPlanType, JsError> {
+        let staker_address = Address::from_any(staker_address)?.take_native();
+        let validator_address = Address::from_any(validator_address)?.take_native();
+        let staker = self.get_native_staker(&staker_address).await?;
+        let current_validator = self.get_delegated_validator(staker.as_ref()).await?;
+        let validator = self.get_native_validator(&validator_address).await?;
+
+        let plan = staking::plan_switch_validator(
+            staker.as_ref(),
+            current_validator.as_ref(),
+            &validator_address,
+            validator.as_ref(),
+            self.get_head_height().await,
+        )
+        .map_err(|error| JsError::new(&error.to_string()))?;
+
+        self.to_plain_staking_plan(&staker_address, plan, fee).await
+    }
+
+    /// Creates the transactions to unstake `value` luna of the staker address, so that it can be
+    /// withdrawn with `withdraw`.
+    ///
+    /// If there is not enough inactive stake, the returned transaction deactivates the missing
+    /// amount first, and `nextStepAt` is the block height from which on `unstake` can be called
+    /// again to retire it. Throws with the block height to wait for if the inactive stake is still
+    /// locked, or if the remaining stake would be below the minimum stake.
+    #[wasm_bindgen(js_name = unstake)]
+    pub async fn unstake(
+        &self,
+        staker_address: &AddressAnyType,
+        value: u64,
+        fee: Option<u64>,
+    ) -> Result<PlainStakingPlanType, JsError> {
+        let staker_address = Address::from_any(staker_address)?.take_native();
+        let staker = self.get_native_staker(&staker_address).await?;
+        let current_validator = self.get_delegated_validator(staker.as_ref()).await?;
+
+        let plan = staking::plan_unstake(
+            staker.as_ref(),
+            current_validator.as_ref(),
+            Coin::try_from(value)?,
+            self.get_head_height().await,
+        )
+        .map_err(|error| JsError::new(&error.to_string()))?;
+
+        self.to_plain_staking_plan(&staker_address, plan, fee).await
+    }
+
+    /// Creates the transaction to withdraw the unstaked (retired) balance of the staker address to
+    /// the recipient.
+    ///
+    /// Throws if there is no retired stake to withdraw.
+    #[wasm_bindgen(js_name = withdraw)]
+    pub async fn withdraw(
+        &self,
+        staker_address: &AddressAnyType,
+        recipient: &AddressAnyType,
+        fee: Option<u64>,
+    ) -> Result<PlainStakingPlanType, JsError> {
+        let staker_address = Address::from_any(staker_address)?.take_native();
+        let recipient = Address::from_any(recipient)?.take_native();
+        let staker = self.get_native_staker(&staker_address).await?;
+
+        let plan = staking::plan_withdraw(staker.as_ref(), &recipient)
+            .map_err(|error| JsError::new(&error.to_string()))?;
+
+        self.to_plain_staking_plan(&staker_address, plan, fee).await
+    }
+
     /// Sends a transaction to the network and returns {@link PlainTransactionDetails}.
     ///
     /// Throws in case of network errors.
@@ -746,6 +1013,68 @@ impl Client {
         Ok(serde_wasm_bindgen::to_value(&plain_tx_details)?.into())
     }
 
+    /// This function returns the transactions from and to a specific address, that have been
+    /// included in the chain, from newest to oldest.
+    ///
+    /// The transactions are verified and kept in a local cache. Only the transactions that are not
+    /// cached and confirmed yet are queried from the network. Cached transactions that are later
+    /// confirmed or reverted by a rebranch are reported to the transaction listeners with the state
+    /// `confirmed` or `pending`, respectively.
+    ///
+    /// Only transactions at or after the `since_block_height` are returned, up to a `limit` number
+    /// of transactions. If the network does not have at least `min_peers` to query, then an error
+    /// is returned.
+    #[wasm_bindgen(js_name = getTransactionHistory)]
+    pub async fn get_transaction_history(
+        &self,
+        address: &AddressAnyType,
+        since_block_height: Option<u32>,
+        limit: Option<u16>,
+        min_peers: Option<usize>,
+    ) -> Result<PlainTransactionDetailsArrayType, JsError> {
+        if let Some(max) = limit {
+            if max > MAX_TRANSACTIONS_BY_ADDRESS {
+                return Err(JsError::new(
+                    "The maximum number of transactions exceeds the one that is supported",
+                ));
+            }
+        }
+
+        let address = Address::from_any(address)?.take_native();
+        let since_block_height = since_block_height.unwrap_or(0);
+        let known_hashes = self.transaction_history.borrow().confirmed_hashes(&address);
+
+        let transactions = self
+            .inner
+            .consensus_proxy()
+            .request_transactions_by_address(
+                address.clone(),
+                since_block_height,
+                known_hashes,
+                min_peers.unwrap_or(1),
+                limit,
+            )
+            .await?;
+
+        let current_height = self.get_head_height().await;
+        let last_macro_block = Policy::last_macro_block(current_height);
+
+        let mut history = self.transaction_history.borrow_mut();
+        for hist_tx in transactions {
+            history.insert(hist_tx, last_macro_block);
+        }
+
+        let plain_tx_details: Vec<_> = history
+            .get(&address, since_block_height, limit.map(usize::from))
+            .into_iter()
+            .map(|hist_tx| {
+                PlainTransactionDetails::from_historic_transaction(hist_tx, current_height)
+            })
+            .collect();
+
+        Ok(serde_wasm_bindgen::to_value(&plain_tx_details)?.into())
+    }
+
     fn setup_offline_online_event_handlers(&self) {
         let network = self.inner.network();
 
@@ -998,11 +1327,40 @@ impl Client {
         });
     }
 
+    /// Persists the latest macro block and the peers we connect to, so the client can resume from
+    /// them on the next start.
+    fn setup_persistence_events(&self) {
+        let Some(store) = self.store.clone() else {
+            return;
+        };
+
+        let blockchain = self.inner.blockchain();
+        let mut blockchain_events = blockchain.read().notifier_as_stream();
+        let macro_head_store = store.clone();
+        spawn_local(async move {
+            while let Some(event) = blockchain_events.next().await {
+                if let BlockchainEvent::Finalized(_) | BlockchainEvent::EpochFinalized(_) = event {
+                    macro_head_store.set_macro_head(&blockchain.read().macro_head());
+                }
+            }
+        });
+
+        let mut network_events = self.inner.network().subscribe_events();
+        spawn_local(async move {
+            while let Some(event) = network_events.next().await {
+                if let Ok(NetworkEvent::PeerJoined(_, peer_info)) = event {
+                    store.add_peer(peer_info.get_address().to_string());
+                }
+            }
+        });
+    }
+
     async fn setup_transaction_events(&self) {
         let consensus = self.inner.consensus_proxy();
 
         let transaction_listeners = Rc::clone(&self.transaction_listeners);
         let transaction_oneshots = Rc::clone(&self.transaction_oneshots);
+        let transaction_history = Rc::clone(&self.transaction_history);
 
         spawn_local(async move {
             let mut address_notifications = consensus.subscribe_address_notifications().await;
@@ -1040,12 +1398,17 @@ impl Client {
                         log::error!("Failed to prove transactions from receipts: {}", e);
                     })
                 {
-                    let this = JsValue::null();
+                    let last_macro_block =
+                        Policy::last_macro_block(consensus.blockchain.read().block_number());
 
                     for hist_tx in hist_txs {
                         let block_number = hist_tx.block_number;
                         let block_time = hist_tx.block_time;
 
+                        transaction_history
+                            .borrow_mut()
+                            .insert(hist_tx.clone(), last_macro_block);
+
                         let exe_tx = hist_tx.into_transaction().unwrap();
                         let tx = exe_tx.get_raw_transaction();
 
@@ -1065,32 +1428,66 @@ impl Client {
                             let _ = sender.send(details.clone());
                         }
 
-                        let staker_address = if let PlainTransactionRecipientData::AddStake(data) =
-                            &details.transaction.data
-                        {
-                            Some(
-                                nimiq_keys::Address::from_user_friendly_address(&data.staker)
-                                    .unwrap(),
-                            )
-                        } else {
-                            None
+                        Client::fire_transaction_event(&transaction_listeners, tx, &details);
+                    }
+                }
+            }
+        });
+    }
+
+    /// Reports cached transactions that are confirmed by a macro block or reverted by a rebranch
+    /// to the transaction listeners.
+    fn setup_transaction_history_events(&self) {
+        let blockchain = self.inner.blockchain();
+        let mut blockchain_events = blockchain.read().notifier_as_stream();
+
+        let transaction_listeners = Rc::clone(&self.transaction_listeners);
+        let transaction_history = Rc::clone(&self.transaction_history);
+
+        spawn_local(async move {
+            while let Some(event) = blockchain_events.next().await {
+                let (state, hist_txs) = match event {
+                    BlockchainEvent::Finalized(_) | BlockchainEvent::EpochFinalized(_) => {
+                        let macro_block_number = blockchain.read().macro_head().block_number();
+                        let confirmed =
+                            transaction_history.borrow_mut().confirm(macro_block_number);
+                        (TransactionState::Confirmed, confirmed)
+                    }
+                    BlockchainEvent::Rebranched(old_chain, _) => {
+                        let Some(fork_block_number) = old_chain
+                            .iter()
+                            .map(|(_, block)| block.block_number())
+                            .min()
+                        else {
+                            continue;
                         };
+                        // The transactions of the reverted blocks are back in the mempool.
+                        let reverted = transaction_history.borrow_mut().revert(fork_block_number);
+                        (TransactionState::Pending, reverted)
+                    }
+                    _ => continue,
+                };
 
-                        if let Ok(js_value) = serde_wasm_bindgen::to_value(&details) {
-                            for (listener, addresses) in transaction_listeners.borrow().values() {
-                                if addresses.contains(&tx.sender)
-                                    || addresses.contains(&tx.recipient)
-                                    || if let Some(ref address) = staker_address {
-                                        addresses.contains(address)
-                                    } else {
-                                        false
-                                    }
-                                {
-                                    let _ = listener.call1(&this, &js_value);
-                                }
-                            }
-                        }
+                let current_height = blockchain.read().block_number();
+                for hist_tx in hist_txs {
+                    let mut details = PlainTransactionDetails::from_historic_transaction(
+                        &hist_tx,
+                        current_height,
+                    );
+                    if matches!(state, TransactionState::Pending) {
+                        details.execution_result = None;
+                        details.block_height = None;
+                        details.timestamp = None;
+                        details.confirmations = None;
                     }
+                    details.state = state.clone();
+
+                    let exe_tx = hist_tx.into_transaction().unwrap();
+                    Client::fire_transaction_event(
+                        &transaction_listeners,
+                        exe_tx.get_raw_transaction(),
+                        &details,
+                    );
                 }
             }
         });
@@ -1105,6 +1502,29 @@ impl Client {
 }
 
 impl Client {
+    /// Calls the transaction listeners that are subscribed to any of the addresses of the
+    /// transaction.
+    fn fire_transaction_event(
+        listeners: &Rc<RefCell<HashMap<usize, (Function, HashSet<nimiq_keys::Address>)>>>,
+        tx: &nimiq_transaction::Transaction,
+        details: &PlainTransactionDetails,
+    ) {
+        let Ok(js_value) = serde_wasm_bindgen::to_value(details) else {
+            return;
+        };
+
+        let tx_addresses = transaction_addresses(tx);
+        let this = JsValue::null();
+        for (listener, addresses) in listeners.borrow().values() {
+            if tx_addresses
+                .iter()
+                .any(|address| addresses.contains(address))
+            {
+                let _ = listener.call1(&this, &js_value);
+            }
+        }
+    }
+
     fn unpack_addresses(
         addresses: &AddressAnyArrayType,
     ) -> Result<Vec<nimiq_keys::Address>, JsError> {
@@ -1130,11 +1550,11 @@ impl Client {
     async fn get_plain_accounts(
         &self,
         addresses: Vec<nimiq_keys::Address>,
-    ) -> Result<Vec<PlainAccount>, JsError> {
-        let accounts = self
+    ) -> Result<(Vec<PlainAccount>, PlainTrieProofBlock), JsError> {
+        let (accounts, proof_block) = self
             .inner
             .consensus_proxy()
-            .request_accounts_by_addresses(addresses.clone(), 1)
+            .request_proven_accounts_by_addresses(addresses.clone(), 1)
             .await?;
 
         let mut ordered_accounts = vec![];
@@ -1153,17 +1573,17 @@ impl Client {
             ));
         }
 
-        Ok(ordered_accounts)
+        Ok((ordered_accounts, proof_block.into()))
     }
 
     async fn get_plain_stakers(
         &self,
         addresses: Vec<nimiq_keys::Address>,
-    ) -> Result<Vec<Option<PlainStaker>>, JsError> {
-        let stakers = self
+    ) -> Result<(Vec<Option<PlainStaker>>, PlainTrieProofBlock), JsError> {
+        let (stakers, proof_block) = self
             .inner
             .consensus_proxy()
-            .request_stakers_by_addresses(addresses.clone(), 1)
+            .request_proven_stakers_by_addresses(addresses.clone(), 1)
             .await?;
 
         let mut ordered_stakers = vec![];
@@ -1181,7 +1601,66 @@ impl Client {
             );
         }
 
-        Ok(ordered_stakers)
+        Ok((ordered_stakers, proof_block.into()))
+    }
+
+    async fn get_native_staker(
+        &self,
+        address: &nimiq_keys::Address,
+    ) -> Result<Option<nimiq_account::Staker>, JsError> {
+        let mut stakers = self
+            .inner
+            .consensus_proxy()
+            .request_stakers_by_addresses(vec![address.clone()], 1)
+            .await?;
+        Ok(stakers.remove(address).flatten())
+    }
+
+    async fn get_native_validator(
+        &self,
+        address: &nimiq_keys::Address,
+    ) -> Result<Option<nimiq_account::Validator>, JsError> {
+        let mut validators = self
+            .inner
+            .consensus_proxy()
+            .request_validators_by_addresses(vec![address.clone()], 1)
+            .await?;
+        Ok(validators.remove(address).flatten())
+    }
+
+    /// Fetches the validator the staker delegates its stake to, if any.
+    async fn get_delegated_validator(
+        &self,
+        staker: Option<&nimiq_account::Staker>,
+    ) -> Result<Option<nimiq_account::Validator>, JsError> {
+        match staker.and_then(|staker| staker.delegation.as_ref()) {
+            Some(delegation) => self.get_native_validator(delegation).await,
+            None => Ok(None),
+        }
+    }
+
+    async fn to_plain_staking_plan(
+        &self,
+        staker_address: &nimiq_keys::Address,
+        plan: StakingPlan,
+        fee: Option<u64>,
+    ) -> Result<PlainStakingPlanType, JsError> {
+        let fee = Coin::try_from(fee.unwrap_or(0))?;
+        let network_id = to_network_id(self.network_id)?;
+        let validity_start_height = self.get_head_height().await;
+
+        let mut transactions = vec![];
+        for operation in &plan.operations {
+            let tx =
+                operation.to_transaction(staker_address, fee, validity_start_height, network_id)?;
+            transactions.push(Transaction::from(tx).to_plain_transaction());
+        }
+
+        let plain_plan = PlainStakingPlan {
+            transactions,
+            next_step_at: plan.next_step_at,
+        };
+        Ok(serde_wasm_bindgen::to_value(&plain_plan)?.into())
     }
 
     async fn get_plain_validators(
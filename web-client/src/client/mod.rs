@@ -2,3 +2,6 @@ pub mod account;
 pub mod block;
 pub mod lib;
 pub mod peer_info;
+pub mod persistence;
+pub mod staking;
+pub mod transaction_history;
@@ -0,0 +1,315 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc, sync::Arc};
+
+use futures::{future::LocalBoxFuture, FutureExt};
+use js_sys::{global, Promise, Reflect, Uint8Array};
+use nimiq_block::MacroBlock;
+use nimiq_primitives::networks::NetworkId;
+use nimiq_serde::{Deserialize, Serialize};
+use nimiq_zkp_component::{
+    proof_store::ProofStore,
+    types::{ZKPState, ZKProof},
+};
+use parking_lot::RwLock;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use wasm_bindgen::{prelude::*, JsCast};
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::{IdbDatabase, IdbFactory, IdbObjectStore, IdbRequest, IdbTransactionMode};
+
+/// The maximum number of peer addresses that are kept to be dialed on the next start.
+pub const MAX_PERSISTED_PEERS: usize = 16;
+
+const ZKP_KEY: &str = "zkp";
+const ZKP_ELECTION_BLOCK_KEY: &str = "zkp-election-block";
+const MACRO_HEAD_KEY: &str = "macro-head";
+const PEERS_KEY: &str = "peers";
+
+/// A key-value storage the client state is persisted in.
+pub trait StorageBackend {
+    /// Gets the value stored under the given key.
+    fn get(&self, key: &'static str) -> LocalBoxFuture<'_, Result<Option<Vec<u8>>, JsError>>;
+
+    /// Stores the value under the given key, replacing any previous value.
+    fn put(&self, key: &'static str, value: Vec<u8>) -> LocalBoxFuture<'_, Result<(), JsError>>;
+}
+
+/// Storage backend that keeps the client state in an IndexedDB database of the browser.
+pub struct IndexedDbBackend {
+    db: IdbDatabase,
+}
+
+impl IndexedDbBackend {
+    const DB_VERSION: u32 = 1;
+    const STORE_NAME: &'static str = "client-state";
+
+    /// Opens the IndexedDB database of the client for the given network, creating it if necessary.
+    pub async fn open(network_id: NetworkId) -> Result<Self, JsError> {
+        let factory: IdbFactory = Reflect::get(&global(), &JsValue::from_str("indexedDB"))
+            .ok()
+            .and_then(|factory| factory.dyn_into().ok())
+            .ok_or_else(|| JsError::new("IndexedDB is not available"))?;
+
+        let name = format!("nimiq-web-client-{}", network_id);
+        let request = factory
+            .open_with_u32(&name, Self::DB_VERSION)
+            .map_err(idb_error)?;
+
+        let upgrade_request = request.clone();
+        let on_upgrade_needed = Closure::once(move || {
+            // The database is new, since there is only a single version so far.
+            if let Ok(db) = upgrade_request.result() {
+                let db: IdbDatabase = db.unchecked_into();
+                let _ = db.create_object_store(Self::STORE_NAME);
+            }
+        });
+        request.set_onupgradeneeded(Some(on_upgrade_needed.as_ref().unchecked_ref()));
+
+        let db = await_request(&request).await?;
+        request.set_onupgradeneeded(None);
+
+        Ok(Self {
+            db: db.unchecked_into(),
+        })
+    }
+
+    fn object_store(&self, mode: IdbTransactionMode) -> Result<IdbObjectStore, JsError> {
+        self.db
+            .transaction_with_str_and_mode(Self::STORE_NAME, mode)
+            .and_then(|transaction| transaction.object_store(Self::STORE_NAME))
+            .map_err(idb_error)
+    }
+}
+
+impl StorageBackend for IndexedDbBackend {
+    fn get(&self, key: &'static str) -> LocalBoxFuture<'_, Result<Option<Vec<u8>>, JsError>> {
+        async move {
+            let request = self
+                .object_store(IdbTransactionMode::Readonly)?
+                .get(&JsValue::from_str(key))
+                .map_err(idb_error)?;
+            let value = await_request(&request).await?;
+
+            if value.is_undefined() {
+                return Ok(None);
+            }
+            Ok(Some(Uint8Array::new(&value).to_vec()))
+        }
+        .boxed_local()
+    }
+
+    fn put(&self, key: &'static str, value: Vec<u8>) -> LocalBoxFuture<'_, Result<(), JsError>> {
+        async move {
+            let request = self
+                .object_store(IdbTransactionMode::Readwrite)?
+                .put_with_key(&Uint8Array::from(&value[..]), &JsValue::from_str(key))
+                .map_err(idb_error)?;
+            await_request(&request).await?;
+            Ok(())
+        }
+        .boxed_local()
+    }
+}
+
+/// Storage backend that keeps the client state in memory. Clones share the same storage, which
+/// allows tests to simulate a restart of the client.
+#[derive(Clone, Default)]
+pub struct MemoryBackend {
+    entries: Rc<RefCell<HashMap<&'static str, Vec<u8>>>>,
+}
+
+impl StorageBackend for MemoryBackend {
+    fn get(&self, key: &'static str) -> LocalBoxFuture<'_, Result<Option<Vec<u8>>, JsError>> {
+        let value = self.entries.borrow().get(key).cloned();
+        async move { Ok(value) }.boxed_local()
+    }
+
+    fn put(&self, key: &'static str, value: Vec<u8>) -> LocalBoxFuture<'_, Result<(), JsError>> {
+        self.entries.borrow_mut().insert(key, value);
+        async move { Ok(()) }.boxed_local()
+    }
+}
+
+/// Waits for the given IndexedDB request to succeed and returns its result.
+async fn await_request(request: &IdbRequest) -> Result<JsValue, JsError> {
+    let promise = Promise::new(&mut |resolve, reject| {
+        request.set_onsuccess(Some(&resolve));
+        request.set_onerror(Some(&reject));
+    });
+    let result = JsFuture::from(promise).await;
+
+    request.set_onsuccess(None);
+    request.set_onerror(None);
+    result.map_err(idb_error)?;
+
+    request.result().map_err(idb_error)
+}
+
+fn idb_error(error: JsValue) -> JsError {
+    JsError::new(&format!("IndexedDB request failed: {:?}", error))
+}
+
+/// The client state that was persisted by a previous run of the client.
+#[derive(Default)]
+pub struct PersistedState {
+    pub zk_proof: Option<ZKProof>,
+    pub zkp_election_block: Option<MacroBlock>,
+    pub macro_head: Option<MacroBlock>,
+    pub peers: Vec<String>,
+}
+
+/// Persists the light client state: the ZK proof with its election block, the latest macro block
+/// and the addresses of known peers.
+///
+/// The state is cached in memory and written to the storage backend in the background, so that it
+/// can be used as a `ProofStore` of the ZKP component, which requires synchronous access.
+#[derive(Clone)]
+pub struct ClientStore {
+    zk_proof: Arc<RwLock<Option<ZKProof>>>,
+    zkp_election_block: Arc<RwLock<Option<MacroBlock>>>,
+    peers: Arc<RwLock<Vec<String>>>,
+    writes: UnboundedSender<(&'static str, Vec<u8>)>,
+}
+
+impl ClientStore {
+    /// Loads the persisted state from the backend and starts writing changes back to it.
+    /// Entries that can't be read or decoded are ignored.
+    pub async fn load<B: StorageBackend + 'static>(backend: B) -> (Self, PersistedState) {
+        let state = PersistedState {
+            zk_proof: read(&backend, ZKP_KEY).await,
+            zkp_election_block: read(&backend, ZKP_ELECTION_BLOCK_KEY).await,
+            macro_head: read(&backend, MACRO_HEAD_KEY).await,
+            peers: read(&backend, PEERS_KEY).await.unwrap_or_default(),
+        };
+
+        let (writes, receiver) = unbounded_channel();
+        spawn_local(Self::write_to_backend(backend, receiver));
+
+        let store = Self {
+            zk_proof: Arc::new(RwLock::new(state.zk_proof.clone())),
+            zkp_election_block: Arc::new(RwLock::new(state.zkp_election_block.clone())),
+            peers: Arc::new(RwLock::new(state.peers.clone())),
+            writes,
+        };
+        (store, state)
+    }
+
+    async fn write_to_backend<B: StorageBackend>(
+        backend: B,
+        mut receiver: UnboundedReceiver<(&'static str, Vec<u8>)>,
+    ) {
+        while let Some((key, value)) = receiver.recv().await {
+            if let Err(error) = backend.put(key, value).await {
+                log::warn!(key, ?error, "Failed to persist client state");
+            }
+        }
+    }
+
+    fn write<T: Serialize>(&self, key: &'static str, value: &T) {
+        let _ = self.writes.send((key, value.serialize_to_vec()));
+    }
+
+    /// Persists the latest macro block of the blockchain.
+    pub fn set_macro_head(&self, block: &MacroBlock) {
+        self.write(MACRO_HEAD_KEY, block);
+    }
+
+    /// Remembers the address of a peer we connected to. The most recently seen addresses are
+    /// kept, up to `MAX_PERSISTED_PEERS`.
+    pub fn add_peer(&self, address: String) {
+        let mut peers = self.peers.write();
+        peers.retain(|peer| *peer != address);
+        peers.insert(0, address);
+        peers.truncate(MAX_PERSISTED_PEERS);
+        self.write(PEERS_KEY, &*peers);
+    }
+}
+
+async fn read<B: StorageBackend, T: Deserialize>(backend: &B, key: &'static str) -> Option<T> {
+    match backend.get(key).await {
+        Ok(Some(value)) => match T::deserialize_from_vec(&value) {
+            Ok(value) => Some(value),
+            Err(error) => {
+                log::warn!(key, ?error, "Failed to decode persisted client state");
+                None
+            }
+        },
+        Ok(None) => None,
+        Err(error) => {
+            log::warn!(key, ?error, "Failed to read persisted client state");
+            None
+        }
+    }
+}
+
+impl ProofStore for ClientStore {
+    fn get_zkp(&self) -> Option<ZKProof> {
+        self.zk_proof.read().clone()
+    }
+
+    fn set_zkp(&self, zk_proof: &ZKProof) {
+        *self.zk_proof.write() = Some(zk_proof.clone());
+        self.write(ZKP_KEY, zk_proof);
+    }
+
+    fn get_zkp_at(&self, block_number: u32) -> Option<ZKProof> {
+        self.get_zkp()
+            .filter(|zk_proof| zk_proof.block_number == block_number)
+    }
+
+    fn set_zkp_state(&self, zkp_state: &ZKPState) {
+        *self.zkp_election_block.write() = Some(zkp_state.latest_block.clone());
+        self.write(ZKP_ELECTION_BLOCK_KEY, &zkp_state.latest_block);
+        self.set_zkp(&zkp_state.clone().into());
+    }
+
+    fn get_zkp_election_block(&self) -> Option<MacroBlock> {
+        self.zkp_election_block.read().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nimiq_genesis::NetworkInfo;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test]
+    async fn it_can_resume_the_persisted_state() {
+        let backend = MemoryBackend::default();
+        let genesis_block = NetworkInfo::from_network_id(NetworkId::DevAlbatross)
+            .genesis_block()
+            .unwrap_macro();
+
+        let (store, state) = ClientStore::load(backend.clone()).await;
+        assert!(state.zk_proof.is_none());
+        assert!(state.peers.is_empty());
+
+        let zkp_state = ZKPState::with_genesis(&genesis_block).unwrap();
+        store.set_zkp_state(&zkp_state);
+        store.set_macro_head(&genesis_block);
+        for i in 0..=MAX_PERSISTED_PEERS {
+            store.add_peer(format!("/dns4/peer{}.nimiq.local/tcp/8443/wss", i));
+        }
+        store.add_peer("/dns4/peer3.nimiq.local/tcp/8443/wss".to_string());
+
+        // Let the background task write the changes.
+        drop(store);
+        wasm_timer::Delay::new(std::time::Duration::from_millis(10))
+            .await
+            .unwrap();
+
+        let (store, state) = ClientStore::load(backend).await;
+        assert_eq!(state.zk_proof, Some(zkp_state.clone().into()));
+        assert_eq!(state.zkp_election_block, Some(genesis_block.clone()));
+        assert_eq!(state.macro_head, Some(genesis_block.clone()));
+        assert_eq!(state.peers.len(), MAX_PERSISTED_PEERS);
+        assert_eq!(state.peers[0], "/dns4/peer3.nimiq.local/tcp/8443/wss");
+        assert!(!state
+            .peers
+            .contains(&"/dns4/peer0.nimiq.local/tcp/8443/wss".to_string()));
+
+        assert_eq!(store.get_zkp_election_block(), Some(genesis_block.clone()));
+        assert!(store.get_zkp_at(genesis_block.block_number()).is_some());
+        assert!(store.get_zkp_at(genesis_block.block_number() + 1).is_none());
+    }
+}
@@ -0,0 +1,575 @@
+use std::fmt;
+
+use nimiq_account::{Staker, Validator};
+use nimiq_keys::Address;
+use nimiq_primitives::{coin::Coin, networks::NetworkId, policy::Policy};
+use nimiq_transaction::Transaction;
+use nimiq_transaction_builder::{Recipient, Sender, TransactionBuilder, TransactionBuilderError};
+use tsify::Tsify;
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::transaction::PlainTransaction;
+
+/// A staking transaction picked by the staking helpers. Incoming staking transactions are sent
+/// from the staker address.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StakingOperation {
+    CreateStaker { delegation: Address, value: Coin },
+    AddStake { value: Coin },
+    UpdateStaker { new_delegation: Address },
+    SetActiveStake { new_active_balance: Coin },
+    RetireStake { value: Coin },
+    RemoveStake { recipient: Address, value: Coin },
+}
+
+/// The transactions to send now, in order, and the block height from which on the next step of
+/// the staking operation can be taken, if there is one.
+#[derive(Debug, PartialEq, Eq)]
+pub struct StakingPlan {
+    pub operations: Vec<StakingOperation>,
+    pub next_step_at: Option<u32>,
+}
+
+/// Why a staking operation can't be performed in the current staker state.
+#[derive(Debug, PartialEq, Eq)]
+pub enum StakingError {
+    InvalidValue,
+    NoStaker,
+    ValidatorNotFound(Address),
+    ValidatorRetired(Address),
+    AlreadyDelegated(Address),
+    DelegatedToOtherValidator(Address),
+    InsufficientBalance { available: Coin },
+    BelowMinimumStake { remaining: Coin },
+    StakeLocked { wait_until: u32 },
+    NothingToWithdraw,
+}
+
+impl fmt::Display for StakingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StakingError::InvalidValue => write!(f, "The value must be greater than zero"),
+            StakingError::NoStaker => write!(f, "The address is not a staker"),
+            StakingError::ValidatorNotFound(address) => {
+                write!(f, "Validator {} does not exist", address)
+            }
+            StakingError::ValidatorRetired(address) => {
+                write!(f, "Validator {} is retired", address)
+            }
+            StakingError::AlreadyDelegated(address) => {
+                write!(f, "The stake is already delegated to validator {}", address)
+            }
+            StakingError::DelegatedToOtherValidator(address) => write!(
+                f,
+                "The stake is delegated to validator {}, switch the validator first",
+                address
+            ),
+            StakingError::InsufficientBalance { available } => write!(
+                f,
+                "Insufficient stake, only {} luna are available",
+                u64::from(*available)
+            ),
+            StakingError::BelowMinimumStake { remaining } => write!(
+                f,
+                "The remaining stake of {} luna would be below the minimum stake of {} luna",
+                u64::from(*remaining),
+                Policy::MINIMUM_STAKE
+            ),
+            StakingError::StakeLocked { wait_until } => write!(
+                f,
+                "The inactive stake is locked until block #{}",
+                wait_until
+            ),
+            StakingError::NothingToWithdraw => write!(f, "There is no retired stake to withdraw"),
+        }
+    }
+}
+
+/// Returns the block height from which on the inactive stake of the staker is released, i.e. the
+/// reporting window of its inactivation has passed and its validator is not jailed anymore.
+fn release_height(
+    staker: &Staker,
+    validator: Option<&Validator>,
+    inactive_from: Option<u32>,
+) -> u32 {
+    // Stake that is not delegated is never locked.
+    if staker.delegation.is_none() {
+        return 0;
+    }
+
+    let inactive_release = inactive_from
+        .map(Policy::block_after_reporting_window)
+        .unwrap_or(0);
+    let jail_release = validator
+        .and_then(|validator| validator.jailed_from)
+        .map(Policy::block_after_jail)
+        .unwrap_or(0);
+
+    inactive_release.max(jail_release)
+}
+
+/// Returns the release height of stake that is deactivated at the given block height.
+fn release_height_after_deactivation(
+    staker: &Staker,
+    validator: Option<&Validator>,
+    block_number: u32,
+) -> u32 {
+    let inactive_from = Policy::election_block_after(block_number);
+    release_height(staker, validator, Some(inactive_from))
+}
+
+fn check_validator(address: &Address, validator: Option<&Validator>) -> Result<(), StakingError> {
+    match validator {
+        None => Err(StakingError::ValidatorNotFound(address.clone())),
+        Some(validator) if validator.retired => {
+            Err(StakingError::ValidatorRetired(address.clone()))
+        }
+        Some(_) => Ok(()),
+    }
+}
+
+/// Stakes `value` for the given validator, creating the staker if necessary.
+pub fn plan_stake(
+    staker: Option<&Staker>,
+    validator_address: &Address,
+    validator: Option<&Validator>,
+    value: Coin,
+) -> Result<StakingPlan, StakingError> {
+    if value.is_zero() {
+        return Err(StakingError::InvalidValue);
+    }
+    check_validator(validator_address, validator)?;
+
+    let operations = match staker {
+        None => {
+            if value < Coin::from_u64_unchecked(Policy::MINIMUM_STAKE) {
+                return Err(StakingError::BelowMinimumStake { remaining: value });
+            }
+            vec![StakingOperation::CreateStaker {
+                delegation: validator_address.clone(),
+                value,
+            }]
+        }
+        Some(staker) => match &staker.delegation {
+            Some(delegation) if delegation == validator_address => {
+                vec![StakingOperation::AddStake { value }]
+            }
+            Some(delegation) => {
+                return Err(StakingError::DelegatedToOtherValidator(delegation.clone()))
+            }
+            // An undelegated staker can delegate right away.
+            None => vec![
+                StakingOperation::UpdateStaker {
+                    new_delegation: validator_address.clone(),
+                },
+                StakingOperation::AddStake { value },
+            ],
+        },
+    };
+
+    Ok(StakingPlan {
+        operations,
+        next_step_at: None,
+    })
+}
+
+/// Delegates the stake to another validator. Stake can only be re-delegated once all of it is
+/// inactive and released, so active stake is deactivated first.
+pub fn plan_switch_validator(
+    staker: Option<&Staker>,
+    current_validator: Option<&Validator>,
+    validator_address: &Address,
+    validator: Option<&Validator>,
+    block_number: u32,
+) -> Result<StakingPlan, StakingError> {
+    let staker = staker.ok_or(StakingError::NoStaker)?;
+    check_validator(validator_address, validator)?;
+
+    if staker.delegation.as_ref() == Some(validator_address) {
+        return Err(StakingError::AlreadyDelegated(validator_address.clone()));
+    }
+
+    if !staker.active_balance.is_zero() {
+        return Ok(StakingPlan {
+            operations: vec![StakingOperation::SetActiveStake {
+                new_active_balance: Coin::ZERO,
+            }],
+            next_step_at: Some(release_height_after_deactivation(
+                staker,
+                current_validator,
+                block_number,
+            )),
+        });
+    }
+
+    let wait_until = release_height(staker, current_validator, staker.inactive_from);
+    if block_number < wait_until {
+        return Err(StakingError::StakeLocked { wait_until });
+    }
+
+    Ok(StakingPlan {
+        operations: vec![StakingOperation::UpdateStaker {
+            new_delegation: validator_address.clone(),
+        }],
+        next_step_at: None,
+    })
+}
+
+/// Unstakes `value` by retiring it, so that it can be withdrawn. Active stake is deactivated
+/// first if there is not enough inactive stake.
+pub fn plan_unstake(
+    staker: Option<&Staker>,
+    current_validator: Option<&Validator>,
+    value: Coin,
+    block_number: u32,
+) -> Result<StakingPlan, StakingError> {
+    let staker = staker.ok_or(StakingError::NoStaker)?;
+    if value.is_zero() {
+        return Err(StakingError::InvalidValue);
+    }
+
+    let available = staker.active_balance + staker.inactive_balance;
+    if value > available {
+        return Err(StakingError::InsufficientBalance { available });
+    }
+
+    let remaining = available - value;
+    if !remaining.is_zero() && remaining < Coin::from_u64_unchecked(Policy::MINIMUM_STAKE) {
+        return Err(StakingError::BelowMinimumStake { remaining });
+    }
+
+    if staker.inactive_balance < value {
+        let missing = value - staker.inactive_balance;
+        return Ok(StakingPlan {
+            operations: vec![StakingOperation::SetActiveStake {
+                new_active_balance: staker.active_balance - missing,
+            }],
+            next_step_at: Some(release_height_after_deactivation(
+                staker,
+                current_validator,
+                block_number,
+            )),
+        });
+    }
+
+    let wait_until = release_height(staker, current_validator, staker.inactive_from);
+    if block_number < wait_until {
+        return Err(StakingError::StakeLocked { wait_until });
+    }
+
+    Ok(StakingPlan {
+        operations: vec![StakingOperation::RetireStake { value }],
+        next_step_at: Some(block_number + 1),
+    })
+}
+
+/// Withdraws the retired stake to the recipient. Only the whole retired balance can be withdrawn.
+pub fn plan_withdraw(
+    staker: Option<&Staker>,
+    recipient: &Address,
+) -> Result<StakingPlan, StakingError> {
+    let staker = staker.ok_or(StakingError::NoStaker)?;
+    if staker.retired_balance.is_zero() {
+        return Err(StakingError::NothingToWithdraw);
+    }
+
+    Ok(StakingPlan {
+        operations: vec![StakingOperation::RemoveStake {
+            recipient: recipient.clone(),
+            value: staker.retired_balance,
+        }],
+        next_step_at: None,
+    })
+}
+
+impl StakingOperation {
+    /// Builds the unsigned transaction for this operation.
+    pub fn to_transaction(
+        &self,
+        staker_address: &Address,
+        fee: Coin,
+        validity_start_height: u32,
+        network_id: NetworkId,
+    ) -> Result<Transaction, TransactionBuilderError> {
+        let mut staking_recipient = Recipient::new_staking_builder();
+        let (sender, recipient, value) = match self {
+            StakingOperation::CreateStaker { delegation, value } => {
+                staking_recipient.create_staker(Some(delegation.clone()));
+                (None, None, *value)
+            }
+            StakingOperation::AddStake { value } => {
+                staking_recipient.stake(staker_address.clone());
+                (None, None, *value)
+            }
+            StakingOperation::UpdateStaker { new_delegation } => {
+                staking_recipient.update_staker(Some(new_delegation.clone()), true);
+                (None, None, Coin::ZERO)
+            }
+            StakingOperation::SetActiveStake { new_active_balance } => {
+                staking_recipient.set_active_stake(*new_active_balance);
+                (None, None, Coin::ZERO)
+            }
+            StakingOperation::RetireStake { value } => {
+                staking_recipient.retire_stake(*value);
+                (None, None, Coin::ZERO)
+            }
+            StakingOperation::RemoveStake { recipient, value } => (
+                Sender::new_staking_builder().remove_stake().generate(),
+                Some(Recipient::new_basic(recipient.clone())),
+                *value,
+            ),
+        };
+
+        // Incoming staking transactions are sent from the staker address.
+        let sender = sender.unwrap_or_else(|| Sender::new_basic(staker_address.clone()));
+        let recipient = recipient.unwrap_or_else(|| staking_recipient.generate().unwrap());
+
+        let mut builder = TransactionBuilder::new();
+        builder
+            .with_sender(sender)
+            .with_recipient(recipient)
+            .with_value(value)
+            .with_fee(fee)
+            .with_validity_start_height(validity_start_height)
+            .with_network_id(network_id);
+
+        let proof_builder = builder.generate()?;
+        Ok(proof_builder.preliminary_transaction().to_owned())
+    }
+}
+
+/// The transactions of a staking operation that have to be sent now, in order, and the block height
+/// from which on the next step of the operation can be taken, if there is one.
+#[derive(serde::Serialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub struct PlainStakingPlan {
+    /// The unsigned transactions to send now, in order.
+    pub transactions: Vec<PlainTransaction>,
+    /// The block height from which on the next step of the operation can be taken, e.g. the
+    /// height at which deactivated stake is released and can be retired or re-delegated.
+    #[tsify(optional)]
+    pub next_step_at: Option<u32>,
+}
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "PlainStakingPlan")]
+    pub type PlainStakingPlanType;
+}
+
+#[cfg(test)]
+mod tests {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    fn staker(active: u64, inactive: u64, inactive_from: Option<u32>) -> Staker {
+        Staker {
+            address: Address::from([1u8; Address::SIZE]),
+            active_balance: Coin::from_u64_unchecked(active),
+            inactive_balance: Coin::from_u64_unchecked(inactive),
+            inactive_from,
+            retired_balance: Coin::ZERO,
+            delegation: Some(Address::from([2u8; Address::SIZE])),
+        }
+    }
+
+    fn validator(jailed_from: Option<u32>) -> Validator {
+        Validator {
+            address: Address::from([2u8; Address::SIZE]),
+            signing_key: Default::default(),
+            voting_key: Default::default(),
+            reward_address: Address::default(),
+            signal_data: None,
+            total_stake: Coin::ZERO,
+            deposit: Coin::ZERO,
+            num_stakers: 1,
+            inactive_from: None,
+            jailed_from,
+            retired: false,
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn it_stakes_with_new_and_existing_stakers() {
+        let validator_address = Address::from([2u8; Address::SIZE]);
+        let other_address = Address::from([3u8; Address::SIZE]);
+        let minimum = Coin::from_u64_unchecked(Policy::MINIMUM_STAKE);
+
+        assert_eq!(
+            plan_stake(None, &validator_address, Some(&validator(None)), minimum)
+                .unwrap()
+                .operations,
+            vec![StakingOperation::CreateStaker {
+                delegation: validator_address.clone(),
+                value: minimum,
+            }]
+        );
+        assert!(matches!(
+            plan_stake(
+                None,
+                &validator_address,
+                Some(&validator(None)),
+                Coin::from_u64_unchecked(1)
+            ),
+            Err(StakingError::BelowMinimumStake { .. })
+        ));
+        assert_eq!(
+            plan_stake(None, &other_address, None, minimum),
+            Err(StakingError::ValidatorNotFound(other_address.clone()))
+        );
+
+        let staker = staker(Policy::MINIMUM_STAKE, 0, None);
+        assert_eq!(
+            plan_stake(
+                Some(&staker),
+                &validator_address,
+                Some(&validator(None)),
+                minimum
+            )
+            .unwrap()
+            .operations,
+            vec![StakingOperation::AddStake { value: minimum }]
+        );
+        assert_eq!(
+            plan_stake(
+                Some(&staker),
+                &other_address,
+                Some(&validator(None)),
+                minimum
+            ),
+            Err(StakingError::DelegatedToOtherValidator(validator_address))
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn it_deactivates_stake_before_switching_validators() {
+        let new_validator = Address::from([3u8; Address::SIZE]);
+        let block_number = Policy::blocks_per_epoch() + 1;
+
+        let active = staker(Policy::MINIMUM_STAKE, 0, None);
+        let plan = plan_switch_validator(
+            Some(&active),
+            Some(&validator(None)),
+            &new_validator,
+            Some(&validator(None)),
+            block_number,
+        )
+        .unwrap();
+        let release =
+            Policy::block_after_reporting_window(Policy::election_block_after(block_number));
+        assert_eq!(
+            plan.operations,
+            vec![StakingOperation::SetActiveStake {
+                new_active_balance: Coin::ZERO
+            }]
+        );
+        assert_eq!(plan.next_step_at, Some(release));
+
+        let inactive = staker(
+            0,
+            Policy::MINIMUM_STAKE,
+            Some(Policy::election_block_after(block_number)),
+        );
+        assert_eq!(
+            plan_switch_validator(
+                Some(&inactive),
+                Some(&validator(None)),
+                &new_validator,
+                Some(&validator(None)),
+                block_number,
+            ),
+            Err(StakingError::StakeLocked {
+                wait_until: release
+            })
+        );
+        assert_eq!(
+            plan_switch_validator(
+                Some(&inactive),
+                Some(&validator(None)),
+                &new_validator,
+                Some(&validator(None)),
+                release,
+            )
+            .unwrap()
+            .operations,
+            vec![StakingOperation::UpdateStaker {
+                new_delegation: new_validator.clone()
+            }]
+        );
+
+        // A jailed validator keeps the stake locked until the jail is over.
+        let jail_release = Policy::block_after_jail(release);
+        assert_eq!(
+            plan_switch_validator(
+                Some(&inactive),
+                Some(&validator(Some(release))),
+                &new_validator,
+                Some(&validator(None)),
+                release,
+            ),
+            Err(StakingError::StakeLocked {
+                wait_until: jail_release
+            })
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn it_unstakes_and_withdraws() {
+        let block_number = Policy::blocks_per_epoch() + 1;
+        let minimum = Coin::from_u64_unchecked(Policy::MINIMUM_STAKE);
+
+        let active = staker(2 * Policy::MINIMUM_STAKE, 0, None);
+        assert_eq!(
+            plan_unstake(Some(&active), None, minimum, block_number)
+                .unwrap()
+                .operations,
+            vec![StakingOperation::SetActiveStake {
+                new_active_balance: minimum
+            }]
+        );
+        assert!(matches!(
+            plan_unstake(
+                Some(&active),
+                None,
+                minimum + minimum + minimum,
+                block_number
+            ),
+            Err(StakingError::InsufficientBalance { .. })
+        ));
+        assert!(matches!(
+            plan_unstake(
+                Some(&active),
+                None,
+                Coin::from_u64_unchecked(1),
+                block_number
+            ),
+            Err(StakingError::BelowMinimumStake { .. })
+        ));
+
+        let inactive = staker(0, Policy::MINIMUM_STAKE, Some(0));
+        assert_eq!(
+            plan_unstake(Some(&inactive), None, minimum, block_number)
+                .unwrap()
+                .operations,
+            vec![StakingOperation::RetireStake { value: minimum }]
+        );
+
+        let recipient = Address::from([4u8; Address::SIZE]);
+        assert_eq!(
+            plan_withdraw(Some(&inactive), &recipient),
+            Err(StakingError::NothingToWithdraw)
+        );
+        let mut retired = inactive;
+        retired.retired_balance = minimum;
+        assert_eq!(
+            plan_withdraw(Some(&retired), &recipient)
+                .unwrap()
+                .operations,
+            vec![StakingOperation::RemoveStake {
+                recipient,
+                value: minimum
+            }]
+        );
+    }
+}
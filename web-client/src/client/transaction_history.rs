@@ -0,0 +1,217 @@
+use std::collections::{BTreeSet, HashMap};
+
+use nimiq_hash::Blake2bHash;
+use nimiq_keys::Address;
+use nimiq_primitives::policy::Policy;
+use nimiq_transaction::{
+    account::staking_contract::IncomingStakingTransactionData,
+    historic_transaction::HistoricTransaction, Transaction,
+};
+
+/// A cache of verified transactions, indexed by the addresses they involve.
+///
+/// Transactions that are included in a block that is not yet finalized by a macro block are
+/// tracked until they are either confirmed by a macro block or reverted by a rebranch.
+#[derive(Default)]
+pub struct TransactionHistory {
+    /// The cached transactions by their hash.
+    transactions: HashMap<Blake2bHash, HistoricTransaction>,
+    /// The block numbers and hashes of the cached transactions of each address.
+    by_address: HashMap<Address, BTreeSet<(u32, Blake2bHash)>>,
+    /// The block numbers and hashes of the cached transactions that are not yet confirmed.
+    unconfirmed: BTreeSet<(u32, Blake2bHash)>,
+}
+
+impl TransactionHistory {
+    /// Adds a verified transaction to the cache. Transactions with a block number after the
+    /// `last_macro_block` are tracked until they are confirmed or reverted.
+    ///
+    /// Returns `false` if the transaction was already cached.
+    pub fn insert(&mut self, hist_tx: HistoricTransaction, last_macro_block: u32) -> bool {
+        let hash: Blake2bHash = hist_tx.tx_hash().into();
+        if self.transactions.contains_key(&hash) {
+            return false;
+        }
+
+        let entry = (hist_tx.block_number, hash.clone());
+        if let Ok(executed_tx) = hist_tx.clone().into_transaction() {
+            for address in transaction_addresses(executed_tx.get_raw_transaction()) {
+                self.by_address
+                    .entry(address)
+                    .or_default()
+                    .insert(entry.clone());
+            }
+        }
+        if hist_tx.block_number > last_macro_block {
+            self.unconfirmed.insert(entry);
+        }
+
+        self.transactions.insert(hash, hist_tx);
+        true
+    }
+
+    /// Returns the hashes of the confirmed transactions of the given address, which don't need to
+    /// be fetched again.
+    pub fn confirmed_hashes(&self, address: &Address) -> Vec<Blake2bHash> {
+        self.by_address
+            .get(address)
+            .map(|entries| {
+                entries
+                    .iter()
+                    .filter(|entry| !self.unconfirmed.contains(entry))
+                    .map(|(_, hash)| hash.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Returns the cached transactions of the given address from newest to oldest, starting at the
+    /// given block height. Up to `limit` transactions are returned.
+    pub fn get(
+        &self,
+        address: &Address,
+        since_block_height: u32,
+        limit: Option<usize>,
+    ) -> Vec<&HistoricTransaction> {
+        let Some(entries) = self.by_address.get(address) else {
+            return vec![];
+        };
+
+        entries
+            .range((since_block_height, Blake2bHash::default())..)
+            .rev()
+            .take(limit.unwrap_or(usize::MAX))
+            .filter_map(|(_, hash)| self.transactions.get(hash))
+            .collect()
+    }
+
+    /// Marks the transactions up to the given macro block as confirmed and returns them.
+    pub fn confirm(&mut self, macro_block_number: u32) -> Vec<HistoricTransaction> {
+        let still_unconfirmed = self
+            .unconfirmed
+            .split_off(&(macro_block_number + 1, Blake2bHash::default()));
+        let confirmed = std::mem::replace(&mut self.unconfirmed, still_unconfirmed);
+
+        confirmed
+            .into_iter()
+            .filter_map(|(_, hash)| self.transactions.get(&hash).cloned())
+            .collect()
+    }
+
+    /// Removes the unconfirmed transactions from the given block on, which were reverted by a
+    /// rebranch, and returns them.
+    pub fn revert(&mut self, from_block_number: u32) -> Vec<HistoricTransaction> {
+        let reverted = self
+            .unconfirmed
+            .split_off(&(from_block_number, Blake2bHash::default()));
+
+        let mut reverted_txs = vec![];
+        for entry in reverted {
+            for entries in self.by_address.values_mut() {
+                entries.remove(&entry);
+            }
+            if let Some(hist_tx) = self.transactions.remove(&entry.1) {
+                reverted_txs.push(hist_tx);
+            }
+        }
+        self.by_address.retain(|_, entries| !entries.is_empty());
+
+        reverted_txs
+    }
+}
+
+/// Returns the addresses a transaction is relevant for: its sender, its recipient and, for stake
+/// that is added for someone else, the staker.
+pub fn transaction_addresses(tx: &Transaction) -> Vec<Address> {
+    let mut addresses = vec![tx.sender.clone(), tx.recipient.clone()];
+
+    if tx.recipient == Policy::STAKING_CONTRACT_ADDRESS {
+        if let Ok(IncomingStakingTransactionData::AddStake { staker_address }) =
+            IncomingStakingTransactionData::parse(tx)
+        {
+            addresses.push(staker_address);
+        }
+    }
+
+    addresses
+}
+
+#[cfg(test)]
+mod tests {
+    use nimiq_primitives::{coin::Coin, networks::NetworkId};
+    use nimiq_transaction::{historic_transaction::HistoricTransactionData, ExecutedTransaction};
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    fn historic_transaction(
+        sender: &Address,
+        recipient: &Address,
+        block_number: u32,
+    ) -> HistoricTransaction {
+        let tx = Transaction::new_basic(
+            sender.clone(),
+            recipient.clone(),
+            Coin::from_u64_unchecked(block_number as u64),
+            Coin::ZERO,
+            block_number,
+            NetworkId::UnitAlbatross,
+        );
+        HistoricTransaction {
+            network_id: NetworkId::UnitAlbatross,
+            block_number,
+            block_time: 0,
+            data: HistoricTransactionData::Basic(ExecutedTransaction::Ok(tx)),
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn it_can_page_through_the_cached_transactions() {
+        let alice = Address::from([1u8; Address::SIZE]);
+        let bob = Address::from([2u8; Address::SIZE]);
+        let mut history = TransactionHistory::default();
+
+        for block_number in 1..=5 {
+            assert!(history.insert(historic_transaction(&alice, &bob, block_number), 10));
+        }
+        assert!(!history.insert(historic_transaction(&alice, &bob, 3), 10));
+
+        let block_numbers = |txs: Vec<&HistoricTransaction>| -> Vec<u32> {
+            txs.iter().map(|hist_tx| hist_tx.block_number).collect()
+        };
+        assert_eq!(
+            block_numbers(history.get(&bob, 0, None)),
+            vec![5, 4, 3, 2, 1]
+        );
+        assert_eq!(block_numbers(history.get(&alice, 3, None)), vec![5, 4, 3]);
+        assert_eq!(block_numbers(history.get(&alice, 0, Some(2))), vec![5, 4]);
+        assert!(history.get(&Address::default(), 0, None).is_empty());
+        assert_eq!(history.confirmed_hashes(&alice).len(), 5);
+    }
+
+    #[wasm_bindgen_test]
+    fn it_confirms_and_reverts_unconfirmed_transactions() {
+        let alice = Address::from([1u8; Address::SIZE]);
+        let bob = Address::from([2u8; Address::SIZE]);
+        let mut history = TransactionHistory::default();
+
+        for block_number in 11..=14 {
+            history.insert(historic_transaction(&alice, &bob, block_number), 10);
+        }
+        assert!(history.confirmed_hashes(&alice).is_empty());
+
+        let confirmed = history.confirm(12);
+        assert_eq!(confirmed.len(), 2);
+        assert_eq!(history.confirmed_hashes(&alice).len(), 2);
+        assert!(history.confirm(12).is_empty());
+
+        let reverted = history.revert(14);
+        assert_eq!(reverted.len(), 1);
+        assert_eq!(reverted[0].block_number, 14);
+        assert_eq!(history.get(&alice, 0, None).len(), 3);
+
+        // Confirmed transactions are never reverted.
+        assert_eq!(history.revert(0).len(), 1);
+        assert_eq!(history.get(&bob, 0, None).len(), 2);
+    }
+}
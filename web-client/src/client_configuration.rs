@@ -21,6 +21,8 @@ pub struct ClientConfiguration {
     pub seed_nodes: Vec<String>,
     #[wasm_bindgen(skip)]
     pub log_level: String,
+    #[wasm_bindgen(skip)]
+    pub persistence: bool,
 }
 
 #[cfg(any(feature = "client", feature = "primitives"))]
@@ -38,6 +40,8 @@ pub struct PlainClientConfiguration {
     pub seed_nodes: Option<Vec<String>>,
     #[cfg_attr(feature = "client", serde(skip_serializing_if = "Option::is_none"))]
     pub log_level: Option<String>,
+    #[cfg_attr(feature = "client", serde(skip_serializing_if = "Option::is_none"))]
+    pub persistence: Option<bool>,
 }
 
 impl Default for ClientConfiguration {
@@ -46,6 +50,7 @@ impl Default for ClientConfiguration {
             network_id: NetworkId::TestAlbatross,
             seed_nodes: vec!["/dns4/seed1.pos.nimiq-testnet.com/tcp/8443/wss".to_string()],
             log_level: "info".to_string(),
+            persistence: true,
         }
     }
 }
@@ -91,6 +96,15 @@ impl ClientConfiguration {
         self.log_level = log_level.to_lowercase();
     }
 
+    /// Sets whether the client persists its state, such as the latest verified election block,
+    /// the ZK proof and known peers, in the browser's IndexedDB. A client with persistence resumes
+    /// from the persisted state on the next start instead of syncing from scratch.
+    ///
+    /// Default is `true`.
+    pub fn persistence(&mut self, enabled: bool) {
+        self.persistence = enabled;
+    }
+
     // TODO: Find a way to make this method work, maybe by using the synthetic Client from the main thread as an import?
     // /// Instantiates a client from this configuration builder.
     // #[wasm_bindgen(js_name = instantiateClient)]
@@ -107,6 +121,7 @@ impl ClientConfiguration {
             network_id: Some(self.network_id.to_string()),
             seed_nodes: Some(self.seed_nodes.clone()),
             log_level: Some(self.log_level.clone()),
+            persistence: Some(self.persistence),
         })
         .unwrap()
         .into()
@@ -133,6 +148,10 @@ impl TryFrom<PlainClientConfiguration> for ClientConfiguration {
             client_config.log_level = log_level;
         }
 
+        if let Some(persistence) = config.persistence {
+            client_config.persistence = persistence;
+        }
+
         Ok(client_config)
     }
 }
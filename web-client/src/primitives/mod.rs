@@ -6,8 +6,10 @@ pub mod es256_signature;
 pub mod hash;
 pub mod key_pair;
 pub mod merkle_tree;
+pub mod multisig;
 pub mod private_key;
 pub mod public_key;
 pub mod signature;
 pub mod signature_proof;
+pub mod signed_message;
 pub mod transaction_builder;
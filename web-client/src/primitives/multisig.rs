@@ -0,0 +1,569 @@
+use js_sys::Array;
+use nimiq_hash::Blake2bHasher;
+use nimiq_keys::{
+    multisig::{
+        address::{combine_public_keys, compute_address},
+        commitment::{Commitment, CommitmentPair},
+        CommitmentsBuilder, CommitmentsData, MUSIG2_PARAMETER_V,
+    },
+    SecureGenerate,
+};
+use nimiq_utils::merkle::Blake2bMerklePath;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_derive::TryFromJsValue;
+
+use crate::{
+    address::Address,
+    primitives::{key_pair::KeyPair, public_key::PublicKey, signature_proof::SignatureProof},
+    transaction::Transaction,
+};
+
+/// A multi-signature account requires a minimum number of its owners to sign outgoing transactions.
+///
+/// Signing uses MuSig2 in two rounds: first, each signer creates {@link CommitmentPairs} and shares
+/// their public commitments with the other signers. Then each signer creates a
+/// {@link PartialSignature}, and one of them combines all partial signatures into the final
+/// signature proof with `createProof` or `signTransaction`.
+#[wasm_bindgen]
+pub struct MultiSigAccount {
+    key_pair: nimiq_keys::KeyPair,
+    min_signatures: u8,
+    /// The aggregated public keys of all combinations of `min_signatures` owners.
+    combined_public_keys: Vec<nimiq_keys::Ed25519PublicKey>,
+}
+
+#[wasm_bindgen]
+impl MultiSigAccount {
+    /// Creates the multi-signature account of the owners' public keys that requires
+    /// `min_signatures` of them to sign. The key pair is the one of the owner using this account.
+    ///
+    /// Throws when the key pair is not one of the owners or `min_signatures` is not between 1 and
+    /// the number of owners.
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        key_pair: &KeyPair,
+        min_signatures: u8,
+        public_keys: &PublicKeyArray,
+    ) -> Result<MultiSigAccount, JsError> {
+        let public_keys = MultiSigAccount::unpack_public_keys(public_keys)?;
+        if !public_keys.contains(&key_pair.native_ref().public) {
+            return Err(JsError::new(
+                "The public key of the key pair must be one of the public keys",
+            ));
+        }
+
+        Ok(MultiSigAccount {
+            key_pair: key_pair.native_ref().clone(),
+            min_signatures,
+            combined_public_keys: MultiSigAccount::combine(min_signatures, public_keys)?,
+        })
+    }
+
+    /// Computes the address of the multi-signature account of the owners' public keys that
+    /// requires `min_signatures` of them to sign.
+    ///
+    /// Throws when `min_signatures` is not between 1 and the number of owners.
+    #[wasm_bindgen(js_name = computeAddress)]
+    pub fn compute_address(
+        min_signatures: u8,
+        public_keys: &PublicKeyArray,
+    ) -> Result<Address, JsError> {
+        let public_keys = MultiSigAccount::unpack_public_keys(public_keys)?;
+        let combined_public_keys = MultiSigAccount::combine(min_signatures, public_keys)?;
+        Ok(Address::from(compute_address(&combined_public_keys)))
+    }
+
+    /// The address of the multi-signature account.
+    #[wasm_bindgen(getter)]
+    pub fn address(&self) -> Address {
+        Address::from(compute_address(&self.combined_public_keys))
+    }
+
+    /// The number of owners that need to sign a transaction.
+    #[wasm_bindgen(getter, js_name = minSignatures)]
+    pub fn min_signatures(&self) -> u8 {
+        self.min_signatures
+    }
+
+    /// Creates the partial signature of this owner for the transaction.
+    ///
+    /// The other signers are given by their public keys and the commitments they shared, in the
+    /// same order. The commitment pairs are consumed, as reusing them for another signature would
+    /// reveal the private key.
+    ///
+    /// Throws when the number of signers does not match the account's minimum number of signatures.
+    #[wasm_bindgen(js_name = partiallySignTransaction)]
+    pub fn partially_sign_transaction(
+        &self,
+        transaction: &Transaction,
+        own_commitment_pairs: CommitmentPairs,
+        other_public_keys: &PublicKeyArray,
+        other_commitments: &MultiSigCommitmentsArray,
+    ) -> Result<PartialSignature, JsError> {
+        let mut builder = CommitmentsBuilder::with_private_commitments(
+            self.key_pair.public,
+            own_commitment_pairs.inner,
+        );
+        let other_public_keys = MultiSigAccount::unpack_public_keys(other_public_keys)?;
+        let other_commitments = MultiSigAccount::unpack_commitments(other_commitments)?;
+        if other_public_keys.len() != other_commitments.len() {
+            return Err(JsError::new(
+                "Every signer's public key must have its commitments",
+            ));
+        }
+        for (public_key, commitments) in other_public_keys.into_iter().zip(other_commitments) {
+            builder.push_signer(public_key, commitments);
+        }
+
+        let commitments_data = self.build_commitments(builder, transaction)?;
+        let partial_signature = self
+            .key_pair
+            .partial_sign(&commitments_data, &transaction.serialize_content())?;
+        Ok(PartialSignature::from(partial_signature))
+    }
+
+    /// Combines the partial signatures of all signers into the signature proof for the
+    /// transaction.
+    ///
+    /// The signers, including this owner if it signed, are given by their public keys and the
+    /// commitments they shared, in the same order.
+    ///
+    /// Throws when the number of signers or partial signatures does not match the account's
+    /// minimum number of signatures.
+    #[wasm_bindgen(js_name = createProof)]
+    pub fn create_proof(
+        &self,
+        transaction: &Transaction,
+        signer_public_keys: &PublicKeyArray,
+        signer_commitments: &MultiSigCommitmentsArray,
+        partial_signatures: &PartialSignatureArray,
+    ) -> Result<SignatureProof, JsError> {
+        let signer_public_keys = MultiSigAccount::unpack_public_keys(signer_public_keys)?;
+        let signer_commitments = MultiSigAccount::unpack_commitments(signer_commitments)?;
+        let partial_signatures = MultiSigAccount::unpack_partial_signatures(partial_signatures)?;
+        if signer_public_keys.len() != signer_commitments.len() {
+            return Err(JsError::new(
+                "Every signer's public key must have its commitments",
+            ));
+        }
+        if partial_signatures.len() != self.min_signatures as usize {
+            return Err(JsError::new(
+                "The number of partial signatures must be the minimum number of signatures",
+            ));
+        }
+
+        let mut signers = signer_public_keys.into_iter().zip(signer_commitments);
+        let (public_key, commitments) = signers.next().unwrap();
+        let mut builder = CommitmentsBuilder::with_public_commitments(public_key, commitments);
+        for (public_key, commitments) in signers {
+            builder.push_signer(public_key, commitments);
+        }
+        let commitments_data = self.build_commitments(builder, transaction)?;
+
+        let signature = partial_signatures
+            .iter()
+            .sum::<nimiq_keys::multisig::partial_signature::PartialSignature>()
+            .to_signature(&commitments_data.aggregate_commitment);
+
+        Ok(SignatureProof::from(nimiq_transaction::SignatureProof {
+            merkle_path: Blake2bMerklePath::new::<Blake2bHasher, _>(
+                &self.combined_public_keys,
+                &commitments_data.aggregate_public_key,
+            ),
+            public_key: nimiq_keys::PublicKey::Ed25519(commitments_data.aggregate_public_key),
+            signature: nimiq_keys::Signature::Ed25519(signature),
+            webauthn_fields: None,
+        }))
+    }
+
+    /// Combines the partial signatures of all signers and sets the resulting signature proof on
+    /// the transaction. See `createProof` for the parameters.
+    #[wasm_bindgen(js_name = signTransaction)]
+    pub fn sign_transaction(
+        &self,
+        transaction: &mut Transaction,
+        signer_public_keys: &PublicKeyArray,
+        signer_commitments: &MultiSigCommitmentsArray,
+        partial_signatures: &PartialSignatureArray,
+    ) -> Result<(), JsError> {
+        let proof = self.create_proof(
+            transaction,
+            signer_public_keys,
+            signer_commitments,
+            partial_signatures,
+        )?;
+        transaction.set_proof(proof.serialize());
+        Ok(())
+    }
+}
+
+impl MultiSigAccount {
+    fn combine(
+        min_signatures: u8,
+        mut public_keys: Vec<nimiq_keys::Ed25519PublicKey>,
+    ) -> Result<Vec<nimiq_keys::Ed25519PublicKey>, JsError> {
+        // Duplicate public keys would be counted as separate owners.
+        public_keys.sort();
+        public_keys.dedup();
+        if min_signatures == 0 || min_signatures as usize > public_keys.len() {
+            return Err(JsError::new(
+                "The minimum number of signatures must be between 1 and the number of public keys",
+            ));
+        }
+
+        Ok(combine_public_keys(public_keys, min_signatures as usize))
+    }
+
+    /// Builds the commitments data for the transaction and checks that the signers are a valid
+    /// combination of owners of this account.
+    fn build_commitments(
+        &self,
+        builder: CommitmentsBuilder,
+        transaction: &Transaction,
+    ) -> Result<CommitmentsData, JsError> {
+        let commitments_data = builder.build(&transaction.serialize_content());
+
+        if commitments_data.all_public_keys.len() != self.min_signatures as usize {
+            return Err(JsError::new(
+                "The number of signers must be the minimum number of signatures",
+            ));
+        }
+        if !self
+            .combined_public_keys
+            .contains(&commitments_data.aggregate_public_key)
+        {
+            return Err(JsError::new("The signers must be owners of this account"));
+        }
+
+        Ok(commitments_data)
+    }
+
+    fn unpack_public_keys(
+        public_keys: &PublicKeyArray,
+    ) -> Result<Vec<nimiq_keys::Ed25519PublicKey>, JsError> {
+        MultiSigAccount::unpack_array(public_keys.unchecked_ref(), "public_keys")?
+            .iter()
+            .map(|item| {
+                PublicKey::try_from(&item)
+                    .map(|key| *key.native_ref())
+                    .map_err(|_| JsError::new("Invalid public key in array"))
+            })
+            .collect()
+    }
+
+    fn unpack_commitments(
+        commitments: &MultiSigCommitmentsArray,
+    ) -> Result<Vec<[Commitment; MUSIG2_PARAMETER_V]>, JsError> {
+        MultiSigAccount::unpack_array(commitments.unchecked_ref(), "commitments")?
+            .iter()
+            .map(|item| {
+                MultiSigCommitments::try_from(&item)
+                    .map(|commitments| commitments.inner)
+                    .map_err(|_| JsError::new("Invalid commitments in array"))
+            })
+            .collect()
+    }
+
+    fn unpack_partial_signatures(
+        partial_signatures: &PartialSignatureArray,
+    ) -> Result<Vec<nimiq_keys::multisig::partial_signature::PartialSignature>, JsError> {
+        MultiSigAccount::unpack_array(partial_signatures.unchecked_ref(), "partial_signatures")?
+            .iter()
+            .map(|item| {
+                PartialSignature::try_from(&item)
+                    .map(|partial_signature| partial_signature.inner)
+                    .map_err(|_| JsError::new("Invalid partial signature in array"))
+            })
+            .collect()
+    }
+
+    fn unpack_array<'a>(js_value: &'a JsValue, name: &str) -> Result<&'a Array, JsError> {
+        let array: &Array = js_value
+            .dyn_ref()
+            .ok_or_else(|| JsError::new(&format!("`{}` must be an array", name)))?;
+
+        if array.length() == 0 {
+            return Err(JsError::new(&format!("No {} provided", name)));
+        }
+
+        Ok(array)
+    }
+}
+
+/// The secret nonces and public commitments of a signer for a single MuSig2 signing session.
+///
+/// Only the commitments are shared with the other signers. The pairs must be kept secret and are
+/// consumed when signing, so they can't be used for more than one signature.
+#[wasm_bindgen]
+pub struct CommitmentPairs {
+    inner: [CommitmentPair; MUSIG2_PARAMETER_V],
+}
+
+#[wasm_bindgen]
+impl CommitmentPairs {
+    /// Generates new commitment pairs from secure randomness.
+    pub fn generate() -> CommitmentPairs {
+        let pairs: Vec<_> = (0..MUSIG2_PARAMETER_V)
+            .map(|_| CommitmentPair::generate_default_csprng())
+            .collect();
+        CommitmentPairs {
+            inner: pairs.try_into().unwrap(),
+        }
+    }
+
+    /// The public commitments to share with the other signers.
+    #[wasm_bindgen(getter)]
+    pub fn commitments(&self) -> MultiSigCommitments {
+        MultiSigCommitments {
+            inner: self.inner.map(|pair| pair.commitment()),
+        }
+    }
+}
+
+/// The public commitments of a signer for a single MuSig2 signing session.
+#[derive(TryFromJsValue)]
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct MultiSigCommitments {
+    inner: [Commitment; MUSIG2_PARAMETER_V],
+}
+
+impl MultiSigCommitments {
+    const SIZE: usize = Commitment::SIZE * MUSIG2_PARAMETER_V;
+}
+
+#[wasm_bindgen]
+impl MultiSigCommitments {
+    /// Deserializes commitments from a byte array.
+    ///
+    /// Throws when the byte array does not contain valid commitments.
+    pub fn unserialize(bytes: &[u8]) -> Result<MultiSigCommitments, JsError> {
+        if bytes.len() != Self::SIZE {
+            return Err(JsError::new("Invalid commitments length"));
+        }
+
+        let mut commitments = [Commitment::default(); MUSIG2_PARAMETER_V];
+        for (commitment, chunk) in commitments
+            .iter_mut()
+            .zip(bytes.chunks_exact(Commitment::SIZE))
+        {
+            *commitment = Commitment::from_bytes(chunk.try_into().unwrap())
+                .ok_or_else(|| JsError::new("Invalid commitment"))?;
+        }
+
+        Ok(MultiSigCommitments { inner: commitments })
+    }
+
+    /// Parses commitments from their hex representation.
+    ///
+    /// Throws when the string is not valid hex format or does not contain valid commitments.
+    #[wasm_bindgen(js_name = fromHex)]
+    pub fn from_hex(hex: &str) -> Result<MultiSigCommitments, JsError> {
+        MultiSigCommitments::unserialize(&hex::decode(hex)?)
+    }
+
+    /// Serializes the commitments to a byte array.
+    pub fn serialize(&self) -> Vec<u8> {
+        self.inner
+            .iter()
+            .flat_map(|commitment| commitment.to_bytes())
+            .collect()
+    }
+
+    /// Formats the commitments into a hex string.
+    #[wasm_bindgen(js_name = toHex)]
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.serialize())
+    }
+}
+
+/// The partial signature of one signer of a multi-signature account. The partial signatures of all
+/// signers are combined into the transaction's signature.
+#[derive(TryFromJsValue)]
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct PartialSignature {
+    inner: nimiq_keys::multisig::partial_signature::PartialSignature,
+}
+
+#[wasm_bindgen]
+impl PartialSignature {
+    /// Deserializes a partial signature from a byte array.
+    ///
+    /// Throws when the byte array does not contain exactly 32 bytes.
+    pub fn unserialize(bytes: &[u8]) -> Result<PartialSignature, JsError> {
+        let bytes: [u8; nimiq_keys::multisig::partial_signature::PartialSignature::SIZE] = bytes
+            .try_into()
+            .map_err(|_| JsError::new("Invalid partial signature length"))?;
+        Ok(PartialSignature::from(
+            nimiq_keys::multisig::partial_signature::PartialSignature::from(bytes),
+        ))
+    }
+
+    /// Parses a partial signature from its hex representation.
+    ///
+    /// Throws when the string is not valid hex format or does not represent 32 bytes.
+    #[wasm_bindgen(js_name = fromHex)]
+    pub fn from_hex(hex: &str) -> Result<PartialSignature, JsError> {
+        PartialSignature::unserialize(&hex::decode(hex)?)
+    }
+
+    /// Serializes the partial signature to a byte array.
+    pub fn serialize(&self) -> Vec<u8> {
+        self.inner.as_bytes().to_vec()
+    }
+
+    /// Formats the partial signature into a hex string.
+    #[wasm_bindgen(js_name = toHex)]
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.serialize())
+    }
+}
+
+impl From<nimiq_keys::multisig::partial_signature::PartialSignature> for PartialSignature {
+    fn from(partial_signature: nimiq_keys::multisig::partial_signature::PartialSignature) -> Self {
+        PartialSignature {
+            inner: partial_signature,
+        }
+    }
+}
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "PublicKey[]")]
+    pub type PublicKeyArray;
+
+    #[wasm_bindgen(typescript_type = "MultiSigCommitments[]")]
+    pub type MultiSigCommitmentsArray;
+
+    #[wasm_bindgen(typescript_type = "PartialSignature[]")]
+    pub type PartialSignatureArray;
+}
+
+#[cfg(test)]
+mod tests {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+    use crate::primitives::transaction_builder::TransactionBuilder;
+
+    fn array<T: Into<JsValue>>(items: Vec<T>) -> Array {
+        items.into_iter().map(Into::<JsValue>::into).collect()
+    }
+
+    #[wasm_bindgen_test]
+    pub fn it_can_sign_a_2_of_3_multisig_transaction() {
+        let key_pairs = [
+            KeyPair::generate(),
+            KeyPair::generate(),
+            KeyPair::generate(),
+        ];
+        let public_keys = || -> PublicKeyArray {
+            array(key_pairs.iter().map(|kp| kp.public_key()).collect()).unchecked_into()
+        };
+        let alice = MultiSigAccount::new(&key_pairs[0], 2, &public_keys())
+            .map_err(JsValue::from)
+            .unwrap();
+        let bob = MultiSigAccount::new(&key_pairs[1], 2, &public_keys())
+            .map_err(JsValue::from)
+            .unwrap();
+        let address = MultiSigAccount::compute_address(2, &public_keys())
+            .map_err(JsValue::from)
+            .unwrap();
+        assert_eq!(alice.address().native_ref(), address.native_ref());
+        assert_eq!(bob.address().native_ref(), address.native_ref());
+
+        let mut tx = TransactionBuilder::new_basic(
+            &address,
+            &key_pairs[2].to_address(),
+            100_00000,
+            None,
+            1,
+            5,
+        )
+        .map_err(JsValue::from)
+        .unwrap();
+
+        // Round 1: exchange commitments, which survive a round trip through bytes.
+        let alice_pairs = CommitmentPairs::generate();
+        let bob_pairs = CommitmentPairs::generate();
+        let alice_commitments = alice_pairs.commitments();
+        let bob_commitments = MultiSigCommitments::from_hex(&bob_pairs.commitments().to_hex())
+            .map_err(JsValue::from)
+            .unwrap();
+        assert_eq!(
+            bob_commitments.serialize(),
+            bob_pairs.commitments().serialize()
+        );
+
+        // Round 2: exchange partial signatures.
+        let alice_partial = alice
+            .partially_sign_transaction(
+                &tx,
+                alice_pairs,
+                &array(vec![key_pairs[1].public_key()]).unchecked_into(),
+                &array(vec![bob_commitments.clone()]).unchecked_into(),
+            )
+            .map_err(JsValue::from)
+            .unwrap();
+        let bob_partial = bob
+            .partially_sign_transaction(
+                &tx,
+                bob_pairs,
+                &array(vec![key_pairs[0].public_key()]).unchecked_into(),
+                &array(vec![alice_commitments.clone()]).unchecked_into(),
+            )
+            .map_err(JsValue::from)
+            .unwrap();
+
+        let signer_public_keys = || -> PublicKeyArray {
+            array(vec![key_pairs[0].public_key(), key_pairs[1].public_key()]).unchecked_into()
+        };
+        let signer_commitments = || -> MultiSigCommitmentsArray {
+            array(vec![alice_commitments.clone(), bob_commitments.clone()]).unchecked_into()
+        };
+
+        // A single partial signature is not enough.
+        assert!(alice
+            .create_proof(
+                &tx,
+                &signer_public_keys(),
+                &signer_commitments(),
+                &array(vec![alice_partial.clone()]).unchecked_into(),
+            )
+            .is_err());
+
+        alice
+            .sign_transaction(
+                &mut tx,
+                &signer_public_keys(),
+                &signer_commitments(),
+                &array(vec![alice_partial, bob_partial]).unchecked_into(),
+            )
+            .map_err(JsValue::from)
+            .unwrap();
+        assert_eq!(tx.verify(None).map_err(JsValue::from), Ok(()));
+    }
+
+    #[wasm_bindgen_test]
+    pub fn it_rejects_invalid_multisig_parameters() {
+        let key_pair = KeyPair::generate();
+        let other = KeyPair::generate();
+
+        let only_other: PublicKeyArray = array(vec![other.public_key()]).unchecked_into();
+        assert!(MultiSigAccount::new(&key_pair, 1, &only_other).is_err());
+
+        let both = || -> PublicKeyArray {
+            array(vec![key_pair.public_key(), other.public_key()]).unchecked_into()
+        };
+        assert!(MultiSigAccount::compute_address(0, &both()).is_err());
+        assert!(MultiSigAccount::compute_address(3, &both()).is_err());
+
+        // Duplicate public keys don't count as separate owners.
+        let duplicate: PublicKeyArray =
+            array(vec![key_pair.public_key(), key_pair.public_key()]).unchecked_into();
+        assert!(MultiSigAccount::compute_address(2, &duplicate).is_err());
+        assert!(MultiSigAccount::compute_address(1, &duplicate).is_ok());
+        assert!(MultiSigCommitments::unserialize(&[0u8; 10]).is_err());
+    }
+}
@@ -0,0 +1,141 @@
+use nimiq_hash::HashOutput;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    address::Address,
+    primitives::{
+        key_pair::KeyPair,
+        signature_proof::{PublicKeyUnion, SignatureProof},
+    },
+};
+
+/// A signed message proves that the owner of an address signed a message. It carries the signer's
+/// address and a signature proof with the key type, public key and signature, which can also be a
+/// Webauthn signature of a passkey-controlled account.
+///
+/// Signed messages are compatible with the `createSignedMessage` and `verifySignedMessage` RPC methods.
+#[wasm_bindgen]
+pub struct SignedMessage {
+    inner: nimiq_transaction::signed_message::SignedMessage,
+}
+
+#[wasm_bindgen]
+impl SignedMessage {
+    /// Signs the message with the key pair.
+    pub fn create(key_pair: &KeyPair, message: &[u8]) -> SignedMessage {
+        let key_pair = key_pair.native_ref();
+        let hash = nimiq_transaction::signed_message::SignedMessage::message_hash(message);
+        let signature = key_pair.sign(hash.as_bytes());
+
+        SignedMessage::from(nimiq_transaction::signed_message::SignedMessage::new(
+            nimiq_keys::Address::from(key_pair),
+            nimiq_transaction::SignatureProof::from_ed25519(key_pair.public, signature),
+        ))
+    }
+
+    /// Creates a signed message from a signature proof over the message hash, e.g. from a Webauthn
+    /// signature. For Webauthn, the challenge is the Blake2b hash of the message hash.
+    ///
+    /// The signer is the address the signature proof resolves to.
+    #[wasm_bindgen(js_name = fromSignatureProof)]
+    pub fn from_signature_proof(proof: &SignatureProof) -> SignedMessage {
+        SignedMessage::from(
+            nimiq_transaction::signed_message::SignedMessage::from_proof(
+                proof.native_ref().clone(),
+            ),
+        )
+    }
+
+    /// Computes the hash that is signed for a message.
+    #[wasm_bindgen(js_name = messageHash)]
+    pub fn message_hash(message: &[u8]) -> Vec<u8> {
+        nimiq_transaction::signed_message::SignedMessage::message_hash(message)
+            .as_bytes()
+            .to_vec()
+    }
+
+    /// Verifies that this is a valid signature of the message. If an address is given, the message
+    /// must also have been signed by it.
+    pub fn verify(&self, message: &[u8], address: Option<Address>) -> bool {
+        self.inner.verify(message).is_ok()
+            && address.map_or(true, |address| *address.native_ref() == self.inner.signer)
+    }
+
+    /// The address of the signer.
+    #[wasm_bindgen(getter)]
+    pub fn signer(&self) -> Address {
+        Address::from(self.inner.signer.clone())
+    }
+
+    /// The public key the message was signed with.
+    #[wasm_bindgen(getter, js_name = publicKey)]
+    pub fn public_key(&self) -> PublicKeyUnion {
+        self.proof().public_key()
+    }
+
+    /// The embedded signature proof.
+    #[wasm_bindgen(getter)]
+    pub fn proof(&self) -> SignatureProof {
+        SignatureProof::from(self.inner.proof.clone())
+    }
+
+    /// Deserializes a signed message from a byte array.
+    ///
+    /// Throws when the byte array is not a valid signed message or of an unsupported version.
+    pub fn unserialize(bytes: &[u8]) -> Result<SignedMessage, JsError> {
+        Ok(SignedMessage::from(
+            nimiq_transaction::signed_message::SignedMessage::from_bytes(bytes)?,
+        ))
+    }
+
+    /// Parses a signed message from its hex representation.
+    ///
+    /// Throws when the string is not valid hex format or not a valid signed message.
+    #[wasm_bindgen(js_name = fromHex)]
+    pub fn from_hex(hex: &str) -> Result<SignedMessage, JsError> {
+        SignedMessage::unserialize(&hex::decode(hex)?)
+    }
+
+    /// Serializes the signed message to a byte array.
+    pub fn serialize(&self) -> Vec<u8> {
+        self.inner.to_bytes()
+    }
+
+    /// Formats the signed message into a hex string.
+    #[wasm_bindgen(js_name = toHex)]
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.serialize())
+    }
+}
+
+impl From<nimiq_transaction::signed_message::SignedMessage> for SignedMessage {
+    fn from(signed_message: nimiq_transaction::signed_message::SignedMessage) -> Self {
+        SignedMessage {
+            inner: signed_message,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wasm_bindgen::prelude::JsValue;
+    use wasm_bindgen_test::*;
+
+    use crate::primitives::{key_pair::KeyPair, signed_message::SignedMessage};
+
+    #[wasm_bindgen_test]
+    fn it_can_sign_and_verify_messages() {
+        let key_pair = KeyPair::generate();
+        let signed_message = SignedMessage::create(&key_pair, b"I own this address");
+
+        let restored = SignedMessage::from_hex(&signed_message.to_hex())
+            .map_err(JsValue::from)
+            .unwrap();
+        assert!(restored.verify(b"I own this address", Some(key_pair.to_address())));
+        assert!(!restored.verify(b"I own another address", None));
+        assert!(!restored.verify(
+            b"I own this address",
+            Some(KeyPair::generate().to_address())
+        ));
+    }
+}
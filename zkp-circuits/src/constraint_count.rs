@@ -0,0 +1,153 @@
+use ark_ff::{Field, UniformRand};
+use ark_mnt6_753::{
+    constraints::{G1Var, G2Var},
+    Fq as MNT6Fq, G2Projective,
+};
+use ark_r1cs_std::{alloc::AllocVar, uint8::UInt8};
+use ark_relations::r1cs::{
+    ConstraintSynthesizer, ConstraintSystem, ConstraintSystemRef, OptimizationGoal, SynthesisError,
+};
+use nimiq_block::MacroBlock;
+use rand::Rng;
+
+use crate::{
+    blake2s::evaluate_blake2s,
+    gadgets::mnt6::{CheckSigGadget, MacroBlockGadget},
+};
+
+/// The size of a constraint system or of the part of it that was added by a gadget.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ConstraintCount {
+    pub constraints: usize,
+    /// The number of public inputs, not counting the constant one.
+    pub instance_variables: usize,
+    pub witness_variables: usize,
+}
+
+impl ConstraintCount {
+    /// Returns the current size of the given constraint system.
+    pub fn of<F: Field>(cs: &ConstraintSystemRef<F>) -> Self {
+        ConstraintCount {
+            constraints: cs.num_constraints(),
+            instance_variables: cs.num_instance_variables() - 1,
+            witness_variables: cs.num_witness_variables(),
+        }
+    }
+
+    /// Returns the size that was added since `before` was taken.
+    fn since<F: Field>(before: ConstraintCount, cs: &ConstraintSystemRef<F>) -> Self {
+        let after = Self::of(cs);
+        ConstraintCount {
+            constraints: after.constraints - before.constraints,
+            instance_variables: after.instance_variables - before.instance_variables,
+            witness_variables: after.witness_variables - before.witness_variables,
+        }
+    }
+}
+
+/// Generates the constraints of the circuit and returns its size along with the assigned public
+/// inputs. The constraints are not checked for satisfiability.
+pub fn circuit_constraint_count<F: Field, C: ConstraintSynthesizer<F>>(
+    circuit: C,
+) -> Result<(ConstraintCount, Vec<F>), SynthesisError> {
+    let cs = ConstraintSystem::new_ref();
+    cs.set_optimization_goal(OptimizationGoal::Constraints);
+    circuit.generate_constraints(cs.clone())?;
+    cs.finalize();
+
+    let count = ConstraintCount::of(&cs);
+    let inputs = cs
+        .borrow()
+        .map(|cs| cs.instance_assignment[1..].to_vec())
+        .unwrap_or_default();
+
+    Ok((count, inputs))
+}
+
+/// Measures the constraints added by the individual gadgets the circuits are built from. The
+/// gadgets are evaluated on random witnesses, the counts don't depend on the witness values.
+pub fn gadget_constraint_counts<R: Rng + ?Sized>(
+    rng: &mut R,
+) -> Result<Vec<(&'static str, ConstraintCount)>, SynthesisError> {
+    let mut counts = vec![];
+
+    // A Blake2s hash over a single 64 byte block.
+    let cs = ConstraintSystem::<MNT6Fq>::new_ref();
+    let input: Vec<u8> = (0..64).map(|_| rng.gen()).collect();
+    let input = UInt8::new_witness_vec(cs.clone(), &input)?;
+    let before = ConstraintCount::of(&cs);
+    evaluate_blake2s(&input)?;
+    counts.push(("blake2s_64_bytes", ConstraintCount::since(before, &cs)));
+
+    // The steps of the macro block verification, in the order the macro block gadget runs them.
+    let cs = ConstraintSystem::<MNT6Fq>::new_ref();
+    let before = ConstraintCount::of(&cs);
+    let mut block =
+        MacroBlockGadget::new_witness(cs.clone(), || Ok(MacroBlock::non_empty_default()))?;
+    let agg_pk = G2Var::new_witness(cs.clone(), || Ok(G2Projective::rand(rng)))?;
+    counts.push(("macro_block_alloc", ConstraintCount::since(before, &cs)));
+
+    let before = ConstraintCount::of(&cs);
+    block.check_signers(cs.clone())?;
+    counts.push((
+        "macro_block_check_signers",
+        ConstraintCount::since(before, &cs),
+    ));
+
+    let before = ConstraintCount::of(&cs);
+    block.hash(cs.clone())?;
+    counts.push((
+        "macro_block_header_hash",
+        ConstraintCount::since(before, &cs),
+    ));
+
+    // The header hash is cached, so this only counts the vote hash and the hash-to-curve.
+    let before = ConstraintCount::of(&cs);
+    let hash_point: G1Var = block.tendermint_hash(cs.clone())?;
+    counts.push((
+        "macro_block_tendermint_hash",
+        ConstraintCount::since(before, &cs),
+    ));
+
+    let before = ConstraintCount::of(&cs);
+    CheckSigGadget::check_signature(cs.clone(), &agg_pk, &hash_point, &block.signature)?;
+    counts.push((
+        "macro_block_check_signature",
+        ConstraintCount::since(before, &cs),
+    ));
+
+    Ok(counts)
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_r1cs_std::{eq::EqGadget, fields::fp::FpVar};
+
+    use super::*;
+
+    #[derive(Clone)]
+    struct SquareCircuit {
+        x: MNT6Fq,
+    }
+
+    impl ConstraintSynthesizer<MNT6Fq> for SquareCircuit {
+        fn generate_constraints(
+            self,
+            cs: ConstraintSystemRef<MNT6Fq>,
+        ) -> Result<(), SynthesisError> {
+            let x = FpVar::new_witness(cs.clone(), || Ok(self.x))?;
+            let y = FpVar::new_input(cs, || Ok(self.x * self.x))?;
+            (&x * &x).enforce_equal(&y)
+        }
+    }
+
+    #[test]
+    fn test_circuit_constraint_count() {
+        let x = MNT6Fq::from(3u64);
+        let (count, inputs) = circuit_constraint_count(SquareCircuit { x }).unwrap();
+
+        assert_eq!(count.instance_variables, 1);
+        assert_eq!(count.witness_variables, 2);
+        assert_eq!(inputs, vec![x * x]);
+    }
+}
@@ -0,0 +1,203 @@
+use std::{fs, io, path::PathBuf, time::Instant};
+
+use ark_crypto_primitives::snark::{CircuitSpecificSetupSNARK, SNARK};
+use ark_ec::pairing::Pairing;
+use ark_groth16::Groth16;
+use ark_mnt4_753::MNT4_753;
+use ark_mnt6_753::MNT6_753;
+use ark_relations::r1cs::ConstraintSynthesizer;
+use clap::Parser;
+use log::{info, level_filters::LevelFilter};
+use nimiq_log::TargetsExt;
+use nimiq_primitives::policy::{Policy, TEST_POLICY};
+use nimiq_zkp_circuits::{
+    circuits::{mnt4, mnt6},
+    constraint_count::{circuit_constraint_count, gadget_constraint_counts, ConstraintCount},
+};
+use rand::{thread_rng, CryptoRng, Rng};
+use serde_json::{json, Value};
+use tracing_subscriber::{filter::Targets, layer::SubscriberExt, util::SubscriberInitExt};
+
+/// Reports the size of every circuit and gadget of the light macro sync as JSON and optionally
+/// measures the setup, proving and verification times of the circuits.
+#[derive(Debug, Parser)]
+struct Bench {
+    /// Also measure the setup, proving and verification times. This takes several hours for all
+    /// circuits.
+    #[clap(short, long)]
+    timings: bool,
+
+    /// Only benchmark the circuits with the given names, e.g. `pk_tree_5` or `merger_wrapper`.
+    #[clap(short, long)]
+    circuit: Vec<String>,
+
+    /// Write the results to the given file instead of stdout.
+    #[clap(short, long)]
+    output: Option<PathBuf>,
+}
+
+/// Measures a circuit that is proven on the given curve. The circuits are built from random
+/// inputs, so the proofs generally don't verify. Only the time it takes to find out is meaningful.
+fn bench_circuit<E, C, R>(
+    name: &str,
+    gadget: &str,
+    circuit: C,
+    timings: bool,
+    rng: &mut R,
+) -> Result<Value, anyhow::Error>
+where
+    E: Pairing,
+    C: ConstraintSynthesizer<E::ScalarField> + Clone,
+    R: Rng + CryptoRng,
+{
+    info!("- {}", name);
+    let (count, inputs) = circuit_constraint_count(circuit.clone())?;
+    let mut result = json!({
+        "name": name,
+        "gadget": gadget,
+        "constraints": count_to_json(&count),
+    });
+
+    if timings {
+        let start = Instant::now();
+        let (pk, vk) = Groth16::<E>::setup(circuit.clone(), rng)?;
+        let setup = start.elapsed();
+
+        let start = Instant::now();
+        let proof = Groth16::<E>::prove(&pk, circuit, rng)?;
+        let prove = start.elapsed();
+
+        let start = Instant::now();
+        let verified = Groth16::<E>::verify(&vk, &inputs, &proof)?;
+        let verify = start.elapsed();
+
+        result["timings"] = json!({
+            "setupMs": setup.as_millis() as u64,
+            "proveMs": prove.as_millis() as u64,
+            "verifyMs": verify.as_millis() as u64,
+            "verified": verified,
+        });
+    }
+
+    Ok(result)
+}
+
+fn count_to_json(count: &ConstraintCount) -> Value {
+    json!({
+        "constraints": count.constraints,
+        "instanceVariables": count.instance_variables,
+        "witnessVariables": count.witness_variables,
+    })
+}
+
+fn main() -> Result<(), anyhow::Error> {
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::fmt::layer().with_writer(io::stderr))
+        .with(
+            Targets::new()
+                .with_default(LevelFilter::INFO)
+                .with_nimiq_targets(LevelFilter::DEBUG)
+                .with_target("r1cs", LevelFilter::WARN)
+                .with_env(),
+        )
+        .init();
+
+    let args = Bench::parse();
+
+    // Use test constants for the circuits.
+    let _ = Policy::get_or_init(TEST_POLICY);
+
+    info!("====== ZKP circuit benchmark initiated ======");
+    let start = Instant::now();
+    let mut rng = thread_rng();
+    let selected = |name: &str| args.circuit.is_empty() || args.circuit.iter().any(|c| c == name);
+
+    // The circuits in proving order. Circuits over the MNT6 base field are proven on MNT4 and
+    // vice versa.
+    let mut circuits = vec![];
+    if selected("pk_tree_5") {
+        let circuit: mnt6::PKTreeLeafCircuit = rng.gen();
+        circuits.push(bench_circuit::<MNT4_753, _, _>(
+            "pk_tree_5",
+            "pk_tree",
+            circuit,
+            args.timings,
+            &mut rng,
+        )?);
+    }
+    for tree_level in (0..5).rev() {
+        let name = format!("pk_tree_{tree_level}");
+        if !selected(&name) {
+            continue;
+        }
+        let result = if tree_level % 2 == 0 {
+            let circuit = mnt4::PKTreeNodeCircuit::rand(tree_level, &mut rng);
+            bench_circuit::<MNT6_753, _, _>(&name, "pk_tree", circuit, args.timings, &mut rng)?
+        } else {
+            let circuit = mnt6::PKTreeNodeCircuit::rand(tree_level, &mut rng);
+            bench_circuit::<MNT4_753, _, _>(&name, "pk_tree", circuit, args.timings, &mut rng)?
+        };
+        circuits.push(result);
+    }
+    if selected("macro_block") {
+        let circuit = mnt6::MacroBlockCircuit::rand(&mut rng);
+        circuits.push(bench_circuit::<MNT4_753, _, _>(
+            "macro_block",
+            "macro_block",
+            circuit,
+            args.timings,
+            &mut rng,
+        )?);
+    }
+    if selected("macro_block_wrapper") {
+        let circuit = mnt4::MacroBlockWrapperCircuit::rand(&mut rng);
+        circuits.push(bench_circuit::<MNT6_753, _, _>(
+            "macro_block_wrapper",
+            "macro_block",
+            circuit,
+            args.timings,
+            &mut rng,
+        )?);
+    }
+    if selected("merger") {
+        let circuit = mnt6::MergerCircuit::rand(&mut rng);
+        circuits.push(bench_circuit::<MNT4_753, _, _>(
+            "merger",
+            "merger",
+            circuit,
+            args.timings,
+            &mut rng,
+        )?);
+    }
+    if selected("merger_wrapper") {
+        let circuit = mnt4::MergerWrapperCircuit::rand(&mut rng);
+        circuits.push(bench_circuit::<MNT6_753, _, _>(
+            "merger_wrapper",
+            "merger",
+            circuit,
+            args.timings,
+            &mut rng,
+        )?);
+    }
+
+    let gadgets: Vec<_> = gadget_constraint_counts(&mut rng)?
+        .into_iter()
+        .map(|(name, count)| json!({ "name": name, "constraints": count_to_json(&count) }))
+        .collect();
+
+    let report = json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "circuits": circuits,
+        "gadgets": gadgets,
+    });
+    let report = serde_json::to_string_pretty(&report)?;
+    match args.output {
+        Some(path) => fs::write(path, report)?,
+        None => println!("{report}"),
+    }
+
+    info!("====== ZKP circuit benchmark finished ======");
+    info!("Total time elapsed: {:?} seconds", start.elapsed());
+
+    Ok(())
+}
@@ -6,6 +6,7 @@ pub mod proof_store;
 pub mod proof_utils;
 #[cfg(feature = "zkp-prover")]
 pub mod prover_binary;
+pub mod proving_job;
 pub mod types;
 pub mod zkp_component;
 #[cfg(feature = "zkp-prover")]
@@ -1,5 +1,6 @@
 use std::{
     env,
+    net::SocketAddr,
     path::{Path, PathBuf},
     process::Stdio,
 };
@@ -8,7 +9,11 @@ use ark_groth16::Proof;
 use ark_mnt6_753::MNT6_753;
 use nimiq_block::MacroBlock;
 use nimiq_serde::{Deserialize, Serialize};
-use nimiq_zkp::prove::prove;
+use nimiq_zkp::{
+    distributed::WorkerPool,
+    prove::{prove, prove_with_pk_tree_prover},
+};
+use nimiq_zkp_circuits::setup::load_keys;
 use tokio::{
     io::{AsyncReadExt, AsyncWriteExt},
     process::Command,
@@ -25,15 +30,33 @@ pub fn generate_new_proof(
     final_block: MacroBlock,
     genesis_header_hash: [u8; 32],
     prover_keys_path: &Path,
+    prover_workers: &[SocketAddr],
+    prover_worker_secret: &str,
 ) -> Result<ZKPState, ZKProofGenerationError> {
-    let proof = prove(
-        prev_block,
-        final_block.clone(),
-        previous_proof.map(|proof| (proof, genesis_header_hash)),
-        true,
-        true,
-        prover_keys_path,
-    );
+    let genesis_data = previous_proof.map(|proof| (proof, genesis_header_hash));
+    let proof = if prover_workers.is_empty() {
+        prove(
+            prev_block,
+            final_block.clone(),
+            genesis_data,
+            true,
+            true,
+            prover_keys_path,
+        )
+    } else {
+        // The verifying keys are needed to check the proofs returned by the workers.
+        load_keys(prover_keys_path).and_then(|keys| {
+            prove_with_pk_tree_prover(
+                prev_block,
+                final_block.clone(),
+                genesis_data,
+                true,
+                true,
+                prover_keys_path,
+                &WorkerPool::new(prover_workers.to_vec(), prover_worker_secret, keys),
+            )
+        })
+    };
 
     match proof {
         Ok(proof) => Ok(ZKPState {
@@ -1,18 +1,36 @@
+use nimiq_block::MacroBlock;
 #[cfg(feature = "database-storage")]
 use nimiq_database::{
-    traits::{Database, ReadTransaction, WriteTransaction},
-    DatabaseProxy, TableProxy,
+    traits::{Database, ReadCursor, ReadTransaction, WriteCursor, WriteTransaction},
+    DatabaseProxy, TableFlags, TableProxy,
 };
+#[cfg(feature = "database-storage")]
+use nimiq_primitives::policy::Policy;
 
 use crate::types::*;
 
 /// Defines an interface for storing and retrieving ZK proofs.
-pub trait ProofStore: Send {
+pub trait ProofStore: Send + Sync {
     /// Gets a ZK proof.
     fn get_zkp(&self) -> Option<ZKProof>;
 
     /// Sets or stores a ZK proof.
     fn set_zkp(&self, zk_proof: &ZKProof);
+
+    /// Gets the ZK proof for the given election block, if it is still kept in the history.
+    fn get_zkp_at(&self, block_number: u32) -> Option<ZKProof>;
+
+    /// Stores the ZK proof of the given state. Stores that are able to keep the election block
+    /// of the proof store it as well, by default only the proof is stored.
+    fn set_zkp_state(&self, zkp_state: &ZKPState) {
+        self.set_zkp(&zkp_state.clone().into());
+    }
+
+    /// Gets the election block of the stored ZK proof, if this store keeps it. This allows the proof
+    /// to be loaded before the block is known to the blockchain, e.g. when a light client resumes.
+    fn get_zkp_election_block(&self) -> Option<MacroBlock> {
+        None
+    }
 }
 
 #[cfg(feature = "database-storage")]
@@ -23,17 +41,38 @@ pub struct DBProofStore {
     env: DatabaseProxy,
     // A database of the current zkp state.
     zkp_db: TableProxy,
+    // A database of past proofs indexed by the block number of their election block.
+    history_db: TableProxy,
+    // The number of epochs for which proofs are kept in the history.
+    history_size: usize,
 }
 
 #[cfg(feature = "database-storage")]
 impl DBProofStore {
     const PROOF_DB_NAME: &'static str = "ZKPState";
+    const PROOF_HISTORY_DB_NAME: &'static str = "ZKPHistory";
     const PROOF_KEY: &'static str = "proof";
 
+    /// The default number of epochs for which proofs are kept in the history.
+    pub const DEFAULT_HISTORY_SIZE: usize = 256;
+
     pub fn new(env: DatabaseProxy) -> Self {
+        Self::with_history_size(env, Self::DEFAULT_HISTORY_SIZE)
+    }
+
+    pub fn with_history_size(env: DatabaseProxy, history_size: usize) -> Self {
         let zkp_db = env.open_table(Self::PROOF_DB_NAME.to_string());
+        let history_db = env.open_table_with_flags(
+            Self::PROOF_HISTORY_DB_NAME.to_string(),
+            TableFlags::UINT_KEYS,
+        );
 
-        Self { env, zkp_db }
+        Self {
+            env,
+            zkp_db,
+            history_db,
+            history_size,
+        }
     }
 }
 
@@ -48,6 +87,35 @@ impl ProofStore for DBProofStore {
     fn set_zkp(&self, zk_proof: &ZKProof) {
         let mut tx = self.env.write_transaction();
         tx.put(&self.zkp_db, Self::PROOF_KEY, zk_proof);
+
+        // The genesis block has no proof, so there is nothing to keep.
+        if zk_proof.proof.is_some() {
+            tx.put(&self.history_db, &zk_proof.block_number, zk_proof);
+
+            // Prune the proofs of epochs that fell out of the history. Keys are ordered by block
+            // number, so the oldest proofs come first.
+            let history_blocks =
+                (self.history_size as u32).saturating_mul(Policy::blocks_per_epoch());
+            let oldest_block_number = zk_proof.block_number.saturating_sub(history_blocks);
+
+            let mut cursor = WriteTransaction::cursor(&tx, &self.history_db);
+            let mut pos: Option<(u32, ZKProof)> = cursor.first();
+
+            while let Some((block_number, _)) = pos {
+                if block_number > oldest_block_number {
+                    break;
+                }
+                cursor.remove();
+                pos = cursor.next();
+            }
+        }
+
         tx.commit();
     }
+
+    fn get_zkp_at(&self, block_number: u32) -> Option<ZKProof> {
+        self.env
+            .read_transaction()
+            .get(&self.history_db, &block_number)
+    }
 }
@@ -1,7 +1,13 @@
-use std::io::{self, BufReader, BufWriter, Error};
+use std::{
+    io::{self, BufReader, BufWriter, Error, ErrorKind},
+    net::{SocketAddr, TcpListener},
+    path::PathBuf,
+};
 
 use ark_serialize::{Read, Write};
 use nimiq_serde::{Deserialize, Serialize};
+use nimiq_utils::Sensitive;
+use nimiq_zkp::distributed::run_worker;
 
 use crate::{
     proof_gen_utils::generate_new_proof,
@@ -29,6 +35,8 @@ pub async fn prover_main() -> Result<(), Error> {
             proof_input.final_block,
             proof_input.genesis_header_hash,
             &proof_input.prover_keys_path,
+            &proof_input.prover_workers,
+            &proof_input.prover_worker_secret,
         ),
         Err(e) => Err(ZKProofGenerationError::from(e)),
     };
@@ -43,3 +51,23 @@ pub async fn prover_main() -> Result<(), Error> {
 
     Ok(())
 }
+
+/// Runs a worker that generates public key tree sub-proofs for the provers configured to use it.
+/// The provers must be configured with the same secret. In debug mode, every proof is verified
+/// right after it was generated.
+pub async fn prover_worker_main(
+    listen_address: SocketAddr,
+    prover_keys_path: PathBuf,
+    secret: Sensitive<String>,
+    debug_mode: bool,
+) -> Result<(), Error> {
+    let listener = TcpListener::bind(listen_address)?;
+    log::info!(%listen_address, debug_mode, "Prover worker listening");
+
+    tokio::task::spawn_blocking(move || {
+        run_worker(listener, &prover_keys_path, &secret, debug_mode)
+    })
+    .await
+    .map_err(|e| Error::new(ErrorKind::Other, e))?
+    .map_err(|e| Error::new(ErrorKind::Other, e))
+}
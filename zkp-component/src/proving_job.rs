@@ -0,0 +1,266 @@
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use nimiq_block::MacroBlock;
+use nimiq_hash::{Blake2bHash, Blake2sHash};
+use nimiq_primitives::slots_allocation::PK_TREE_DEPTH;
+use nimiq_serde::{Deserialize, Serialize};
+use parking_lot::RwLock;
+
+/// The file the proving job is persisted to, relative to the prover keys path.
+const PROVING_JOB_FILE: &str = "proving_job.bin";
+
+/// The sub-circuits that are proven to generate a proof for an election block, in proving order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SubCircuit {
+    /// A level of the public key tree, the leaves being at level `PK_TREE_DEPTH`.
+    PkTree(usize),
+    MacroBlock,
+    MacroBlockWrapper,
+    Merger,
+    MergerWrapper,
+}
+
+impl SubCircuit {
+    /// All sub-circuits in the order they are proven.
+    pub fn all() -> Vec<SubCircuit> {
+        let mut circuits: Vec<_> = (0..=PK_TREE_DEPTH).rev().map(SubCircuit::PkTree).collect();
+        circuits.extend([
+            SubCircuit::MacroBlock,
+            SubCircuit::MacroBlockWrapper,
+            SubCircuit::Merger,
+            SubCircuit::MergerWrapper,
+        ]);
+        circuits
+    }
+
+    /// The number of proofs generated for this sub-circuit.
+    pub fn num_proofs(&self) -> usize {
+        match self {
+            SubCircuit::PkTree(level) => 1 << level,
+            _ => 1,
+        }
+    }
+
+    /// The names of the files the proofs of this sub-circuit are cached in.
+    fn proof_files(&self) -> Vec<String> {
+        match self {
+            SubCircuit::PkTree(level) => (0..self.num_proofs())
+                .map(|position| format!("pk_tree_{level}_{position}.bin"))
+                .collect(),
+            _ => vec![format!("{self}.bin")],
+        }
+    }
+}
+
+impl fmt::Display for SubCircuit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SubCircuit::PkTree(level) => write!(f, "pk_tree_{level}"),
+            SubCircuit::MacroBlock => write!(f, "macro_block"),
+            SubCircuit::MacroBlockWrapper => write!(f, "macro_block_wrapper"),
+            SubCircuit::Merger => write!(f, "merger"),
+            SubCircuit::MergerWrapper => write!(f, "merger_wrapper"),
+        }
+    }
+}
+
+/// The number of proofs of a sub-circuit that have already been generated.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SubCircuitProgress {
+    pub circuit: SubCircuit,
+    pub completed: usize,
+    pub total: usize,
+}
+
+/// The state of a proving job.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum ProvingJobStatus {
+    /// The prover process is generating the proof.
+    Running,
+    /// The proof was generated.
+    Finished,
+    /// The job was superseded by a more recent proof.
+    Cancelled,
+    /// The prover process failed, the job is retried once the prover restarts.
+    Failed,
+}
+
+impl fmt::Display for ProvingJobStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProvingJobStatus::Running => write!(f, "running"),
+            ProvingJobStatus::Finished => write!(f, "finished"),
+            ProvingJobStatus::Cancelled => write!(f, "cancelled"),
+            ProvingJobStatus::Failed => write!(f, "failed"),
+        }
+    }
+}
+
+/// The proof generation for a single election block.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProvingJob {
+    /// The block number of the election block the proof builds upon.
+    pub previous_block_number: u32,
+    /// The block number of the election block that is proven.
+    pub block_number: u32,
+    /// The hash of the election block that is proven.
+    pub block_hash: Blake2bHash,
+    /// The hash the cached sub-proofs are tagged with by the prover process.
+    pub header_hash: Blake2sHash,
+    pub status: ProvingJobStatus,
+    /// The number of times the prover process was launched for this job.
+    pub attempts: u32,
+}
+
+impl ProvingJob {
+    pub fn new(previous_block: &MacroBlock, block: &MacroBlock) -> Self {
+        ProvingJob {
+            previous_block_number: previous_block.block_number(),
+            block_number: block.block_number(),
+            block_hash: block.hash(),
+            header_hash: block.hash_blake2s(),
+            status: ProvingJobStatus::Running,
+            attempts: 0,
+        }
+    }
+
+    /// Whether this job generates the proof for the given transition.
+    pub fn proves(&self, previous_block: &MacroBlock, block: &MacroBlock) -> bool {
+        self.previous_block_number == previous_block.block_number()
+            && self.block_hash == block.hash()
+    }
+}
+
+/// A proving job along with the progress of its sub-circuits.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProvingProgress {
+    pub job: ProvingJob,
+    pub sub_circuits: Vec<SubCircuitProgress>,
+}
+
+/// Persists the proving job of the local prover next to the proving keys, such that proof
+/// generation resumes from the cached sub-proofs after a crash or restart.
+///
+/// The sub-proofs are cached by the prover process itself. They can only be reused if the cache is
+/// tagged with the header hash of the job, otherwise the prover process discards them.
+pub struct ProvingJobStore {
+    prover_keys_path: PathBuf,
+    job: RwLock<Option<ProvingJob>>,
+}
+
+impl ProvingJobStore {
+    /// Creates the store and loads the job persisted in the given prover keys path, if any.
+    pub fn new(prover_keys_path: &Path) -> Self {
+        let job = match fs::read(prover_keys_path.join(PROVING_JOB_FILE)) {
+            Ok(bytes) => match ProvingJob::deserialize_from_vec(&bytes) {
+                Ok(job) => Some(job),
+                Err(error) => {
+                    log::warn!(%error, "Discarding unreadable proving job");
+                    None
+                }
+            },
+            Err(error) if error.kind() == io::ErrorKind::NotFound => None,
+            Err(error) => {
+                log::warn!(%error, "Failed to read proving job");
+                None
+            }
+        };
+
+        ProvingJobStore {
+            prover_keys_path: prover_keys_path.to_path_buf(),
+            job: RwLock::new(job),
+        }
+    }
+
+    /// Returns the current job.
+    pub fn job(&self) -> Option<ProvingJob> {
+        self.job.read().clone()
+    }
+
+    /// Replaces the current job and persists it.
+    pub fn set_job(&self, job: ProvingJob) {
+        let mut job_lock = self.job.write();
+        if let Err(error) = self.persist(&job) {
+            log::error!(%error, "Failed to persist proving job");
+        }
+        *job_lock = Some(job);
+    }
+
+    /// Sets the status of the current job and persists it.
+    pub fn set_status(&self, status: ProvingJobStatus) {
+        let job = self.job.read().clone();
+        if let Some(mut job) = job {
+            job.status = status;
+            self.set_job(job);
+        }
+    }
+
+    /// Returns the current job along with the progress of its sub-circuits.
+    pub fn progress(&self) -> Option<ProvingProgress> {
+        let job = self.job()?;
+
+        let proofs = self.prover_keys_path.join("proofs");
+        let cache_valid = self.is_cache_of(&job);
+
+        let sub_circuits = SubCircuit::all()
+            .into_iter()
+            .map(|circuit| {
+                let total = circuit.num_proofs();
+                let completed = if job.status == ProvingJobStatus::Finished {
+                    total
+                } else if cache_valid {
+                    circuit
+                        .proof_files()
+                        .iter()
+                        .filter(|file| proofs.join(file).exists())
+                        .count()
+                } else {
+                    0
+                };
+
+                SubCircuitProgress {
+                    circuit,
+                    completed,
+                    total,
+                }
+            })
+            .collect();
+
+        Some(ProvingProgress { job, sub_circuits })
+    }
+
+    /// Deletes the cached sub-proofs. Must only be called while no prover process is running.
+    pub fn clear_cached_proofs(&self) {
+        let proofs = self.prover_keys_path.join("proofs");
+        if proofs.is_dir() {
+            if let Err(error) = fs::remove_dir_all(&proofs) {
+                log::error!(%error, "Failed to delete cached sub-proofs");
+            }
+        }
+    }
+
+    /// Whether the cached sub-proofs belong to the given job.
+    fn is_cache_of(&self, job: &ProvingJob) -> bool {
+        let metadata_file = self.prover_keys_path.join("proofs").join("meta_data.bin");
+
+        fs::read(metadata_file)
+            .ok()
+            .and_then(|bytes| <[u8; 32]>::deserialize_from_vec(&bytes).ok())
+            .map_or(false, |header_hash| header_hash == job.header_hash.0)
+    }
+
+    /// Writes the job to a temporary file and moves it into place, such that a crash never
+    /// leaves a partially written job behind.
+    fn persist(&self, job: &ProvingJob) -> io::Result<()> {
+        fs::create_dir_all(&self.prover_keys_path)?;
+
+        let path = self.prover_keys_path.join(PROVING_JOB_FILE);
+        let tmp_path = path.with_extension("bin.tmp");
+        fs::write(&tmp_path, job.serialize_to_vec())?;
+        fs::rename(tmp_path, path)
+    }
+}
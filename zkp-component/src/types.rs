@@ -1,4 +1,4 @@
-use std::{borrow::Cow, io, path::PathBuf, sync::Arc};
+use std::{borrow::Cow, io, net::SocketAddr, path::PathBuf, sync::Arc};
 
 use ark_groth16::Proof;
 use ark_mnt6_753::MNT6_753;
@@ -12,11 +12,12 @@ use nimiq_network_interface::{
     request::{Handle, RequestCommon, RequestError, RequestMarker},
 };
 use nimiq_serde::{Deserialize, DeserializeError, Serialize};
+use nimiq_utils::Sensitive;
 use nimiq_zkp_primitives::NanoZKPError;
 use parking_lot::RwLock;
 use thiserror::Error;
 
-use crate::ZKPComponent;
+use crate::{proof_store::ProofStore, ZKPComponent};
 
 pub const PROOF_GENERATION_OUTPUT_DELIMITER: [u8; 2] = [242, 208];
 
@@ -113,6 +114,17 @@ impl ZKProof {
             proof,
         }
     }
+
+    /// Returns the compressed serialization of the proof, which is what external verifiers expect.
+    pub fn compressed_proof(&self) -> Option<Vec<u8>> {
+        self.proof.as_ref().map(|proof| {
+            let mut bytes = Vec::with_capacity(proof.compressed_size());
+            proof
+                .serialize_compressed(&mut bytes)
+                .expect("Serializing to a vector can't fail");
+            bytes
+        })
+    }
 }
 
 impl From<ZKPState> for ZKProof {
@@ -149,6 +161,11 @@ pub struct ProofInput {
     pub final_block: MacroBlock,
     pub genesis_header_hash: [u8; 32],
     pub prover_keys_path: PathBuf,
+    /// The workers the public key tree sub-proofs are distributed to. If empty, everything is
+    /// proven by the proof generation process itself.
+    pub prover_workers: Vec<SocketAddr>,
+    /// The secret the proof generation process and the workers authenticate each other with.
+    pub prover_worker_secret: Sensitive<String>,
 }
 
 /// The topic for zkp gossiping.
@@ -177,6 +194,9 @@ pub enum Error {
     #[error("Invalid proof")]
     InvalidProof,
 
+    #[error("Proof not found")]
+    ProofNotFound,
+
     #[error("Request Error: {0}")]
     Request(#[from] RequestError),
 }
@@ -245,10 +265,33 @@ pub enum RequestZKPResponse {
     Outdated(u32),
 }
 
+/// The request of the zkp for a specific election block. Peers reply with the proof if it is their latest proof
+/// or if it is still kept in their proof history.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RequestZKPAt {
+    pub(crate) block_number: u32,
+}
+
+impl RequestCommon for RequestZKPAt {
+    type Kind = RequestMarker;
+    const TYPE_ID: u16 = 219;
+    type Response = RequestZKPAtResponse;
+
+    const MAX_REQUESTS: u32 = MAX_REQUEST_RESPONSE_ZKP;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum RequestZKPAtResponse {
+    Proof(ZKProof, MacroBlock),
+    NotFound,
+}
+
 #[derive(Clone)]
 pub(crate) struct ZKPStateEnvironment {
     pub(crate) zkp_state: Arc<RwLock<ZKPState>>,
     pub(crate) blockchain: BlockchainProxy,
+    pub(crate) proof_storage: Option<Arc<dyn ProofStore>>,
 }
 
 impl<N: Network> From<&ZKPComponent<N>> for ZKPStateEnvironment {
@@ -256,6 +299,7 @@ impl<N: Network> From<&ZKPComponent<N>> for ZKPStateEnvironment {
         ZKPStateEnvironment {
             zkp_state: Arc::clone(&component.zkp_state),
             blockchain: component.blockchain.clone(),
+            proof_storage: component.proof_storage.clone(),
         }
     }
 }
@@ -285,6 +329,48 @@ impl<N: Network> Handle<N, Arc<ZKPStateEnvironment>> for RequestZKP {
     }
 }
 
+impl<N: Network> Handle<N, Arc<ZKPStateEnvironment>> for RequestZKPAt {
+    fn handle(&self, _peer_id: N::PeerId, env: &Arc<ZKPStateEnvironment>) -> RequestZKPAtResponse {
+        let zkp_proof = get_zkp_at(
+            &env.zkp_state,
+            env.proof_storage.as_deref(),
+            self.block_number,
+        );
+
+        let block = env
+            .blockchain
+            .read()
+            .get_block_at(self.block_number, true)
+            .ok()
+            .filter(|block| block.is_election());
+
+        match (zkp_proof, block) {
+            (Some(zkp_proof), Some(block)) => {
+                RequestZKPAtResponse::Proof(zkp_proof, block.unwrap_macro())
+            }
+            _ => RequestZKPAtResponse::NotFound,
+        }
+    }
+}
+
+/// Gets the proof for the given election block, either from the current state or from the proof history.
+pub(crate) fn get_zkp_at(
+    zkp_state: &RwLock<ZKPState>,
+    proof_storage: Option<&dyn ProofStore>,
+    block_number: u32,
+) -> Option<ZKProof> {
+    {
+        let zkp_state = zkp_state.read();
+        if zkp_state.latest_block.block_number() == block_number {
+            return zkp_state
+                .latest_proof
+                .is_some()
+                .then(|| (*zkp_state).clone().into());
+        }
+    }
+    proof_storage?.get_zkp_at(block_number)
+}
+
 mod serde_derive {
 
     use std::fmt;
@@ -305,6 +391,8 @@ mod serde_derive {
         "final_block",
         "genesis_header_hash",
         "prover_keys_path",
+        "prover_workers",
+        "prover_worker_secret",
     ];
 
     struct ZKProofVisitor;
@@ -480,6 +568,12 @@ mod serde_derive {
             let path_buf: String = seq
                 .next_element()?
                 .ok_or_else(|| A::Error::invalid_length(4, &self))?;
+            let prover_workers: Vec<String> = seq
+                .next_element()?
+                .ok_or_else(|| A::Error::invalid_length(5, &self))?;
+            let prover_worker_secret: String = seq
+                .next_element()?
+                .ok_or_else(|| A::Error::invalid_length(6, &self))?;
 
             let previous_proof = if let Some(ser_proof) = ser_previous_proof {
                 Some(
@@ -497,6 +591,14 @@ mod serde_derive {
                 final_block,
                 genesis_header_hash,
                 prover_keys_path: PathBuf::from(path_buf),
+                prover_workers: prover_workers
+                    .iter()
+                    .map(|address| address.parse())
+                    .collect::<Result<_, _>>()
+                    .map_err(|_| {
+                        A::Error::invalid_value(Unexpected::Other("Invalid worker address"), &self)
+                    })?,
+                prover_worker_secret: Sensitive(prover_worker_secret),
             })
         }
     }
@@ -529,6 +631,15 @@ mod serde_derive {
                 PROOF_INPUT_FIELDS[4],
                 &self.prover_keys_path.to_string_lossy().to_string(),
             )?;
+            state.serialize_field(
+                PROOF_INPUT_FIELDS[5],
+                &self
+                    .prover_workers
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>(),
+            )?;
+            state.serialize_field(PROOF_INPUT_FIELDS[6], &*self.prover_worker_secret)?;
             state.end()
         }
     }
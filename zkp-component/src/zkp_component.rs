@@ -1,5 +1,5 @@
 #[cfg(feature = "zkp-prover")]
-use std::path::PathBuf;
+use std::{net::SocketAddr, path::PathBuf};
 use std::{
     pin::Pin,
     sync::Arc,
@@ -16,6 +16,8 @@ use nimiq_network_interface::{
     request::request_handler,
 };
 use nimiq_primitives::task_executor::TaskExecutor;
+#[cfg(feature = "zkp-prover")]
+use nimiq_utils::Sensitive;
 use parking_lot::{Mutex, RwLock, RwLockUpgradableReadGuard};
 use tokio::sync::{
     broadcast::{channel as broadcast, Sender as BroadcastSender},
@@ -25,7 +27,13 @@ use tokio_stream::wrappers::BroadcastStream;
 
 #[cfg(feature = "zkp-prover")]
 use crate::zkp_prover::ZKProver;
-use crate::{proof_store::ProofStore, proof_utils::*, types::*, zkp_requests::ZKPRequests};
+use crate::{
+    proof_store::ProofStore,
+    proof_utils::*,
+    proving_job::{ProvingJobStore, ProvingProgress},
+    types::*,
+    zkp_requests::ZKPRequests,
+};
 
 pub type ZKProofsStream<N> = BoxStream<'static, (ZKProof, <N as Network>::PubsubId)>;
 
@@ -36,6 +44,8 @@ pub struct ZKPComponentProxy<N: Network> {
     zkp_state: Arc<RwLock<ZKPState>>,
     zkp_requests: Arc<Mutex<ZKPRequests<N>>>,
     pub(crate) zkp_events_notifier: BroadcastSender<ZKPEvent<N>>,
+    proof_storage: Option<Arc<dyn ProofStore>>,
+    proving_job_store: Option<Arc<ProvingJobStore>>,
 }
 
 impl<N: Network> Clone for ZKPComponentProxy<N> {
@@ -45,6 +55,8 @@ impl<N: Network> Clone for ZKPComponentProxy<N> {
             zkp_state: Arc::clone(&self.zkp_state),
             zkp_requests: Arc::clone(&self.zkp_requests),
             zkp_events_notifier: self.zkp_events_notifier.clone(),
+            proof_storage: self.proof_storage.clone(),
+            proving_job_store: self.proving_job_store.clone(),
         }
     }
 }
@@ -55,6 +67,16 @@ impl<N: Network> ZKPComponentProxy<N> {
         self.zkp_state.read().clone()
     }
 
+    /// Gets the proof for the given election block, if it is the current proof or still kept in the proof history.
+    pub fn get_zkp_at(&self, block_number: u32) -> Option<ZKProof> {
+        get_zkp_at(&self.zkp_state, self.proof_storage.as_deref(), block_number)
+    }
+
+    /// Gets the job of the local prover along with its progress, if the prover is active.
+    pub fn get_proving_progress(&self) -> Option<ProvingProgress> {
+        self.proving_job_store.as_ref()?.progress()
+    }
+
     /// Sends zkp request to all given peers. If no requests are ongoing, we request and return true,
     /// otherwise no requests will be sent.
     pub fn request_zkp_from_peers(
@@ -87,6 +109,21 @@ impl<N: Network> ZKPComponentProxy<N> {
         (request.await, peer_id)
     }
 
+    /// Requests the proof for the given election block from a single peer. The proof is verified against the
+    /// election block sent along, but it is not pushed into our state.
+    pub async fn request_zkp_at_from_peer(
+        &self,
+        peer_id: N::PeerId,
+        block_number: u32,
+    ) -> Result<(ZKProof, MacroBlock), Error> {
+        let network_id = self.zkp_state.read().latest_block.header.network;
+        let request = self
+            .zkp_requests
+            .lock()
+            .request_zkp_at(peer_id, network_id, block_number);
+        request.await
+    }
+
     pub fn subscribe_zkps(&self) -> BroadcastStream<ZKPEvent<N>> {
         BroadcastStream::new(self.zkp_events_notifier.subscribe())
     }
@@ -102,9 +139,10 @@ impl<N: Network> ZKPComponentProxy<N> {
 /// - The current zkp state
 /// - The proof generating component that can be activated by a client configuration
 /// - The zkp gossip stream
-/// - The db storage for the current proof
+/// - The db storage for the current proof and the proof history
 /// - The zkp requests component to fetch an up to date proof from our peers
 /// - The zkp events notifies newly stored proofs.
+/// - The persisted job of the prover, if activated
 ///
 /// Awaiting this future ensures that the zkp component works, this component should run forever.
 pub struct ZKPComponent<N: Network> {
@@ -114,9 +152,10 @@ pub struct ZKPComponent<N: Network> {
     #[cfg(feature = "zkp-prover")]
     zk_prover: Option<ZKProver<N>>,
     zk_proofs_stream: ZKProofsStream<N>,
-    proof_storage: Option<Box<dyn ProofStore>>,
+    pub(crate) proof_storage: Option<Arc<dyn ProofStore>>,
     zkp_requests: Arc<Mutex<ZKPRequests<N>>>,
     zkp_events_notifier: BroadcastSender<ZKPEvent<N>>,
+    proving_job_store: Option<Arc<ProvingJobStore>>,
 }
 
 impl<N: Network> ZKPComponent<N> {
@@ -146,9 +185,10 @@ impl<N: Network> ZKPComponent<N> {
             #[cfg(feature = "zkp-prover")]
             zk_prover: None,
             zk_proofs_stream,
-            proof_storage,
+            proof_storage: proof_storage.map(Arc::from),
             zkp_requests: Arc::new(Mutex::new(ZKPRequests::new(network))),
             zkp_events_notifier,
+            proving_job_store: None,
         };
 
         // Loads the proof from the db if any.
@@ -167,22 +207,32 @@ impl<N: Network> ZKPComponent<N> {
         is_prover_active: bool,
         prover_path: Option<PathBuf>,
         prover_keys_path: PathBuf,
+        prover_workers: Vec<SocketAddr>,
+        prover_worker_secret: Sensitive<String>,
         proof_storage: Option<Box<dyn ProofStore>>,
     ) -> Self {
         let mut zkp_component = Self::new(blockchain, network, executor, proof_storage).await;
 
         // Activates the prover based on the configuration provided.
         zkp_component.zk_prover = match (is_prover_active, &zkp_component.blockchain) {
-            (true, BlockchainProxy::Full(ref blockchain)) => Some(
-                ZKProver::new(
-                    Arc::clone(blockchain),
-                    Arc::clone(&zkp_component.network),
-                    Arc::clone(&zkp_component.zkp_state),
-                    prover_path,
-                    prover_keys_path,
+            (true, BlockchainProxy::Full(ref blockchain)) => {
+                let proving_job_store = Arc::new(ProvingJobStore::new(&prover_keys_path));
+                zkp_component.proving_job_store = Some(Arc::clone(&proving_job_store));
+
+                Some(
+                    ZKProver::new(
+                        Arc::clone(blockchain),
+                        Arc::clone(&zkp_component.network),
+                        Arc::clone(&zkp_component.zkp_state),
+                        prover_path,
+                        prover_keys_path,
+                        prover_workers,
+                        prover_worker_secret,
+                        proving_job_store,
+                    )
+                    .await,
                 )
-                .await,
-            ),
+            }
             (true, _) => {
                 log::error!("ZKP Prover cannot be activated for a light node.");
                 None
@@ -195,8 +245,12 @@ impl<N: Network> ZKPComponent<N> {
 
     /// Launches thread that processes the zkp requests and replies to them.
     fn launch_request_handler(&self, executor: impl TaskExecutor + Send + 'static) {
-        let stream = self.network.receive_requests::<RequestZKP>();
         let env = Arc::new(ZKPStateEnvironment::from(self));
+
+        let stream = self.network.receive_requests::<RequestZKP>();
+        executor.exec(Box::pin(request_handler(&self.network, stream, &env)));
+
+        let stream = self.network.receive_requests::<RequestZKPAt>();
         executor.exec(Box::pin(request_handler(&self.network, stream, &env)));
     }
 
@@ -207,6 +261,8 @@ impl<N: Network> ZKPComponent<N> {
             zkp_state: Arc::clone(&self.zkp_state),
             zkp_requests: Arc::clone(&self.zkp_requests),
             zkp_events_notifier: self.zkp_events_notifier.clone(),
+            proof_storage: self.proof_storage.clone(),
+            proving_job_store: self.proving_job_store.clone(),
         }
     }
 
@@ -223,11 +279,16 @@ impl<N: Network> ZKPComponent<N> {
     fn load_proof_from_db(&mut self) {
         if let Some(proof_storage) = &self.proof_storage {
             if let Some(loaded_proof) = proof_storage.get_zkp() {
+                // Use the stored election block if there is one for this proof. The proof is verified
+                // against it, so it doesn't need to be known to the blockchain.
+                let election_block = proof_storage
+                    .get_zkp_election_block()
+                    .filter(|block| block.block_number() == loaded_proof.block_number);
                 let mut this = Pin::new(self);
 
                 if let Err(e) = this.as_mut().push_proof_from_peers(
                     loaded_proof,
-                    None,
+                    election_block,
                     false,
                     ProofSource::SelfGenerated,
                 ) {
@@ -235,7 +296,7 @@ impl<N: Network> ZKPComponent<N> {
                     this.proof_storage
                         .as_ref()
                         .unwrap()
-                        .set_zkp(&this.zkp_state.read().clone().into());
+                        .set_zkp_state(&this.zkp_state.read());
                 } else {
                     log::info!("The zk proof was successfully load from disk");
                 }
@@ -271,7 +332,7 @@ impl<N: Network> ZKPComponent<N> {
         // Adds the new proof to storage.
         if let Some(proof_storage) = &self.proof_storage {
             if add_to_storage {
-                proof_storage.set_zkp(&zkp_state_lock.clone().into())
+                proof_storage.set_zkp_state(&zkp_state_lock)
             }
         }
         drop(zkp_state_lock);
@@ -371,7 +432,7 @@ impl<N: Network> Future for ZKPComponent<N> {
                 Poll::Ready(Some((zk_proof, block))) => {
                     log::info!("New ZK Proof generated by us");
                     if let Some(proof_storage) = &self.proof_storage {
-                        proof_storage.set_zkp(&self.zkp_state.read().clone().into());
+                        proof_storage.set_zkp_state(&self.zkp_state.read());
                     }
 
                     _ = self.zkp_events_notifier.send(ZKPEvent::new(
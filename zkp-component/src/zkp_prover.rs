@@ -2,6 +2,7 @@ use std::{
     collections::VecDeque,
     error::Error,
     future,
+    net::SocketAddr,
     path::PathBuf,
     pin::Pin,
     sync::Arc,
@@ -15,10 +16,15 @@ use nimiq_blockchain_interface::{AbstractBlockchain, BlockchainEvent, Direction}
 use nimiq_genesis::NetworkInfo;
 use nimiq_network_interface::network::Network;
 use nimiq_primitives::policy::Policy;
+use nimiq_utils::Sensitive;
 use parking_lot::{lock_api::RwLockUpgradableReadGuard, RwLock, RwLockWriteGuard};
 use tokio::sync::oneshot::{channel, Sender};
 
-use crate::{proof_gen_utils::*, types::*};
+use crate::{
+    proof_gen_utils::*,
+    proving_job::{ProvingJob, ProvingJobStatus, ProvingJobStore},
+    types::*,
+};
 
 /// ZK Prover generates the zk proof for an election block. It has:
 ///
@@ -30,6 +36,9 @@ use crate::{proof_gen_utils::*, types::*};
 /// - The current proof generation future if a proof is being generated
 /// - The path of the proving keys directory
 /// - The path of the prover binary
+/// - The workers the public key tree sub-proofs are distributed to
+/// - The secret shared with the workers
+/// - The persisted state of the current proving job
 ///
 /// The proofs are returned by polling the components.
 pub struct ZKProver<N: Network> {
@@ -43,6 +52,9 @@ pub struct ZKProver<N: Network> {
         Option<BoxFuture<'static, Result<(ZKPState, MacroBlock), ZKProofGenerationError>>>,
     prover_keys_path: PathBuf,
     prover_path: Option<PathBuf>,
+    prover_workers: Vec<SocketAddr>,
+    prover_worker_secret: Sensitive<String>,
+    proving_job_store: Arc<ProvingJobStore>,
 }
 
 impl<N: Network> ZKProver<N> {
@@ -52,6 +64,9 @@ impl<N: Network> ZKProver<N> {
         zkp_state: Arc<RwLock<ZKPState>>,
         prover_path: Option<PathBuf>,
         prover_keys_path: PathBuf,
+        prover_workers: Vec<SocketAddr>,
+        prover_worker_secret: Sensitive<String>,
+        proving_job_store: Arc<ProvingJobStore>,
     ) -> Self {
         let network_info = NetworkInfo::from_network_id(blockchain.read().network_id());
         let genesis_block = network_info.genesis_block().unwrap_macro();
@@ -84,6 +99,32 @@ impl<N: Network> ZKProver<N> {
             VecDeque::new()
         };
 
+        // Resumes the persisted job if it proves the next pending election block, otherwise its cached
+        // sub-proofs are of no use anymore.
+        if let Some(job) = proving_job_store.job() {
+            let resumable = pending_election_blocks
+                .front()
+                .map_or(false, |block| block.hash() == job.block_hash);
+
+            if resumable && job.status != ProvingJobStatus::Finished {
+                log::info!(
+                    block_number = job.block_number,
+                    attempts = job.attempts,
+                    "Resuming proof generation from cached sub-proofs"
+                );
+            } else if matches!(
+                job.status,
+                ProvingJobStatus::Running | ProvingJobStatus::Failed
+            ) {
+                log::info!(
+                    block_number = job.block_number,
+                    "Cancelling outdated proving job"
+                );
+                proving_job_store.set_status(ProvingJobStatus::Cancelled);
+                proving_job_store.clear_cached_proofs();
+            }
+        }
+
         // Gets the stream of blockchain events and converts it into an election macro block stream
         let blockchain_election_rx = blockchain_rg.notifier_as_stream();
         let blockchain2 = Arc::clone(&blockchain);
@@ -113,12 +154,17 @@ impl<N: Network> ZKProver<N> {
             proof_future: None,
             prover_keys_path,
             prover_path,
+            prover_workers,
+            prover_worker_secret,
+            proving_job_store,
         }
     }
 
     /// This sends the kill signal to the proof generation process.
     pub(crate) fn cancel_current_proof_production(&mut self) {
         if let Some(sender) = self.sender.take() {
+            self.proving_job_store
+                .set_status(ProvingJobStatus::Cancelled);
             sender.send(()).unwrap();
         }
     }
@@ -151,6 +197,16 @@ impl<N: Network> ZKProver<N> {
         if zkp_state.latest_block.block_number()
             == block.block_number() - Policy::blocks_per_epoch()
         {
+            // Continues the persisted job if it proves the same block, the prover process then reuses
+            // the cached sub-proofs.
+            let mut job = match self.proving_job_store.job() {
+                Some(job) if job.proves(&zkp_state.latest_block, &block) => job,
+                _ => ProvingJob::new(&zkp_state.latest_block, &block),
+            };
+            job.status = ProvingJobStatus::Running;
+            job.attempts += 1;
+            self.proving_job_store.set_job(job);
+
             let (sender, recv) = channel();
             self.proof_future = Some(
                 launch_generate_new_proof(
@@ -161,6 +217,8 @@ impl<N: Network> ZKProver<N> {
                         final_block: block.clone(),
                         genesis_header_hash: self.genesis_header_hash,
                         prover_keys_path: self.prover_keys_path.clone(),
+                        prover_workers: self.prover_workers.clone(),
+                        prover_worker_secret: self.prover_worker_secret.clone(),
                     },
                     self.prover_path.clone(),
                 )
@@ -221,12 +279,28 @@ impl<N: Network> Stream for ZKProver<N> {
 
                         let zkp_state_lock = RwLockWriteGuard::downgrade(zkp_state_lock);
 
+                        self.proving_job_store
+                            .set_status(ProvingJobStatus::Finished);
+
                         let proof: ZKProof = zkp_state_lock.clone().into();
                         Self::broadcast_zk_proof(&self.network, proof.clone());
                         return Poll::Ready(Some((proof, block)));
                     }
                     Err(e) => {
-                        log::error!(error = %e, "Error generating ZK Proof for block");
+                        let cancelled = self
+                            .proving_job_store
+                            .job()
+                            .map_or(false, |job| job.status == ProvingJobStatus::Cancelled);
+
+                        // The prover process has terminated, so the cached sub-proofs of a cancelled
+                        // job can be safely removed.
+                        if cancelled {
+                            log::debug!("Cancelled ZK Proof generation");
+                            self.proving_job_store.clear_cached_proofs();
+                        } else {
+                            log::error!(error = %e, "Error generating ZK Proof for block");
+                            self.proving_job_store.set_status(ProvingJobStatus::Failed);
+                        }
                     }
                 };
             }
@@ -6,11 +6,14 @@ use std::{
 
 use futures::{future::BoxFuture, stream::FuturesUnordered, FutureExt, Stream, StreamExt};
 use nimiq_block::MacroBlock;
+use nimiq_genesis::NetworkInfo;
 use nimiq_network_interface::{network::Network, request::RequestError};
+use nimiq_primitives::networks::NetworkId;
 use nimiq_utils::WakerExt as _;
+use nimiq_zkp_primitives::NanoZKPError;
 use tokio::sync::oneshot::{channel, Receiver, Sender};
 
-use crate::types::*;
+use crate::{proof_utils::validate_proof_get_new_state, types::*};
 
 pub struct ZKPRequestsItem<N: Network> {
     pub peer_id: N::PeerId,
@@ -83,6 +86,45 @@ impl<N: Network + 'static> ZKPRequests<N> {
         rx
     }
 
+    /// Requests the ZKP for the given election block from a single peer. The returned future resolves once the
+    /// response was verified. The proof is verified against the election block sent by the peer, which thereby
+    /// authenticates the block, so the caller does not need to know the block beforehand.
+    pub fn request_zkp_at(
+        &self,
+        peer_id: N::PeerId,
+        network_id: NetworkId,
+        block_number: u32,
+    ) -> BoxFuture<'static, Result<(ZKProof, MacroBlock), Error>> {
+        let network = Arc::clone(&self.network);
+        async move {
+            let response = network
+                .request::<RequestZKPAt>(RequestZKPAt { block_number }, peer_id)
+                .await?;
+
+            let (proof, block) = match response {
+                RequestZKPAtResponse::Proof(proof, block) => (proof, block),
+                RequestZKPAtResponse::NotFound => return Err(Error::ProofNotFound),
+            };
+
+            if block.header.network != network_id
+                || block.block_number() != block_number
+                || proof.block_number != block_number
+                || !block.is_election_block()
+            {
+                return Err(Error::InvalidBlock);
+            }
+
+            let genesis_block = NetworkInfo::from_network_id(network_id)
+                .genesis_block()
+                .unwrap_macro();
+            let zk_proof = proof.proof.clone().ok_or(NanoZKPError::EmptyProof)?;
+            validate_proof_get_new_state(zk_proof, block.clone(), genesis_block)?;
+
+            Ok((proof, block))
+        }
+        .boxed()
+    }
+
     fn push_request(
         &mut self,
         peer_id: N::PeerId,
@@ -151,7 +151,7 @@ async fn can_detect_valid_proof_none_genesis_blocks() {
 
 #[test(tokio::test)]
 async fn can_store_and_load_zkp_state_from_db() {
-    let env = VolatileDatabase::new(1).unwrap();
+    let env = VolatileDatabase::new(2).unwrap();
 
     let proof_store = DBProofStore::new(env);
     let new_proof = ZKProof {
@@ -178,3 +178,41 @@ async fn can_store_and_load_zkp_state_from_db() {
         "Load from db was not successful"
     );
 }
+
+#[test(tokio::test)]
+async fn can_keep_a_bounded_zkp_history() {
+    let env = VolatileDatabase::new(2).unwrap();
+
+    let proof_store = DBProofStore::with_history_size(env, 2);
+    let proof_at = |epoch: u32| ZKProof {
+        block_number: epoch * Policy::blocks_per_epoch(),
+        proof: Some(Proof::default()),
+    };
+
+    // The genesis proof is never part of the history.
+    proof_store.set_zkp(&ZKProof {
+        block_number: Policy::genesis_block_number(),
+        proof: None,
+    });
+    assert_eq!(proof_store.get_zkp_at(Policy::genesis_block_number()), None);
+
+    for epoch in 1..=4 {
+        proof_store.set_zkp(&proof_at(epoch));
+    }
+
+    assert_eq!(proof_store.get_zkp().unwrap(), proof_at(4));
+    assert_eq!(
+        proof_store.get_zkp_at(proof_at(4).block_number),
+        Some(proof_at(4))
+    );
+    assert_eq!(
+        proof_store.get_zkp_at(proof_at(3).block_number),
+        Some(proof_at(3))
+    );
+    assert_eq!(
+        proof_store.get_zkp_at(proof_at(2).block_number),
+        None,
+        "Old proofs were not pruned"
+    );
+    assert_eq!(proof_store.get_zkp_at(proof_at(1).block_number), None);
+}
@@ -0,0 +1,80 @@
+use std::fs;
+
+use nimiq_block::MacroBlock;
+use nimiq_serde::Serialize;
+use nimiq_zkp_component::proving_job::{ProvingJob, ProvingJobStatus, ProvingJobStore, SubCircuit};
+use tempfile::tempdir;
+
+fn election_blocks() -> (MacroBlock, MacroBlock) {
+    let previous_block = MacroBlock::default();
+    let mut block = MacroBlock::default();
+    block.header.block_number = 43200;
+    (previous_block, block)
+}
+
+#[test]
+fn it_persists_proving_jobs() {
+    let dir = tempdir().unwrap();
+    let (previous_block, block) = election_blocks();
+
+    let store = ProvingJobStore::new(dir.path());
+    assert_eq!(store.job(), None);
+
+    let mut job = ProvingJob::new(&previous_block, &block);
+    job.attempts = 1;
+    store.set_job(job.clone());
+    store.set_status(ProvingJobStatus::Failed);
+
+    // A restarted prover sees the job as it was left.
+    let store = ProvingJobStore::new(dir.path());
+    job.status = ProvingJobStatus::Failed;
+    assert_eq!(store.job(), Some(job.clone()));
+    assert!(job.proves(&previous_block, &block));
+    assert!(!job.proves(&block, &block));
+}
+
+#[test]
+fn it_reports_progress_from_cached_sub_proofs() {
+    let dir = tempdir().unwrap();
+    let (previous_block, block) = election_blocks();
+
+    let store = ProvingJobStore::new(dir.path());
+    store.set_job(ProvingJob::new(&previous_block, &block));
+
+    let proofs = dir.path().join("proofs");
+    fs::create_dir_all(&proofs).unwrap();
+    fs::write(proofs.join("pk_tree_5_0.bin"), b"").unwrap();
+    fs::write(proofs.join("pk_tree_5_7.bin"), b"").unwrap();
+    fs::write(proofs.join("pk_tree_5_3.bin.tmp"), b"").unwrap();
+
+    // The cache is not tagged with the job yet, so nothing can be reused.
+    let progress = store.progress().unwrap();
+    assert!(progress.sub_circuits.iter().all(|sub| sub.completed == 0));
+
+    fs::write(
+        proofs.join("meta_data.bin"),
+        block.hash_blake2s().0.serialize_to_vec(),
+    )
+    .unwrap();
+
+    let progress = store.progress().unwrap();
+    assert_eq!(progress.sub_circuits.len(), SubCircuit::all().len());
+    let leaves = &progress.sub_circuits[0];
+    assert_eq!(leaves.circuit, SubCircuit::PkTree(5));
+    assert_eq!((leaves.completed, leaves.total), (2, 32));
+    assert!(progress.sub_circuits[1..]
+        .iter()
+        .all(|sub| sub.completed == 0));
+
+    store.clear_cached_proofs();
+    let progress = store.progress().unwrap();
+    assert!(progress.sub_circuits.iter().all(|sub| sub.completed == 0));
+
+    // Finished jobs report all sub-proofs, even though the prover deleted the cache.
+    store.set_status(ProvingJobStatus::Finished);
+    let progress = store.progress().unwrap();
+    assert!(progress
+        .sub_circuits
+        .iter()
+        .all(|sub| sub.completed == sub.total));
+}
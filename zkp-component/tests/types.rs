@@ -5,6 +5,7 @@ use nimiq_block::MacroBlock;
 use nimiq_database_value::{AsDatabaseBytes, FromDatabaseValue};
 use nimiq_serde::{Deserialize, Serialize};
 use nimiq_test_utils::zkp_test_data::ZKP_TEST_KEYS_PATH;
+use nimiq_utils::Sensitive;
 use nimiq_zkp_component::types::{ProofInput, ZKPState, ZKProof};
 
 #[test]
@@ -72,6 +73,8 @@ fn it_serializes_and_deserializes_proof_input() {
         final_block: MacroBlock::default(),
         genesis_header_hash: [2; 32],
         prover_keys_path: PathBuf::from(ZKP_TEST_KEYS_PATH),
+        prover_workers: vec!["127.0.0.1:8650".parse().unwrap()],
+        prover_worker_secret: Sensitive("secret".to_string()),
     };
     let serialized = Serialize::serialize_to_vec(&proof_input);
     let deserialized: ProofInput = Deserialize::deserialize_from_vec(&serialized).unwrap();
@@ -83,6 +86,8 @@ fn it_serializes_and_deserializes_proof_input() {
         final_block: MacroBlock::default(),
         genesis_header_hash: [0; 32],
         prover_keys_path: PathBuf::from(ZKP_TEST_KEYS_PATH),
+        prover_workers: vec![],
+        prover_worker_secret: Sensitive::default(),
     };
     let serialized = Serialize::serialize_to_vec(&proof_input);
     let deserialized: ProofInput = Deserialize::deserialize_from_vec(&serialized).unwrap();
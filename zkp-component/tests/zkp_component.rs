@@ -68,7 +68,7 @@ async fn loads_valid_zkp_state_from_db() {
     let mut hub = MockHub::new();
     let network = Arc::new(hub.new_network());
 
-    let proof_store = DBProofStore::new(VolatileDatabase::new(1).unwrap());
+    let proof_store = DBProofStore::new(VolatileDatabase::new(2).unwrap());
     let producer = BlockProducer::new(signing_key(), voting_key());
     produce_macro_blocks_with_rng(
         &producer,
@@ -111,7 +111,7 @@ async fn does_not_load_invalid_zkp_state_from_db() {
     let mut hub = MockHub::new();
     let network = Arc::new(hub.new_network());
 
-    let env = VolatileDatabase::new(1).unwrap();
+    let env = VolatileDatabase::new(2).unwrap();
 
     let proof_store = DBProofStore::new(env);
     let new_proof = ZKProof {
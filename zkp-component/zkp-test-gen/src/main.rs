@@ -108,6 +108,8 @@ async fn produce_two_consecutive_valid_zk_proofs() {
         block,
         genesis_header_hash,
         Path::new(DEFAULT_TEST_KEYS_PATH),
+        &[],
+        "",
     )
     .unwrap();
     let proof = zkp_state.clone().into();
@@ -135,6 +137,8 @@ async fn produce_two_consecutive_valid_zk_proofs() {
         block,
         genesis_header_hash,
         Path::new(DEFAULT_TEST_KEYS_PATH),
+        &[],
+        "",
     )
     .unwrap();
     let proof = zkp_state.into();
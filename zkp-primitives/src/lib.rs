@@ -32,4 +32,8 @@ pub enum NanoZKPError {
     EmptyProof,
     #[error("invalid block")]
     InvalidBlock,
+    #[error("invalid proof job")]
+    InvalidProofJob,
+    #[error("proof worker error: {0}")]
+    Worker(String),
 }
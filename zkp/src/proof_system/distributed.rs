@@ -0,0 +1,459 @@
+//! Distributes the sub-proofs of the public key tree across several worker processes.
+//!
+//! The coordinator connects to the workers over TCP and hands out the jobs of one tree level at a
+//! time. Every message is prefixed with its length as a big endian u32. After connecting, both
+//! sides prove that they know the secret shared between coordinator and workers, so workers only
+//! accept jobs from their coordinators. The connection is not encrypted, though: anyone on the
+//! network path can read the jobs and proofs, so workers should still be run on a trusted network.
+use std::{
+    collections::VecDeque,
+    io::{self, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    path::Path,
+    thread,
+    time::Duration,
+};
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use nimiq_hash::hmac::compute_hmac_sha512;
+use nimiq_zkp_circuits::{circuits::vk_commitments::VerifyingKeys, setup::load_keys};
+use nimiq_zkp_primitives::NanoZKPError;
+use parking_lot::Mutex;
+use rand::{thread_rng, RngCore};
+
+use super::prove::{prove_pk_tree_job, PkTreeJob, PkTreeProofVerifier, PkTreeProver};
+
+/// The version of the protocol between coordinator and workers. Both sides send it after connecting
+/// and close the connection on a mismatch.
+pub const WORKER_PROTOCOL_VERSION: u32 = 2;
+
+/// The maximum time to establish a connection to a worker.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The maximum time to complete the handshake and to send a message.
+const IO_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The maximum time the coordinator waits for a proof, and a worker waits for the next job. While a
+/// worker is idle, the coordinator might be waiting for the proofs of the other workers.
+const PROOF_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+/// The size of the random challenge each side sends during the handshake.
+const NONCE_SIZE: usize = 32;
+
+/// The size of the authentication tag, a HMAC-SHA512.
+const TAG_SIZE: usize = 64;
+
+/// The maximum size of a message. Jobs contain at most all public keys and two proofs.
+const MAX_MESSAGE_SIZE: usize = 16 * 1024 * 1024;
+
+const STATUS_OK: u8 = 0;
+const STATUS_ERROR: u8 = 1;
+
+fn write_message(stream: &mut TcpStream, message: &[u8]) -> io::Result<()> {
+    stream.write_all(&(message.len() as u32).to_be_bytes())?;
+    stream.write_all(message)?;
+    stream.flush()
+}
+
+fn read_message(stream: &mut TcpStream) -> io::Result<Vec<u8>> {
+    let mut len = [0u8; 4];
+    stream.read_exact(&mut len)?;
+
+    let len = u32::from_be_bytes(len) as usize;
+    if len > MAX_MESSAGE_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "message exceeds maximum size",
+        ));
+    }
+
+    let mut message = vec![0u8; len];
+    stream.read_exact(&mut message)?;
+    Ok(message)
+}
+
+/// The side of a connection, which is part of the authentication tag so that a tag of one side
+/// can't be replayed by the other.
+#[derive(Clone, Copy)]
+enum Role {
+    Coordinator,
+    Worker,
+}
+
+impl Role {
+    fn label(self) -> &'static [u8] {
+        match self {
+            Role::Coordinator => b"nimiq-zkp-coordinator",
+            Role::Worker => b"nimiq-zkp-worker",
+        }
+    }
+
+    fn peer(self) -> Role {
+        match self {
+            Role::Coordinator => Role::Worker,
+            Role::Worker => Role::Coordinator,
+        }
+    }
+}
+
+/// The tag with which `role` proves the knowledge of the secret, bound to the challenge of the
+/// peer and its own.
+fn authentication_tag(
+    secret: &[u8],
+    role: Role,
+    peer_nonce: &[u8],
+    own_nonce: &[u8],
+) -> [u8; TAG_SIZE] {
+    compute_hmac_sha512(secret, &[role.label(), peer_nonce, own_nonce].concat()).into()
+}
+
+/// Exchanges the protocol versions and authenticates the peer by a challenge-response with the
+/// shared secret.
+fn handshake(stream: &mut TcpStream, secret: &[u8], role: Role) -> io::Result<()> {
+    let mut own_nonce = [0u8; NONCE_SIZE];
+    thread_rng().fill_bytes(&mut own_nonce);
+
+    stream.write_all(&WORKER_PROTOCOL_VERSION.to_be_bytes())?;
+    stream.write_all(&own_nonce)?;
+    stream.flush()?;
+
+    let mut version = [0u8; 4];
+    stream.read_exact(&mut version)?;
+    let version = u32::from_be_bytes(version);
+
+    if version != WORKER_PROTOCOL_VERSION {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("unsupported worker protocol version {version}"),
+        ));
+    }
+
+    let mut peer_nonce = [0u8; NONCE_SIZE];
+    stream.read_exact(&mut peer_nonce)?;
+
+    stream.write_all(&authentication_tag(secret, role, &peer_nonce, &own_nonce))?;
+    stream.flush()?;
+
+    let mut tag = [0u8; TAG_SIZE];
+    stream.read_exact(&mut tag)?;
+    let expected_tag = authentication_tag(secret, role.peer(), &own_nonce, &peer_nonce);
+
+    // Compare in constant time to not leak how much of the tag is correct.
+    let difference = tag
+        .iter()
+        .zip(expected_tag.iter())
+        .fold(0, |difference, (a, b)| difference | (a ^ b));
+    if difference != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "peer failed to authenticate",
+        ));
+    }
+
+    Ok(())
+}
+
+/// The outcome of sending a job to a worker.
+enum JobResult {
+    /// The worker returned the proof.
+    Proof(Vec<u8>),
+    /// The worker failed to generate the proof.
+    Failed(String),
+}
+
+/// A connection to a single worker process.
+struct Worker {
+    address: SocketAddr,
+    stream: Option<TcpStream>,
+}
+
+impl Worker {
+    fn prove(
+        &mut self,
+        job: &[u8],
+        secret: &[u8],
+        proof_timeout: Duration,
+    ) -> io::Result<JobResult> {
+        // The worker might have closed a connection that was idle for too long, so retry once on
+        // a new connection. A worker that timed out proving the job isn't given another try.
+        let reused = self.stream.is_some();
+        match self.try_prove(job, secret, proof_timeout) {
+            Err(error) if reused && !is_timeout(&error) => {
+                self.try_prove(job, secret, proof_timeout)
+            }
+            result => result,
+        }
+    }
+
+    fn try_prove(
+        &mut self,
+        job: &[u8],
+        secret: &[u8],
+        proof_timeout: Duration,
+    ) -> io::Result<JobResult> {
+        if self.stream.is_none() {
+            let mut stream = TcpStream::connect_timeout(&self.address, CONNECT_TIMEOUT)?;
+            stream.set_read_timeout(Some(IO_TIMEOUT))?;
+            stream.set_write_timeout(Some(IO_TIMEOUT))?;
+            handshake(&mut stream, secret, Role::Coordinator)?;
+
+            // The worker only answers once the proof is generated.
+            stream.set_read_timeout(Some(proof_timeout))?;
+            self.stream = Some(stream);
+        }
+
+        let stream = self.stream.as_mut().unwrap();
+        let result = write_message(stream, job).and_then(|_| read_message(stream));
+
+        let response = match result {
+            Ok(response) => response,
+            Err(error) => {
+                self.stream = None;
+                return Err(error);
+            }
+        };
+
+        match response.split_first() {
+            Some((&STATUS_OK, proof)) => Ok(JobResult::Proof(proof.to_vec())),
+            Some((&STATUS_ERROR, error)) => Ok(JobResult::Failed(
+                String::from_utf8_lossy(error).into_owned(),
+            )),
+            _ => {
+                self.stream = None;
+                Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "invalid worker response",
+                ))
+            }
+        }
+    }
+}
+
+fn is_timeout(error: &io::Error) -> bool {
+    matches!(
+        error.kind(),
+        io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock
+    )
+}
+
+/// Proves public key tree jobs on a set of remote workers.
+///
+/// The jobs are handed out from a shared queue, so faster workers take on more jobs. If a worker
+/// becomes unreachable, doesn't return a proof in time or returns a proof that doesn't verify, its
+/// job is handed to the remaining workers and it is not used anymore.
+pub struct WorkerPool<V = VerifyingKeys> {
+    workers: Mutex<Vec<Worker>>,
+    secret: Vec<u8>,
+    proof_timeout: Duration,
+    verifier: V,
+}
+
+impl<V: PkTreeProofVerifier + Sync> WorkerPool<V> {
+    /// Creates a pool of the workers at the given addresses, which must have been started with the
+    /// same secret. The proofs returned by the workers are checked with the given verifier, usually
+    /// the verifying keys of the circuits.
+    pub fn new(addresses: Vec<SocketAddr>, secret: &str, verifier: V) -> WorkerPool<V> {
+        let workers = addresses
+            .into_iter()
+            .map(|address| Worker {
+                address,
+                stream: None,
+            })
+            .collect();
+
+        WorkerPool {
+            workers: Mutex::new(workers),
+            secret: secret.as_bytes().to_vec(),
+            proof_timeout: PROOF_TIMEOUT,
+            verifier,
+        }
+    }
+
+    /// Sets the maximum time to wait for a worker to return a proof. Jobs of workers that take
+    /// longer are rescheduled. Defaults to one hour.
+    pub fn with_proof_timeout(mut self, proof_timeout: Duration) -> Self {
+        self.proof_timeout = proof_timeout;
+        self
+    }
+}
+
+impl<V: PkTreeProofVerifier + Sync> PkTreeProver for WorkerPool<V> {
+    fn prove_pk_tree_jobs(&self, jobs: Vec<PkTreeJob>) -> Result<Vec<Vec<u8>>, NanoZKPError> {
+        let mut queue = VecDeque::with_capacity(jobs.len());
+        for (index, job) in jobs.iter().enumerate() {
+            let mut bytes = vec![];
+            job.serialize_uncompressed(&mut bytes)?;
+            queue.push_back((index, bytes));
+        }
+
+        let num_jobs = queue.len();
+        let queue = Mutex::new(queue);
+        let proofs = Mutex::new(vec![None; num_jobs]);
+        let failure = Mutex::new(None);
+
+        let mut workers = self.workers.lock();
+
+        // Jobs of unreachable workers are rescheduled, so keep going until the queue is drained.
+        while !queue.lock().is_empty() {
+            if workers.is_empty() {
+                return Err(NanoZKPError::Worker(
+                    "No reachable prover workers".to_string(),
+                ));
+            }
+
+            // Every worker runs in its own thread and takes jobs from the queue until it is empty.
+            let retired: Vec<bool> = thread::scope(|scope| {
+                let handles: Vec<_> = workers
+                    .iter_mut()
+                    .map(|worker| {
+                        let (jobs, queue, proofs, failure) = (&jobs, &queue, &proofs, &failure);
+                        let (secret, proof_timeout) = (&self.secret, self.proof_timeout);
+                        let verifier = &self.verifier;
+                        scope.spawn(move || loop {
+                            if failure.lock().is_some() {
+                                return false;
+                            }
+
+                            let Some((index, job)) = queue.lock().pop_front() else {
+                                return false;
+                            };
+
+                            match worker.prove(&job, secret, proof_timeout) {
+                                // Workers are authenticated, but their proofs are still checked.
+                                Ok(JobResult::Proof(proof))
+                                    if verifier.verify_pk_tree_proof(&jobs[index], &proof) =>
+                                {
+                                    proofs.lock()[index] = Some(proof)
+                                }
+                                Ok(JobResult::Proof(_)) => {
+                                    log::warn!(
+                                        address = %worker.address,
+                                        "Prover worker returned an invalid proof, rescheduling its job"
+                                    );
+                                    queue.lock().push_back((index, job));
+                                    return true;
+                                }
+                                Ok(JobResult::Failed(error)) => {
+                                    *failure.lock() = Some(format!(
+                                        "Worker {} failed to prove job: {}",
+                                        worker.address, error
+                                    ));
+                                    return false;
+                                }
+                                Err(error) => {
+                                    log::warn!(
+                                        address = %worker.address,
+                                        %error,
+                                        "Prover worker unreachable, rescheduling its job"
+                                    );
+                                    queue.lock().push_back((index, job));
+                                    return true;
+                                }
+                            }
+                        })
+                    })
+                    .collect();
+
+                handles
+                    .into_iter()
+                    .map(|handle| handle.join().unwrap_or(true))
+                    .collect()
+            });
+
+            let mut retired = retired.into_iter();
+            workers.retain(|_| !retired.next().unwrap_or(false));
+
+            if let Some(error) = failure.lock().take() {
+                return Err(NanoZKPError::Worker(error));
+            }
+        }
+
+        Ok(proofs.into_inner().into_iter().flatten().collect())
+    }
+}
+
+/// Runs a worker that proves public key tree jobs for the coordinators connecting to the listener.
+/// Connections are served one at a time, since a single proof already uses all available cores.
+///
+/// In debug mode, every proof is verified right after it was generated.
+pub fn run_worker(
+    listener: TcpListener,
+    prover_keys_path: &Path,
+    secret: &str,
+    debug_mode: bool,
+) -> Result<(), NanoZKPError> {
+    if secret.is_empty() {
+        return Err(NanoZKPError::Worker(
+            "A secret shared with the coordinators is required".to_string(),
+        ));
+    }
+    let keys = load_keys(prover_keys_path)?;
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(error) => {
+                log::warn!(%error, "Failed to accept coordinator connection");
+                continue;
+            }
+        };
+
+        // The jobs come from an authenticated coordinator, but are still checked to be valid.
+        serve_coordinator(stream, secret.as_bytes(), |job| {
+            let job = PkTreeJob::deserialize_uncompressed(job)?;
+            prove_pk_tree_job(&job, &keys, debug_mode, prover_keys_path)
+        });
+    }
+
+    Ok(())
+}
+
+/// Authenticates a connected coordinator and returns the proofs `prove` generates for its jobs
+/// until it disconnects.
+pub fn serve_coordinator<F>(mut stream: TcpStream, secret: &[u8], mut prove: F)
+where
+    F: FnMut(&[u8]) -> Result<Vec<u8>, NanoZKPError>,
+{
+    let peer = stream
+        .peer_addr()
+        .map(|address| address.to_string())
+        .unwrap_or_default();
+    log::info!(%peer, "Coordinator connected");
+
+    let result = stream
+        .set_read_timeout(Some(IO_TIMEOUT))
+        .and_then(|_| stream.set_write_timeout(Some(IO_TIMEOUT)))
+        .and_then(|_| handshake(&mut stream, secret, Role::Worker))
+        .and_then(|_| stream.set_read_timeout(Some(PROOF_TIMEOUT)));
+    if let Err(error) = result {
+        log::warn!(%peer, %error, "Handshake with coordinator failed");
+        return;
+    }
+
+    loop {
+        let job = match read_message(&mut stream) {
+            Ok(job) => job,
+            Err(error) => {
+                if error.kind() != io::ErrorKind::UnexpectedEof {
+                    log::warn!(%peer, %error, "Failed to read job");
+                }
+                break;
+            }
+        };
+
+        let response = match prove(&job) {
+            Ok(proof) => [&[STATUS_OK], proof.as_slice()].concat(),
+            Err(error) => {
+                log::error!(%peer, %error, "Failed to prove job");
+                [&[STATUS_ERROR], error.to_string().as_bytes()].concat()
+            }
+        };
+
+        if let Err(error) = write_message(&mut stream, &response) {
+            log::warn!(%peer, %error, "Failed to send proof");
+            break;
+        }
+    }
+
+    log::info!(%peer, "Coordinator disconnected");
+}
@@ -1,6 +1,8 @@
 use ark_groth16::Proof;
 use ark_mnt6_753::MNT6_753;
 
+#[cfg(feature = "zkp-prover")]
+pub mod distributed;
 #[cfg(feature = "zkp-prover")]
 pub mod prove;
 pub mod verify;
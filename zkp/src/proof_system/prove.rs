@@ -1,7 +1,8 @@
 use std::{
     fs,
     fs::{DirBuilder, File},
-    path::Path,
+    io::Write,
+    path::{Path, PathBuf},
 };
 
 use ark_crypto_primitives::snark::SNARK;
@@ -72,6 +73,30 @@ pub fn update_proof_cache(
 /// This function generates a proof for a new epoch, it uses the entire light macro sync. Note
 /// that the proof generation can easily take longer than 12 hours.
 pub fn prove(
+    prev_block: MacroBlock,
+    final_block: MacroBlock,
+    genesis_data: Option<(Proof<MNT6_753>, [u8; 32])>,
+    proof_caching: bool,
+    debug_mode: bool,
+    prover_keys_path: &Path,
+) -> Result<Proof<MNT6_753>, NanoZKPError> {
+    let keys = load_keys(prover_keys_path)?;
+    let pk_tree_prover = LocalPkTreeProver::new(keys, debug_mode, prover_keys_path);
+
+    prove_with_pk_tree_prover(
+        prev_block,
+        final_block,
+        genesis_data,
+        proof_caching,
+        debug_mode,
+        prover_keys_path,
+        &pk_tree_prover,
+    )
+}
+
+/// Same as [`prove`], but delegates the sub-proofs of the public key tree to the given prover.
+/// This allows to spread the most expensive part of the proof generation across several machines.
+pub fn prove_with_pk_tree_prover<P: PkTreeProver + ?Sized>(
     // The previous macro block.
     prev_block: MacroBlock,
     // The current election macro block.
@@ -88,6 +113,8 @@ pub fn prove(
     debug_mode: bool,
     // The path to where the `prover_keys` folder is stored in.
     prover_keys_path: &Path,
+    // The prover generating the sub-proofs of the public key tree.
+    pk_tree_prover: &P,
 ) -> Result<Proof<MNT6_753>, NanoZKPError> {
     // Make sure proofs cache is up-to-date.
     update_proof_cache(prover_keys_path, &final_block.hash_blake2s().0)?;
@@ -115,6 +142,7 @@ pub fn prove(
         prove_macro_block(
             rng,
             &keys,
+            pk_tree_prover,
             prev_block,
             final_block,
             debug_mode,
@@ -187,46 +215,356 @@ pub fn prove(
     Ok(proof)
 }
 
-fn prove_pk_tree_leaf<R: CryptoRng + Rng>(
-    rng: &mut R,
-    position: usize,
+/// An independent sub-proof of the public key tree. The jobs of one tree level don't depend on each
+/// other and can thus be proven in parallel, possibly by different worker processes.
+#[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct PkTreeJob {
+    /// The level of the node in the tree. The leaves are at level `PK_TREE_DEPTH`.
+    pub tree_level: u64,
+    /// The position of the node within its level.
+    pub position: u64,
+    /// The public keys covered by the node.
+    pub pks: Vec<G2MNT6>,
+    /// The signer bitmap of the public keys covered by the node.
+    pub signer_bitmap: Vec<bool>,
+    /// The serialized proofs of the left and right child. Empty for leaves.
+    pub child_proofs: Vec<Vec<u8>>,
+}
+
+/// Generates the sub-proofs of the public key tree.
+pub trait PkTreeProver {
+    /// Proves the given jobs and returns their serialized proofs in the same order.
+    fn prove_pk_tree_jobs(&self, jobs: Vec<PkTreeJob>) -> Result<Vec<Vec<u8>>, NanoZKPError>;
+}
+
+/// Proves all public key tree jobs sequentially in the current process.
+pub struct LocalPkTreeProver {
+    keys: VerifyingKeys,
+    debug_mode: bool,
+    prover_keys_path: PathBuf,
+}
+
+impl LocalPkTreeProver {
+    pub fn new(
+        keys: VerifyingKeys,
+        debug_mode: bool,
+        prover_keys_path: &Path,
+    ) -> LocalPkTreeProver {
+        LocalPkTreeProver {
+            keys,
+            debug_mode,
+            prover_keys_path: prover_keys_path.to_path_buf(),
+        }
+    }
+}
+
+impl PkTreeProver for LocalPkTreeProver {
+    fn prove_pk_tree_jobs(&self, jobs: Vec<PkTreeJob>) -> Result<Vec<Vec<u8>>, NanoZKPError> {
+        jobs.iter()
+            .map(|job| prove_pk_tree_job(job, &self.keys, self.debug_mode, &self.prover_keys_path))
+            .collect()
+    }
+}
+
+/// Generates the proofs of the public key tree bottom up and caches them to file. All missing
+/// proofs of a level are handed to the pk tree prover at once, such that it can prove them in parallel.
+fn prove_pk_tree<P: PkTreeProver + ?Sized>(
+    pk_tree_prover: &P,
     pks: &[G2MNT6],
     signer_bitmap: &[bool],
-    debug_mode: bool,
     proof_caching: bool,
-    dir_path: &Path,
-) -> Result<[u8; 32], NanoZKPError> {
+    path: &Path,
+) -> Result<(), NanoZKPError> {
     assert_eq!(pks.len(), signer_bitmap.len());
 
-    let name = "pk_tree_5";
+    let proofs = path.join("proofs");
 
-    // Calculate the aggregate public key commitment.
-    let mut agg_pk = G2MNT6::zero();
+    for tree_level in (0..=PK_TREE_DEPTH).rev() {
+        let name = format!("pk_tree_{tree_level}");
+        let num_nodes = 1 << tree_level;
+        let node_size = pks.len() / num_nodes;
+
+        let mut jobs = vec![];
+        for position in 0..num_nodes {
+            if proof_caching && proofs.join(format!("{name}_{position}.bin")).exists() {
+                continue;
+            }
+
+            // The children of the node have been proven in the previous iteration.
+            let child_proofs = if tree_level == PK_TREE_DEPTH {
+                vec![]
+            } else {
+                vec![
+                    fs::read(proofs.join(format!(
+                        "pk_tree_{}_{}.bin",
+                        tree_level + 1,
+                        2 * position
+                    )))?,
+                    fs::read(proofs.join(format!(
+                        "pk_tree_{}_{}.bin",
+                        tree_level + 1,
+                        2 * position + 1
+                    )))?,
+                ]
+            };
+
+            let range = position * node_size..(position + 1) * node_size;
+            jobs.push(PkTreeJob {
+                tree_level: tree_level as u64,
+                position: position as u64,
+                pks: pks[range.clone()].to_vec(),
+                signer_bitmap: signer_bitmap[range].to_vec(),
+                child_proofs,
+            });
+        }
+
+        if jobs.is_empty() {
+            continue;
+        }
+
+        log::info!(
+            "Generating {} sub-proofs for pk tree level {}",
+            jobs.len(),
+            tree_level
+        );
+
+        let positions: Vec<u64> = jobs.iter().map(|job| job.position).collect();
+        let job_proofs = pk_tree_prover.prove_pk_tree_jobs(jobs)?;
+        if job_proofs.len() != positions.len() {
+            return Err(NanoZKPError::Worker(
+                "Wrong number of pk tree proofs returned".to_string(),
+            ));
+        }
+
+        // Cache proofs to file.
+        for (position, proof) in positions.into_iter().zip(job_proofs) {
+            bytes_to_file(&proof, &name, Some(position as usize), path)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Proves a single node of the public key tree and returns the serialized proof.
+pub fn prove_pk_tree_job(
+    job: &PkTreeJob,
+    keys: &VerifyingKeys,
+    debug_mode: bool,
+    dir_path: &Path,
+) -> Result<Vec<u8>, NanoZKPError> {
+    let tree_level = job.tree_level as usize;
+    let num_children = if tree_level == PK_TREE_DEPTH { 0 } else { 2 };
+
+    // Jobs might have been received from another process, so make sure they are well-formed.
+    if tree_level > PK_TREE_DEPTH
+        || job.pks.is_empty()
+        || job.pks.len() != job.signer_bitmap.len()
+        || job.pks.len() % (1 << (PK_TREE_DEPTH - tree_level)) != 0
+        || job.child_proofs.len() != num_children
+    {
+        return Err(NanoZKPError::InvalidProofJob);
+    }
+
+    log::info!(
+        "Generating sub-proof: pk_tree_{}_{}",
+        tree_level,
+        job.position
+    );
+
+    let rng = &mut thread_rng();
+    let mut proof = vec![];
+
+    if tree_level == PK_TREE_DEPTH {
+        prove_pk_tree_leaf(rng, &job.pks, &job.signer_bitmap, debug_mode, dir_path)?
+            .serialize_uncompressed(&mut proof)?;
+    } else if tree_level % 2 == 0 {
+        prove_pk_tree_node_mnt4(
+            rng,
+            keys,
+            tree_level,
+            &job.pks,
+            &job.signer_bitmap,
+            Proof::deserialize_uncompressed_unchecked(&*job.child_proofs[0])?,
+            Proof::deserialize_uncompressed_unchecked(&*job.child_proofs[1])?,
+            debug_mode,
+            dir_path,
+        )?
+        .serialize_uncompressed(&mut proof)?;
+    } else {
+        prove_pk_tree_node_mnt6(
+            rng,
+            keys,
+            tree_level,
+            &job.pks,
+            &job.signer_bitmap,
+            Proof::deserialize_uncompressed_unchecked(&*job.child_proofs[0])?,
+            Proof::deserialize_uncompressed_unchecked(&*job.child_proofs[1])?,
+            debug_mode,
+            dir_path,
+        )?
+        .serialize_uncompressed(&mut proof)?;
+    }
+
+    Ok(proof)
+}
+
+/// Checks the proofs of public key tree jobs, e.g. the ones returned by remote workers.
+pub trait PkTreeProofVerifier {
+    /// Returns whether the serialized proof is a valid proof of the job.
+    fn verify_pk_tree_proof(&self, job: &PkTreeJob, proof: &[u8]) -> bool;
+}
+
+impl PkTreeProofVerifier for VerifyingKeys {
+    fn verify_pk_tree_proof(&self, job: &PkTreeJob, proof: &[u8]) -> bool {
+        verify_pk_tree_job_proof(job, proof, self).unwrap_or(false)
+    }
+}
+
+/// Verifies the serialized proof of a single node of the public key tree against the verifying key
+/// of its tree level and the public inputs derived from the job.
+pub fn verify_pk_tree_job_proof(
+    job: &PkTreeJob,
+    proof: &[u8],
+    keys: &VerifyingKeys,
+) -> Result<bool, NanoZKPError> {
+    let tree_level = job.tree_level as usize;
+
+    if tree_level > PK_TREE_DEPTH
+        || job.pks.is_empty()
+        || job.pks.len() != job.signer_bitmap.len()
+        || job.pks.len() % (1 << (PK_TREE_DEPTH - tree_level)) != 0
+    {
+        return Err(NanoZKPError::InvalidProofJob);
+    }
+
+    let pks = &job.pks;
+    let signer_bitmap = &job.signer_bitmap;
+
+    if tree_level != PK_TREE_DEPTH && tree_level % 2 == 0 {
+        // Even nodes are proven on MNT6 and expose the keys of their children.
+        let (l_pks, r_pks) = pks.split_at(pks.len() / 2);
+        let (l_signer_bitmap, r_signer_bitmap) = signer_bitmap.split_at(signer_bitmap.len() / 2);
+
+        // Prepare the inputs.
+        let mut inputs = vec![];
+        inputs.append(
+            &mut pk_node_hash(tree_level + 1, l_pks)
+                .to_field_elements()
+                .unwrap(),
+        );
+        inputs.append(
+            &mut pk_node_hash(tree_level + 1, r_pks)
+                .to_field_elements()
+                .unwrap(),
+        );
+        inputs.append(
+            &mut agg_pk_commitment(&aggregate_pk(l_pks, l_signer_bitmap))
+                .to_field_elements()
+                .unwrap(),
+        );
+        inputs.append(
+            &mut agg_pk_commitment(&aggregate_pk(r_pks, r_signer_bitmap))
+                .to_field_elements()
+                .unwrap(),
+        );
+        inputs.append(
+            &mut BitVec::<MNT4Fq>::to_bytes_le(signer_bitmap)
+                .to_field_elements()
+                .unwrap(),
+        );
+        inputs.append(&mut keys.commitment().to_field_elements().unwrap());
+
+        let child_key: &VerifyingKey<MNT4_753> = keys
+            .get_key(CircuitId::PkTree(tree_level + 1))
+            .ok_or(SynthesisError::AssignmentMissing)?;
+        let (y_bytes, mut elements) = child_key
+            .to_field_elements()
+            .ok_or(SynthesisError::AssignmentMissing)?;
+        inputs.append(&mut y_bytes.to_field_elements().unwrap());
+        inputs.append(&mut elements);
+
+        // Verify proof. The proof comes from another process, so its points are checked.
+        let verifying_key: &VerifyingKey<MNT6_753> = keys
+            .get_key(CircuitId::PkTree(tree_level))
+            .ok_or(SynthesisError::AssignmentMissing)?;
+        let proof = Proof::deserialize_uncompressed(proof)?;
+        Ok(Groth16::<MNT6_753>::verify(verifying_key, &inputs, &proof)?)
+    } else {
+        // Leaves and odd nodes are proven on MNT4.
+        let mut inputs = vec![];
+        inputs.append(&mut pk_node_hash(tree_level, pks).to_field_elements().unwrap());
+        inputs.append(
+            &mut agg_pk_commitment(&aggregate_pk(pks, signer_bitmap))
+                .to_field_elements()
+                .unwrap(),
+        );
+        inputs.append(
+            &mut BitVec::<MNT6Fq>::to_bytes_le(signer_bitmap)
+                .to_field_elements()
+                .unwrap(),
+        );
+        if tree_level != PK_TREE_DEPTH {
+            inputs.append(&mut keys.commitment().to_field_elements().unwrap());
+        }
+
+        // Verify proof. The proof comes from another process, so its points are checked.
+        let verifying_key: &VerifyingKey<MNT4_753> = keys
+            .get_key(CircuitId::PkTree(tree_level))
+            .ok_or(SynthesisError::AssignmentMissing)?;
+        let proof = Proof::deserialize_uncompressed(proof)?;
+        Ok(Groth16::<MNT4_753>::verify(verifying_key, &inputs, &proof)?)
+    }
+}
+
+/// Calculates the hash of the public key tree node at the given level covering the given public keys.
+fn pk_node_hash(tree_level: usize, pks: &[G2MNT6]) -> [u8; 32] {
     let mut pk_node_hash = vec![];
 
+    if tree_level == PK_TREE_DEPTH {
+        for pk in pks {
+            pk_node_hash.extend(serialize_g2_mnt6(pk));
+        }
+    } else {
+        let (l_pks, r_pks) = pks.split_at(pks.len() / 2);
+        pk_node_hash.extend(self::pk_node_hash(tree_level + 1, l_pks));
+        pk_node_hash.extend(self::pk_node_hash(tree_level + 1, r_pks));
+    }
+
+    pk_node_hash.hash::<Blake2sHash>().0
+}
+
+/// Calculates the aggregate public key of the signers.
+fn aggregate_pk(pks: &[G2MNT6], signer_bitmap: &[bool]) -> G2MNT6 {
+    let mut agg_pk = G2MNT6::zero();
+
     for (i, pk) in pks.iter().enumerate() {
-        pk_node_hash.extend(serialize_g2_mnt6(pk));
         if signer_bitmap[i] {
             agg_pk += pk;
         }
     }
-    let pk_node_hash = pk_node_hash.hash::<Blake2sHash>().0;
 
-    let agg_pk_bytes = serialize_g2_mnt6(&agg_pk);
+    agg_pk
+}
 
+/// Calculates the commitment to an aggregate public key.
+fn agg_pk_commitment(agg_pk: &G2MNT6) -> [u8; 95] {
+    let agg_pk_bytes = serialize_g2_mnt6(agg_pk);
     let hash = default_pedersen_hash::<MNT6_753>(&agg_pk_bytes);
-    let agg_pk_commitment = serialize_g1_mnt6(&hash);
+    serialize_g1_mnt6(&hash)
+}
 
-    if proof_caching
-        && dir_path
-            .join("proofs")
-            .join(format!("{name}_{position}.bin"))
-            .exists()
-    {
-        return Ok(pk_node_hash);
-    }
+fn prove_pk_tree_leaf<R: CryptoRng + Rng>(
+    rng: &mut R,
+    pks: &[G2MNT6],
+    signer_bitmap: &[bool],
+    debug_mode: bool,
+    dir_path: &Path,
+) -> Result<Proof<MNT4_753>, NanoZKPError> {
+    let name = format!("pk_tree_{PK_TREE_DEPTH}");
 
-    log::info!("Generating sub-proof: {name}_{}", position);
+    // Calculate the node hash and the aggregate public key commitment.
+    let pk_node_hash = pk_node_hash(PK_TREE_DEPTH, pks);
+    let agg_pk_commitment = agg_pk_commitment(&aggregate_pk(pks, signer_bitmap));
 
     // Load the proving key from file.
     let mut file = File::open(dir_path.join("proving_keys").join(format!("{name}.bin")))?;
@@ -267,135 +605,38 @@ fn prove_pk_tree_leaf<R: CryptoRng + Rng>(
         )?);
     }
 
-    // Cache proof to file.
-    proof_to_file(proof, name, Some(position), dir_path)?;
-
-    Ok(pk_node_hash)
+    Ok(proof)
 }
 
 fn prove_pk_tree_node_mnt4<R: CryptoRng + Rng>(
     rng: &mut R,
     keys: &VerifyingKeys,
-    position: usize,
     tree_level: usize,
     pks: &[G2MNT6],
     signer_bitmap: &[bool],
+    left_proof: Proof<MNT4_753>,
+    right_proof: Proof<MNT4_753>,
     debug_mode: bool,
-    proof_caching: bool,
     dir_path: &Path,
-) -> Result<([u8; 32], [u8; 32]), NanoZKPError> {
-    assert_eq!(pks.len(), signer_bitmap.len());
-
+) -> Result<Proof<MNT6_753>, NanoZKPError> {
     let name = format!("pk_tree_{}", tree_level);
-    let vk_file = format!("pk_tree_{}", tree_level + 1);
 
     let l_pks = &pks[..pks.len() / 2];
     let r_pks = &pks[pks.len() / 2..];
     let l_signer_bitmap = &signer_bitmap[..signer_bitmap.len() / 2];
     let r_signer_bitmap = &signer_bitmap[signer_bitmap.len() / 2..];
 
-    // First create sub-proofs.
-    let l_pk_node_hash;
-    let r_pk_node_hash;
-    if tree_level == PK_TREE_DEPTH - 1 {
-        // Next level is the leaf node.
-        l_pk_node_hash = prove_pk_tree_leaf(
-            rng,
-            2 * position,
-            l_pks,
-            l_signer_bitmap,
-            debug_mode,
-            proof_caching,
-            dir_path,
-        )?;
-
-        r_pk_node_hash = prove_pk_tree_leaf(
-            rng,
-            2 * position + 1,
-            r_pks,
-            r_signer_bitmap,
-            debug_mode,
-            proof_caching,
-            dir_path,
-        )?;
-    } else {
-        // Next level is an inner node.
-        l_pk_node_hash = prove_pk_tree_node_mnt6(
-            rng,
-            keys,
-            2 * position,
-            tree_level + 1,
-            l_pks,
-            l_signer_bitmap,
-            debug_mode,
-            proof_caching,
-            dir_path,
-        )?;
-
-        r_pk_node_hash = prove_pk_tree_node_mnt6(
-            rng,
-            keys,
-            2 * position + 1,
-            tree_level + 1,
-            r_pks,
-            r_signer_bitmap,
-            debug_mode,
-            proof_caching,
-            dir_path,
-        )?;
-    }
-
-    let proving_keys = dir_path.join("proving_keys");
-    let verifying_keys = dir_path.join("verifying_keys");
-    let proofs = dir_path.join("proofs");
-
-    if proof_caching && proofs.join(format!("{name}_{position}.bin")).exists() {
-        return Ok((l_pk_node_hash, r_pk_node_hash));
-    }
-
-    log::info!("Generating sub-proof: {name}_{position}");
+    // Calculate the node hashes of the children.
+    let l_pk_node_hash = pk_node_hash(tree_level + 1, l_pks);
+    let r_pk_node_hash = pk_node_hash(tree_level + 1, r_pks);
 
     // Load the proving key from file.
-    let mut file = File::open(proving_keys.join(format!("{name}.bin")))?;
+    let mut file = File::open(dir_path.join("proving_keys").join(format!("{name}.bin")))?;
     let proving_key = ProvingKey::deserialize_uncompressed_unchecked(&mut file)?;
 
-    // Load the left proof from file.
-    let left_position = 2 * position;
-
-    let mut file = File::open(proofs.join(format!("{vk_file}_{left_position}.bin")))?;
-    let left_proof = Proof::deserialize_uncompressed_unchecked(&mut file)?;
-
-    // Load the right proof from file.
-    let right_position = 2 * position + 1;
-
-    let mut file = File::open(proofs.join(format!("{vk_file}_{right_position}.bin")))?;
-    let right_proof = Proof::deserialize_uncompressed_unchecked(&mut file)?;
-
-    // Calculate the left aggregate public key commitment.
-    let mut agg_pk = G2MNT6::zero();
-
-    for (i, pk) in l_pks.iter().enumerate() {
-        if l_signer_bitmap[i] {
-            agg_pk += pk;
-        }
-    }
-
-    let agg_pk_bytes = serialize_g2_mnt6(&agg_pk);
-    let hash = default_pedersen_hash::<MNT6_753>(&agg_pk_bytes);
-    let left_agg_pk_comm = serialize_g1_mnt6(&hash);
-
-    // Calculate the right aggregate public key commitment.
-    let mut agg_pk = G2MNT6::zero();
-
-    for (i, pk) in r_pks.iter().enumerate() {
-        if r_signer_bitmap[i] {
-            agg_pk += pk;
-        }
-    }
-
-    let agg_pk_bytes = serialize_g2_mnt6(&agg_pk);
-    let hash = default_pedersen_hash::<MNT6_753>(&agg_pk_bytes);
-    let right_agg_pk_comm = serialize_g1_mnt6(&hash);
+    // Calculate the left and right aggregate public key commitments.
+    let left_agg_pk_comm = agg_pk_commitment(&aggregate_pk(l_pks, l_signer_bitmap));
+    let right_agg_pk_comm = agg_pk_commitment(&aggregate_pk(r_pks, r_signer_bitmap));
 
     // Create the circuit.
     let circuit = NodeMNT4::new(
@@ -416,7 +657,7 @@ fn prove_pk_tree_node_mnt4<R: CryptoRng + Rng>(
     // Optionally verify the proof.
     if debug_mode {
         // Load the verifying key from file.
-        let mut file = File::open(verifying_keys.join(format!("{name}.bin")))?;
+        let mut file = File::open(dir_path.join("verifying_keys").join(format!("{name}.bin")))?;
         let verifying_key = VerifyingKey::deserialize_uncompressed_unchecked(&mut file)?;
 
         // Prepare the inputs.
@@ -449,26 +690,21 @@ fn prove_pk_tree_node_mnt4<R: CryptoRng + Rng>(
         )?);
     }
 
-    // Cache proof to file.
-    proof_to_file(proof, &name, Some(position), dir_path)?;
-    Ok((l_pk_node_hash, r_pk_node_hash))
+    Ok(proof)
 }
 
 fn prove_pk_tree_node_mnt6<R: CryptoRng + Rng>(
     rng: &mut R,
     keys: &VerifyingKeys,
-    position: usize,
     tree_level: usize,
     pks: &[G2MNT6],
     signer_bitmap: &[bool],
+    left_proof: Proof<MNT6_753>,
+    right_proof: Proof<MNT6_753>,
     debug_mode: bool,
-    proof_caching: bool,
     dir_path: &Path,
-) -> Result<[u8; 32], NanoZKPError> {
-    assert_eq!(pks.len(), signer_bitmap.len());
-
+) -> Result<Proof<MNT4_753>, NanoZKPError> {
     let name = format!("pk_tree_{}", tree_level);
-    let vk_file = format!("pk_tree_{}", tree_level + 1);
 
     let l_pks = &pks[..pks.len() / 2];
     let r_pks = &pks[pks.len() / 2..];
@@ -483,110 +719,24 @@ fn prove_pk_tree_node_mnt6<R: CryptoRng + Rng>(
     let rl_signer_bitmap = &r_signer_bitmap[..r_signer_bitmap.len() / 2];
     let rr_signer_bitmap = &r_signer_bitmap[r_signer_bitmap.len() / 2..];
 
-    let proving_keys = dir_path.join("proving_keys");
-    let verifying_keys = dir_path.join("verifying_keys");
-    let proofs = dir_path.join("proofs");
-
-    // Next level is always an inner node.
-    let (ll_pk_node_hash, lr_pk_node_hash) = prove_pk_tree_node_mnt4(
-        rng,
-        keys,
-        2 * position,
-        tree_level + 1,
-        l_pks,
-        l_signer_bitmap,
-        debug_mode,
-        proof_caching,
-        dir_path,
-    )?;
-
-    let (rl_pk_node_hash, rr_pk_node_hash) = prove_pk_tree_node_mnt4(
-        rng,
-        keys,
-        2 * position + 1,
-        tree_level + 1,
-        r_pks,
-        r_signer_bitmap,
-        debug_mode,
-        proof_caching,
-        dir_path,
-    )?;
-
-    // Calculate the node hash.
-    let mut l_pk_node_hash = vec![];
-    l_pk_node_hash.extend(ll_pk_node_hash);
-    l_pk_node_hash.extend(lr_pk_node_hash);
-
-    let l_pk_node_hash = l_pk_node_hash.hash::<Blake2sHash>().0;
-
-    let mut r_pk_node_hash = vec![];
-    r_pk_node_hash.extend(rl_pk_node_hash);
-    r_pk_node_hash.extend(rr_pk_node_hash);
-
-    let r_pk_node_hash = r_pk_node_hash.hash::<Blake2sHash>().0;
-
-    let mut pk_node_hash = vec![];
-    pk_node_hash.extend(l_pk_node_hash);
-    pk_node_hash.extend(r_pk_node_hash);
-
-    let pk_node_hash = pk_node_hash.hash::<Blake2sHash>().0;
-
-    if proof_caching && proofs.join(format!("{name}_{position}.bin")).exists() {
-        return Ok(pk_node_hash);
-    }
-
-    log::info!("Generating sub-proof: {name}_{position}");
+    // Calculate the node hashes of the grandchildren and of this node.
+    let ll_pk_node_hash = pk_node_hash(tree_level + 2, ll_pks);
+    let lr_pk_node_hash = pk_node_hash(tree_level + 2, lr_pks);
+    let rl_pk_node_hash = pk_node_hash(tree_level + 2, rl_pks);
+    let rr_pk_node_hash = pk_node_hash(tree_level + 2, rr_pks);
+    let pk_node_hash = pk_node_hash(tree_level, pks);
 
     // Load the proving key from file.
-    let mut file = File::open(proving_keys.join(format!("{name}.bin")))?;
+    let mut file = File::open(dir_path.join("proving_keys").join(format!("{name}.bin")))?;
     let proving_key = ProvingKey::deserialize_uncompressed_unchecked(&mut file)?;
 
-    // Load the left proof from file.
-    let left_position = 2 * position;
-
-    let mut file = File::open(proofs.join(format!("{vk_file}_{left_position}.bin")))?;
-    let left_proof = Proof::deserialize_uncompressed_unchecked(&mut file)?;
-
-    // Load the right proof from file.
-    let right_position = 2 * position + 1;
-
-    let mut file = File::open(proofs.join(format!("{vk_file}_{right_position}.bin")))?;
-    let right_proof = Proof::deserialize_uncompressed_unchecked(&mut file)?;
-
     // Calculate the aggregate public key chunks.
-    let mut agg_pk_chunks = vec![];
-
-    let mut agg_pk = G2MNT6::zero();
-    for (i, pk) in ll_pks.iter().enumerate() {
-        if ll_signer_bitmap[i] {
-            agg_pk += pk;
-        }
-    }
-    agg_pk_chunks.push(agg_pk);
-
-    let mut agg_pk = G2MNT6::zero();
-    for (i, pk) in lr_pks.iter().enumerate() {
-        if lr_signer_bitmap[i] {
-            agg_pk += pk;
-        }
-    }
-    agg_pk_chunks.push(agg_pk);
-
-    let mut agg_pk = G2MNT6::zero();
-    for (i, pk) in rl_pks.iter().enumerate() {
-        if rl_signer_bitmap[i] {
-            agg_pk += pk;
-        }
-    }
-    agg_pk_chunks.push(agg_pk);
-
-    let mut agg_pk = G2MNT6::zero();
-    for (i, pk) in rr_pks.iter().enumerate() {
-        if rr_signer_bitmap[i] {
-            agg_pk += pk;
-        }
-    }
-    agg_pk_chunks.push(agg_pk);
+    let agg_pk_chunks = [
+        aggregate_pk(ll_pks, ll_signer_bitmap),
+        aggregate_pk(lr_pks, lr_signer_bitmap),
+        aggregate_pk(rl_pks, rl_signer_bitmap),
+        aggregate_pk(rr_pks, rr_signer_bitmap),
+    ];
 
     // Calculate the aggregate public key commitment.
     let mut agg_pk = G2MNT6::zero();
@@ -595,9 +745,7 @@ fn prove_pk_tree_node_mnt6<R: CryptoRng + Rng>(
         agg_pk += chunk;
     }
 
-    let agg_pk_bytes = serialize_g2_mnt6(&agg_pk);
-    let hash = default_pedersen_hash::<MNT6_753>(&agg_pk_bytes);
-    let agg_pk_comm = serialize_g1_mnt6(&hash);
+    let agg_pk_comm = agg_pk_commitment(&agg_pk);
 
     // Create the circuit.
     let circuit = NodeMNT6::new(
@@ -624,7 +772,7 @@ fn prove_pk_tree_node_mnt6<R: CryptoRng + Rng>(
     // Optionally verify the proof.
     if debug_mode {
         // Load the verifying key from file.
-        let mut file = File::open(verifying_keys.join(format!("{name}.bin")))?;
+        let mut file = File::open(dir_path.join("verifying_keys").join(format!("{name}.bin")))?;
         let verifying_key = VerifyingKey::deserialize_uncompressed_unchecked(&mut file)?;
 
         // Prepare the inputs.
@@ -647,15 +795,13 @@ fn prove_pk_tree_node_mnt6<R: CryptoRng + Rng>(
         )?);
     }
 
-    // Cache proof to file.
-    proof_to_file(proof, &name, Some(position), dir_path)?;
-
-    Ok(pk_node_hash)
+    Ok(proof)
 }
 
-fn prove_macro_block<R: CryptoRng + Rng>(
+fn prove_macro_block<R: CryptoRng + Rng, P: PkTreeProver + ?Sized>(
     rng: &mut R,
     keys: &VerifyingKeys,
+    pk_tree_prover: &P,
     prev_block: MacroBlock,
     final_block: MacroBlock,
     debug_mode: bool,
@@ -680,18 +826,18 @@ fn prove_macro_block<R: CryptoRng + Rng>(
         .collect();
 
     // Generate the PK Tree proofs.
-    let (l_pk_node_hash, r_pk_node_hash) = prove_pk_tree_node_mnt4(
-        rng,
-        keys,
-        0,
-        0,
+    prove_pk_tree(
+        pk_tree_prover,
         &prev_pks,
         &signer_bitmap,
-        debug_mode,
         proof_caching,
         path,
     )?;
 
+    // Calculate the node hashes of the children of the root.
+    let l_pk_node_hash = pk_node_hash(1, &prev_pks[..prev_pks.len() / 2]);
+    let r_pk_node_hash = pk_node_hash(1, &prev_pks[prev_pks.len() / 2..]);
+
     let proving_keys = path.join("proving_keys");
     let verifying_keys = path.join("verifying_keys");
     let proofs = path.join("proofs");
@@ -953,6 +1099,18 @@ fn proof_to_file<T: Pairing>(
     name: &str,
     number: Option<usize>,
     path: &Path,
+) -> Result<(), NanoZKPError> {
+    let mut bytes = vec![];
+    pk.serialize_uncompressed(&mut bytes)?;
+    bytes_to_file(&bytes, name, number, path)
+}
+
+// Cache a serialized proof to file.
+fn bytes_to_file(
+    bytes: &[u8],
+    name: &str,
+    number: Option<usize>,
+    path: &Path,
 ) -> Result<(), NanoZKPError> {
     let proofs = path.join("proofs");
     if !proofs.is_dir() {
@@ -964,9 +1122,13 @@ fn proof_to_file<T: Pairing>(
         Some(n) => format!("_{n}"),
     };
 
-    let mut file = File::create(proofs.join(format!("{name}{suffix}.bin")))?;
-    pk.serialize_uncompressed(&mut file)?;
+    // Write to a temporary file first, such that a killed prover never leaves a partially written
+    // proof behind that would be picked up as cached.
+    let tmp_path = proofs.join(format!("{name}{suffix}.bin.tmp"));
+    let mut file = File::create(&tmp_path)?;
+    file.write_all(bytes)?;
     file.sync_all()?;
+    fs::rename(tmp_path, proofs.join(format!("{name}{suffix}.bin")))?;
 
     Ok(())
 }
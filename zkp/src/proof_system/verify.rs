@@ -2,7 +2,10 @@ use ark_crypto_primitives::snark::SNARK;
 use ark_ff::ToConstraintField;
 use ark_groth16::{Groth16, Proof};
 use ark_mnt6_753::MNT6_753;
-use nimiq_hash::Blake2sHash;
+use ark_serialize::CanonicalDeserialize;
+use nimiq_block::MacroHeader;
+use nimiq_hash::{Blake2sHash, Hash};
+use nimiq_primitives::{networks::NetworkId, policy::Policy};
 use nimiq_zkp_primitives::{NanoZKPError, VerifyingData};
 
 /// This function verifies a proof for the Merger Wrapper circuit, which implicitly is a proof for
@@ -30,3 +33,42 @@ pub fn verify(
     // Return result.
     Ok(result)
 }
+
+/// Verifies that the proof attests the chain of the given network to have transitioned from the
+/// initial to the final election block. This allows external verifiers, like bridges, to check
+/// a proof without running a node.
+///
+/// Fails if the headers are not election block headers of the given network in ascending order.
+pub fn verify_election_headers(
+    network_id: NetworkId,
+    // The header of the initial election block, usually the genesis block.
+    initial_header: &MacroHeader,
+    // The header of the election block the proof was generated for.
+    final_header: &MacroHeader,
+    // The SNARK proof for this transition.
+    proof: Proof<MNT6_753>,
+    verifying_data: &VerifyingData,
+) -> Result<bool, NanoZKPError> {
+    for header in [initial_header, final_header] {
+        if header.network != network_id || !Policy::is_election_block_at(header.block_number) {
+            return Err(NanoZKPError::InvalidBlock);
+        }
+    }
+
+    if final_header.block_number <= initial_header.block_number {
+        return Err(NanoZKPError::InvalidBlock);
+    }
+
+    verify(
+        initial_header.hash::<Blake2sHash>(),
+        final_header.hash::<Blake2sHash>(),
+        proof,
+        verifying_data,
+    )
+}
+
+/// Deserializes a proof in the compressed format it is gossiped and stored in. The proof is fully
+/// validated, so it is safe to use on untrusted input.
+pub fn deserialize_proof(bytes: &[u8]) -> Result<Proof<MNT6_753>, NanoZKPError> {
+    Ok(Proof::deserialize_compressed(bytes)?)
+}
@@ -8,6 +8,13 @@ use nimiq_zkp_circuits::metadata::VerifyingKeyMetadata;
 use nimiq_zkp_primitives::VerifyingData;
 use once_cell::sync::OnceCell;
 
+/// The networks for which a verifying key is included in the binary.
+pub const ZKP_NETWORKS: [NetworkId; 3] = [
+    NetworkId::DevAlbatross,
+    NetworkId::TestAlbatross,
+    NetworkId::UnitAlbatross,
+];
+
 #[derive(Default)]
 pub struct ZKPVerifyingKey {
     cell: OnceCell<VerifyingData>,
@@ -0,0 +1,178 @@
+#![cfg(feature = "zkp-prover")]
+
+use std::{
+    net::{SocketAddr, TcpListener},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use ark_serialize::CanonicalSerialize;
+use nimiq_primitives::slots_allocation::PK_TREE_DEPTH;
+use nimiq_test_log::test;
+use nimiq_zkp::{
+    distributed::{serve_coordinator, WorkerPool},
+    prove::{PkTreeJob, PkTreeProofVerifier, PkTreeProver},
+};
+use nimiq_zkp_primitives::NanoZKPError;
+
+const SECRET: &str = "secret";
+
+fn jobs(num_jobs: u64) -> Vec<PkTreeJob> {
+    (0..num_jobs)
+        .map(|position| PkTreeJob {
+            tree_level: PK_TREE_DEPTH as u64,
+            position,
+            pks: vec![],
+            signer_bitmap: vec![],
+            child_proofs: vec![],
+        })
+        .collect()
+}
+
+/// The stub workers return the serialized job as its proof.
+fn expected_proofs(jobs: &[PkTreeJob]) -> Vec<Vec<u8>> {
+    jobs.iter()
+        .map(|job| {
+            let mut bytes = vec![];
+            job.serialize_uncompressed(&mut bytes).unwrap();
+            bytes
+        })
+        .collect()
+}
+
+/// Accepts the proofs of the stub workers.
+struct EchoVerifier;
+
+impl PkTreeProofVerifier for EchoVerifier {
+    fn verify_pk_tree_proof(&self, job: &PkTreeJob, proof: &[u8]) -> bool {
+        expected_proofs(std::slice::from_ref(job))[0] == proof
+    }
+}
+
+/// Spawns a worker on a loopback port that proves jobs with `prove`.
+fn spawn_worker<F>(secret: &'static str, prove: F) -> SocketAddr
+where
+    F: FnMut(&[u8]) -> Result<Vec<u8>, NanoZKPError> + Clone + Send + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            serve_coordinator(stream.unwrap(), secret.as_bytes(), prove.clone());
+        }
+    });
+    address
+}
+
+fn spawn_echo_worker() -> SocketAddr {
+    spawn_worker(SECRET, |job| Ok(job.to_vec()))
+}
+
+#[test]
+fn it_distributes_jobs_to_workers() {
+    let pool = WorkerPool::new(
+        vec![spawn_echo_worker(), spawn_echo_worker()],
+        SECRET,
+        EchoVerifier,
+    );
+
+    // The connections are reused for the jobs of the next level.
+    for num_jobs in [8, 4, 1] {
+        let jobs = jobs(num_jobs);
+        let expected = expected_proofs(&jobs);
+        assert_eq!(pool.prove_pk_tree_jobs(jobs).unwrap(), expected);
+    }
+}
+
+#[test]
+fn it_reschedules_jobs_of_unreachable_workers() {
+    // Nothing listens on this port anymore.
+    let closed = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+
+    // Closes every connection right away.
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let dropping = listener.local_addr().unwrap();
+    thread::spawn(move || listener.incoming().for_each(drop));
+
+    // Takes longer than the coordinator is willing to wait.
+    let slow = spawn_worker(SECRET, |job| {
+        thread::sleep(Duration::from_secs(1));
+        Ok(job.to_vec())
+    });
+
+    let pool = WorkerPool::new(
+        vec![closed, dropping, slow, spawn_echo_worker()],
+        SECRET,
+        EchoVerifier,
+    )
+    .with_proof_timeout(Duration::from_millis(100));
+
+    let jobs = jobs(8);
+    let expected = expected_proofs(&jobs);
+    assert_eq!(pool.prove_pk_tree_jobs(jobs).unwrap(), expected);
+}
+
+#[test]
+fn it_reschedules_jobs_with_invalid_proofs() {
+    let lying = spawn_worker(SECRET, |_| Ok(b"not a proof".to_vec()));
+    let pool = WorkerPool::new(vec![lying, spawn_echo_worker()], SECRET, EchoVerifier);
+
+    let jobs = jobs(8);
+    let expected = expected_proofs(&jobs);
+    assert_eq!(pool.prove_pk_tree_jobs(jobs).unwrap(), expected);
+
+    // Without an honest worker, the jobs can't be proven.
+    let pool = WorkerPool::new(vec![lying], SECRET, EchoVerifier);
+    assert!(matches!(
+        pool.prove_pk_tree_jobs(jobs(2)),
+        Err(NanoZKPError::Worker(_))
+    ));
+}
+
+#[test]
+fn it_rejects_peers_with_another_secret() {
+    let proved = Arc::new(AtomicBool::new(false));
+    let worker = {
+        let proved = Arc::clone(&proved);
+        spawn_worker(SECRET, move |job| {
+            proved.store(true, Ordering::Relaxed);
+            Ok(job.to_vec())
+        })
+    };
+
+    let pool = WorkerPool::new(vec![worker], "another secret", EchoVerifier);
+    assert!(matches!(
+        pool.prove_pk_tree_jobs(jobs(2)),
+        Err(NanoZKPError::Worker(_))
+    ));
+    assert!(!proved.load(Ordering::Relaxed));
+
+    // The coordinator doesn't accept a worker with another secret either.
+    let pool = WorkerPool::new(
+        vec![spawn_worker("another secret", |job| Ok(job.to_vec()))],
+        SECRET,
+        EchoVerifier,
+    );
+    assert!(matches!(
+        pool.prove_pk_tree_jobs(jobs(2)),
+        Err(NanoZKPError::Worker(_))
+    ));
+}
+
+#[test]
+fn it_fails_if_a_worker_fails_to_prove_a_job() {
+    let worker = spawn_worker(SECRET, |_| Err(NanoZKPError::Worker("boom".to_string())));
+    let pool = WorkerPool::new(vec![worker], SECRET, EchoVerifier);
+
+    match pool.prove_pk_tree_jobs(jobs(2)) {
+        Err(NanoZKPError::Worker(error)) => assert!(error.contains("boom")),
+        result => panic!("Unexpected result: {result:?}"),
+    }
+}
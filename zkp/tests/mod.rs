@@ -1,2 +1,4 @@
+mod distributed;
 #[cfg(feature = "zkp-prover")]
 mod prover;
+mod verify;
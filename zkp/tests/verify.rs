@@ -0,0 +1,125 @@
+use std::path::Path;
+
+use ark_groth16::Proof;
+use ark_mnt6_753::MNT6_753;
+use ark_serialize::CanonicalSerialize;
+use nimiq_block::MacroHeader;
+use nimiq_hash::{Blake2sHash, Hash};
+use nimiq_primitives::{networks::NetworkId, policy::Policy};
+use nimiq_test_utils::zkp_test_data::{get_base_seed, simulate_proof, ZKP_TEST_KEYS_PATH};
+use nimiq_zkp::{
+    verify::{deserialize_proof, verify_election_headers},
+    ZKP_VERIFYING_DATA,
+};
+use nimiq_zkp_primitives::NanoZKPError;
+
+fn election_header(network: NetworkId, epoch: u32) -> MacroHeader {
+    MacroHeader {
+        network,
+        block_number: Policy::genesis_block_number() + epoch * Policy::blocks_per_epoch(),
+        ..Default::default()
+    }
+}
+
+fn proof(initial_header: &MacroHeader, final_header: &MacroHeader) -> Proof<MNT6_753> {
+    simulate_proof(
+        Path::new(ZKP_TEST_KEYS_PATH),
+        &initial_header.hash::<Blake2sHash>(),
+        &final_header.hash::<Blake2sHash>(),
+        &ZKP_VERIFYING_DATA,
+        &mut get_base_seed(),
+    )
+}
+
+#[test]
+fn it_verifies_proofs_of_election_headers() {
+    let network_id = NetworkId::UnitAlbatross;
+    let genesis = election_header(network_id, 0);
+    let election = election_header(network_id, 1);
+    let proof = proof(&genesis, &election);
+
+    let result = verify_election_headers(
+        network_id,
+        &genesis,
+        &election,
+        proof.clone(),
+        &ZKP_VERIFYING_DATA,
+    );
+    assert!(matches!(result, Ok(true)));
+
+    // The proof doesn't attest a transition to another election block.
+    let result = verify_election_headers(
+        network_id,
+        &genesis,
+        &election_header(network_id, 2),
+        proof.clone(),
+        &ZKP_VERIFYING_DATA,
+    );
+    assert!(matches!(result, Ok(false)));
+
+    // Nor one from another initial block.
+    let mut other_genesis = genesis.clone();
+    other_genesis.timestamp += 1;
+    let result = verify_election_headers(
+        network_id,
+        &other_genesis,
+        &election,
+        proof,
+        &ZKP_VERIFYING_DATA,
+    );
+    assert!(matches!(result, Ok(false)));
+}
+
+#[test]
+fn it_deserializes_compressed_proofs() {
+    let network_id = NetworkId::UnitAlbatross;
+    let proof = proof(
+        &election_header(network_id, 0),
+        &election_header(network_id, 1),
+    );
+
+    let mut bytes = vec![];
+    proof.serialize_compressed(&mut bytes).unwrap();
+    assert_eq!(deserialize_proof(&bytes).unwrap(), proof);
+
+    assert!(deserialize_proof(&bytes[1..]).is_err());
+    assert!(deserialize_proof(&[0xff; 96]).is_err());
+}
+
+#[test]
+fn it_rejects_unrelated_headers() {
+    let network_id = NetworkId::UnitAlbatross;
+    let genesis = election_header(network_id, 0);
+
+    // Header of another network.
+    let result = verify_election_headers(
+        network_id,
+        &genesis,
+        &election_header(NetworkId::TestAlbatross, 1),
+        Proof::default(),
+        &ZKP_VERIFYING_DATA,
+    );
+    assert!(matches!(result, Err(NanoZKPError::InvalidBlock)));
+
+    // Header of a checkpoint block.
+    let mut checkpoint = election_header(network_id, 1);
+    checkpoint.block_number -= Policy::blocks_per_batch();
+    let result = verify_election_headers(
+        network_id,
+        &genesis,
+        &checkpoint,
+        Proof::default(),
+        &ZKP_VERIFYING_DATA,
+    );
+    assert!(matches!(result, Err(NanoZKPError::InvalidBlock)));
+
+    // Final header preceding the initial one.
+    let result = verify_election_headers(
+        network_id,
+        &election_header(network_id, 2),
+        &election_header(network_id, 1),
+        Proof::default(),
+        &ZKP_VERIFYING_DATA,
+    );
+    assert!(matches!(result, Err(NanoZKPError::InvalidBlock)));
+}